@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use vericortex_sdk::Address;
+use vericortex_submitter::ChainConfig;
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Endpoint, contract and submitter key (`[signer]`, or `PRIVATE_KEY` for
+    /// development), with the outbox and fee limits of `[prover]`: the API
+    /// and the prover submit through the same outbox.
+    pub chain: ChainConfig,
+    /// First block indexed for contract events.
+    pub start_block: u64,
+    pub port: u16,
+    /// SQLite file the event indexer writes and listings read; it also
+    /// holds the model registry.
+    pub index_db: PathBuf,
+    /// Blocks an event must be buried under before it is indexed.
+    pub confirmations: u64,
+    pub index_poll_interval: Duration,
+    /// Trusted signers for `signed-attestation` proofs.
+    pub attesters: Vec<Address>,
+}
+
+impl Config {
+    /// The API's settings from the shared configuration file, env and flags.
+    pub fn from_shared(shared: &vericortex_config::Config) -> Result<Self, vericortex_config::ConfigError> {
+        let network = shared.network()?;
+        Ok(Self {
+            chain: ChainConfig::from_config(shared)?,
+            start_block: network.start_block,
+            port: shared.api.port,
+            index_db: shared.api.index_db.clone(),
+            confirmations: network.confirmations.unwrap_or(12),
+            index_poll_interval: Duration::from_millis(shared.api.index_poll_ms),
+            attesters: shared.api.attesters.clone(),
+        })
+    }
+}
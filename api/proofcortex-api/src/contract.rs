@@ -0,0 +1,106 @@
+use ethers::prelude::*;
+use tokio::sync::Mutex;
+use vericortex_sdk::contract::{OnChainProof, ProofCortexVerifier, VerifierRevert};
+use vericortex_sdk::{compute_proof_id, Hash32, ModelId, ProofId, ProofIdPreimage};
+use vericortex_submitter::{Client, Sender};
+
+use crate::config::Config;
+use crate::error::ApiError;
+
+/// Reads go straight to the contract; submissions queue through the
+/// prover's outbox, one at a time, under its fee limits.
+pub struct VerifierClient {
+    pub contract: ProofCortexVerifier<Client>,
+    sender: Mutex<Sender>,
+    submitter: vericortex_sdk::Address,
+}
+
+/// Fields of one `submitProof` call.
+pub struct ProofSubmission<'a> {
+    pub model_id: &'a ModelId,
+    pub version: &'a str,
+    pub input_hash: Hash32,
+    pub output_hash: Hash32,
+    pub trace_hash: Hash32,
+    pub proof_hash: Hash32,
+    pub valid: bool,
+}
+
+pub struct Submitted {
+    pub proof_id: ProofId,
+    pub tx_hash: H256,
+    pub block_number: Option<u64>,
+}
+
+fn chain_err(e: impl std::fmt::Display) -> ApiError {
+    ApiError::Chain(anyhow::anyhow!("{e}"))
+}
+
+/// Reverts become `ApiError::Reverted` with the `require` message; anything else is a chain failure.
+fn contract_err(e: ContractError<Client>) -> ApiError {
+    match VerifierRevert::from_contract_error(&e) {
+        Some(revert) => ApiError::Reverted(revert.to_string()),
+        None => chain_err(e),
+    }
+}
+
+impl VerifierClient {
+    /// Connect, then finish whatever an earlier run left in the outbox.
+    pub async fn new(cfg: &Config) -> anyhow::Result<Self> {
+        let mut sender = Sender::connect(cfg.chain.clone()).await?;
+        sender.resume().await?;
+        let contract = ProofCortexVerifier::new(cfg.chain.contract_address, sender.client().clone());
+        let submitter = sender.submitter();
+        Ok(Self { contract, sender: Mutex::new(sender), submitter })
+    }
+
+    pub fn submitter(&self) -> vericortex_sdk::Address {
+        self.submitter
+    }
+
+    /// Queue `submitProof` in the outbox and wait for it to be confirmed.
+    /// A proof already on-chain is refused like the contract would.
+    pub async fn submit_proof(&self, proof: &ProofSubmission<'_>) -> Result<Submitted, ApiError> {
+        let preimage =
+            ProofIdPreimage::ProofCortex { model_id: proof.model_id, version: proof.version, proof_hash: &proof.proof_hash };
+        let proof_id = compute_proof_id(&preimage, &self.submitter);
+        if self.get_proof(&proof_id).await?.is_some() {
+            return Err(ApiError::Reverted(VerifierRevert::ProofExists.to_string()));
+        }
+        let calldata = self
+            .contract
+            .submit_proof(
+                proof.model_id.to_string(),
+                proof.version.to_string(),
+                proof.input_hash.0,
+                proof.output_hash.0,
+                proof.trace_hash.0,
+                proof.proof_hash.0,
+                proof.valid,
+            )
+            .calldata()
+            .ok_or_else(|| chain_err("Failed to encode submitProof"))?;
+
+        let entry = self.sender.lock().await.submit(proof_id, proof.model_id, calldata).await.map_err(|e| {
+            match e.downcast_ref::<VerifierRevert>() {
+                Some(revert) => ApiError::Reverted(revert.to_string()),
+                None => ApiError::Chain(e),
+            }
+        })?;
+        Ok(Submitted {
+            proof_id,
+            tx_hash: entry.tx_hash.ok_or_else(|| chain_err("confirmed entry without tx hash"))?,
+            block_number: entry.block_number,
+        })
+    }
+
+    pub async fn get_proof(&self, proof_id: &ProofId) -> Result<Option<OnChainProof>, ApiError> {
+        let record = self.contract.get_proof(proof_id.0).call().await.map_err(contract_err)?;
+        let proof = OnChainProof::try_from(record).map_err(|e| ApiError::Chain(e.into()))?;
+        Ok(proof.exists().then_some(proof))
+    }
+
+    pub async fn is_valid(&self, proof_id: &ProofId) -> Result<bool, ApiError> {
+        self.contract.is_proof_valid(proof_id.0).call().await.map_err(contract_err)
+    }
+}
@@ -0,0 +1,62 @@
+use axum::extract::rejection::JsonRejection;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::json;
+
+/// Handler errors, rendered as `{"error": kind, "message": ...}` like the prover CLI.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Conflict(String),
+    /// The contract rejected the call (`require` failed).
+    #[error("{0}")]
+    Reverted(String),
+    #[error("{0:#}")]
+    Chain(anyhow::Error),
+    /// The local event index could not be read.
+    #[error("{0:#}")]
+    Index(anyhow::Error),
+    /// The model registry could not be read or written.
+    #[error("{0:#}")]
+    Registry(anyhow::Error),
+}
+
+impl ApiError {
+    fn kind(&self) -> (StatusCode, &'static str) {
+        match self {
+            ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, "malformed_input"),
+            ApiError::NotFound(_) => (StatusCode::NOT_FOUND, "not_found"),
+            ApiError::Conflict(_) => (StatusCode::CONFLICT, "conflict"),
+            ApiError::Reverted(_) => (StatusCode::CONFLICT, "reverted"),
+            ApiError::Chain(_) => (StatusCode::BAD_GATEWAY, "chain_failure"),
+            ApiError::Index(_) => (StatusCode::INTERNAL_SERVER_ERROR, "index_failure"),
+            ApiError::Registry(_) => (StatusCode::INTERNAL_SERVER_ERROR, "registry_failure"),
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(e: JsonRejection) -> Self {
+        ApiError::BadRequest(e.body_text())
+    }
+}
+
+impl From<vericortex_sdk::HexError> for ApiError {
+    fn from(e: vericortex_sdk::HexError) -> Self {
+        ApiError::BadRequest(e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, kind) = self.kind();
+        (status, Json(json!({ "error": kind, "message": self.to_string() }))).into_response()
+    }
+}
+
+pub type ApiResult<T> = Result<Json<T>, ApiError>;
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, extract::State, Json};
+use serde::Serialize;
+use vericortex_sdk::contract::OnChainProof;
+use vericortex_sdk::{Address, Hash32, ModelId, ProofId};
+
+use crate::error::{ApiError, ApiResult};
+use crate::state::AppState;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofView {
+    pub proof_id: ProofId,
+    pub model_id: ModelId,
+    pub version: String,
+    pub input_hash: Hash32,
+    pub output_hash: Hash32,
+    pub trace_hash: Hash32,
+    pub proof_hash: Hash32,
+    pub submitter: Address,
+    pub valid: bool,
+    pub timestamp: u64,
+    pub locked: bool,
+}
+
+impl ProofView {
+    fn new(proof_id: ProofId, p: OnChainProof) -> Self {
+        Self {
+            proof_id,
+            model_id: p.model_id,
+            version: p.version,
+            input_hash: p.input_hash,
+            output_hash: p.output_hash,
+            trace_hash: p.trace_hash,
+            proof_hash: p.proof_hash,
+            submitter: p.submitter,
+            valid: p.valid,
+            timestamp: p.timestamp,
+            locked: p.locked,
+        }
+    }
+}
+
+pub async fn get_proof(Path(proof_id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<ProofView> {
+    let proof_id: ProofId = proof_id.parse()?;
+    match state.verifier.get_proof(&proof_id).await? {
+        Some(proof) => Ok(Json(ProofView::new(proof_id, proof))),
+        None => Err(ApiError::NotFound(format!("proof {proof_id} not found"))),
+    }
+}
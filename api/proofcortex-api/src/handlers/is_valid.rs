@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, extract::State, Json};
+use serde::Serialize;
+use vericortex_sdk::ProofId;
+
+use crate::error::ApiResult;
+use crate::state::AppState;
+
+#[derive(Serialize)]
+pub struct ValidResp {
+    pub valid: bool,
+}
+
+/// `isProofValid`: unknown proofs are reported as not valid, as the contract does.
+pub async fn is_valid(Path(proof_id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<ValidResp> {
+    let proof_id: ProofId = proof_id.parse()?;
+    Ok(Json(ValidResp { valid: state.verifier.is_valid(&proof_id).await? }))
+}
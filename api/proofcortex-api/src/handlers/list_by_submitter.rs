@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, extract::State, Json};
+use serde::Serialize;
+use vericortex_sdk::{Address, ModelId, ProofId};
+
+use crate::error::ApiResult;
+use crate::state::AppState;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofEntry {
+    pub proof_id: ProofId,
+    pub model_id: ModelId,
+    pub valid: bool,
+}
+
+#[derive(Serialize)]
+pub struct ListResp {
+    pub submitter: Address,
+    pub proofs: Vec<ProofEntry>,
+}
+
+/// Proofs of `address` from the event index, in submission order.
+pub async fn list_by_submitter(Path(address): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<ListResp> {
+    let submitter: Address = address.parse()?;
+    let proofs = state
+        .query(|index| index.proofs_by_submitter(&submitter))?
+        .into_iter()
+        .map(|p| ProofEntry { proof_id: p.proof_id, model_id: p.model_id, valid: p.valid })
+        .collect();
+    Ok(Json(ListResp { submitter, proofs }))
+}
@@ -0,0 +1,7 @@
+pub mod get_proof;
+pub mod is_valid;
+pub mod list_by_submitter;
+pub mod model_registry;
+pub mod stats;
+pub mod submit;
+pub mod trust_proxy;
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use axum::extract::rejection::JsonRejection;
+use axum::{extract::Path, extract::State, Json};
+use serde::Deserialize;
+use vericortex_sdk::{ModelFingerprint, ModelId};
+
+use crate::error::{ApiError, ApiResult};
+use crate::services::ModelInfo;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterReq {
+    /// The `modelId` the model's proofs are submitted under.
+    pub model_id: ModelId,
+    pub name: String,
+    pub owner: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub fingerprint: Option<ModelFingerprint>,
+}
+
+pub async fn register_model(
+    State(state): State<Arc<AppState>>,
+    req: Result<Json<RegisterReq>, JsonRejection>,
+) -> ApiResult<ModelInfo> {
+    let Json(req) = req?;
+    if req.model_id.as_str().trim().is_empty() || req.name.trim().is_empty() {
+        return Err(ApiError::BadRequest("modelId and name must not be empty".into()));
+    }
+    let info = ModelInfo {
+        model_id: req.model_id,
+        name: req.name,
+        owner: req.owner,
+        description: req.description,
+        fingerprint: req.fingerprint,
+    };
+    if !state.registry.register(&info).map_err(ApiError::Registry)? {
+        return Err(ApiError::Conflict(format!("model {} is already registered", info.model_id)));
+    }
+    Ok(Json(info))
+}
+
+pub async fn get_model(State(state): State<Arc<AppState>>, Path(model_id): Path<String>) -> ApiResult<ModelInfo> {
+    state
+        .registry
+        .get(&ModelId(model_id.clone()))
+        .map_err(ApiError::Registry)?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("model {model_id} not registered")))
+}
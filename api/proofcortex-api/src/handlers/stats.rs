@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Json};
+use serde::Serialize;
+
+use crate::error::ApiResult;
+use crate::state::AppState;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    pub total_proofs: u64,
+    pub valid_proofs: u64,
+    pub invalid_proofs: u64,
+    pub unique_submitters: u64,
+}
+
+/// Totals over the confirmed proofs in the event index.
+pub async fn stats(State(state): State<Arc<AppState>>) -> ApiResult<Stats> {
+    let stats = state.query(|index| index.stats())?;
+    Ok(Json(Stats {
+        total_proofs: stats.total_proofs,
+        valid_proofs: stats.valid_proofs,
+        invalid_proofs: stats.invalid_proofs,
+        unique_submitters: stats.unique_submitters,
+    }))
+}
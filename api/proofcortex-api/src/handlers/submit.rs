@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use axum::extract::rejection::JsonRejection;
+use axum::{extract::State, Json};
+use ethers::types::H256;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use vericortex_sdk::{Hash32, ModelId, Multihash, ProofId, ProofJson};
+
+use crate::contract::ProofSubmission;
+use crate::error::{ApiError, ApiResult};
+use crate::state::AppState;
+
+/// Proof envelope fields, camelCased. `valid` is never taken from the
+/// caller: the proof is checked by the adapter for its `proofType`. By
+/// default the output hash is recomputed with the algorithm `outputHash`
+/// names (a bare 32-byte hash means SHA-256), while `signed-attestation`
+/// proofs carry the signature in `evidence`. The SDK bundle hash of the
+/// fields is anchored; a `proofHash`, if given, must equal it.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SubmitReq {
+    pub model_id: ModelId,
+    #[serde(default)]
+    pub model_version: String,
+    pub input_hash: Multihash,
+    pub output_hash: Multihash,
+    pub trace_hash: Option<Hash32>,
+    pub proof_hash: Option<Hash32>,
+    pub proof_type: Option<String>,
+    pub evidence: Option<Value>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitResp {
+    pub proof_id: ProofId,
+    pub proof_hash: Hash32,
+    pub submitter: vericortex_sdk::Address,
+    pub valid: bool,
+    /// Why the adapter decided `valid`.
+    pub reason: String,
+    pub tx_hash: H256,
+    pub block_number: Option<u64>,
+}
+
+pub async fn submit_proof(
+    State(state): State<Arc<AppState>>,
+    req: Result<Json<SubmitReq>, JsonRejection>,
+) -> ApiResult<SubmitResp> {
+    let Json(req) = req?;
+
+    let mut proof = ProofJson::new(req.model_id, req.input_hash, req.output_hash);
+    proof.model_version = req.model_version;
+    proof.trace_hash = req.trace_hash;
+    proof.proof_type = req.proof_type;
+    proof.evidence = req.evidence;
+    let proof_hash = proof.bundle_hash();
+    if let Some(claimed) = req.proof_hash.filter(|h| *h != proof_hash) {
+        return Err(ApiError::BadRequest(format!("proofHash {claimed} is not the bundle hash {proof_hash} of the fields")));
+    }
+
+    let verdict = state.adapters.verify(&proof).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let valid = verdict.valid;
+    let submitted = state
+        .verifier
+        .submit_proof(&ProofSubmission {
+            model_id: &proof.model_id,
+            version: &proof.model_version,
+            input_hash: proof.input_hash.digest,
+            output_hash: proof.output_hash.digest,
+            trace_hash: proof.trace_hash32(),
+            proof_hash,
+            valid,
+        })
+        .await?;
+    tracing::info!("proof {} submitted in tx {:?}", submitted.proof_id, submitted.tx_hash);
+
+    Ok(Json(SubmitResp {
+        proof_id: submitted.proof_id,
+        proof_hash,
+        submitter: state.verifier.submitter(),
+        valid,
+        reason: verdict.reason,
+        tx_hash: submitted.tx_hash,
+        block_number: submitted.block_number,
+    }))
+}
@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, extract::State, Json};
+use vericortex_sdk::ModelId;
+
+use crate::error::ApiResult;
+use crate::services::TrustScore;
+use crate::state::AppState;
+
+/// Trust score of the on-chain `modelId`, from its proofs in the event index.
+pub async fn get_trust(State(state): State<Arc<AppState>>, Path(model_id): Path<String>) -> ApiResult<TrustScore> {
+    let mut trust = TrustScore::new(&model_id);
+    for proof in state.query(|index| index.proofs_by_model(&ModelId(model_id.clone())))? {
+        trust.record(proof.valid);
+    }
+    Ok(Json(trust))
+}
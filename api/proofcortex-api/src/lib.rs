@@ -0,0 +1,14 @@
+//! ProofCortex public API: an Axum gateway over the ProofCortexVerifier
+//! contract, built on the `vericortex-sdk` proof library and bindings.
+
+pub mod config;
+pub mod contract;
+pub mod error;
+pub mod handlers;
+pub mod routes;
+pub mod services;
+pub mod state;
+
+pub use config::Config;
+pub use routes::router;
+pub use state::AppState;
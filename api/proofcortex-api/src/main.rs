@@ -1,3 +1,36 @@
-fn main() {
-    println!("Hello, world!");
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::Context;
+use clap::Parser;
+use proofcortex_api::{router, AppState, Config};
+use vericortex_config::ConfigArgs;
+
+#[derive(Parser)]
+#[command(name = "proofcortex-api", version, about = "ProofCortex REST API")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt::init();
+    dotenvy::dotenv().ok();
+
+    let cli = Cli::parse();
+    let shared = vericortex_config::Config::load(&cli.config)?;
+    let cfg = Config::from_shared(&shared)?;
+    tracing::info!("Using profile {} ({})", shared.profile, cfg.chain.rpc_url);
+    let app_state = Arc::new(AppState::new(&cfg).await?);
+
+    let app = router(app_state.clone());
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], cfg.port));
+    tracing::info!("ProofCortex API listening on {}", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await.context("failed to bind")?;
+    axum::serve(listener, app).await.context("failed to start server")?;
+
+    Ok(())
 }
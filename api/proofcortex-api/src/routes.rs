@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::handlers::{get_proof, is_valid, list_by_submitter, model_registry, stats, submit, trust_proxy};
+use crate::state::AppState;
+
+pub fn router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/health", get(|| async { "OK" }))
+        .route("/proofs/submit", post(submit::submit_proof))
+        .route("/proofs/:proof_id", get(get_proof::get_proof))
+        .route("/proofs/:proof_id/valid", get(is_valid::is_valid))
+        .route("/proofs/submitter/:address", get(list_by_submitter::list_by_submitter))
+        .route("/stats", get(stats::stats))
+        .route("/models/register", post(model_registry::register_model))
+        .route("/models/:model_id", get(model_registry::get_model))
+        .route("/trust/:model_id", get(trust_proxy::get_trust))
+        .with_state(state)
+}
@@ -0,0 +1,5 @@
+pub mod registry;
+pub mod trust;
+
+pub use registry::{ModelInfo, RegistryClient};
+pub use trust::TrustScore;
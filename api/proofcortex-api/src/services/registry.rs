@@ -0,0 +1,76 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::Context;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use vericortex_sdk::{ModelFingerprint, ModelId};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS models (
+    model_id    TEXT PRIMARY KEY,
+    name        TEXT NOT NULL,
+    owner       TEXT NOT NULL,
+    description TEXT NOT NULL,
+    fingerprint TEXT
+);
+";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInfo {
+    /// The `modelId` its proofs are submitted under on-chain.
+    pub model_id: ModelId,
+    pub name: String,
+    pub owner: String,
+    pub description: String,
+    /// Commitment to the model artifact; check a copy on disk with
+    /// `verifier_offchain fingerprint --entry`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<ModelFingerprint>,
+}
+
+/// Registered models, kept in the API's SQLite database next to the event
+/// index, so they survive restarts and share the index's `modelId`s.
+pub struct RegistryClient {
+    conn: Mutex<Connection>,
+}
+
+impl RegistryClient {
+    /// Open (or create) the `models` table in the database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let conn = Connection::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        conn.execute_batch(SCHEMA).context("Failed to create models schema")?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    /// Record `info`, unless its `model_id` is already registered; returns
+    /// whether it was.
+    pub fn register(&self, info: &ModelInfo) -> anyhow::Result<bool> {
+        let fingerprint = info.fingerprint.as_ref().map(serde_json::to_string).transpose()?;
+        let inserted = self.conn.lock().expect("registry lock poisoned").execute(
+            "INSERT INTO models (model_id, name, owner, description, fingerprint) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (model_id) DO NOTHING",
+            params![info.model_id.as_str(), info.name, info.owner, info.description, fingerprint],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    pub fn get(&self, model_id: &ModelId) -> anyhow::Result<Option<ModelInfo>> {
+        let conn = self.conn.lock().expect("registry lock poisoned");
+        let row = conn
+            .query_row(
+                "SELECT name, owner, description, fingerprint FROM models WHERE model_id = ?1",
+                params![model_id.as_str()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get::<_, Option<String>>(3)?)),
+            )
+            .optional()?;
+        let Some((name, owner, description, fingerprint)) = row else {
+            return Ok(None);
+        };
+        let fingerprint = fingerprint.map(|f| serde_json::from_str(&f)).transpose().context("stored fingerprint")?;
+        Ok(Some(ModelInfo { model_id: model_id.clone(), name, owner, description, fingerprint }))
+    }
+}
@@ -0,0 +1,28 @@
+use serde::Serialize;
+
+/// Running trust score of a model: +1 per valid proof, -0.5 per invalid one.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustScore {
+    pub model_id: String,
+    pub valid_proofs: u64,
+    pub invalid_proofs: u64,
+    pub score: f64,
+}
+
+impl TrustScore {
+    pub fn new(model_id: &str) -> Self {
+        Self { model_id: model_id.to_string(), ..Self::default() }
+    }
+
+    pub fn record(&mut self, valid: bool) -> f64 {
+        if valid {
+            self.valid_proofs += 1;
+            self.score += 1.0;
+        } else {
+            self.invalid_proofs += 1;
+            self.score -= 0.5;
+        }
+        self.score
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::Mutex;
+
+use anyhow::Context;
+use vericortex_indexer::{Indexer, IndexerConfig, Store};
+use vericortex_sdk::adapter::SignedAttestation;
+use vericortex_sdk::AdapterRegistry;
+
+use crate::config::Config;
+use crate::contract::VerifierClient;
+use crate::error::ApiError;
+use crate::services::RegistryClient;
+
+pub struct AppState {
+    pub cfg: Config,
+    pub verifier: VerifierClient,
+    pub registry: RegistryClient,
+    /// Decides `valid` for every submission.
+    pub adapters: AdapterRegistry,
+    /// Read connection to the index; the indexer task writes through its own.
+    pub index: Mutex<Store>,
+}
+
+impl AppState {
+    /// Connect to the contract and start indexing its events in the background.
+    pub async fn new(cfg: &Config) -> anyhow::Result<Self> {
+        let verifier = VerifierClient::new(cfg).await?;
+        let registry = RegistryClient::open(&cfg.index_db)?;
+
+        let mut index_cfg = IndexerConfig::new(cfg.chain.rpc_url.clone(), cfg.chain.contract_address);
+        index_cfg.start_block = cfg.start_block;
+        index_cfg.confirmations = cfg.confirmations;
+        index_cfg.poll_interval = cfg.index_poll_interval;
+        let writer = Store::open(&cfg.index_db).with_context(|| format!("opening {}", cfg.index_db.display()))?;
+        let indexer = Indexer::new(index_cfg, writer)?;
+        tokio::spawn(indexer.run());
+        let index = Mutex::new(Store::open(&cfg.index_db)?);
+
+        let mut adapters = AdapterRegistry::default();
+        if !cfg.attesters.is_empty() {
+            adapters.register(SignedAttestation::new(cfg.attesters.iter().copied()));
+        }
+
+        Ok(Self { cfg: cfg.clone(), verifier, registry, adapters, index })
+    }
+
+    /// Run a query against the event index.
+    pub fn query<T>(&self, f: impl FnOnce(&Store) -> anyhow::Result<T>) -> Result<T, ApiError> {
+        let store = self.index.lock().map_err(|_| ApiError::Index(anyhow::anyhow!("index lock poisoned")))?;
+        f(&store).map_err(ApiError::Index)
+    }
+}
@@ -0,0 +1,311 @@
+//! HTTP integration tests against an in-process dev chain.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use proofcortex_api::services::RegistryClient;
+use proofcortex_api::{router, AppState, Config};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use vericortex_devchain::{dev_address, DevChain, DEV_KEYS};
+use ethers::core::k256::ecdsa::SigningKey;
+use vericortex_sdk::adapter::{Attestation, SignedAttestation};
+use vericortex_sdk::signer::SignerConfig;
+use vericortex_sdk::{Hash32, ModelId, ProofJson};
+use vericortex_submitter::{ChainConfig, FeePolicy, Outbox, TxState};
+
+const INPUT: &str = "0x00000000000000000000000000000000000000000000000000000000000000aa";
+/// `compute_recomputed_output_hash("vericortex-demo", INPUT)`.
+const OUTPUT: &str = "0xc986fdfa018a412046a2f5bb104370d3864fafdea4f40438ad646e14d3f4deab";
+const OTHER: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+
+/// The one signer the harness trusts for `signed-attestation` proofs.
+fn attester() -> SigningKey {
+    SigningKey::from_slice(&[7; 32]).unwrap()
+}
+
+struct Harness {
+    base: String,
+    http: reqwest::Client,
+    dir: PathBuf,
+    _chain: DevChain,
+}
+
+/// A fresh directory for one harness's index database.
+fn temp_dir() -> PathBuf {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    let dir = std::env::temp_dir().join(format!("proofcortex-api-{}-{n}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// No spending limits.
+fn unlimited() -> FeePolicy {
+    FeePolicy { max_fee_per_tx: None, daily_budget: None, bump_after: Duration::from_secs(60), bump_percent: 20 }
+}
+
+impl Harness {
+    async fn start() -> Self {
+        Self::with_fees(unlimited()).await
+    }
+
+    async fn with_fees(fees: FeePolicy) -> Self {
+        let chain = DevChain::spawn().unwrap();
+        let verifier = chain.deploy_verifier(dev_address(0));
+        let dir = temp_dir();
+        let cfg = Config {
+            chain: ChainConfig {
+                rpc_url: chain.url(),
+                signer: SignerConfig::PrivateKey(DEV_KEYS[0].to_string()),
+                contract_address: verifier,
+                chain_id: chain.chain_id(),
+                outbox: dir.join("outbox.sqlite"),
+                confirmations: 1,
+                tx_timeout: Duration::from_secs(30),
+                fees,
+            },
+            start_block: 0,
+            port: 0,
+            index_db: dir.join("index.sqlite"),
+            confirmations: 0,
+            index_poll_interval: Duration::from_millis(20),
+            attesters: vec![Attestation::sign(&ProofJson::new("", Hash32::default(), Hash32::default()), &attester()).signer],
+        };
+        let state = Arc::new(AppState::new(&cfg).await.unwrap());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router(state)).await.unwrap() });
+        Self { base, http: reqwest::Client::new(), dir, _chain: chain }
+    }
+
+    /// Wait until the background indexer has caught up with `total` proofs.
+    async fn wait_indexed(&self, total: u64) {
+        for _ in 0..250 {
+            if self.get("/stats").await.1["totalProofs"] == total {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("index never reached {total} proofs");
+    }
+
+    async fn get(&self, path: &str) -> (StatusCode, Value) {
+        let resp = self.http.get(format!("{}{path}", self.base)).send().await.unwrap();
+        (resp.status(), resp.json().await.unwrap())
+    }
+
+    async fn post(&self, path: &str, body: Value) -> (StatusCode, Value) {
+        let resp = self.http.post(format!("{}{path}", self.base)).json(&body).send().await.unwrap();
+        (resp.status(), resp.json().await.unwrap())
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn submitted_proof_is_readable_listed_and_counted() {
+    let api = Harness::start().await;
+    let resp = api.http.get(format!("{}/health", api.base)).send().await.unwrap();
+    assert_eq!(resp.text().await.unwrap(), "OK");
+
+    let (status, submitted) = api
+        .post(
+            "/proofs/submit",
+            json!({ "modelId": "vericortex-demo", "modelVersion": "v0.1.0", "inputHash": INPUT, "outputHash": OUTPUT }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{submitted}");
+    assert_eq!(submitted["valid"], true);
+    let proof_id = submitted["proofId"].as_str().unwrap();
+    let submitter = format!("{:?}", dev_address(0));
+    assert_eq!(submitted["submitter"], submitter);
+
+    let (status, proof) = api.get(&format!("/proofs/{proof_id}")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(proof["modelId"], "vericortex-demo");
+    assert_eq!(proof["version"], "v0.1.0");
+    assert_eq!(proof["inputHash"], INPUT);
+    assert_eq!(proof["outputHash"], OUTPUT);
+    assert_eq!(proof["proofHash"], submitted["proofHash"]);
+    assert_eq!(proof["submitter"], submitter);
+    assert_eq!(proof["valid"], true);
+
+    assert_eq!(api.get(&format!("/proofs/{proof_id}/valid")).await.1, json!({ "valid": true }));
+
+    api.wait_indexed(1).await;
+    let (_, listed) = api.get(&format!("/proofs/submitter/{submitter}")).await;
+    assert_eq!(listed["proofs"], json!([{ "proofId": proof_id, "modelId": "vericortex-demo", "valid": true }]));
+    let (_, other) = api.get(&format!("/proofs/submitter/{:?}", dev_address(1))).await;
+    assert_eq!(other["proofs"], json!([]));
+
+    let (status, bad) = api
+        .post("/proofs/submit", json!({ "modelId": "vericortex-demo", "inputHash": INPUT, "outputHash": OTHER }))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(bad["valid"], false);
+
+    api.wait_indexed(2).await;
+    let (_, stats) = api.get("/stats").await;
+    assert_eq!(stats, json!({ "totalProofs": 2, "validProofs": 1, "invalidProofs": 1, "uniqueSubmitters": 1 }));
+
+    let (_, trust) = api.get("/trust/vericortex-demo").await;
+    assert_eq!(trust["validProofs"], 1);
+    assert_eq!(trust["invalidProofs"], 1);
+    assert_eq!(trust["score"], 0.5);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn attested_submissions_are_checked_against_trusted_signers() {
+    let api = Harness::start().await;
+    // The claimed output is not the hash recompute, but a trusted attester vouches for it.
+    let mut proof = ProofJson::new("attested", INPUT.parse::<Hash32>().unwrap(), OTHER.parse::<Hash32>().unwrap());
+    proof.proof_type = Some(SignedAttestation::PROOF_TYPE.into());
+    let body = |proof: &ProofJson, key: &SigningKey| {
+        json!({ "modelId": "attested", "modelVersion": proof.model_version, "inputHash": INPUT, "outputHash": OTHER,
+                "proofType": "signed-attestation", "evidence": Attestation::sign(proof, key) })
+    };
+
+    let (status, submitted) = api.post("/proofs/submit", body(&proof, &attester())).await;
+    assert_eq!(status, StatusCode::OK, "{submitted}");
+    assert_eq!(submitted["valid"], true);
+    assert!(submitted["reason"].as_str().unwrap().starts_with("attested by"), "{submitted}");
+
+    // Replaying that signature with other hashes is not attested, and cannot
+    // be pinned to the signed bundle hash through proofHash.
+    let mut replay = body(&proof, &attester());
+    replay["modelVersion"] = json!("v3");
+    replay["outputHash"] = json!(INPUT);
+    let (status, submitted) = api.post("/proofs/submit", replay.clone()).await;
+    assert_eq!(status, StatusCode::OK, "{submitted}");
+    assert_eq!(submitted["valid"], false);
+    replay["modelVersion"] = json!("v4");
+    replay["proofHash"] = json!(proof.bundle_hash());
+    assert_eq!(api.post("/proofs/submit", replay).await.0, StatusCode::BAD_REQUEST);
+
+    // A new version, so a new proof hash, signed by someone untrusted.
+    proof.model_version = "v2".into();
+    let (status, submitted) = api.post("/proofs/submit", body(&proof, &SigningKey::from_slice(&[9; 32]).unwrap())).await;
+    assert_eq!(status, StatusCode::OK, "{submitted}");
+    assert_eq!(submitted["valid"], false);
+
+    let (status, err) = api
+        .post("/proofs/submit", json!({ "modelId": "m", "inputHash": INPUT, "outputHash": OTHER, "proofType": "groth16" }))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(err["message"].as_str().unwrap().contains("groth16"), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn duplicate_submission_is_a_conflict() {
+    let api = Harness::start().await;
+    let body = json!({ "modelId": "m", "inputHash": INPUT, "outputHash": OTHER });
+
+    assert_eq!(api.post("/proofs/submit", body.clone()).await.0, StatusCode::OK);
+    let (status, err) = api.post("/proofs/submit", body).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(err, json!({ "error": "reverted", "message": "Proof exists" }));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn submissions_go_through_the_outbox_and_its_fee_limits() {
+    let api = Harness::start().await;
+    let (status, submitted) =
+        api.post("/proofs/submit", json!({ "modelId": "m", "inputHash": INPUT, "outputHash": OTHER })).await;
+    assert_eq!(status, StatusCode::OK, "{submitted}");
+    let entries = Outbox::open(api.dir.join("outbox.sqlite")).unwrap().list().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].state, TxState::Confirmed);
+    assert_eq!(json!(entries[0].proof_id), submitted["proofId"]);
+    assert_eq!(json!(entries[0].tx_hash), submitted["txHash"]);
+
+    // MAX_FEE_PER_TX holds here as it does for the prover.
+    let capped = Harness::with_fees(FeePolicy { max_fee_per_tx: Some(1.into()), ..unlimited() }).await;
+    let (status, err) =
+        capped.post("/proofs/submit", json!({ "modelId": "m", "inputHash": INPUT, "outputHash": OTHER })).await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert!(err["message"].as_str().unwrap().contains("MAX_FEE_PER_TX"), "{err}");
+    let entries = Outbox::open(capped.dir.join("outbox.sqlite")).unwrap().list().unwrap();
+    assert_eq!(entries[0].state, TxState::Failed);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn malformed_and_unknown_ids_are_rejected() {
+    let api = Harness::start().await;
+
+    let (status, err) = api.get("/proofs/0x1234").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(err["error"], "malformed_input");
+
+    let (status, err) = api.get(&format!("/proofs/{OTHER}")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(err["error"], "not_found");
+    assert_eq!(api.get(&format!("/proofs/{OTHER}/valid")).await.1, json!({ "valid": false }));
+
+    let (status, err) = api.post("/proofs/submit", json!({ "modelId": "m", "inputHash": "0xaa", "outputHash": OUTPUT })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(err["error"], "malformed_input");
+
+    // Validity is decided by the adapters, never stated by the caller.
+    for body in [
+        json!({ "modelId": "m", "proofHash": OTHER }),
+        json!({ "modelId": "m", "proofHash": OTHER, "valid": true }),
+        json!({ "modelId": "m", "inputHash": INPUT, "outputHash": OTHER, "valid": true }),
+        json!({ "modelId": "m", "inputHash": INPUT, "outputHash": OTHER, "proofHash": OTHER }),
+    ] {
+        assert_eq!(api.post("/proofs/submit", body).await.0, StatusCode::BAD_REQUEST);
+    }
+    let (status, _) = api.get("/proofs/submitter/not-an-address").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn models_can_be_registered_and_fetched() {
+    let api = Harness::start().await;
+
+    let (status, model) = api
+        .post(
+            "/models/register",
+            json!({ "modelId": "vericortex-demo", "name": "llama", "owner": "0xabc", "description": "demo" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{model}");
+    assert_eq!(model["modelId"], "vericortex-demo");
+
+    let (status, fetched) = api.get("/models/vericortex-demo").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched, model);
+
+    // The on-chain modelId is the key: it can be registered once.
+    let (status, err) = api.post("/models/register", json!({ "modelId": "vericortex-demo", "name": "x", "owner": "0xdef" })).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(err["error"], "conflict");
+    assert_eq!(api.get("/models/vericortex-demo").await.1, model);
+
+    assert_eq!(api.get("/models/missing").await.0, StatusCode::NOT_FOUND);
+    assert_eq!(api.post("/models/register", json!({ "name": "llama", "owner": "0xabc" })).await.0, StatusCode::BAD_REQUEST);
+    assert_eq!(api.post("/models/register", json!({ "modelId": " ", "name": "llama", "owner": "0xabc" })).await.0, StatusCode::BAD_REQUEST);
+
+    // An entry can carry the artifact fingerprint, which is returned as given.
+    let fingerprint = json!({ "format": "safetensors", "alg": "blake3", "root": OUTPUT, "leaves": 291 });
+    let (status, _) = api
+        .post("/models/register", json!({ "modelId": "llama-3", "name": "llama", "owner": "0xabc", "fingerprint": fingerprint }))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, fetched) = api.get("/models/llama-3").await;
+    assert_eq!(fetched["fingerprint"], fingerprint);
+    let bad = json!({ "modelId": "m", "name": "llama", "owner": "0xabc", "fingerprint": { "format": "pickle", "alg": "blake3", "root": OUTPUT, "leaves": 1 } });
+    assert_eq!(api.post("/models/register", bad).await.0, StatusCode::BAD_REQUEST);
+
+    // Registrations are stored in the database, not the server process.
+    let reopened = RegistryClient::open(api.dir.join("index.sqlite")).unwrap();
+    let stored = reopened.get(&ModelId::from("llama-3")).unwrap().unwrap();
+    assert_eq!(json!(stored), fetched);
+}
@@ -1,8 +1,32 @@
 //! This crate contains all shared fullstack server functions.
 use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
 
 /// Echo the user input on the server.
 #[post("/api/echo")]
 pub async fn echo(input: String) -> Result<String, ServerFnError> {
     Ok(input)
 }
+
+/// The network the dashboard is pointed at, from the shared `vericortex.toml`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NetworkInfo {
+    pub profile: String,
+    pub chain_id: Option<u64>,
+    pub verifier_contract: Option<String>,
+    /// ProofCortex API the dashboard reads proofs from.
+    pub api_url: String,
+}
+
+/// Load the configuration on the server (`VERICORTEX_CONFIG`, `VERICORTEX_PROFILE`
+/// and the usual environment overrides apply) and report the selected network.
+#[get("/api/network")]
+pub async fn network_info() -> Result<NetworkInfo, ServerFnError> {
+    let config = vericortex_config::Config::load(&Default::default()).map_err(|e| ServerFnError::new(e.to_string()))?;
+    Ok(NetworkInfo {
+        profile: config.profile,
+        chain_id: config.network.chain_id,
+        verifier_contract: config.network.verifier_contract.map(|a| a.to_string()),
+        api_url: config.dashboard.api_url,
+    })
+}
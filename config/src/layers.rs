@@ -0,0 +1,179 @@
+//! Building the merged TOML table for one profile.
+
+use std::path::Path;
+
+use toml::{Table, Value};
+
+use crate::{ConfigError, Sources, DEFAULT_PROFILE, PROFILES};
+
+/// How an environment variable's value is read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Str,
+    Int,
+}
+
+/// Environment variables and the setting each one overrides. Later entries
+/// win, so an alias comes before the name it stands in for.
+pub const ENV_VARS: &[(&str, &str, Kind)] = &[
+    ("RPC_URL", "network.rpc_url", Kind::Str),
+    ("CHAIN_ID", "network.chain_id", Kind::Int),
+    ("CONTRACT_ADDRESS", "network.verifier_contract", Kind::Str),
+    ("VERIFIER_CONTRACT_ADDRESS", "network.verifier_contract", Kind::Str),
+    ("START_BLOCK", "network.start_block", Kind::Int),
+    ("CONFIRMATIONS", "network.confirmations", Kind::Int),
+    ("SIGNER_BACKEND", "signer.backend", Kind::Str),
+    ("KEYSTORE_PATH", "signer.keystore_path", Kind::Str),
+    ("KEYSTORE_PASSWORD_FILE", "signer.password_file", Kind::Str),
+    ("REMOTE_SIGNER_URL", "signer.remote_url", Kind::Str),
+    ("REMOTE_SIGNER_ADDRESS", "signer.remote_address", Kind::Str),
+    ("PKCS11_MODULE", "signer.pkcs11_module", Kind::Str),
+    ("PKCS11_TOKEN_LABEL", "signer.pkcs11_token_label", Kind::Str),
+    ("PKCS11_KEY_LABEL", "signer.pkcs11_key_label", Kind::Str),
+    ("PKCS11_PIN_FILE", "signer.pkcs11_pin_file", Kind::Str),
+    ("OUTBOX_DB", "prover.outbox", Kind::Str),
+    ("TX_TIMEOUT_SECS", "prover.tx_timeout_secs", Kind::Int),
+    ("MAX_FEE_PER_TX", "prover.max_fee_per_tx", Kind::Str),
+    ("DAILY_FEE_BUDGET", "prover.daily_fee_budget", Kind::Str),
+    ("FEE_BUMP_SECS", "prover.fee_bump_secs", Kind::Int),
+    ("FEE_BUMP_PERCENT", "prover.fee_bump_percent", Kind::Int),
+    ("PORT", "api.port", Kind::Int),
+    ("INDEX_DB", "api.index_db", Kind::Str),
+    ("INDEX_POLL_MS", "api.index_poll_ms", Kind::Int),
+    ("DASHBOARD_API_URL", "dashboard.api_url", Kind::Str),
+];
+
+/// Raw submitter key variables, in order of preference.
+const KEY_VARS: [&str; 2] = ["SUBMITTER_PRIVATE_KEY", "PRIVATE_KEY"];
+
+fn lookup<'a>(env: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    env.iter().rev().find(|(k, _)| k == name).map(|(_, v)| v.trim()).filter(|v| !v.is_empty())
+}
+
+pub(crate) fn private_key(env: &[(String, String)]) -> Option<String> {
+    KEY_VARS.iter().find_map(|name| lookup(env, name)).map(str::to_string)
+}
+
+pub(crate) struct Layered {
+    pub profile: String,
+    pub table: Table,
+}
+
+/// Settings a profile has before the file is read.
+fn preset(profile: &str) -> Table {
+    let mut table = Table::new();
+    if profile == DEFAULT_PROFILE {
+        set(&mut table, "network.rpc_url", Value::String("http://127.0.0.1:8545".into()));
+        set(&mut table, "network.chain_id", Value::Integer(1337));
+    }
+    table
+}
+
+/// Merge `over` into `base`; tables merge key by key, anything else replaces.
+fn merge(base: &mut Table, over: Table) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(inner)), Value::Table(value)) => merge(inner, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Set a dotted key, creating tables on the way.
+fn set(table: &mut Table, key: &str, value: Value) {
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let last = parts.pop().unwrap_or_default();
+    let mut nested = Table::new();
+    nested.insert(last.to_string(), value);
+    for part in parts.into_iter().rev() {
+        let mut outer = Table::new();
+        outer.insert(part.to_string(), Value::Table(nested));
+        nested = outer;
+    }
+    merge(table, nested);
+}
+
+/// A `--set` value: a TOML literal (`42`, `true`, `"x"`), else the raw text.
+fn literal(raw: &str) -> Value {
+    format!("v = {raw}")
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut t| t.remove("v"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+struct File {
+    default_profile: Option<String>,
+    profiles: Table,
+    shared: Table,
+}
+
+fn read_file(path: &Path) -> Result<File, ConfigError> {
+    let text = std::fs::read_to_string(path).map_err(|error| ConfigError::Read { path: path.into(), error })?;
+    let parse = |message: String| ConfigError::Parse { path: path.into(), message };
+    let mut shared: Table = text.parse().map_err(|e: toml::de::Error| parse(e.message().to_string()))?;
+    let default_profile = match shared.remove("default_profile") {
+        None => None,
+        Some(Value::String(name)) => Some(name),
+        Some(_) => return Err(parse("default_profile must be a string".into())),
+    };
+    let profiles = match shared.remove("profiles") {
+        None => Table::new(),
+        Some(Value::Table(profiles)) => profiles,
+        Some(_) => return Err(parse("profiles must be a table of [profiles.<name>] sections".into())),
+    };
+    if let Some((name, _)) = profiles.iter().find(|(_, p)| !p.is_table()) {
+        return Err(parse(format!("profiles.{name} must be a table")));
+    }
+    Ok(File { default_profile, profiles, shared })
+}
+
+fn env_layer(env: &[(String, String)]) -> Result<Table, ConfigError> {
+    let mut table = Table::new();
+    for (var, key, kind) in ENV_VARS {
+        let Some(raw) = lookup(env, var) else { continue };
+        let value = match kind {
+            Kind::Str => Value::String(raw.to_string()),
+            Kind::Int => Value::Integer(raw.parse().map_err(|_| ConfigError::Env {
+                var: var.to_string(),
+                message: format!("{raw:?} is not an integer"),
+            })?),
+        };
+        set(&mut table, key, value);
+    }
+    Ok(table)
+}
+
+pub(crate) fn build(sources: &Sources) -> Result<Layered, ConfigError> {
+    let file = sources.file.as_deref().map(read_file).transpose()?;
+    let profile = sources
+        .profile
+        .clone()
+        .or_else(|| file.as_ref().and_then(|f| f.default_profile.clone()))
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
+    let defined = file.as_ref().map(|f| f.profiles.keys().cloned().collect::<Vec<_>>()).unwrap_or_default();
+    if !PROFILES.contains(&profile.as_str()) && !defined.contains(&profile) {
+        let mut known: Vec<String> = PROFILES.iter().map(|p| p.to_string()).collect();
+        known.extend(defined.into_iter().filter(|p| !PROFILES.contains(&p.as_str())));
+        return Err(ConfigError::UnknownProfile { name: profile, known: known.join(", ") });
+    }
+
+    let mut table = preset(&profile);
+    if let Some(mut file) = file {
+        merge(&mut table, file.shared);
+        if let Some(Value::Table(selected)) = file.profiles.remove(&profile) {
+            merge(&mut table, selected);
+        }
+    }
+    merge(&mut table, env_layer(&sources.env)?);
+    for (key, raw) in &sources.overrides {
+        if key.split('.').any(str::is_empty) {
+            return Err(ConfigError::Override(format!("{key}={raw}")));
+        }
+        set(&mut table, key, literal(raw));
+    }
+    Ok(Layered { profile, table })
+}
@@ -0,0 +1,473 @@
+//! Shared configuration for the prover, API server, indexer and dashboard.
+//!
+//! Settings come from one TOML file with named network profiles, layered
+//! from lowest to highest precedence:
+//!
+//! 1. built-in defaults (and the `local` profile's dev chain endpoint)
+//! 2. top-level sections of the file, shared by every profile
+//! 3. `[profiles.<name>]` of the selected profile
+//! 4. environment variables (the names in [`ENV_VARS`])
+//! 5. `--set section.key=value` on the command line
+//!
+//! The merged result is validated once, when it is loaded; components then
+//! ask for the parts they need ([`Config::network`], [`Config::signer`]),
+//! which fail with every missing setting named.
+
+mod layers;
+
+use std::fmt;
+use std::path::PathBuf;
+
+use clap::Args;
+use serde::Deserialize;
+use vericortex_sdk::signer::{Passphrase, SignerConfig};
+use vericortex_sdk::Address;
+
+pub use layers::{Kind, ENV_VARS};
+
+/// File looked up in the working directory and its parents.
+pub const DEFAULT_FILE: &str = "vericortex.toml";
+/// Profiles every deployment knows, whether or not the file defines them.
+pub const PROFILES: [&str; 3] = ["local", "blockdag-testnet", "blockdag-mainnet"];
+pub const DEFAULT_PROFILE: &str = "local";
+/// Chain ids of local development chains (ganache/geth dev and the devchain,
+/// anvil/hardhat), the only ones a raw submitter key is accepted on unless
+/// `signer.allow_raw_key` is set.
+pub const DEV_CHAIN_IDS: [u64; 2] = [1337, 31337];
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("cannot read config file {path}: {error}")]
+    Read { path: PathBuf, error: std::io::Error },
+
+    #[error("config file {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+
+    #[error("unknown profile {name:?} (known: {known})")]
+    UnknownProfile { name: String, known: String },
+
+    #[error("{var}: {message}")]
+    Env { var: String, message: String },
+
+    #[error("--set {0:?}: expected section.key=value")]
+    Override(String),
+
+    #[error("invalid configuration for profile {profile}: {}", .problems.join("; "))]
+    Invalid { profile: String, problems: Vec<String> },
+}
+
+/// `--config`, `--profile` and `--set`, for flattening into a CLI.
+#[derive(Args, Clone, Debug, Default)]
+pub struct ConfigArgs {
+    /// Configuration file (default: VERICORTEX_CONFIG, else vericortex.toml in this or a parent directory)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    /// Network profile: local, blockdag-testnet, blockdag-mainnet or one defined in the file
+    /// (default: VERICORTEX_PROFILE, else the file's default_profile, else local)
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+    /// Override one setting, e.g. `--set network.rpc_url=http://127.0.0.1:8545` (repeatable)
+    #[arg(long = "set", global = true, value_name = "KEY=VALUE")]
+    pub overrides: Vec<String>,
+}
+
+/// Everything a configuration is built from. [`Sources::from_args`] reads the
+/// process; tests fill it in directly.
+#[derive(Clone, Debug, Default)]
+pub struct Sources {
+    pub file: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub env: Vec<(String, String)>,
+    /// `(dotted key, raw value)`; the value is read as a TOML literal, else as a string.
+    pub overrides: Vec<(String, String)>,
+}
+
+impl Sources {
+    pub fn from_args(args: &ConfigArgs) -> Result<Self, ConfigError> {
+        let env: Vec<(String, String)> = std::env::vars().collect();
+        let lookup = |name: &str| env.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone()).filter(|v| !v.is_empty());
+        let file = args.config.clone().or_else(|| lookup("VERICORTEX_CONFIG").map(PathBuf::from)).or_else(find_file);
+        let profile = args.profile.clone().or_else(|| lookup("VERICORTEX_PROFILE"));
+        let overrides = args
+            .overrides
+            .iter()
+            .map(|o| match o.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.to_string())),
+                _ => Err(ConfigError::Override(o.clone())),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { file, profile, env, overrides })
+    }
+
+    /// Add an override, for component flags such as `--rpc-url`.
+    pub fn set(mut self, key: &str, value: impl fmt::Display) -> Self {
+        self.overrides.push((key.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// `vericortex.toml` in the working directory or the nearest parent holding one.
+fn find_file() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors().map(|dir| dir.join(DEFAULT_FILE)).find(|path| path.is_file())
+}
+
+/// A value kept out of `Debug` output.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(..)")
+    }
+}
+
+/// Chain endpoint and contract.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkSection {
+    pub rpc_url: Option<String>,
+    pub chain_id: Option<u64>,
+    pub verifier_contract: Option<Address>,
+    /// The contract's deployment block; event indexing starts here.
+    pub start_block: u64,
+    /// Blocks after which a transaction or event counts as final. Components
+    /// have their own default when unset (prover 1, indexers 12).
+    pub confirmations: Option<u64>,
+}
+
+/// A complete [`NetworkSection`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Network {
+    pub rpc_url: String,
+    pub chain_id: u64,
+    pub verifier_contract: Address,
+    pub start_block: u64,
+    pub confirmations: Option<u64>,
+}
+
+/// Where the submitter key lives. Passphrases and PINs are only ever read
+/// from files or prompts; a raw key only from the environment.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignerSection {
+    /// `key`, `keystore`, `remote` or `pkcs11`; inferred when unset.
+    pub backend: Option<String>,
+    pub keystore_path: Option<PathBuf>,
+    pub password_file: Option<PathBuf>,
+    pub remote_url: Option<String>,
+    pub remote_address: Option<Address>,
+    pub pkcs11_module: Option<PathBuf>,
+    pub pkcs11_token_label: Option<String>,
+    pub pkcs11_key_label: Option<String>,
+    pub pkcs11_pin_file: Option<PathBuf>,
+    /// Accept a raw key on chains outside [`DEV_CHAIN_IDS`].
+    pub allow_raw_key: bool,
+    /// `SUBMITTER_PRIVATE_KEY` / `PRIVATE_KEY`; never read from the file.
+    #[serde(skip)]
+    pub private_key: Option<Secret>,
+}
+
+impl SignerSection {
+    fn backend(&self) -> Option<&str> {
+        match &self.backend {
+            Some(backend) => Some(backend),
+            None if self.private_key.is_some() => Some("key"),
+            None if self.keystore_path.is_some() => Some("keystore"),
+            None => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProverSection {
+    /// SQLite transaction outbox.
+    pub outbox: PathBuf,
+    pub tx_timeout_secs: u64,
+    /// Native token units, e.g. `"0.01"`.
+    pub max_fee_per_tx: Option<String>,
+    pub daily_fee_budget: Option<String>,
+    pub fee_bump_secs: u64,
+    pub fee_bump_percent: u64,
+}
+
+impl Default for ProverSection {
+    fn default() -> Self {
+        Self {
+            outbox: "prover-outbox.sqlite".into(),
+            tx_timeout_secs: 300,
+            max_fee_per_tx: None,
+            daily_fee_budget: None,
+            fee_bump_secs: 60,
+            fee_bump_percent: 20,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiSection {
+    pub port: u16,
+    /// SQLite event index behind listings, stats and trust scores.
+    pub index_db: PathBuf,
+    pub index_poll_ms: u64,
+    /// Signers whose `signed-attestation` proofs `/proofs/submit` accepts.
+    pub attesters: Vec<Address>,
+}
+
+impl Default for ApiSection {
+    fn default() -> Self {
+        Self { port: 8080, index_db: "proofcortex-index.sqlite".into(), index_poll_ms: 2000, attesters: Vec::new() }
+    }
+}
+
+/// The standalone `vericortex-indexer`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IndexerSection {
+    pub db: PathBuf,
+    pub poll_interval_ms: u64,
+    /// Largest block range per `eth_getLogs`.
+    pub max_range: u64,
+}
+
+impl Default for IndexerSection {
+    fn default() -> Self {
+        Self { db: "vericortex-index.sqlite".into(), poll_interval_ms: 2000, max_range: 2_000 }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DashboardSection {
+    /// Base URL of the ProofCortex API the dashboard talks to.
+    pub api_url: String,
+}
+
+impl Default for DashboardSection {
+    fn default() -> Self {
+        Self { api_url: "http://127.0.0.1:8080".into() }
+    }
+}
+
+/// The sections of one profile after layering.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Sections {
+    network: NetworkSection,
+    signer: SignerSection,
+    prover: ProverSection,
+    api: ApiSection,
+    indexer: IndexerSection,
+    dashboard: DashboardSection,
+}
+
+/// A loaded and validated configuration.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub profile: String,
+    /// The file it was read from, if any.
+    pub file: Option<PathBuf>,
+    pub network: NetworkSection,
+    pub signer: SignerSection,
+    pub prover: ProverSection,
+    pub api: ApiSection,
+    pub indexer: IndexerSection,
+    pub dashboard: DashboardSection,
+}
+
+fn is_url(value: &str, schemes: &[&str]) -> bool {
+    schemes.iter().any(|s| value.strip_prefix(s).and_then(|rest| rest.strip_prefix("://")).is_some_and(|h| !h.is_empty()))
+}
+
+/// `"0.05"`-style amounts.
+fn is_amount(value: &str) -> bool {
+    let mut parts = value.splitn(2, '.');
+    let whole = parts.next().unwrap_or_default();
+    let fraction = parts.next().unwrap_or("0");
+    !value.is_empty()
+        && value != "."
+        && whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+        && fraction.len() <= 18
+}
+
+impl Config {
+    /// Load from the process environment and `args`.
+    pub fn load(args: &ConfigArgs) -> Result<Self, ConfigError> {
+        Self::from_sources(Sources::from_args(args)?)
+    }
+
+    pub fn from_sources(sources: Sources) -> Result<Self, ConfigError> {
+        let layered = layers::build(&sources)?;
+        let sections: Sections = toml::Value::Table(layered.table)
+            .try_into()
+            .map_err(|e: toml::de::Error| ConfigError::Invalid { profile: layered.profile.clone(), problems: vec![e.message().to_string()] })?;
+        let mut signer = sections.signer;
+        signer.private_key = layers::private_key(&sources.env).map(Secret);
+
+        let config = Self {
+            profile: layered.profile,
+            file: sources.file,
+            network: sections.network,
+            signer,
+            prover: sections.prover,
+            api: sections.api,
+            indexer: sections.indexer,
+            dashboard: sections.dashboard,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn invalid(&self, problems: Vec<String>) -> ConfigError {
+        ConfigError::Invalid { profile: self.profile.clone(), problems }
+    }
+
+    /// Check every value that is set; completeness is checked by the accessors.
+    fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
+        let network = &self.network;
+        if let Some(url) = &network.rpc_url {
+            if !is_url(url, &["http", "https", "ws", "wss"]) {
+                problems.push(format!("network.rpc_url {url:?} is not an http(s) or ws(s) URL"));
+            }
+        }
+        if network.chain_id == Some(0) {
+            problems.push("network.chain_id must not be 0".into());
+        }
+        if network.verifier_contract == Some(Address([0; 20])) {
+            problems.push("network.verifier_contract is the zero address".into());
+        }
+
+        let prover = &self.prover;
+        for (key, amount) in [("max_fee_per_tx", &prover.max_fee_per_tx), ("daily_fee_budget", &prover.daily_fee_budget)] {
+            if let Some(amount) = amount {
+                if !is_amount(amount) {
+                    problems.push(format!("prover.{key} {amount:?} is not an amount like \"0.05\""));
+                }
+            }
+        }
+        if prover.fee_bump_percent < 10 {
+            problems.push("prover.fee_bump_percent must be at least 10 (nodes refuse smaller replacements)".into());
+        }
+        if prover.tx_timeout_secs == 0 {
+            problems.push("prover.tx_timeout_secs must not be 0".into());
+        }
+        if self.api.index_poll_ms == 0 || self.indexer.poll_interval_ms == 0 {
+            problems.push("poll intervals must not be 0".into());
+        }
+        if self.indexer.max_range == 0 {
+            problems.push("indexer.max_range must not be 0".into());
+        }
+        if !is_url(&self.dashboard.api_url, &["http", "https"]) {
+            problems.push(format!("dashboard.api_url {:?} is not an http(s) URL", self.dashboard.api_url));
+        }
+
+        let signer = &self.signer;
+        if signer.private_key.is_some() {
+            let others = [
+                ("signer.keystore_path", signer.keystore_path.is_some()),
+                ("signer.remote_url", signer.remote_url.is_some()),
+                ("signer.pkcs11_module", signer.pkcs11_module.is_some()),
+            ];
+            for (key, _) in others.into_iter().filter(|(_, set)| *set) {
+                problems.push(format!("both SUBMITTER_PRIVATE_KEY and {key} are set; unset one"));
+            }
+            if let Some(backend) = signer.backend.as_deref().filter(|backend| *backend != "key") {
+                problems.push(format!("SUBMITTER_PRIVATE_KEY is set but signer.backend is {backend:?}; unset one"));
+            }
+        }
+        match signer.backend() {
+            None | Some("keystore" | "remote" | "pkcs11") => {}
+            Some("key") if !signer.allow_raw_key && !network.chain_id.is_some_and(|id| DEV_CHAIN_IDS.contains(&id)) => {
+                problems.push(format!(
+                    "raw private keys are only allowed on dev chains ({}), not on {}; use a keystore, remote or \
+                     pkcs11 signer, or set signer.allow_raw_key",
+                    DEV_CHAIN_IDS.map(|id| id.to_string()).join(", "),
+                    network.chain_id.map_or("an unset chain_id".into(), |id| format!("chain_id {id}")),
+                ));
+            }
+            Some("key") => {}
+            Some(other) => problems.push(format!("signer.backend {other:?} is not key, keystore, remote or pkcs11")),
+        }
+        if let Some(url) = &self.signer.remote_url {
+            if !is_url(url, &["http", "https"]) {
+                problems.push(format!("signer.remote_url {url:?} is not an http(s) URL"));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(self.invalid(problems))
+        }
+    }
+
+    /// The chain endpoint and contract, all of which must be set.
+    pub fn network(&self) -> Result<Network, ConfigError> {
+        let n = &self.network;
+        match (&n.rpc_url, n.chain_id, n.verifier_contract) {
+            (Some(rpc_url), Some(chain_id), Some(verifier_contract)) => Ok(Network {
+                rpc_url: rpc_url.clone(),
+                chain_id,
+                verifier_contract,
+                start_block: n.start_block,
+                confirmations: n.confirmations,
+            }),
+            _ => {
+                let missing = [
+                    (n.rpc_url.is_none(), "network.rpc_url (RPC_URL)"),
+                    (n.chain_id.is_none(), "network.chain_id (CHAIN_ID)"),
+                    (n.verifier_contract.is_none(), "network.verifier_contract (VERIFIER_CONTRACT_ADDRESS)"),
+                ];
+                Err(self.invalid(
+                    missing.into_iter().filter(|(absent, _)| *absent).map(|(_, key)| format!("{key} is not set")).collect(),
+                ))
+            }
+        }
+    }
+
+    /// The submitter signer, with every setting its backend needs.
+    pub fn signer(&self) -> Result<SignerConfig, ConfigError> {
+        let s = &self.signer;
+        let required = |value: Option<String>, key: &str| {
+            value.ok_or_else(|| self.invalid(vec![format!("signer.{key} is required by the {} signer", s.backend().unwrap_or(""))]))
+        };
+        let secret = |file: &Option<PathBuf>, prompt: &str| match file {
+            Some(path) => Passphrase::File(path.clone()),
+            None => Passphrase::Prompt(prompt.into()),
+        };
+        match s.backend() {
+            Some("key") => {
+                let key = s.private_key.as_ref().map(|k| k.expose().to_string());
+                Ok(SignerConfig::PrivateKey(key.ok_or_else(|| {
+                    self.invalid(vec!["the key signer needs SUBMITTER_PRIVATE_KEY in the environment".into()])
+                })?))
+            }
+            Some("keystore") => Ok(SignerConfig::Keystore {
+                path: required(s.keystore_path.as_ref().map(|p| p.display().to_string()), "keystore_path")?.into(),
+                passphrase: secret(&s.password_file, "Keystore passphrase: "),
+            }),
+            Some("remote") => Ok(SignerConfig::Remote {
+                url: required(s.remote_url.clone(), "remote_url")?,
+                address: s.remote_address.map(|a| a.0.into()),
+            }),
+            Some("pkcs11") => Ok(SignerConfig::Pkcs11 {
+                module: required(s.pkcs11_module.as_ref().map(|p| p.display().to_string()), "pkcs11_module")?.into(),
+                token_label: required(s.pkcs11_token_label.clone(), "pkcs11_token_label")?,
+                key_label: required(s.pkcs11_key_label.clone(), "pkcs11_key_label")?,
+                pin: secret(&s.pkcs11_pin_file, "PKCS#11 user PIN: "),
+            }),
+            _ => Err(self.invalid(vec![
+                "no signer configured: set signer.backend (SIGNER_BACKEND), KEYSTORE_PATH or SUBMITTER_PRIVATE_KEY".into(),
+            ])),
+        }
+    }
+}
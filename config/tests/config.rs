@@ -0,0 +1,188 @@
+//! Layering, profile selection and validation of the shared configuration.
+
+use std::path::PathBuf;
+
+use vericortex_config::{Config, ConfigError, Sources};
+use vericortex_sdk::signer::{Passphrase, SignerConfig};
+use vericortex_sdk::Address;
+
+const CONTRACT: &str = "0x5fbdb2315678afecb367f032d93f642f64180aa3";
+const EXAMPLE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../vericortex.example.toml");
+
+/// Write `body` to a file private to this test.
+fn file(name: &str, body: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("vericortex-config-{}-{name}.toml", std::process::id()));
+    std::fs::write(&path, body).unwrap();
+    path
+}
+
+fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+    vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+fn problems(err: ConfigError) -> Vec<String> {
+    match err {
+        ConfigError::Invalid { problems, .. } => problems,
+        other => panic!("expected Invalid, got {other}"),
+    }
+}
+
+const LAYERED: &str = r#"
+default_profile = "staging"
+
+[network]
+start_block = 10
+confirmations = 3
+
+[prover]
+fee_bump_secs = 30
+
+[profiles.staging.network]
+rpc_url = "https://staging.example/rpc"
+chain_id = 4242
+verifier_contract = "0x5fbdb2315678afecb367f032d93f642f64180aa3"
+
+[profiles.staging.prover]
+max_fee_per_tx = "0.05"
+"#;
+
+#[test]
+fn layers_apply_in_order() {
+    let path = file("layers", LAYERED);
+    let base = Sources { file: Some(path.clone()), ..Default::default() };
+
+    let config = Config::from_sources(base.clone()).unwrap();
+    assert_eq!(config.profile, "staging");
+    let network = config.network().unwrap();
+    assert_eq!(network.rpc_url, "https://staging.example/rpc");
+    assert_eq!(network.chain_id, 4242);
+    assert_eq!(network.verifier_contract, CONTRACT.parse::<Address>().unwrap());
+    assert_eq!((network.start_block, network.confirmations), (10, Some(3)));
+    assert_eq!(config.prover.fee_bump_secs, 30);
+    assert_eq!(config.prover.max_fee_per_tx.as_deref(), Some("0.05"));
+    assert_eq!(config.prover.tx_timeout_secs, 300);
+
+    // The environment beats the file, and --set beats the environment.
+    let sources = Sources { env: env(&[("CHAIN_ID", "5"), ("FEE_BUMP_SECS", "45")]), ..base }
+        .set("network.chain_id", 6)
+        .set("network.rpc_url", "http://127.0.0.1:9545");
+    let config = Config::from_sources(sources).unwrap();
+    assert_eq!(config.network.chain_id, Some(6));
+    assert_eq!(config.network.rpc_url.as_deref(), Some("http://127.0.0.1:9545"));
+    assert_eq!(config.prover.fee_bump_secs, 45);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn profiles_are_selected_and_checked() {
+    // Without a file the local dev chain is assumed.
+    let config = Config::from_sources(Sources::default()).unwrap();
+    assert_eq!(config.profile, "local");
+    assert_eq!(config.network.rpc_url.as_deref(), Some("http://127.0.0.1:8545"));
+    assert_eq!(config.network.chain_id, Some(1337));
+
+    let path = file("profiles", LAYERED);
+    let sources = Sources { file: Some(path.clone()), profile: Some("local".into()), ..Default::default() };
+    let config = Config::from_sources(sources).unwrap();
+    assert_eq!(config.network.chain_id, Some(1337));
+    assert_eq!(config.network.start_block, 10, "shared sections apply to every profile");
+
+    // Known profiles need no section; others must be defined in the file.
+    let sources = Sources { file: Some(path.clone()), profile: Some("blockdag-testnet".into()), ..Default::default() };
+    let config = Config::from_sources(sources).unwrap();
+    assert_eq!(config.network.rpc_url, None);
+    let missing = problems(config.network().unwrap_err());
+    assert_eq!(missing.len(), 3, "{missing:?}");
+
+    let sources = Sources { file: Some(path.clone()), profile: Some("prod".into()), ..Default::default() };
+    let err = Config::from_sources(sources).unwrap_err();
+    assert!(matches!(err, ConfigError::UnknownProfile { ref name, .. } if name == "prod"), "{err}");
+    assert!(err.to_string().contains("staging"), "{err}");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn invalid_values_are_reported_together() {
+    let path = file(
+        "invalid",
+        r#"
+[network]
+rpc_url = "localhost:8545"
+chain_id = 0
+
+[prover]
+max_fee_per_tx = "lots"
+fee_bump_percent = 5
+"#,
+    );
+    let sources = Sources { file: Some(path.clone()), ..Default::default() };
+    let found = problems(Config::from_sources(sources).unwrap_err());
+    assert_eq!(found.len(), 4, "{found:?}");
+    assert!(found[0].contains("network.rpc_url"));
+    assert!(found.iter().any(|p| p.contains("fee_bump_percent")));
+
+    std::fs::write(&path, "[network]\nrpc = \"http://x\"\n").unwrap();
+    let sources = Sources { file: Some(path.clone()), ..Default::default() };
+    let found = problems(Config::from_sources(sources).unwrap_err());
+    assert!(found[0].contains("unknown field `rpc`"), "{found:?}");
+
+    let err = Config::from_sources(Sources { env: env(&[("CHAIN_ID", "mainnet")]), ..Default::default() }).unwrap_err();
+    assert!(matches!(err, ConfigError::Env { ref var, .. } if var == "CHAIN_ID"), "{err}");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn signer_settings_follow_the_backend() {
+    let with_env = |vars: &[(&str, &str)]| Config::from_sources(Sources { env: env(vars), ..Default::default() });
+
+    let config = with_env(&[("SUBMITTER_PRIVATE_KEY", "0xabc")]).unwrap();
+    assert!(matches!(config.signer().unwrap(), SignerConfig::PrivateKey(k) if k == "0xabc"));
+    assert!(!format!("{config:?}").contains("0xabc"), "the raw key never shows in Debug output");
+
+    let config = with_env(&[("KEYSTORE_PATH", "/secrets/key.json")]).unwrap();
+    match config.signer().unwrap() {
+        SignerConfig::Keystore { path, passphrase } => {
+            assert_eq!(path, PathBuf::from("/secrets/key.json"));
+            assert!(matches!(passphrase, Passphrase::Prompt(_)));
+        }
+        other => panic!("expected a keystore, got {other:?}"),
+    }
+
+    let config = with_env(&[("SIGNER_BACKEND", "remote")]).unwrap();
+    assert!(problems(config.signer().unwrap_err())[0].contains("signer.remote_url"));
+    assert!(problems(Config::from_sources(Sources::default()).unwrap().signer().unwrap_err())[0]
+        .contains("no signer configured"));
+
+    // Raw keys are refused off dev chains, whatever the profile is called, unless explicitly allowed.
+    let raw_key = |profile: &str, chain_id: &str, overrides: &[(&str, &str)]| {
+        Config::from_sources(Sources {
+            profile: Some(profile.into()),
+            env: env(&[("SUBMITTER_PRIVATE_KEY", "0xabc"), ("CHAIN_ID", chain_id)]),
+            overrides: env(overrides),
+            ..Default::default()
+        })
+    };
+    for (profile, chain_id) in [("blockdag-mainnet", "7"), ("blockdag-testnet", "7"), ("local", "1")] {
+        let found = problems(raw_key(profile, chain_id, &[]).unwrap_err());
+        assert!(found[0].contains(&format!("raw private keys are only allowed on dev chains (1337, 31337), not on chain_id {chain_id}")), "{found:?}");
+    }
+    raw_key("blockdag-mainnet", "31337", &[]).unwrap();
+    raw_key("blockdag-testnet", "7", &[("signer.allow_raw_key", "true")]).unwrap();
+    let found = problems(Config::from_sources(Sources { profile: Some("blockdag-mainnet".into()), env: env(&[("SUBMITTER_PRIVATE_KEY", "0xabc")]), ..Default::default() }).unwrap_err());
+    assert!(found[0].contains("not on an unset chain_id"), "{found:?}");
+
+    // A raw key never silently wins over, or loses to, another signer.
+    let found = problems(with_env(&[("SUBMITTER_PRIVATE_KEY", "0xabc"), ("KEYSTORE_PATH", "/secrets/key.json")]).unwrap_err());
+    assert_eq!(found, ["both SUBMITTER_PRIVATE_KEY and signer.keystore_path are set; unset one"]);
+    let found = problems(with_env(&[("SUBMITTER_PRIVATE_KEY", "0xabc"), ("SIGNER_BACKEND", "remote"), ("REMOTE_SIGNER_URL", "http://signer")]).unwrap_err());
+    assert!(found.iter().any(|p| p.contains("signer.backend is \"remote\"")), "{found:?}");
+}
+
+#[test]
+fn example_file_parses_for_every_profile() {
+    for profile in vericortex_config::PROFILES {
+        let sources = Sources { file: Some(EXAMPLE.into()), profile: Some(profile.into()), ..Default::default() };
+        let config = Config::from_sources(sources).unwrap_or_else(|e| panic!("{profile}: {e}"));
+        assert_eq!(config.profile, profile);
+    }
+}
@@ -0,0 +1,190 @@
+//! Follows the verifier contract's logs into a [`Store`].
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use ethers::contract::EthLogDecode;
+use ethers::prelude::*;
+use log::{info, warn};
+use vericortex_sdk::contract::{
+    timestamp, ProofAnchoredFilter, ProofCortexVerifierEvents, ProofSubmittedFilter, ProofUpdatedFilter,
+};
+use vericortex_sdk::{Hash32, ModelId, ProofId};
+
+use crate::store::{EventRecord, ProofEvent, Store};
+
+/// Blocks kept for reorg detection below the newest confirmed one. Older
+/// hashes are pruned, so a reorg reaching below them re-indexes from
+/// `start_block`.
+pub const REORG_MARGIN: u64 = 64;
+
+/// Where to index from and how cautiously.
+#[derive(Clone, Debug)]
+pub struct IndexerConfig {
+    pub rpc_url: String,
+    pub contract_address: Address,
+    /// First block to index (the contract's deployment block).
+    pub start_block: u64,
+    /// Blocks a log must be buried under before it is indexed.
+    pub confirmations: u64,
+    /// Largest block range requested from `eth_getLogs` at once.
+    pub max_range: u64,
+    pub poll_interval: Duration,
+}
+
+impl IndexerConfig {
+    pub fn new(rpc_url: impl Into<String>, contract_address: Address) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            contract_address,
+            start_block: 0,
+            confirmations: 12,
+            max_range: 2_000,
+            poll_interval: Duration::from_secs(2),
+        }
+    }
+}
+
+/// What one [`Indexer::poll`] did.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PollOutcome {
+    /// Set when a reorg was detected: everything above this block was dropped.
+    pub rolled_back_to: Option<u64>,
+    /// Events stored by this poll.
+    pub events: usize,
+    /// First block still to be indexed.
+    pub next_block: u64,
+}
+
+pub struct Indexer {
+    cfg: IndexerConfig,
+    provider: Provider<Http>,
+    store: Store,
+}
+
+fn decode(log: &Log) -> Result<EventRecord> {
+    let raw = ethers::abi::RawLog { topics: log.topics.clone(), data: log.data.to_vec() };
+    let event = match ProofCortexVerifierEvents::decode_log(&raw).context("Undecodable verifier log")? {
+        ProofCortexVerifierEvents::ProofSubmittedFilter(ev) => ProofEvent::Submitted {
+            proof_id: ProofId(ev.proof_id),
+            submitter: vericortex_sdk::Address(ev.submitter.0),
+            model_id: ModelId(ev.model_id),
+            valid: ev.valid,
+        },
+        ProofCortexVerifierEvents::ProofUpdatedFilter(ev) => {
+            ProofEvent::Updated { proof_id: ProofId(ev.proof_id), valid: ev.valid, timestamp: timestamp(ev.timestamp)? }
+        }
+        ProofCortexVerifierEvents::ProofAnchoredFilter(ev) => ProofEvent::Anchored {
+            proof_id: ProofId(ev.proof_id),
+            proof_hash: Hash32(ev.proof_hash),
+            timestamp: timestamp(ev.time)?,
+        },
+    };
+    Ok(EventRecord {
+        block_number: log.block_number.context("log without block number")?.as_u64(),
+        log_index: log.log_index.context("log without index")?.low_u64(),
+        tx_hash: Hash32(log.transaction_hash.context("log without tx hash")?.0),
+        event,
+    })
+}
+
+// Takes the provider rather than `&Indexer`: the SQLite connection is not
+// `Sync`, so borrowing the indexer across an await would make `run` unspawnable.
+async fn block_hash(provider: &Provider<Http>, number: u64) -> Result<Option<Hash32>> {
+    let block = provider.get_block(number).await.with_context(|| format!("eth_getBlockByNumber {number}"))?;
+    Ok(block.and_then(|b| b.hash).map(|h| Hash32(h.0)))
+}
+
+impl Indexer {
+    pub fn new(cfg: IndexerConfig, store: Store) -> Result<Self> {
+        let provider = Provider::<Http>::try_from(cfg.rpc_url.as_str()).context("Invalid RPC_URL")?;
+        Ok(Self { cfg, provider, store })
+    }
+
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+
+    /// Compare recorded hashes with the chain, newest first, and roll back to
+    /// the newest block that still matches.
+    async fn check_reorg(&mut self) -> Result<Option<u64>> {
+        let recorded = self.store.block_hashes(self.cfg.confirmations + REORG_MARGIN)?;
+        let Some((newest, hash)) = recorded.first() else { return Ok(None) };
+        if block_hash(&self.provider, *newest).await? == Some(*hash) {
+            return Ok(None);
+        }
+
+        let mut ancestor = self.cfg.start_block.saturating_sub(1);
+        for (number, hash) in &recorded[1..] {
+            if block_hash(&self.provider, *number).await? == Some(*hash) {
+                ancestor = *number;
+                break;
+            }
+        }
+        warn!("Reorg detected at block {}: rolling back to {}", newest, ancestor);
+        self.store.rollback(ancestor)?;
+        Ok(Some(ancestor))
+    }
+
+    /// Index every confirmed block not yet seen, at most `max_range` blocks at a time.
+    pub async fn poll(&mut self) -> Result<PollOutcome> {
+        let mut outcome = PollOutcome { rolled_back_to: self.check_reorg().await?, ..Default::default() };
+
+        let head = self.provider.get_block_number().await.context("eth_blockNumber failed")?.as_u64();
+        let mut next = self.store.next_block()?.unwrap_or(self.cfg.start_block).max(self.cfg.start_block);
+        let Some(confirmed) = head.checked_sub(self.cfg.confirmations) else {
+            outcome.next_block = next;
+            return Ok(outcome);
+        };
+
+        while next <= confirmed {
+            let to = confirmed.min(next + self.cfg.max_range - 1);
+            let filter = Filter::new()
+                .address(self.cfg.contract_address)
+                .topic0(vec![
+                    ProofSubmittedFilter::signature(),
+                    ProofUpdatedFilter::signature(),
+                    ProofAnchoredFilter::signature(),
+                ])
+                .from_block(next)
+                .to_block(to);
+            let logs = self.provider.get_logs(&filter).await.context("eth_getLogs failed")?;
+
+            let mut blocks: Vec<(u64, Hash32)> = Vec::new();
+            let mut events = Vec::with_capacity(logs.len());
+            for log in &logs {
+                let record = decode(log)?;
+                if let Some(hash) = log.block_hash {
+                    if blocks.last().map(|(n, _)| *n) != Some(record.block_number) {
+                        blocks.push((record.block_number, Hash32(hash.0)));
+                    }
+                }
+                events.push(record);
+            }
+            // The range end anchors reorg detection even when it held no logs.
+            if blocks.last().map(|(n, _)| *n) != Some(to) {
+                let hash = block_hash(&self.provider, to).await?.with_context(|| format!("block {to} not found"))?;
+                blocks.push((to, hash));
+            }
+
+            self.store.apply(&events, &blocks, to + 1)?;
+            info!("Indexed blocks {}..={} ({} events)", next, to, events.len());
+            outcome.events += events.len();
+            next = to + 1;
+        }
+        self.store.prune_blocks(confirmed.saturating_sub(REORG_MARGIN))?;
+        outcome.next_block = next;
+        Ok(outcome)
+    }
+
+    /// Poll forever, sleeping `poll_interval` between rounds; RPC errors are
+    /// logged and retried.
+    pub async fn run(mut self) -> Result<()> {
+        loop {
+            if let Err(e) = self.poll().await {
+                warn!("Indexer poll failed: {:#}", e);
+            }
+            tokio::time::sleep(self.cfg.poll_interval).await;
+        }
+    }
+}
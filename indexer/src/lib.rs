@@ -0,0 +1,13 @@
+//! VeriCortex event indexer.
+//!
+//! Follows `ProofSubmitted`, `ProofAnchored` and `ProofUpdated` logs of a
+//! ProofCortexVerifier contract into SQLite. Only blocks buried under the
+//! configured confirmation depth are indexed, and the hashes of indexed
+//! blocks are re-checked on every poll so rows from reorged-out blocks are
+//! rolled back and re-indexed from the new chain.
+
+pub mod indexer;
+pub mod store;
+
+pub use indexer::{Indexer, IndexerConfig, PollOutcome, REORG_MARGIN};
+pub use store::{EventRecord, IndexedProof, ProofEvent, Stats, Store, ValidityChange};
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::Parser;
+use dotenv::dotenv;
+use log::info;
+use vericortex_config::{Config, ConfigArgs, Sources};
+use vericortex_indexer::{Indexer, IndexerConfig, Store};
+
+/// Flags override the `[indexer]` and `[network]` settings of the selected profile.
+#[derive(Parser)]
+#[command(name = "vericortex-indexer", version, about = "Index ProofCortexVerifier events into SQLite")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// SQLite database file, created if missing (default: indexer.db)
+    #[arg(long)]
+    db: Option<PathBuf>,
+    /// RPC endpoint (default: network.rpc_url)
+    #[arg(long)]
+    rpc_url: Option<String>,
+    /// Verifier contract address (default: network.verifier_contract)
+    #[arg(long)]
+    contract: Option<vericortex_sdk::Address>,
+    /// First block to index, usually the contract's deployment block (default: network.start_block)
+    #[arg(long)]
+    start_block: Option<u64>,
+    /// Blocks a log must be buried under before it is indexed (default: network.confirmations, else 12)
+    #[arg(long)]
+    confirmations: Option<u64>,
+    /// Milliseconds between polls (default: indexer.poll_interval_ms)
+    #[arg(long)]
+    poll_interval_ms: Option<u64>,
+}
+
+impl Cli {
+    fn config(&self) -> Result<Config> {
+        let mut sources = Sources::from_args(&self.config)?;
+        let flags = [
+            ("indexer.db", self.db.as_ref().map(|p| format!("{:?}", p.display().to_string()))),
+            ("network.rpc_url", self.rpc_url.clone()),
+            ("network.verifier_contract", self.contract.map(|a| a.to_string())),
+            ("network.start_block", self.start_block.map(|b| b.to_string())),
+            ("network.confirmations", self.confirmations.map(|c| c.to_string())),
+            ("indexer.poll_interval_ms", self.poll_interval_ms.map(|ms| ms.to_string())),
+        ];
+        for (key, value) in flags {
+            if let Some(value) = value {
+                sources = sources.set(key, value);
+            }
+        }
+        Ok(Config::from_sources(sources)?)
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::init();
+    dotenv().ok();
+    let config = Cli::parse().config()?;
+    let network = config.network()?;
+
+    let contract = ethers::types::Address::from(network.verifier_contract.0);
+    let mut cfg = IndexerConfig::new(network.rpc_url, contract);
+    cfg.start_block = network.start_block;
+    cfg.confirmations = network.confirmations.unwrap_or(12);
+    cfg.max_range = config.indexer.max_range;
+    cfg.poll_interval = Duration::from_millis(config.indexer.poll_interval_ms);
+
+    let db = &config.indexer.db;
+    let store = Store::open(db)?;
+    info!("Indexing {:?} on {} into {} from block {}", contract, config.profile, db.display(), cfg.start_block);
+    let indexer = Indexer::new(cfg, store)?;
+    tokio::select! {
+        res = indexer.run() => res,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
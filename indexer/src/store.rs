@@ -0,0 +1,318 @@
+//! SQLite storage for indexed verifier events.
+//!
+//! Every row remembers the block it came from, so rolling back a reorged
+//! block range is a set of deletes followed by recomputing the derived
+//! columns (`proofs.valid`, `submitters`).
+
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension, Row, Transaction};
+use serde::Serialize;
+use vericortex_sdk::{Address, Hash32, ModelId, ProofId};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS meta (
+    key   TEXT PRIMARY KEY,
+    value INTEGER NOT NULL
+);
+-- Hashes of indexed blocks, used to detect reorgs.
+CREATE TABLE IF NOT EXISTS blocks (
+    number INTEGER PRIMARY KEY,
+    hash   TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS proofs (
+    proof_id        TEXT PRIMARY KEY,
+    model_id        TEXT NOT NULL,
+    submitter       TEXT NOT NULL,
+    submitted_valid INTEGER NOT NULL,
+    valid           INTEGER NOT NULL,
+    proof_hash      TEXT,
+    anchored_at     INTEGER,
+    block_number    INTEGER NOT NULL,
+    tx_hash         TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS proofs_by_submitter ON proofs (submitter, block_number);
+CREATE INDEX IF NOT EXISTS proofs_by_model ON proofs (model_id);
+CREATE TABLE IF NOT EXISTS submitters (
+    address     TEXT PRIMARY KEY,
+    proof_count INTEGER NOT NULL,
+    first_block INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS validity_changes (
+    proof_id     TEXT NOT NULL,
+    valid        INTEGER NOT NULL,
+    timestamp    INTEGER NOT NULL,
+    block_number INTEGER NOT NULL,
+    log_index    INTEGER NOT NULL,
+    tx_hash      TEXT NOT NULL,
+    PRIMARY KEY (block_number, log_index)
+);
+CREATE INDEX IF NOT EXISTS validity_changes_by_proof ON validity_changes (proof_id);
+";
+
+const NEXT_BLOCK: &str = "next_block";
+
+/// A decoded `ProofCortexVerifier` event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofEvent {
+    Submitted { proof_id: ProofId, submitter: Address, model_id: ModelId, valid: bool },
+    Updated { proof_id: ProofId, valid: bool, timestamp: u64 },
+    Anchored { proof_id: ProofId, proof_hash: Hash32, timestamp: u64 },
+}
+
+/// An event with its position in the chain.
+#[derive(Clone, Debug)]
+pub struct EventRecord {
+    pub block_number: u64,
+    pub log_index: u64,
+    pub tx_hash: Hash32,
+    pub event: ProofEvent,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct IndexedProof {
+    pub proof_id: ProofId,
+    pub model_id: ModelId,
+    pub submitter: Address,
+    /// Validity after the newest indexed `ProofUpdated`.
+    pub valid: bool,
+    pub proof_hash: Option<Hash32>,
+    pub anchored_at: Option<u64>,
+    pub block_number: u64,
+    pub tx_hash: Hash32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ValidityChange {
+    pub valid: bool,
+    pub timestamp: u64,
+    pub block_number: u64,
+    pub tx_hash: Hash32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Stats {
+    pub total_proofs: u64,
+    pub valid_proofs: u64,
+    pub invalid_proofs: u64,
+    pub unique_submitters: u64,
+}
+
+/// Read a hex column written by this store.
+fn hex_col<T: FromStr>(row: &Row<'_>, idx: usize) -> rusqlite::Result<T>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let s: String = row.get(idx)?;
+    s.parse()
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+fn proof_row(row: &Row<'_>) -> rusqlite::Result<IndexedProof> {
+    let proof_hash: Option<String> = row.get(4)?;
+    Ok(IndexedProof {
+        proof_id: hex_col(row, 0)?,
+        model_id: ModelId(row.get(1)?),
+        submitter: hex_col(row, 2)?,
+        valid: row.get(3)?,
+        proof_hash: match proof_hash {
+            Some(_) => Some(hex_col(row, 4)?),
+            None => None,
+        },
+        anchored_at: row.get(5)?,
+        block_number: row.get(6)?,
+        tx_hash: hex_col(row, 7)?,
+    })
+}
+
+const PROOF_COLUMNS: &str =
+    "proof_id, model_id, submitter, valid, proof_hash, anchored_at, block_number, tx_hash";
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Open (or create) the database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let conn = Connection::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        // WAL lets API readers query while the indexer writes.
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        Self::init(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA).context("Failed to create index schema")?;
+        Ok(Self { conn })
+    }
+
+    /// First block not yet indexed, if indexing has started.
+    pub fn next_block(&self) -> Result<Option<u64>> {
+        Ok(self
+            .conn
+            .query_row("SELECT value FROM meta WHERE key = ?1", [NEXT_BLOCK], |r| r.get(0))
+            .optional()?)
+    }
+
+    /// The newest `limit` recorded block hashes, newest first.
+    pub fn block_hashes(&self, limit: u64) -> Result<Vec<(u64, Hash32)>> {
+        let mut stmt = self.conn.prepare("SELECT number, hash FROM blocks ORDER BY number DESC LIMIT ?1")?;
+        let rows = stmt.query_map([limit], |r| Ok((r.get(0)?, hex_col(r, 1)?)))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Forget the hashes of blocks below `block`, which are final.
+    pub fn prune_blocks(&mut self, block: u64) -> Result<()> {
+        self.conn.execute("DELETE FROM blocks WHERE number < ?1", [block])?;
+        Ok(())
+    }
+
+    /// Store `events` and `blocks` and advance the cursor to `next_block`, atomically.
+    pub fn apply(&mut self, events: &[EventRecord], blocks: &[(u64, Hash32)], next_block: u64) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        for (number, hash) in blocks {
+            tx.execute(
+                "INSERT OR REPLACE INTO blocks (number, hash) VALUES (?1, ?2)",
+                params![number, hash.to_string()],
+            )?;
+        }
+        for record in events {
+            apply_event(&tx, record)?;
+        }
+        set_next_block(&tx, next_block)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Forget everything above `block` (it was reorged out) and resume from `block + 1`.
+    pub fn rollback(&mut self, block: u64) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM proofs WHERE block_number > ?1", [block])?;
+        tx.execute("DELETE FROM validity_changes WHERE block_number > ?1", [block])?;
+        tx.execute("DELETE FROM blocks WHERE number > ?1", [block])?;
+        tx.execute(
+            "UPDATE proofs SET valid = COALESCE(
+                (SELECT v.valid FROM validity_changes v WHERE v.proof_id = proofs.proof_id
+                 ORDER BY v.block_number DESC, v.log_index DESC LIMIT 1),
+                submitted_valid)",
+            [],
+        )?;
+        tx.execute("DELETE FROM submitters", [])?;
+        tx.execute(
+            "INSERT INTO submitters (address, proof_count, first_block)
+             SELECT submitter, COUNT(*), MIN(block_number) FROM proofs GROUP BY submitter",
+            [],
+        )?;
+        set_next_block(&tx, block + 1)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn proof(&self, proof_id: &ProofId) -> Result<Option<IndexedProof>> {
+        let sql = format!("SELECT {PROOF_COLUMNS} FROM proofs WHERE proof_id = ?1");
+        Ok(self.conn.query_row(&sql, [proof_id.to_string()], proof_row).optional()?)
+    }
+
+    /// Proofs by `submitter`, oldest first.
+    pub fn proofs_by_submitter(&self, submitter: &Address) -> Result<Vec<IndexedProof>> {
+        self.query_proofs("submitter", &submitter.to_string())
+    }
+
+    pub fn proofs_by_model(&self, model_id: &ModelId) -> Result<Vec<IndexedProof>> {
+        self.query_proofs("model_id", model_id.as_str())
+    }
+
+    fn query_proofs(&self, column: &str, value: &str) -> Result<Vec<IndexedProof>> {
+        let sql = format!("SELECT {PROOF_COLUMNS} FROM proofs WHERE {column} = ?1 ORDER BY block_number, rowid");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([value], proof_row)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// `ProofUpdated` history of `proof_id`, oldest first.
+    pub fn validity_changes(&self, proof_id: &ProofId) -> Result<Vec<ValidityChange>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT valid, timestamp, block_number, tx_hash FROM validity_changes
+             WHERE proof_id = ?1 ORDER BY block_number, log_index",
+        )?;
+        let rows = stmt.query_map([proof_id.to_string()], |r| {
+            Ok(ValidityChange { valid: r.get(0)?, timestamp: r.get(1)?, block_number: r.get(2)?, tx_hash: hex_col(r, 3)? })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Proofs indexed for `submitter` (0 if none).
+    pub fn proof_count(&self, submitter: &Address) -> Result<u64> {
+        Ok(self
+            .conn
+            .query_row("SELECT proof_count FROM submitters WHERE address = ?1", [submitter.to_string()], |r| r.get(0))
+            .optional()?
+            .unwrap_or(0))
+    }
+
+    pub fn stats(&self) -> Result<Stats> {
+        Ok(self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(valid), 0), (SELECT COUNT(*) FROM submitters) FROM proofs",
+            [],
+            |r| {
+                let total: u64 = r.get(0)?;
+                let valid: u64 = r.get(1)?;
+                Ok(Stats { total_proofs: total, valid_proofs: valid, invalid_proofs: total - valid, unique_submitters: r.get(2)? })
+            },
+        )?)
+    }
+}
+
+fn set_next_block(tx: &Transaction<'_>, next_block: u64) -> Result<()> {
+    tx.execute(
+        "INSERT INTO meta (key, value) VALUES (?1, ?2) ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+        params![NEXT_BLOCK, next_block],
+    )?;
+    Ok(())
+}
+
+fn apply_event(tx: &Transaction<'_>, record: &EventRecord) -> Result<()> {
+    let block = record.block_number;
+    let tx_hash = record.tx_hash.to_string();
+    match &record.event {
+        ProofEvent::Submitted { proof_id, submitter, model_id, valid } => {
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO proofs (proof_id, model_id, submitter, submitted_valid, valid, block_number, tx_hash)
+                 VALUES (?1, ?2, ?3, ?4, ?4, ?5, ?6)",
+                params![proof_id.to_string(), model_id.as_str(), submitter.to_string(), valid, block, tx_hash],
+            )?;
+            // A proof seen again (a replayed range) is not another proof.
+            if inserted == 0 {
+                return Ok(());
+            }
+            tx.execute(
+                "INSERT INTO submitters (address, proof_count, first_block) VALUES (?1, 1, ?2)
+                 ON CONFLICT (address) DO UPDATE SET proof_count = proof_count + 1",
+                params![submitter.to_string(), block],
+            )?;
+        }
+        ProofEvent::Updated { proof_id, valid, timestamp } => {
+            tx.execute(
+                "INSERT OR REPLACE INTO validity_changes (proof_id, valid, timestamp, block_number, log_index, tx_hash)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![proof_id.to_string(), valid, timestamp, block, record.log_index, tx_hash],
+            )?;
+            tx.execute("UPDATE proofs SET valid = ?2 WHERE proof_id = ?1", params![proof_id.to_string(), valid])?;
+        }
+        ProofEvent::Anchored { proof_id, proof_hash, timestamp } => {
+            tx.execute(
+                "UPDATE proofs SET proof_hash = ?2, anchored_at = ?3 WHERE proof_id = ?1",
+                params![proof_id.to_string(), proof_hash.to_string(), timestamp],
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,180 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use ethers::prelude::*;
+use vericortex_devchain::{dev_address, dev_wallet, DevChain};
+use vericortex_indexer::{EventRecord, Indexer, IndexerConfig, ProofEvent, Stats, Store, REORG_MARGIN};
+use vericortex_sdk::contract::ProofCortexVerifier;
+use vericortex_sdk::{Hash32, ProofId};
+
+type Client = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+struct Setup {
+    chain: DevChain,
+    verifier: ProofCortexVerifier<Client>,
+    indexer: Indexer,
+}
+
+fn setup(confirmations: u64) -> Setup {
+    let chain = DevChain::spawn().unwrap();
+    let address = chain.deploy_verifier(dev_address(0));
+    let provider = Provider::<Http>::try_from(chain.url()).unwrap().interval(Duration::from_millis(10));
+    let client = Arc::new(SignerMiddleware::new(provider, dev_wallet(0).with_chain_id(chain.chain_id())));
+    let verifier = ProofCortexVerifier::new(address, client);
+
+    let mut cfg = IndexerConfig::new(chain.url(), address);
+    cfg.confirmations = confirmations;
+    cfg.max_range = 3;
+    let indexer = Indexer::new(cfg, Store::open_in_memory().unwrap()).unwrap();
+    Setup { chain, verifier, indexer }
+}
+
+async fn submit(verifier: &ProofCortexVerifier<Client>, model: &str, proof_hash: u8, valid: bool) -> ProofId {
+    let receipt = verifier
+        .submit_proof(model.into(), "v1".into(), [1; 32], [2; 32], [0; 32], [proof_hash; 32], valid)
+        .send()
+        .await
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    vericortex_sdk::contract::submitted_proof_id(&receipt).unwrap()
+}
+
+async fn update(verifier: &ProofCortexVerifier<Client>, proof_id: ProofId, valid: bool) {
+    verifier.update_proof_result(proof_id.0, valid).send().await.unwrap().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proofs_are_indexed_once_confirmed() {
+    let mut s = setup(2);
+    let id = submit(&s.verifier, "m", 1, true).await;
+
+    s.indexer.poll().await.unwrap();
+    assert!(s.indexer.store().proof(&id).unwrap().is_none(), "indexed before confirmation");
+
+    s.chain.mine_blocks(2);
+    let outcome = s.indexer.poll().await.unwrap();
+    assert_eq!(outcome.events, 2);
+    let proof = s.indexer.store().proof(&id).unwrap().expect("indexed");
+    assert_eq!(proof.model_id.as_str(), "m");
+    assert_eq!(proof.submitter.0, dev_address(0).0);
+    assert!(proof.valid);
+    assert_eq!(proof.proof_hash.unwrap().0, [1; 32]);
+    assert!(proof.anchored_at.is_some());
+
+    let submitter = vericortex_sdk::Address(dev_address(0).0);
+    assert_eq!(s.indexer.store().proofs_by_submitter(&submitter).unwrap(), vec![proof]);
+    assert_eq!(
+        s.indexer.store().stats().unwrap(),
+        Stats { total_proofs: 1, valid_proofs: 1, invalid_proofs: 0, unique_submitters: 1 }
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn validity_changes_follow_updates() {
+    let mut s = setup(0);
+    let id = submit(&s.verifier, "m", 1, true).await;
+    update(&s.verifier, id, false).await;
+    submit(&s.verifier, "m", 2, false).await;
+
+    s.indexer.poll().await.unwrap();
+    let store = s.indexer.store();
+    assert!(!store.proof(&id).unwrap().unwrap().valid);
+    let history = store.validity_changes(&id).unwrap();
+    assert_eq!(history.len(), 1);
+    assert!(!history[0].valid);
+    assert_eq!(store.stats().unwrap(), Stats { total_proofs: 2, valid_proofs: 0, invalid_proofs: 2, unique_submitters: 1 });
+    assert_eq!(store.proofs_by_model(&"m".into()).unwrap().len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reorged_rows_are_rolled_back() {
+    let mut s = setup(0);
+    let kept = submit(&s.verifier, "m", 1, true).await;
+    let updated_at = s.chain.block_number() + 1;
+    update(&s.verifier, kept, false).await;
+    let dropped = submit(&s.verifier, "m", 2, true).await;
+    s.indexer.poll().await.unwrap();
+    assert_eq!(s.indexer.store().stats().unwrap().total_proofs, 2);
+
+    // Replace the update and the second submission with empty blocks.
+    let depth = s.chain.block_number() - updated_at + 1;
+    s.chain.reorg(depth);
+    let outcome = s.indexer.poll().await.unwrap();
+    assert_eq!(outcome.rolled_back_to, Some(updated_at - 1));
+
+    let store = s.indexer.store();
+    assert!(store.proof(&dropped).unwrap().is_none());
+    let proof = store.proof(&kept).unwrap().unwrap();
+    assert!(proof.valid, "validity reverts to the submitted value");
+    assert!(store.validity_changes(&kept).unwrap().is_empty());
+    assert_eq!(store.stats().unwrap(), Stats { total_proofs: 1, valid_proofs: 1, invalid_proofs: 0, unique_submitters: 1 });
+
+    // The new fork is indexed normally afterwards.
+    let again = submit(&s.verifier, "m", 3, true).await;
+    s.indexer.poll().await.unwrap();
+    assert!(s.indexer.store().proof(&again).unwrap().is_some());
+    assert_eq!(s.indexer.store().stats().unwrap().total_proofs, 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn indexing_resumes_from_the_stored_cursor() {
+    let mut s = setup(0);
+    let dir = std::env::temp_dir().join(format!("vericortex-indexer-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let db = dir.join("index.sqlite");
+    let _ = std::fs::remove_file(&db);
+
+    let cfg = {
+        let mut cfg = IndexerConfig::new(s.chain.url(), s.verifier.address());
+        cfg.confirmations = 0;
+        cfg
+    };
+    let first = submit(&s.verifier, "m", 1, true).await;
+    let mut indexer = Indexer::new(cfg.clone(), Store::open(&db).unwrap()).unwrap();
+    indexer.poll().await.unwrap();
+    drop(indexer);
+
+    let second = submit(&s.verifier, "m", 2, true).await;
+    let mut indexer = Indexer::new(cfg, Store::open(&db).unwrap()).unwrap();
+    let outcome = indexer.poll().await.unwrap();
+    assert_eq!(outcome.events, 2, "only the new submission is fetched");
+    assert!(indexer.store().proof(&first).unwrap().is_some());
+    assert!(indexer.store().proof(&second).unwrap().is_some());
+    assert_eq!(indexer.store().stats().unwrap().total_proofs, 2);
+
+    // The in-memory indexer from `setup` is unaffected.
+    assert_eq!(s.indexer.poll().await.unwrap().events, 4);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn block_hashes_below_the_reorg_margin_are_pruned() {
+    let mut s = setup(2);
+    submit(&s.verifier, "m", 1, true).await;
+    s.chain.mine_blocks(2 * REORG_MARGIN);
+    s.indexer.poll().await.unwrap();
+
+    let confirmed = s.chain.block_number() - 2;
+    let kept = s.indexer.store().block_hashes(1_000).unwrap();
+    assert_eq!(kept.first().map(|(n, _)| *n), Some(confirmed));
+    assert!(kept.iter().all(|(n, _)| *n >= confirmed - REORG_MARGIN), "{kept:?}");
+    assert_eq!(s.indexer.store().block_hashes(3).unwrap(), kept[..3]);
+}
+
+#[test]
+fn replayed_submissions_are_counted_once() {
+    let mut store = Store::open_in_memory().unwrap();
+    let submitter = vericortex_sdk::Address([9; 20]);
+    let record = EventRecord {
+        block_number: 5,
+        log_index: 0,
+        tx_hash: Hash32([1; 32]),
+        event: ProofEvent::Submitted { proof_id: ProofId([2; 32]), submitter, model_id: "m".into(), valid: true },
+    };
+    store.apply(std::slice::from_ref(&record), &[], 6).unwrap();
+    store.apply(&[record], &[], 6).unwrap();
+    assert_eq!(store.proof_count(&submitter).unwrap(), 1);
+    assert_eq!(store.stats().unwrap().total_proofs, 1);
+}
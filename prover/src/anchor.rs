@@ -0,0 +1,42 @@
+use vericortex_sdk::{ContractSchema, Hash32, ModelId, ProofIdPreimage};
+
+/// Everything the selected verifier contract stores for one proof, validated.
+#[derive(Debug, Clone)]
+pub struct Anchor {
+    pub schema: ContractSchema,
+    pub version: String,
+    pub input_hash: Hash32,
+    pub output_hash: Hash32,
+    pub trace_hash: Hash32,
+    pub proof_hash: Hash32,
+}
+
+impl Anchor {
+    /// The ProofCortexVerifier record for a batch: only `proofHash` (the batch
+    /// root) is meaningful; input, output and trace hashes are zero.
+    pub fn batch(version: String, root: Hash32) -> Self {
+        Self {
+            schema: ContractSchema::ProofCortex,
+            version,
+            input_hash: Hash32::default(),
+            output_hash: Hash32::default(),
+            trace_hash: Hash32::default(),
+            proof_hash: root,
+        }
+    }
+
+    pub fn preimage<'a>(&'a self, model_id: &'a ModelId) -> ProofIdPreimage<'a> {
+        match self.schema {
+            ContractSchema::VeriCortex => ProofIdPreimage::VeriCortex {
+                model_id,
+                input_hash: &self.input_hash,
+                output_hash: &self.output_hash,
+            },
+            ContractSchema::ProofCortex => ProofIdPreimage::ProofCortex {
+                model_id,
+                version: &self.version,
+                proof_hash: &self.proof_hash,
+            },
+        }
+    }
+}
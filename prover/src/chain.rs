@@ -0,0 +1,187 @@
+//! On-chain submission through the generated verifier bindings.
+//!
+//! Calls are checked here (`getProof`, `eth_call`) and then handed to the
+//! [`vericortex_submitter`] outbox, which signs, prices and follows them
+//! within the fee limits; the API submits through the same outbox.
+
+use anyhow::{anyhow, Context, Result};
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use log::{info, warn};
+use std::sync::Arc;
+use vericortex_sdk::contract::{self, legacy::VeriCortexVerifier, OnChainProof, ProofCortexVerifier};
+use vericortex_sdk::{compute_proof_id, ContractSchema, ModelId, ProofId};
+use vericortex_submitter::{revert_of, Client, OutboxEntry, Sender};
+
+pub use vericortex_submitter::ChainConfig;
+
+use crate::anchor::Anchor;
+
+/// Outcome of one confirmed `submitProof` transaction, or of finding the
+/// proof already on-chain.
+#[derive(Debug, Clone)]
+pub struct Submission {
+    pub submitter: vericortex_sdk::Address,
+    /// proofId predicted locally from the anchor and the signer address.
+    pub proof_id: ProofId,
+    /// proofId reported by the `ProofSubmitted` event (ProofCortexVerifier only).
+    pub onchain_proof_id: Option<ProofId>,
+    /// `None` only for an existing proof whose `ProofSubmitted` log was not found.
+    pub tx_hash: Option<H256>,
+    pub block_number: Option<u64>,
+    /// The proof was already stored by an earlier submission; nothing was sent.
+    pub already_submitted: bool,
+}
+
+/// What the pre-flight checks of a submission found.
+#[derive(Debug, Clone)]
+pub enum Preflight {
+    /// `getProof` already holds the proof.
+    Exists(OnChainProof),
+    /// The call succeeds; sending it would cost about `gas_estimate`.
+    Ready { gas_estimate: U256 },
+}
+
+/// Everything needed to send or simulate one `submitProof`.
+struct SubmitCall {
+    calldata: Bytes,
+    proof_id: ProofId,
+}
+
+impl SubmitCall {
+    fn new(client: &Arc<Client>, contract: Address, model_id: &ModelId, anchor: &Anchor, valid: bool) -> Result<Self> {
+        let calldata = match anchor.schema {
+            ContractSchema::ProofCortex => ProofCortexVerifier::new(contract, client.clone())
+                .submit_proof(
+                    model_id.to_string(),
+                    anchor.version.clone(),
+                    anchor.input_hash.0,
+                    anchor.output_hash.0,
+                    anchor.trace_hash.0,
+                    anchor.proof_hash.0,
+                    valid,
+                )
+                .calldata(),
+            ContractSchema::VeriCortex => VeriCortexVerifier::new(contract, client.clone())
+                .submit_proof(model_id.to_string(), anchor.input_hash.0, anchor.output_hash.0)
+                .calldata(),
+        }
+        .context("Failed to encode submitProof")?;
+        let submitter = vericortex_sdk::Address(client.address().0);
+        Ok(Self { calldata, proof_id: compute_proof_id(&anchor.preimage(model_id), &submitter) })
+    }
+}
+
+/// Check `getProof` (ProofCortexVerifier only) and `eth_call` the submission
+/// from the signer. A revert comes back as a [`VerifierRevert`] error.
+async fn preflight(client: &Arc<Client>, contract: Address, schema: ContractSchema, call: &SubmitCall) -> Result<Preflight> {
+    let existing = match schema {
+        ContractSchema::ProofCortex => {
+            let verifier = ProofCortexVerifier::new(contract, client.clone());
+            let record = verifier.get_proof(call.proof_id.0).call().await.context("getProof failed")?;
+            Some(OnChainProof::try_from(record)?).filter(OnChainProof::exists)
+        }
+        ContractSchema::VeriCortex => None,
+    };
+    if let Some(proof) = existing {
+        return Ok(Preflight::Exists(proof));
+    }
+
+    let tx: TypedTransaction =
+        TransactionRequest::new().from(client.address()).to(contract).data(call.calldata.clone()).into();
+    if let Err(e) = client.provider().call(&tx, None).await {
+        return Err(match revert_of(&e) {
+            Some(revert) => anyhow!(revert),
+            None => anyhow!(e).context("eth_call of submitProof failed"),
+        });
+    }
+    let gas_estimate = client.provider().estimate_gas(&tx, None).await.context("eth_estimateGas failed")?;
+    Ok(Preflight::Ready { gas_estimate })
+}
+
+/// The tx and block of the `ProofSubmitted` event for `proof_id`.
+async fn find_submission(client: &Arc<Client>, contract: Address, proof_id: &ProofId) -> Result<Option<(H256, u64)>> {
+    let verifier = ProofCortexVerifier::new(contract, client.clone());
+    let found = verifier
+        .proof_submitted_filter()
+        .topic1(H256(proof_id.0))
+        .from_block(0u64)
+        .query_with_meta()
+        .await
+        .context("eth_getLogs failed")?;
+    Ok(found.first().map(|(_, meta)| (meta.transaction_hash, meta.block_number.as_u64())))
+}
+
+/// Run the pre-flight checks of `submit` without signing or sending anything.
+pub async fn dry_run(cfg: &ChainConfig, model_id: &ModelId, anchor: &Anchor, valid: bool) -> Result<(ProofId, Preflight)> {
+    let client = cfg.client().await?;
+    let call = SubmitCall::new(&client, cfg.contract_address, model_id, anchor, valid)?;
+    let outcome = preflight(&client, cfg.contract_address, anchor.schema, &call).await?;
+    Ok((call.proof_id, outcome))
+}
+
+/// Finish any transactions an earlier run left behind.
+pub async fn resume(cfg: &ChainConfig) -> Result<Vec<OutboxEntry>> {
+    Sender::connect(cfg.clone()).await?.resume().await
+}
+
+/// Connect for a run of submissions, first finishing what earlier runs left
+/// behind. Call once per command, not per submission.
+pub async fn start(cfg: &ChainConfig) -> Result<Sender> {
+    info!("RPC and credentials found. Preparing to submit to contract...");
+    let mut sender = Sender::connect(cfg.clone()).await?;
+    sender.resume().await?;
+    Ok(sender)
+}
+
+pub async fn submit(sender: &mut Sender, model_id: &ModelId, anchor: &Anchor, valid: bool) -> Result<Submission> {
+    let contract = sender.config().contract_address;
+    info!("Submitting {} proof to contract: {:?}", anchor.schema, contract);
+
+    // With wallet address known, predict the proofId and check it against the event
+    let call = SubmitCall::new(sender.client(), contract, model_id, anchor, valid)?;
+    let proof_id = call.proof_id;
+    info!("Final computed proofId: {}", proof_id);
+
+    if let Preflight::Exists(_) = preflight(sender.client(), contract, anchor.schema, &call).await? {
+        info!("Proof {} is already on-chain; nothing to send", proof_id);
+        let found = find_submission(sender.client(), contract, &proof_id).await?;
+        return Ok(Submission {
+            submitter: sender.submitter(),
+            proof_id,
+            onchain_proof_id: Some(proof_id),
+            tx_hash: found.map(|(tx, _)| tx),
+            block_number: found.map(|(_, block)| block),
+            already_submitted: true,
+        });
+    }
+
+    let entry = sender.submit(proof_id, model_id, call.calldata).await?;
+    let tx_hash = entry.tx_hash.context("confirmed entry without tx hash")?;
+    let receipt = sender
+        .client()
+        .get_transaction_receipt(tx_hash)
+        .await
+        .context("tx receipt lookup failed")?
+        .context("receipt of confirmed tx not found")?;
+    info!("Tx mined in block: {:?}", receipt.block_number);
+
+    let onchain_proof_id = match anchor.schema {
+        ContractSchema::ProofCortex => contract::submitted_proof_id(&receipt),
+        ContractSchema::VeriCortex => None,
+    };
+    if let Some(onchain_id) = onchain_proof_id {
+        if onchain_id != proof_id {
+            warn!("⚠️ On-chain proofId {} differs from local prediction {}", onchain_id, proof_id);
+        }
+    }
+
+    Ok(Submission {
+        submitter: sender.submitter(),
+        proof_id,
+        onchain_proof_id,
+        tx_hash: Some(tx_hash),
+        block_number: receipt.block_number.map(|b| b.as_u64()),
+        already_submitted: false,
+    })
+}
@@ -1,175 +1,979 @@
 use anyhow::{Context, Result};
-use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
-use std::{fs, path::Path};
-use hex::encode as hex_encode;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde_json::json;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::ExitCode;
 use dotenv::dotenv;
-use std::env;
 use log::{info, warn};
+use vericortex_sdk::canonical::{DType, Endian, Tensor};
+use vericortex_sdk::{
+    compute_proof_id, Address, ArtifactFormat, BatchAnchor, BatchReceipt, ContractSchema, HashAlg, Hash32,
+    ModelFingerprint, ModelId, Payload, ProofBatch, ProofId, ProofJson, StepProof, Trace,
+};
+use vericortex_sdk::adapter::{ExternalVerifier, Groth16Verifier, SignedAttestation, TeeVerifier};
+use vericortex_sdk::{AdapterRegistry, Verdict};
+use vericortex_wasm_runtime::{Limits, Model, WasmAdapter};
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ProofJson {
-    model_id: String,
-    input_hash: String,  // hex string (0x...)
-    output_hash: String  // hex string (0x...)
-}
-
-/// Compute deterministic recomputed hash: SHA256(model_id || ":" || input_hash)
-fn compute_recomputed_output_hash(model_id: &str, input_hash: &str) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(model_id.as_bytes());
-    hasher.update(b":");
-    hasher.update(input_hash.as_bytes());
-    let out = hasher.finalize();
-    format!("0x{}", hex_encode(out))
-}
-
-/// Compute a proofId equivalent to solidity:
-/// keccak256(abi.encodePacked(modelId, inputHash, outputHash, submitter))
-fn compute_proof_id(model_id: &str, input_hash: &str, output_hash: &str, submitter: &str) -> String {
-    use ethers::utils::keccak256;
-    // assemble bytes similar to abi.encodePacked
-    let mut concat: Vec<u8> = vec![];
-    concat.extend_from_slice(model_id.as_bytes());
-    // remove 0x prefix and decode hex bytes
-    let in_bytes = hex::decode(input_hash.trim_start_matches("0x")).unwrap_or_default();
-    concat.extend_from_slice(&in_bytes);
-    let out_bytes = hex::decode(output_hash.trim_start_matches("0x")).unwrap_or_default();
-    concat.extend_from_slice(&out_bytes);
-    // address bytes: remove 0x and decode (20 bytes expected)
-    let addr_bytes = hex::decode(submitter.trim_start_matches("0x")).unwrap_or_default();
-    concat.extend_from_slice(&addr_bytes);
-
-    let digest = keccak256(concat);
-    format!("0x{}", hex_encode(digest))
+mod anchor;
+mod chain;
+mod reader;
+
+use anchor::Anchor;
+use chain::ChainConfig;
+use reader::ReaderConfig;
+use vericortex_config::{Config, ConfigArgs, ConfigError, Sources};
+use vericortex_sdk::contract::VerifierRevert;
+
+/// Exit code when the recomputed output hash does not match the proof.
+const EXIT_MISMATCH: u8 = 1;
+/// Exit code when the proof file is missing, unreadable or malformed. Not 2,
+/// which clap exits with on a usage error.
+const EXIT_MALFORMED: u8 = 6;
+/// Exit code when the RPC, signer or contract call fails.
+const EXIT_CHAIN: u8 = 3;
+/// Exit code when the contract holds no record for the requested proofId.
+const EXIT_NOT_FOUND: u8 = 4;
+/// Exit code when the configuration file, environment or `--set` overrides are invalid.
+const EXIT_CONFIG: u8 = 5;
+
+#[derive(Parser)]
+#[command(name = "verifier_offchain", version, about = "ProofCortex off-chain verifier")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    #[command(subcommand)]
+    command: Command,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    env_logger::init();
-    dotenv().ok();
+#[derive(Subcommand)]
+enum Command {
+    /// Print the locally recomputed output hash for a proof
+    Hash(ProofArgs),
+    /// Recompute the output hash and compare it with the proof (exit 1 on mismatch); with --wasm, by re-running the model
+    Verify {
+        #[command(flatten)]
+        proof: ProofArgs,
+        #[command(flatten)]
+        adapters: AdapterArgs,
+        #[command(flatten)]
+        replay: ReplayArgs,
+    },
+    /// Canonically hash a raw inference input (exit 1 if --proof disagrees)
+    HashInput(PayloadArgs),
+    /// Canonically hash a raw inference output (exit 1 if --proof disagrees)
+    HashOutput(PayloadArgs),
+    /// Fingerprint a model artifact as a Merkle root over its tensors or files (exit 1 if --proof or --entry disagrees)
+    Fingerprint(FingerprintArgs),
+    /// Compute the proofId the verifier contract will derive for a proof
+    ProofId {
+        #[command(flatten)]
+        proof: ProofArgs,
+        #[command(flatten)]
+        anchor: AnchorArgs,
+        /// Submitter address used in the proofId preimage (defaults to the proof's `submitter`, else zero)
+        #[arg(long)]
+        submitter: Option<Address>,
+    },
+    /// Submit a proof to the verifier contract (needs a network and a signer in the configuration, see README)
+    Submit {
+        #[command(flatten)]
+        proof: ProofArgs,
+        #[command(flatten)]
+        anchor: AnchorArgs,
+        #[command(flatten)]
+        adapters: AdapterArgs,
+        #[command(flatten)]
+        replay: ReplayArgs,
+        /// Only simulate: check getProof and eth_call the submission, send nothing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Build execution trace roots and step inclusion proofs
+    #[command(subcommand)]
+    Trace(TraceCommand),
+    /// Anchor many proofs as Merkle-rooted batches, valid and invalid proofs apart, and write one receipt per proof
+    Batch(BatchArgs),
+    /// Check a batch receipt's Merkle path against its batch root (exit 1 on mismatch)
+    VerifyReceipt(InputArgs),
+    /// Read a proof record from the verifier contract (exit 4 if there is none)
+    Read(ReadArgs),
+    /// Ask the verifier contract whether a proof is valid (exit 1 if not)
+    IsValid(ReadArgs),
+    /// Inspect or resume the persistent transaction outbox (prover.outbox)
+    #[command(subcommand)]
+    Outbox(OutboxCommand),
+    /// Print ProofSubmitted, ProofUpdated and ProofAnchored events as JSON lines
+    Listen {
+        #[command(flatten)]
+        contract: ContractArgs,
+        /// Replay events from this block before following new ones
+        #[arg(long)]
+        from_block: Option<u64>,
+        /// Exit after this many events
+        #[arg(long)]
+        count: Option<usize>,
+    },
+}
+
+#[derive(Args)]
+struct ReadArgs {
+    /// The proofId (bytes32) to look up
+    proof_id: ProofId,
+    #[command(flatten)]
+    contract: ContractArgs,
+}
+
+/// Read-only chain access; defaults to the configured network.
+#[derive(Args)]
+struct ContractArgs {
+    /// RPC endpoint override
+    #[arg(long)]
+    rpc_url: Option<String>,
+    /// Verifier contract address override
+    #[arg(long)]
+    contract: Option<Address>,
+}
+
+#[derive(Args)]
+struct BatchArgs {
+    /// Proof JSON files, or JSON Lines files (`.jsonl`) with one proof per line
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+    /// Directory receipts are written to, one `<bundle_hash>.json` per proof
+    #[arg(long, default_value = "receipts")]
+    receipts: PathBuf,
+    /// Maximum proofs per anchored batch
+    #[arg(long, default_value_t = 1000)]
+    max_batch: usize,
+    /// Model id recorded for the batch (required when a batch mixes models)
+    #[arg(long)]
+    model_id: Option<String>,
+    /// Model version recorded for the batch (default: the batch's common version; required when it mixes versions)
+    #[arg(long)]
+    model_version: Option<String>,
+    /// Submit each batch root on-chain; otherwise only roots and receipts are computed
+    #[arg(long)]
+    submit: bool,
+    #[command(flatten)]
+    adapters: AdapterArgs,
+}
+
+#[derive(Subcommand)]
+enum OutboxCommand {
+    /// Print every outbox entry with its state, nonce and tx hash
+    List,
+    /// Reconcile unfinished entries with the chain, rebroadcasting lost transactions
+    Resume,
+}
+
+#[derive(Subcommand)]
+enum TraceCommand {
+    /// Print the traceHash (Merkle root) of an ordered list of execution steps
+    Root(InputArgs),
+    /// Emit inclusion proofs for individual steps of a trace
+    Prove {
+        #[command(flatten)]
+        input: InputArgs,
+        /// Zero-based index of a step to prove (repeatable)
+        #[arg(long = "step", required = true)]
+        steps: Vec<usize>,
+    },
+    /// Check a step inclusion proof against an anchored traceHash (exit 1 on mismatch)
+    Verify {
+        #[command(flatten)]
+        input: InputArgs,
+        /// The anchored traceHash
+        #[arg(long)]
+        trace_hash: Hash32,
+    },
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// Path to the JSON input, or `-` to read from stdin
+    #[arg(default_value = "-")]
+    input: PathBuf,
+}
+
+#[derive(Args)]
+struct ProofArgs {
+    /// Path to the proof JSON file, or `-` to read from stdin
+    #[arg(default_value = "-")]
+    proof: PathBuf,
+}
+
+/// Verification backends beyond the built-in hash recompute.
+#[derive(Args)]
+struct AdapterArgs {
+    /// Accept signed-attestation proofs signed by this address (repeatable)
+    #[arg(long = "attester", value_name = "ADDRESS")]
+    attesters: Vec<Address>,
+    /// Check proofs of TYPE with a program that reads the proof JSON on stdin and prints a verdict, e.g.
+    /// `--external 'ezkl=ezkl-verify --vk vk.key'` (repeatable)
+    #[arg(long, value_name = "TYPE=COMMAND", value_parser = parse_external)]
+    external: Vec<ExternalVerifier>,
+    /// Check groth16 proofs of MODEL against this arkworks BN254 verifying key (repeatable)
+    #[arg(long = "groth16-vk", value_name = "MODEL=PATH", value_parser = parse_groth16_key)]
+    groth16_keys: Vec<(ModelId, PathBuf)>,
+    /// Trust these PEM root certificates for tee-attestation quotes besides the bundled Intel SGX Root CA
+    /// and AMD ARKs (repeatable)
+    #[arg(long = "tee-roots", value_name = "PEM")]
+    tee_roots: Vec<PathBuf>,
+    /// Check tee-attestation certificate chains against these CRLs, PEM or DER (repeatable)
+    #[arg(long = "tee-crl", value_name = "CRL")]
+    tee_crls: Vec<PathBuf>,
+    /// Accept tee-attestation proofs of MODEL from an enclave, TD or guest with this measurement, as 0x hex
+    /// (repeatable)
+    #[arg(long = "tee-measurement", value_name = "MODEL=HEX", value_parser = parse_tee_measurement)]
+    tee_measurements: Vec<(ModelId, ethers::types::Bytes)>,
+    /// Accept tee-attestation quotes whose certificates no current --tee-crl covers (SEV-SNP VCEKs never are)
+    #[arg(long = "tee-accept-unknown-revocation")]
+    tee_accept_unknown_revocation: bool,
+    /// Accept tee-attestation quotes without evaluating the platform's TCB level, which is not implemented
+    #[arg(long = "tee-accept-unevaluated-tcb")]
+    tee_accept_unevaluated_tcb: bool,
+}
+
+#[derive(Args)]
+struct ReplayArgs {
+    /// Model compiled to WebAssembly (see wasm-runtime/README.md): re-run it on --input and hash its output
+    #[arg(long, requires = "input")]
+    wasm: Option<PathBuf>,
+    /// The inference input, exactly the bytes the proof's input_hash covers
+    #[arg(long, requires = "wasm")]
+    input: Option<PathBuf>,
+    /// Fuel limit for the re-execution, roughly one unit per WebAssembly instruction
+    #[arg(long, default_value_t = Limits::default().fuel)]
+    fuel: u64,
+}
+
+/// How a raw payload is canonicalized before hashing.
+#[derive(Clone, Copy, ValueEnum)]
+enum PayloadFormat {
+    /// JSON, serialized per RFC 8785
+    Json,
+    /// Opaque bytes, hashed as they are
+    Bytes,
+    /// A raw row-major tensor buffer (needs --dtype and --shape)
+    Tensor,
+    /// A tensor as nested JSON arrays (needs --dtype)
+    TensorJson,
+}
+
+#[derive(Args)]
+struct PayloadArgs {
+    /// Path to the payload, or `-` to read from stdin
+    #[arg(default_value = "-")]
+    payload: PathBuf,
+    #[arg(long, value_enum)]
+    format: PayloadFormat,
+    /// Tensor element type: bool, u8, i8, u16, i16, u32, i32, u64, i64, f16, bf16, f32, f64
+    #[arg(long)]
+    dtype: Option<DType>,
+    /// Tensor dimensions, e.g. `1,3,224,224`
+    #[arg(long, value_delimiter = ',')]
+    shape: Option<Vec<u64>>,
+    /// Byte order of a raw tensor buffer
+    #[arg(long, default_value_t = Endian::Little)]
+    endian: Endian,
+    /// Digest algorithm: sha2-256, sha3-256, keccak-256 or blake3 (with --proof, the proof's own)
+    #[arg(long, default_value_t = HashAlg::Sha2_256)]
+    alg: HashAlg,
+    /// Compare with this proof's input_hash / output_hash
+    #[arg(long)]
+    proof: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct FingerprintArgs {
+    /// A .safetensors, .onnx or .gguf file, or a model directory
+    path: PathBuf,
+    /// safetensors, onnx, gguf or directory (detected when omitted)
+    #[arg(long)]
+    format: Option<ArtifactFormat>,
+    /// Digest algorithm (with --proof or --entry, the recorded one)
+    #[arg(long, default_value_t = HashAlg::Blake3)]
+    alg: HashAlg,
+    /// Also print every leaf: name, dtype, shape, size and digest
+    #[arg(long)]
+    leaves: bool,
+    /// Compare with this proof's model_fingerprint
+    #[arg(long, conflicts_with = "entry")]
+    proof: Option<PathBuf>,
+    /// Compare with a model registry entry, as returned by the API's `GET /models/{modelId}`
+    #[arg(long)]
+    entry: Option<PathBuf>,
+}
+
+/// Contract selection plus overrides for the envelope's on-chain fields.
+#[derive(Args)]
+struct AnchorArgs {
+    /// Contract whose encoding to reproduce: `proofcortex` or `vericortex`
+    #[arg(long, default_value_t = ContractSchema::ProofCortex)]
+    schema: ContractSchema,
+    /// Override the proof's `model_version`
+    #[arg(long)]
+    model_version: Option<String>,
+    /// Override the proof bundle hash (defaults to the envelope's `bundle_hash`)
+    #[arg(long)]
+    proof_hash: Option<Hash32>,
+    /// Override the proof's `trace_hash`
+    #[arg(long)]
+    trace_hash: Option<Hash32>,
+}
+
+/// Failure classes the CLI maps onto distinct exit codes.
+#[derive(Debug)]
+enum CliError {
+    Mismatch(serde_json::Value),
+    Malformed(anyhow::Error),
+    Chain(anyhow::Error),
+    /// The verifier contract rejected the submission.
+    Reverted(VerifierRevert),
+    NotFound(ProofId),
+    Config(ConfigError),
+}
 
-    let proof_path = Path::new("src/verifier-offchain/tests/sample_proof.json");
-    if !proof_path.exists() {
-        // also accept tests at crate-root tests/
-        let alt = Path::new("src/verifier-offchain/tests/sample_proof.json");
-        if alt.exists() { }
+/// A chain error, typed as [`CliError::Reverted`] when it is a decoded revert.
+fn chain_err(e: anyhow::Error) -> CliError {
+    match e.downcast::<VerifierRevert>() {
+        Ok(revert) => CliError::Reverted(revert),
+        Err(e) => CliError::Chain(e),
     }
+}
 
-    let raw = fs::read_to_string(proof_path)
-        .context("Failed to read sample_proof.json at src/verifier-offchain/tests/sample_proof.json")?;
-    let proof: ProofJson = serde_json::from_str(&raw).context("Invalid proof JSON")?;
+impl CliError {
+    fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Mismatch(_) => EXIT_MISMATCH,
+            CliError::Malformed(_) => EXIT_MALFORMED,
+            CliError::Chain(_) | CliError::Reverted(_) => EXIT_CHAIN,
+            CliError::NotFound(_) => EXIT_NOT_FOUND,
+            CliError::Config(_) => EXIT_CONFIG,
+        }
+    }
+}
 
+/// Read `path` to a string, treating `-` as stdin.
+fn read_input(path: &PathBuf) -> Result<String, CliError> {
+    if path.as_os_str() == "-" {
+        let mut buf = String::new();
+        io::stdin()
+            .read_to_string(&mut buf)
+            .context("Failed to read JSON from stdin")
+            .map_err(CliError::Malformed)?;
+        Ok(buf)
+    } else {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read JSON at {}", path.display()))
+            .map_err(CliError::Malformed)
+    }
+}
+
+/// Read `path` as bytes, treating `-` as stdin.
+fn read_bytes(path: &PathBuf) -> Result<Vec<u8>, CliError> {
+    let read = if path.as_os_str() == "-" {
+        let mut buf = Vec::new();
+        io::stdin().read_to_end(&mut buf).map(|_| buf).context("Failed to read stdin")
+    } else {
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+    };
+    read.map_err(CliError::Malformed)
+}
+
+/// Parse a JSON document of type `T` from `path`.
+fn load_json<T: serde::de::DeserializeOwned>(path: &PathBuf, what: &str) -> Result<T, CliError> {
+    let raw = read_input(path)?;
+    serde_json::from_str(&raw)
+        .with_context(|| format!("Invalid {}", what))
+        .map_err(CliError::Malformed)
+}
+
+/// Read a proof from `path`, treating `-` as stdin.
+fn load_proof(path: &PathBuf) -> Result<ProofJson, CliError> {
+    let raw = read_input(path)?;
+    let proof = ProofJson::from_json(&raw)
+        .context("Invalid proof JSON")
+        .map_err(CliError::Malformed)?;
     info!("Loaded proof for model: {}", proof.model_id);
-    let recomputed = compute_recomputed_output_hash(&proof.model_id, &proof.input_hash);
-    info!("Local recomputed output hash: {}", recomputed);
-    info!("Output hash from proof:       {}", proof.output_hash);
+    Ok(proof)
+}
+
+fn cmd_hash(args: &ProofArgs) -> Result<serde_json::Value, CliError> {
+    let proof = load_proof(&args.proof)?;
+    let recomputed = proof.recomputed_output_hash();
+    Ok(json!({
+        "model_id": proof.model_id,
+        "input_hash": proof.input_hash,
+        "algorithm": recomputed.alg,
+        "recomputed_output_hash": recomputed,
+    }))
+}
 
-    if recomputed == proof.output_hash {
-        info!("✅ Local recompute success: proof matches");
+fn cmd_verify(args: &ProofArgs, adapters: &AdapterArgs, replay: &ReplayArgs) -> Result<serde_json::Value, CliError> {
+    let proof = load_proof(&args.proof)?;
+    let verdict = check(&adapter_registry(adapters, Some(replay))?, &proof)?;
+    let mut out = json!({
+        "model_id": proof.model_id,
+        "proof_type": verdict.proof_type,
+        "match": verdict.valid,
+        "reason": verdict.reason,
+    });
+    out.as_object_mut().expect("object").extend(verdict.details);
+    if verdict.valid {
+        Ok(out)
     } else {
-        warn!("⚠️ Local recompute mismatch - proof may be invalid for demo");
+        Err(CliError::Mismatch(out))
     }
+}
 
-    // If RPC_URL & SUBMITTER_PRIVATE_KEY & VERIFIER_CONTRACT_ADDRESS present -> attempt submission
-    let rpc_url = env::var("RPC_URL").ok();
-    let submitter_pk = env::var("SUBMITTER_PRIVATE_KEY").ok();
-    let contract_addr = env::var("VERIFIER_CONTRACT_ADDRESS").ok();
+/// The built-in adapters plus those configured on the command line. With
+/// `--wasm`, proofs that name no type are re-run instead of hash-checked.
+fn adapter_registry(args: &AdapterArgs, replay: Option<&ReplayArgs>) -> Result<AdapterRegistry, CliError> {
+    let mut registry = AdapterRegistry::default();
+    if !args.attesters.is_empty() {
+        registry.register(SignedAttestation::new(args.attesters.iter().copied()));
+    }
+    for external in &args.external {
+        registry.register(external.clone());
+    }
+    if !args.groth16_keys.is_empty() {
+        let mut groth16 = Groth16Verifier::new();
+        for (model_id, path) in &args.groth16_keys {
+            groth16
+                .add_key(model_id.clone(), &read_bytes(path)?)
+                .with_context(|| format!("Cannot load {}", path.display()))
+                .map_err(CliError::Malformed)?;
+        }
+        registry.register(groth16);
+    }
+    if !args.tee_roots.is_empty() || !args.tee_crls.is_empty() || !args.tee_measurements.is_empty() {
+        let mut tee = TeeVerifier::new();
+        for path in &args.tee_roots {
+            tee.add_roots(&read_bytes(path)?)
+                .with_context(|| format!("Cannot load {}", path.display()))
+                .map_err(CliError::Malformed)?;
+        }
+        for path in &args.tee_crls {
+            tee.add_crls(&read_bytes(path)?)
+                .with_context(|| format!("Cannot load {}", path.display()))
+                .map_err(CliError::Malformed)?;
+        }
+        for (model_id, measurement) in &args.tee_measurements {
+            tee.allow_measurement(model_id.clone(), measurement);
+        }
+        if args.tee_accept_unknown_revocation {
+            tee.accept_unknown_revocation();
+        }
+        if args.tee_accept_unevaluated_tcb {
+            tee.accept_unevaluated_tcb();
+        }
+        registry.register(tee);
+    }
+    if let Some(ReplayArgs { wasm: Some(wasm), input: Some(input), fuel }) = replay {
+        let model = Model::new(&read_bytes(wasm)?)
+            .with_context(|| format!("Cannot load model {}", wasm.display()))
+            .map_err(CliError::Malformed)?;
+        let limits = Limits { fuel: *fuel, ..Limits::default() };
+        registry.register(WasmAdapter::new(model, read_bytes(input)?, limits));
+        registry.set_default(WasmAdapter::PROOF_TYPE);
+    }
+    Ok(registry)
+}
 
-    let can_submit = rpc_url.is_some() && submitter_pk.is_some() && contract_addr.is_some();
+/// Run `proof` through the adapter for its type. A proof the adapter cannot
+/// check at all (unknown type, bad evidence, backend failure) is malformed.
+fn check(registry: &AdapterRegistry, proof: &ProofJson) -> Result<Verdict, CliError> {
+    let verdict = registry
+        .verify(proof)
+        .context("Cannot verify the proof")
+        .map_err(CliError::Malformed)?;
+    if verdict.valid {
+        info!("✅ Local verification success ({}): {}", verdict.proof_type, verdict.reason);
+    } else {
+        warn!("⚠️ Local verification failed ({}): {} - proof is invalid", verdict.proof_type, verdict.reason);
+    }
+    Ok(verdict)
+}
+
+fn parse_external(value: &str) -> Result<ExternalVerifier, String> {
+    let (proof_type, command) = value.split_once('=').ok_or("expected TYPE=COMMAND")?;
+    let mut words = command.split_whitespace();
+    let program = words.next().ok_or("empty command")?;
+    Ok(ExternalVerifier::new(proof_type, program, words.map(String::from).collect()))
+}
+
+fn parse_groth16_key(value: &str) -> Result<(ModelId, PathBuf), String> {
+    let (model_id, path) = value.split_once('=').ok_or("expected MODEL=PATH")?;
+    Ok((model_id.into(), path.into()))
+}
+
+fn parse_tee_measurement(value: &str) -> Result<(ModelId, ethers::types::Bytes), String> {
+    let (model_id, measurement) = value.split_once('=').ok_or("expected MODEL=HEX")?;
+    Ok((model_id.into(), measurement.parse().map_err(|e| format!("{e}"))?))
+}
 
-    // compute proofId using a dummy submitter address when offline:
-    let offline_submitter = "0x0000000000000000000000000000000000000000";
-    let submitter_for_id = if can_submit {
-        // if will submit, compute with wallet address later - placeholder for now
-        offline_submitter
+fn load_payload(args: &PayloadArgs) -> Result<Payload, CliError> {
+    let raw = read_bytes(&args.payload)?;
+    let text = || {
+        String::from_utf8(raw.clone()).context("Payload is not UTF-8 JSON").map_err(CliError::Malformed)
+    };
+    let dtype = || args.dtype.context("--dtype is required for tensors").map_err(CliError::Malformed);
+    let payload = match args.format {
+        PayloadFormat::Bytes => Ok(Payload::Bytes(raw.clone())),
+        PayloadFormat::Json => Payload::json(&text()?),
+        PayloadFormat::Tensor => {
+            let shape = args.shape.as_deref().context("--shape is required for raw tensors").map_err(CliError::Malformed)?;
+            Tensor::from_bytes(dtype()?, shape, &raw, args.endian).map(Payload::Tensor)
+        }
+        PayloadFormat::TensorJson => {
+            let dtype = dtype()?;
+            vericortex_sdk::canonical::parse_json(&text()?)
+                .and_then(|value| Tensor::from_json(dtype, &value))
+                .map(Payload::Tensor)
+        }
+    };
+    payload.context("Cannot canonicalize payload").map_err(CliError::Malformed)
+}
+
+/// `hash-input` / `hash-output`: the canonical digest under `field`, checked
+/// against the same field of `--proof` when given, using that field's
+/// algorithm.
+fn cmd_hash_payload(args: &PayloadArgs, field: &str) -> Result<serde_json::Value, CliError> {
+    let payload = load_payload(args)?;
+    let proof = args.proof.as_ref().map(load_proof).transpose()?;
+    let expected = proof.map(|p| if field == "input_hash" { p.input_hash } else { p.output_hash });
+    let alg = expected.map_or(args.alg, |e| e.alg);
+    let hash = payload.digest(alg).context("Cannot canonicalize payload").map_err(CliError::Malformed)?;
+    let mut out = json!({
+        field: hash,
+        "algorithm": alg,
+        "format": args.format.to_possible_value().map(|v| v.get_name().to_string()),
+    });
+    if let Payload::Tensor(tensor) = &payload {
+        out["dtype"] = json!(tensor.dtype().name());
+        out["shape"] = json!(tensor.shape());
+    }
+    let Some(expected) = expected else { return Ok(out) };
+    out["proof_hash"] = json!(expected);
+    out["match"] = json!(expected == hash);
+    if expected == hash {
+        Ok(out)
     } else {
-        offline_submitter
+        Err(CliError::Mismatch(out))
+    }
+}
+
+fn cmd_fingerprint(args: &FingerprintArgs) -> Result<serde_json::Value, CliError> {
+    let expected = if let Some(path) = &args.proof {
+        let proof = load_proof(path)?;
+        Some(proof.model_fingerprint.context("The proof has no model_fingerprint").map_err(CliError::Malformed)?)
+    } else if let Some(path) = &args.entry {
+        let entry: serde_json::Value = load_json(path, "registry entry")?;
+        let fingerprint = entry.get("fingerprint").filter(|f| !f.is_null()).context("The entry has no fingerprint");
+        let fingerprint = fingerprint
+            .and_then(|f| serde_json::from_value::<ModelFingerprint>(f.clone()).context("Invalid fingerprint"))
+            .map_err(CliError::Malformed)?;
+        Some(fingerprint)
+    } else {
+        None
     };
+    let (format, alg) = expected.map_or((args.format, args.alg), |e| (Some(e.format), e.alg));
+    let fingerprint = vericortex_sdk::fingerprint::fingerprint(&args.path, format, alg)
+        .context("Cannot fingerprint the model")
+        .map_err(CliError::Malformed)?;
+    let summary = fingerprint.summary();
+    info!("Fingerprinted {} leaves of {}", summary.leaves, args.path.display());
 
-    let proof_id = compute_proof_id(
-        &proof.model_id,
-        &proof.input_hash,
-        &proof.output_hash,
-        submitter_for_id,
-    );
+    let mut out = json!({ "model_fingerprint": summary });
+    if args.leaves {
+        out["leaves"] = json!(fingerprint.leaves);
+    }
+    let Some(expected) = expected else { return Ok(out) };
+    out["expected"] = json!(expected);
+    out["match"] = json!(expected == summary);
+    if expected == summary {
+        Ok(out)
+    } else {
+        Err(CliError::Mismatch(out))
+    }
+}
+
+fn cmd_proof_id(args: &ProofArgs, anchor: &AnchorArgs, submitter: Option<Address>) -> Result<serde_json::Value, CliError> {
+    let proof = load_proof(&args.proof)?;
+    let submitter = submitter.or(proof.submitter).unwrap_or_default();
+    let anchor = resolve_anchor(&proof, anchor);
+    let proof_id = compute_proof_id(&anchor.preimage(&proof.model_id), &submitter);
     info!("Computed proofId (local): {}", proof_id);
+    Ok(json!({
+        "model_id": proof.model_id,
+        "schema": anchor.schema,
+        "submitter": submitter,
+        "proof_id": proof_id,
+    }))
+}
+
+/// Collect the envelope's on-chain fields, applying any CLI overrides.
+fn resolve_anchor(proof: &ProofJson, overrides: &AnchorArgs) -> Anchor {
+    let mut proof = proof.clone();
+    if let Some(v) = &overrides.model_version {
+        proof.model_version = v.clone();
+    }
+    if let Some(h) = overrides.trace_hash {
+        proof.trace_hash = Some(h);
+    }
+    if let Some(h) = overrides.proof_hash {
+        proof.proof_hash = Some(h);
+    }
+
+    Anchor {
+        schema: overrides.schema,
+        version: proof.model_version.clone(),
+        input_hash: proof.input_hash.digest,
+        output_hash: proof.output_hash.digest,
+        trace_hash: proof.trace_hash32(),
+        proof_hash: proof.bundle_hash(),
+    }
+}
+
+/// The shared configuration, with a command's `--rpc-url` / `--contract` on top.
+fn load_config(args: &ConfigArgs, contract: Option<&ContractArgs>) -> Result<Config, CliError> {
+    let mut sources = Sources::from_args(args).map_err(CliError::Config)?;
+    if let Some(contract) = contract {
+        if let Some(url) = &contract.rpc_url {
+            sources = sources.set("network.rpc_url", url);
+        }
+        if let Some(address) = contract.contract {
+            sources = sources.set("network.verifier_contract", address);
+        }
+    }
+    Config::from_sources(sources).map_err(CliError::Config)
+}
+
+fn chain_config(args: &ConfigArgs) -> Result<ChainConfig, CliError> {
+    ChainConfig::from_config(&load_config(args, None)?).map_err(CliError::Config)
+}
 
-    if !can_submit {
-        info!("RPC_URL or SUBMITTER_PRIVATE_KEY or VERIFIER_CONTRACT_ADDRESS not set. Running in OFFLINE mode.");
-        info!("To enable on-chain submission, set values in .env (copy .env.example -> .env).");
-        return Ok(());
-    }
-
-    // If we reach here, attempt to submit to chain
-    info!("RPC and credentials found. Preparing to submit to contract...");
-
-    // perform on-chain submission using ethers
-    use ethers::prelude::*;
-    use std::sync::Arc;
-
-    let rpc = rpc_url.unwrap();
-    let pk = submitter_pk.unwrap();
-    let contract = contract_addr.unwrap();
-    let chain_id: u64 = env::var("CHAIN_ID").ok().and_then(|s| s.parse().ok()).unwrap_or(1337);
-
-    // provider + wallet
-    let provider = Provider::<Http>::try_from(rpc.clone())?.interval(std::time::Duration::from_millis(200u64));
-    let wallet: LocalWallet = pk.parse::<LocalWallet>()?.with_chain_id(chain_id);
-    let wallet_addr = wallet.address();
-    let client = SignerMiddleware::new(provider, wallet);
-    let client = Arc::new(client);
-
-    // abigen the contract inline (same interface as demo)
-    abigen!(
-        VeriCortexVerifierContract,
-        r#"[
-            function submitProof(string modelId, bytes32 inputHash, bytes32 outputHash) public returns (bytes32)
-            event ProofSubmitted(bytes32 indexed proofId, address indexed submitter, string modelId)
-            event ProofVerified(bytes32 indexed proofId, bool success)
-        ]"#
-    );
-
-    let contract_address: Address = contract.parse()?;
-    let contract = VeriCortexVerifierContract::new(contract_address, client.clone());
-
-    // convert input/output hex to H256
-    let to_h256 = |h: &str| -> Result<H256, anyhow::Error> {
-        let clean = h.trim_start_matches("0x");
-        let bytes = hex::decode(clean)?;
-        if bytes.len() > 32 { anyhow::bail!("hash too long"); }
-        let mut arr = [0u8; 32];
-        arr[32 - bytes.len()..].copy_from_slice(&bytes);
-        Ok(H256::from(arr))
+async fn cmd_submit(
+    config: &ConfigArgs,
+    args: &ProofArgs,
+    anchor: &AnchorArgs,
+    adapters: &AdapterArgs,
+    replay: &ReplayArgs,
+    dry_run: bool,
+) -> Result<serde_json::Value, CliError> {
+    let proof = load_proof(&args.proof)?;
+    let anchor = resolve_anchor(&proof, anchor);
+    let verdict = check(&adapter_registry(adapters, Some(replay))?, &proof)?;
+    let valid = verdict.valid;
+
+    let cfg = chain_config(config)?;
+    if dry_run {
+        let (proof_id, outcome) = chain::dry_run(&cfg, &proof.model_id, &anchor, valid).await.map_err(chain_err)?;
+        let (status, gas_estimate, existing) = match outcome {
+            chain::Preflight::Exists(record) => ("exists", None, Some(record)),
+            chain::Preflight::Ready { gas_estimate } => ("would_submit", Some(gas_estimate.as_u64()), None),
+        };
+        return Ok(json!({
+            "dry_run": true,
+            "model_id": proof.model_id,
+            "schema": anchor.schema,
+            "proof_id": proof_id,
+            "valid": valid,
+            "verdict": verdict,
+            "status": status,
+            "gas_estimate": gas_estimate,
+            "onchain": existing,
+        }));
+    }
+
+    let mut sender = chain::start(&cfg).await.map_err(chain_err)?;
+    let sub = chain::submit(&mut sender, &proof.model_id, &anchor, valid)
+        .await
+        .map_err(chain_err)?;
+
+    Ok(json!({
+        "model_id": proof.model_id,
+        "schema": anchor.schema,
+        "submitter": sub.submitter,
+        "proof_id": sub.proof_id,
+        "onchain_proof_id": sub.onchain_proof_id,
+        "valid": valid,
+        "verdict": verdict,
+        "already_submitted": sub.already_submitted,
+        "tx_hash": sub.tx_hash.map(|h| format!("{:?}", h)),
+        "block_number": sub.block_number,
+    }))
+}
+
+/// Load proofs from JSON files, or JSON Lines files (`.jsonl`) with one proof per line.
+fn load_proofs(inputs: &[PathBuf]) -> Result<Vec<ProofJson>, CliError> {
+    let mut proofs = Vec::new();
+    for path in inputs {
+        if path.extension().is_some_and(|e| e == "jsonl") {
+            let raw = read_input(path)?;
+            for (n, line) in raw.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+                let proof = ProofJson::from_json(line)
+                    .with_context(|| format!("Invalid proof JSON at {}:{}", path.display(), n + 1))
+                    .map_err(CliError::Malformed)?;
+                proofs.push(proof);
+            }
+        } else {
+            proofs.push(load_proof(path)?);
+        }
+    }
+    Ok(proofs)
+}
+
+async fn cmd_batch(config: &ConfigArgs, args: &BatchArgs) -> Result<serde_json::Value, CliError> {
+    let proofs = load_proofs(&args.inputs)?;
+    if proofs.is_empty() || args.max_batch == 0 {
+        return Err(CliError::Malformed(anyhow::anyhow!("No proofs to batch")));
+    }
+    // Receipts are named by bundle hash, so two proofs sharing one would
+    // overwrite each other's.
+    let mut seen = std::collections::HashMap::new();
+    for (n, proof) in proofs.iter().enumerate() {
+        if let Some(first) = seen.insert(proof.bundle_hash(), n) {
+            return Err(CliError::Malformed(anyhow::anyhow!(
+                "Proofs {} and {} have the same bundle hash {}",
+                first + 1,
+                n + 1,
+                proof.bundle_hash()
+            )));
+        }
+    }
+    let cfg = if args.submit { Some(chain_config(config)?) } else { None };
+    let adapters = adapter_registry(&args.adapters, None)?;
+    std::fs::create_dir_all(&args.receipts)
+        .with_context(|| format!("Failed to create {}", args.receipts.display()))
+        .map_err(CliError::Malformed)?;
+
+    // A batch record has one `valid` flag, so valid and invalid proofs are
+    // batched apart and each record's flag holds for every proof under it.
+    let (mut valid_proofs, mut invalid_proofs) = (Vec::new(), Vec::new());
+    for proof in proofs {
+        if check(&adapters, &proof)?.valid {
+            valid_proofs.push(proof);
+        } else {
+            invalid_proofs.push(proof);
+        }
+    }
+    let groups = [(true, valid_proofs), (false, invalid_proofs)];
+    let chunks = groups.iter().flat_map(|(valid, proofs)| proofs.chunks(args.max_batch).map(move |chunk| (*valid, chunk)));
+
+    let mut sender = match &cfg {
+        Some(cfg) => Some(chain::start(cfg).await.map_err(chain_err)?),
+        None => None,
     };
+    let mut batches = Vec::new();
+    for (valid, chunk) in chunks {
+        let batch = ProofBatch::new(chunk.to_vec());
+        let (model_id, version) = match (&args.model_id, batch.common_model()) {
+            (Some(id), _) => match args.model_version.as_deref().or(batch.common_version()) {
+                Some(version) => (ModelId::from(id.as_str()), version.to_string()),
+                None => {
+                    return Err(CliError::Malformed(anyhow::anyhow!(
+                        "Batch mixes model versions; pass --model-version to name the batch record"
+                    )))
+                }
+            },
+            (None, Some((id, v))) => (id.clone(), args.model_version.clone().unwrap_or_else(|| v.to_string())),
+            (None, None) => {
+                return Err(CliError::Malformed(anyhow::anyhow!(
+                    "Batch mixes models; pass --model-id to name the batch record"
+                )))
+            }
+        };
+        let verdict = if valid { "valid" } else { "invalid" };
+        info!("Batch of {} {} proofs for {}: root {}", batch.len(), verdict, model_id, batch.root());
 
-    let input32 = to_h256(&proof.input_hash)?;
-    let output32 = to_h256(&proof.output_hash)?;
+        let anchored = match &mut sender {
+            Some(sender) => {
+                let anchor = Anchor::batch(version.clone(), batch.root());
+                let sub = chain::submit(sender, &model_id, &anchor, valid)
+                    .await
+                    .map_err(chain_err)?;
+                let tx_hash = sub
+                    .tx_hash
+                    .context("Batch record exists on-chain but its submission tx was not found")
+                    .map_err(CliError::Chain)?;
+                Some(BatchAnchor {
+                    proof_id: sub.onchain_proof_id.unwrap_or(sub.proof_id),
+                    tx_hash: Hash32(tx_hash.0),
+                    block_number: sub.block_number,
+                })
+            }
+            None => None,
+        };
 
-    info!("Submitting transaction to contract: {}", contract_address);
+        for receipt in batch.receipts(anchored.as_ref()) {
+            let path = args.receipts.join(format!("{}.json", receipt.proof.bundle_hash()));
+            let body = serde_json::to_string_pretty(&receipt).expect("receipt serializes");
+            std::fs::write(&path, body)
+                .with_context(|| format!("Failed to write receipt {}", path.display()))
+                .map_err(CliError::Malformed)?;
+        }
+
+        batches.push(json!({
+            "model_id": model_id,
+            "model_version": version,
+            "size": batch.len(),
+            "batch_root": batch.root(),
+            "valid": valid,
+            "proof_id": anchored.as_ref().map(|a| a.proof_id),
+            "tx_hash": anchored.as_ref().map(|a| a.tx_hash),
+        }));
+    }
 
-    let pending_tx = contract
-        .submit_proof(proof.model_id.clone(), input32, output32)
-        .legacy()
-        .send()
+    Ok(json!({ "receipts": args.receipts, "batches": batches }))
+}
+
+fn cmd_verify_receipt(args: &InputArgs) -> Result<serde_json::Value, CliError> {
+    let receipt: BatchReceipt = load_json(&args.input, "batch receipt")?;
+    let valid = receipt.verify();
+    let out = json!({
+        "bundle_hash": receipt.proof.bundle_hash(),
+        "batch_root": receipt.batch_root,
+        "index": receipt.path.index,
+        "proof_id": receipt.anchor.as_ref().map(|a| a.proof_id),
+        "valid": valid,
+    });
+    if valid {
+        Ok(out)
+    } else {
+        Err(CliError::Mismatch(out))
+    }
+}
+
+fn cmd_trace(command: &TraceCommand) -> Result<serde_json::Value, CliError> {
+    match command {
+        TraceCommand::Root(args) => {
+            let trace: Trace = load_json(&args.input, "execution trace")?;
+            Ok(json!({ "trace_hash": trace.root(), "steps": trace.steps.len() }))
+        }
+        TraceCommand::Prove { input, steps } => {
+            let trace: Trace = load_json(&input.input, "execution trace")?;
+            let proofs = trace
+                .prove(steps)
+                .with_context(|| format!("Step index out of range (trace has {} steps)", trace.steps.len()))
+                .map_err(CliError::Malformed)?;
+            Ok(json!({ "trace_hash": trace.root(), "proofs": proofs }))
+        }
+        TraceCommand::Verify { input, trace_hash } => {
+            let proof: StepProof = load_json(&input.input, "step proof")?;
+            let valid = proof.verify(trace_hash);
+            let out = json!({ "trace_hash": trace_hash, "index": proof.index(), "valid": valid });
+            if valid {
+                Ok(out)
+            } else {
+                Err(CliError::Mismatch(out))
+            }
+        }
+    }
+}
+
+async fn cmd_outbox(config: &ConfigArgs, command: &OutboxCommand) -> Result<serde_json::Value, CliError> {
+    let (path, entries) = match command {
+        OutboxCommand::List => {
+            let path = load_config(config, None)?.prover.outbox;
+            let entries = vericortex_submitter::Outbox::open(&path).and_then(|o| o.list()).map_err(CliError::Chain)?;
+            (path, entries)
+        }
+        OutboxCommand::Resume => {
+            let cfg = chain_config(config)?;
+            let entries = chain::resume(&cfg).await.map_err(CliError::Chain)?;
+            (cfg.outbox, entries)
+        }
+    };
+    Ok(json!({ "outbox": path, "entries": entries }))
+}
+
+fn reader_config(config: &ConfigArgs, args: &ContractArgs) -> Result<ReaderConfig, CliError> {
+    ReaderConfig::from_config(&load_config(config, Some(args))?).map_err(CliError::Config)
+}
+
+async fn cmd_read(config: &ConfigArgs, args: &ReadArgs) -> Result<serde_json::Value, CliError> {
+    let cfg = reader_config(config, &args.contract)?;
+    let proof = reader::read_proof(&cfg, &args.proof_id)
         .await
-        .context("submit tx failed")?;
+        .map_err(CliError::Chain)?
+        .ok_or(CliError::NotFound(args.proof_id))?;
+    let mut out = serde_json::to_value(&proof).expect("proof record serializes");
+    out["proof_id"] = json!(args.proof_id);
+    Ok(out)
+}
 
-    info!("Submitted tx hash: {:?}", pending_tx.tx_hash());
+async fn cmd_is_valid(config: &ConfigArgs, args: &ReadArgs) -> Result<serde_json::Value, CliError> {
+    let cfg = reader_config(config, &args.contract)?;
+    let valid = reader::is_valid(&cfg, &args.proof_id).await.map_err(CliError::Chain)?;
+    let out = json!({ "proof_id": args.proof_id, "valid": valid });
+    if valid {
+        Ok(out)
+    } else {
+        Err(CliError::Mismatch(out))
+    }
+}
 
-    let receipt = pending_tx.await.context("tx await failed")?;
-    info!("Tx mined in block: {:?}", receipt.block_number);
+async fn cmd_listen(
+    config: &ConfigArgs,
+    args: &ContractArgs,
+    from_block: Option<u64>,
+    count: Option<usize>,
+) -> Result<serde_json::Value, CliError> {
+    let cfg = reader_config(config, args)?;
+    let seen = reader::listen(&cfg, from_block, count).await.map_err(CliError::Chain)?;
+    info!("Stopped after {} events", seen);
+    Ok(serde_json::Value::Null)
+}
 
-    // With wallet address known, recompute proofId properly
-    let final_proof_id = compute_proof_id(
-        &proof.model_id,
-        &proof.input_hash,
-        &proof.output_hash,
-        &format!("0x{:x}", wallet_addr),
-    );
-    info!("Final computed proofId: {}", final_proof_id);
+#[tokio::main]
+async fn main() -> ExitCode {
+    env_logger::init();
+    dotenv().ok();
 
-    info!("Done. Monitor ProofSubmitted / ProofVerified events on-chain.");
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        Command::Hash(args) => cmd_hash(args),
+        Command::Verify { proof, adapters, replay } => cmd_verify(proof, adapters, replay),
+        Command::HashInput(args) => cmd_hash_payload(args, "input_hash"),
+        Command::HashOutput(args) => cmd_hash_payload(args, "output_hash"),
+        Command::Fingerprint(args) => cmd_fingerprint(args),
+        Command::ProofId { proof, anchor, submitter } => cmd_proof_id(proof, anchor, *submitter),
+        Command::Submit { proof, anchor, adapters, replay, dry_run } => {
+            cmd_submit(&cli.config, proof, anchor, adapters, replay, *dry_run).await
+        }
+        Command::Trace(command) => cmd_trace(command),
+        Command::Batch(args) => cmd_batch(&cli.config, args).await,
+        Command::VerifyReceipt(args) => cmd_verify_receipt(args),
+        Command::Read(args) => cmd_read(&cli.config, args).await,
+        Command::IsValid(args) => cmd_is_valid(&cli.config, args).await,
+        Command::Outbox(command) => cmd_outbox(&cli.config, command).await,
+        Command::Listen { contract, from_block, count } => cmd_listen(&cli.config, contract, *from_block, *count).await,
+    };
 
-    Ok(())
+    match result {
+        // `listen` has already streamed its output
+        Ok(serde_json::Value::Null) => ExitCode::SUCCESS,
+        Ok(out) => {
+            println!("{}", out);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            let code = err.exit_code();
+            let out = match err {
+                CliError::Mismatch(out) => out,
+                CliError::Malformed(e) => json!({ "error": "malformed_input", "message": format!("{:#}", e) }),
+                CliError::Chain(e) => json!({ "error": "chain_failure", "message": format!("{:#}", e) }),
+                CliError::Reverted(revert) => {
+                    json!({ "error": "reverted", "kind": revert.kind(), "reason": revert.to_string() })
+                }
+                CliError::NotFound(id) => json!({ "error": "not_found", "proof_id": id }),
+                CliError::Config(e) => json!({ "error": "invalid_config", "message": e.to_string() }),
+            };
+            println!("{}", out);
+            ExitCode::from(code)
+        }
+    }
 }
@@ -0,0 +1,113 @@
+//! Read-only access to the verifier contract: proof records, validity and
+//! the event stream. Needs only an RPC endpoint and the contract address.
+
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use ethers::providers::StreamExt;
+use log::info;
+use serde_json::json;
+use std::sync::Arc;
+use vericortex_config::{Config, ConfigError};
+use vericortex_sdk::contract::{timestamp, OnChainProof, ProofCortexVerifier, ProofCortexVerifierEvents};
+use vericortex_sdk::ProofId;
+
+/// RPC endpoint and contract address.
+pub struct ReaderConfig {
+    pub rpc_url: String,
+    pub contract_address: Address,
+}
+
+impl ReaderConfig {
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        let network = config.network()?;
+        Ok(Self { rpc_url: network.rpc_url, contract_address: Address::from(network.verifier_contract.0) })
+    }
+
+    fn contract(&self) -> Result<ProofCortexVerifier<Provider<Http>>> {
+        let provider = Provider::<Http>::try_from(self.rpc_url.as_str())?.interval(std::time::Duration::from_millis(200u64));
+        Ok(ProofCortexVerifier::new(self.contract_address, Arc::new(provider)))
+    }
+}
+
+/// `getProof`, or `None` when the contract holds no record for `proof_id`.
+pub async fn read_proof(cfg: &ReaderConfig, proof_id: &ProofId) -> Result<Option<OnChainProof>> {
+    let record = cfg.contract()?.get_proof(proof_id.0).call().await.context("getProof failed")?;
+    let proof = OnChainProof::try_from(record)?;
+    Ok(proof.exists().then_some(proof))
+}
+
+pub async fn is_valid(cfg: &ReaderConfig, proof_id: &ProofId) -> Result<bool> {
+    cfg.contract()?.is_proof_valid(proof_id.0).call().await.context("isProofValid failed")
+}
+
+/// One decoded event as a flat JSON object.
+fn event_json(event: &ProofCortexVerifierEvents, meta: &LogMeta) -> Result<serde_json::Value> {
+    let mut out = match event {
+        ProofCortexVerifierEvents::ProofSubmittedFilter(ev) => json!({
+            "event": "ProofSubmitted",
+            "proof_id": ProofId(ev.proof_id),
+            "submitter": vericortex_sdk::Address(ev.submitter.0),
+            "model_id": ev.model_id,
+            "valid": ev.valid,
+        }),
+        ProofCortexVerifierEvents::ProofUpdatedFilter(ev) => json!({
+            "event": "ProofUpdated",
+            "proof_id": ProofId(ev.proof_id),
+            "valid": ev.valid,
+            "timestamp": timestamp(ev.timestamp)?,
+        }),
+        ProofCortexVerifierEvents::ProofAnchoredFilter(ev) => json!({
+            "event": "ProofAnchored",
+            "proof_id": ProofId(ev.proof_id),
+            "proof_hash": vericortex_sdk::Hash32(ev.proof_hash),
+            "timestamp": timestamp(ev.time)?,
+        }),
+    };
+    out["block_number"] = json!(meta.block_number.as_u64());
+    out["tx_hash"] = json!(meta.transaction_hash);
+    out["log_index"] = json!(meta.log_index.low_u64());
+    Ok(out)
+}
+
+/// Print contract events as JSON lines: first every event from `from_block`
+/// up to the current head (if given), then new events as they are mined.
+/// Stops after `count` events, otherwise runs until the stream ends.
+pub async fn listen(cfg: &ReaderConfig, from_block: Option<u64>, count: Option<usize>) -> Result<usize> {
+    let contract = cfg.contract()?;
+    let head = contract.client().get_block_number().await.context("eth_blockNumber failed")?.as_u64();
+    let mut seen = 0usize;
+    let done = |seen: usize| count.is_some_and(|n| seen >= n);
+
+    if let Some(from) = from_block {
+        let past = contract
+            .events()
+            .from_block(from)
+            .to_block(head)
+            .query_with_meta()
+            .await
+            .context("eth_getLogs failed")?;
+        for (event, meta) in past {
+            if done(seen) {
+                return Ok(seen);
+            }
+            println!("{}", event_json(&event, &meta)?);
+            seen += 1;
+        }
+    }
+    if done(seen) {
+        return Ok(seen);
+    }
+
+    info!("Listening for verifier events after block {}. CTRL+C to exit.", head);
+    let events = contract.events().from_block(head + 1);
+    let mut stream = events.stream_with_meta().await.context("eth_newFilter failed")?;
+    while let Some(item) = stream.next().await {
+        let (event, meta) = item.context("Failed to decode event")?;
+        println!("{}", event_json(&event, &meta)?);
+        seen += 1;
+        if done(seen) {
+            break;
+        }
+    }
+    Ok(seen)
+}
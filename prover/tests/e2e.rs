@@ -0,0 +1,849 @@
+//! End-to-end: the prover binary against a local dev chain, fully offline.
+
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+use ethers::abi::{self, ParamType, Token};
+use ethers::contract::EthEvent;
+use ethers::types::{Address, Log, H256, U256};
+use ethers::utils::format_ether;
+use serde_json::{json, Value};
+use ethers::signers::LocalWallet;
+use vericortex_devchain::{dev_address, dev_wallet, DevChain, MockRemoteSigner, DEFAULT_PRIORITY_FEE, DEV_KEYS};
+use vericortex_sdk::contract::{ProofAnchoredFilter, ProofSubmittedFilter};
+use vericortex_sdk::adapter::Attestation;
+use vericortex_sdk::{compute_proof_id, recompute_output_hash, HashAlg, Hash32, Payload, ProofJson};
+
+const SAMPLE_V2: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/sample_proof_v2.json");
+const SAMPLE_V1: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/sample_proof.json");
+const MALFORMED_HASH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/malformed_hash_proof.json");
+const GWEI: u64 = 1_000_000_000;
+
+/// The prover configured for `chain`, with an outbox private to that chain.
+fn prover_cmd(chain: &DevChain, verifier: Address, key: &str) -> Command {
+    let port = chain.url().rsplit(':').next().unwrap().to_string();
+    let outbox = std::env::temp_dir().join(format!("vericortex-e2e-{}-outbox-{port}.sqlite", std::process::id()));
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_verifier_offchain"));
+    cmd.env("RPC_URL", chain.url())
+        .env("SUBMITTER_PRIVATE_KEY", key)
+        .env("VERIFIER_CONTRACT_ADDRESS", format!("{verifier:?}"))
+        .env("CHAIN_ID", chain.chain_id().to_string())
+        .env("OUTBOX_DB", outbox);
+    cmd
+}
+
+fn prover(chain: &DevChain, verifier: Address, key: &str, args: &[&str]) -> Output {
+    prover_cmd(chain, verifier, key).args(args).output().expect("run prover")
+}
+
+/// Start `submit` with automine off and kill it once its tx is in the mempool.
+fn kill_after_broadcast(chain: &DevChain, verifier: Address) -> H256 {
+    chain.set_automine(false);
+    let mut child = prover_cmd(chain, verifier, DEV_KEYS[0])
+        .args(["submit", SAMPLE_V2])
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("start prover");
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let tx_hash = loop {
+        if let Some(tx) = chain.pending().first() {
+            break tx.hash;
+        }
+        assert!(Instant::now() < deadline, "prover never broadcast");
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    child.kill().unwrap();
+    child.wait().unwrap();
+    tx_hash
+}
+
+fn stdout_json(out: &Output) -> Value {
+    assert!(out.status.success(), "prover failed: {}", String::from_utf8_lossy(&out.stderr));
+    serde_json::from_slice(&out.stdout).expect("prover prints JSON")
+}
+
+/// Assert the typed revert error for `reason`.
+fn assert_reverted(out: &Output, kind: &str, reason: &str) {
+    assert_eq!(out.status.code(), Some(3));
+    let err: Value = serde_json::from_slice(&out.stdout).expect("prover prints JSON");
+    assert_eq!(err["error"], "reverted", "{err}");
+    assert_eq!(err["kind"], kind);
+    assert_eq!(err["reason"], reason);
+}
+
+fn logs_named(chain: &DevChain, signature: H256, proof_id: &str) -> Vec<Log> {
+    let id: H256 = proof_id.parse().unwrap();
+    chain.logs().into_iter().filter(|l| l.topics[0] == signature && l.topics[1] == id).collect()
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vericortex-e2e-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn submit_emits_events_for_the_locally_derived_proof_id() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+
+    let out = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["submit", SAMPLE_V2]));
+    let proof_id = out["proof_id"].as_str().unwrap();
+    assert_eq!(out["onchain_proof_id"], out["proof_id"]);
+    assert_eq!(out["valid"], true);
+
+    // Same id from the SDK alone, with the dev account as submitter.
+    let proof = ProofJson::from_json(&std::fs::read_to_string(SAMPLE_V2).unwrap()).unwrap();
+    let submitter = vericortex_sdk::Address(dev_address(0).0);
+    let anchor = vericortex_sdk::ProofIdPreimage::ProofCortex {
+        model_id: &proof.model_id,
+        version: &proof.model_version,
+        proof_hash: &proof.bundle_hash(),
+    };
+    assert_eq!(compute_proof_id(&anchor, &submitter).to_string(), proof_id);
+
+    let submitted = logs_named(&chain, ProofSubmittedFilter::signature(), proof_id);
+    assert_eq!(submitted.len(), 1);
+    assert_eq!(submitted[0].topics[2], H256::from(dev_address(0)));
+    let fields = abi::decode(&[ParamType::String, ParamType::Bool], &submitted[0].data).unwrap();
+    assert_eq!(fields, vec![Token::String(proof.model_id.to_string()), Token::Bool(true)]);
+
+    let anchored = logs_named(&chain, ProofAnchoredFilter::signature(), proof_id);
+    assert_eq!(anchored.len(), 1);
+    let fields = abi::decode(&[ParamType::FixedBytes(32), ParamType::Uint(256)], &anchored[0].data).unwrap();
+    assert_eq!(fields[0], Token::FixedBytes(proof.bundle_hash().0.to_vec()));
+
+    let record = chain.proof(verifier, proof_id.parse::<H256>().unwrap().0).expect("proof stored");
+    assert_eq!(record.input_hash, proof.input_hash.digest.0);
+    assert_eq!(record.output_hash, proof.output_hash.digest.0);
+    assert!(record.valid);
+}
+
+#[test]
+fn submit_from_untrusted_key_is_refused_before_sending() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+
+    let out = prover(&chain, verifier, DEV_KEYS[1], &["submit", SAMPLE_V2]);
+    assert_reverted(&out, "not_authorized", "Not authorized");
+    assert!(chain.logs().is_empty());
+    assert!(chain.pending().is_empty());
+    assert_eq!(chain.state().nonce(&dev_address(1), None).unwrap(), 0, "nothing was sent");
+}
+
+#[test]
+fn resubmitting_the_same_proof_reuses_the_outbox_entry() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+
+    let first = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["submit", SAMPLE_V2]));
+    let again = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["submit", SAMPLE_V2]));
+    assert_eq!(again["tx_hash"], first["tx_hash"]);
+    assert_eq!(chain.state().nonce(&dev_address(0), None).unwrap(), 1);
+
+    let outbox = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["outbox", "list"]));
+    let entries = outbox["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["state"], "confirmed");
+    assert_eq!(entries[0]["proof_id"], first["proof_id"]);
+    assert_eq!(entries[0]["nonce"], 0);
+
+    // Without the outbox record, getProof finds the proof and nothing is sent.
+    let fresh = scratch_dir("fresh-outbox");
+    let out = prover_cmd(&chain, verifier, DEV_KEYS[0])
+        .args(["submit", SAMPLE_V2])
+        .env("OUTBOX_DB", &fresh)
+        .output()
+        .unwrap();
+    let out = stdout_json(&out);
+    assert_eq!(out["already_submitted"], true);
+    assert_eq!(out["proof_id"], first["proof_id"]);
+    assert_eq!(out["tx_hash"], first["tx_hash"]);
+    assert_eq!(out["block_number"], first["block_number"]);
+    assert_eq!(chain.state().nonce(&dev_address(0), None).unwrap(), 1);
+    let _ = std::fs::remove_file(&fresh);
+}
+
+#[test]
+fn dry_run_simulates_without_sending() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+
+    let out = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["submit", "--dry-run", SAMPLE_V2]));
+    assert_eq!(out["dry_run"], true);
+    assert_eq!(out["status"], "would_submit");
+    assert!(out["gas_estimate"].as_u64().unwrap() > 21_000);
+    assert!(chain.logs().is_empty());
+    assert_eq!(chain.state().nonce(&dev_address(0), None).unwrap(), 0);
+
+    let denied = prover(&chain, verifier, DEV_KEYS[1], &["submit", "--dry-run", SAMPLE_V2]);
+    assert_reverted(&denied, "not_authorized", "Not authorized");
+
+    let submitted = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["submit", SAMPLE_V2]));
+    assert_eq!(submitted["already_submitted"], false);
+    let out = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["submit", "--dry-run", SAMPLE_V2]));
+    assert_eq!(out["status"], "exists");
+    assert_eq!(out["proof_id"], submitted["proof_id"]);
+    assert_eq!(out["onchain"]["valid"], true);
+    assert_eq!(out["gas_estimate"], Value::Null);
+}
+
+#[test]
+fn config_file_profiles_select_the_network() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+    let dir = scratch_dir("config");
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("vericortex.toml");
+    std::fs::write(
+        &file,
+        format!(
+            "default_profile = \"staging\"\n\n\
+             [profiles.staging.network]\nrpc_url = \"{}\"\nchain_id = {}\nverifier_contract = \"{verifier:?}\"\n\n\
+             [profiles.blockdag-mainnet.network]\nrpc_url = \"{}\"\nchain_id = 1404\nverifier_contract = \"{verifier:?}\"\n",
+            chain.url(),
+            chain.chain_id(),
+            chain.url(),
+        ),
+    )
+    .unwrap();
+    let run = |args: &[&str]| {
+        let mut cmd = prover_cmd(&chain, verifier, DEV_KEYS[0]);
+        cmd.env_remove("RPC_URL").env_remove("VERIFIER_CONTRACT_ADDRESS").env_remove("CHAIN_ID");
+        cmd.arg("--config").arg(&file).args(args).output().unwrap()
+    };
+
+    let out = stdout_json(&run(&["submit", "--dry-run", SAMPLE_V2]));
+    assert_eq!(out["status"], "would_submit");
+
+    // A chain id the node does not serve is caught before anything is signed.
+    let out = run(&["--set", "network.chain_id=7", "--set", "signer.allow_raw_key=true", "submit", SAMPLE_V2]);
+    assert_eq!(out.status.code(), Some(3));
+    let err: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(err["message"].as_str().unwrap().contains("configured chain_id is 7"), "{err}");
+
+    for args in [&["--profile", "nope", "submit", SAMPLE_V2][..], &["--profile", "blockdag-mainnet", "submit", SAMPLE_V2]] {
+        let out = run(args);
+        assert_eq!(out.status.code(), Some(5));
+        let err: Value = serde_json::from_slice(&out.stdout).unwrap();
+        assert_eq!(err["error"], "invalid_config", "{err}");
+    }
+    assert!(chain.logs().is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn resume_rebroadcasts_a_transaction_lost_with_the_prover() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+    let tx_hash = kill_after_broadcast(&chain, verifier);
+
+    let outbox = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["outbox", "list"]));
+    assert_eq!(outbox["entries"][0]["state"], "sent");
+    assert_eq!(outbox["entries"][0]["tx_hash"], format!("{tx_hash:?}"));
+
+    // The node restarts and forgets its mempool.
+    assert_eq!(chain.drop_pending(), 1);
+    chain.set_automine(true);
+    let resumed = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["outbox", "resume"]));
+    let entry = &resumed["entries"][0];
+    assert_eq!(entry["state"], "confirmed");
+    assert_eq!(entry["tx_hash"], format!("{tx_hash:?}"), "the signed tx is rebroadcast, not re-signed");
+
+    let proof_id = entry["proof_id"].as_str().unwrap();
+    assert!(chain.proof(verifier, proof_id.parse::<H256>().unwrap().0).is_some());
+    let out = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["submit", SAMPLE_V2]));
+    assert_eq!(out["tx_hash"], format!("{tx_hash:?}"));
+    assert_eq!(logs_named(&chain, ProofSubmittedFilter::signature(), proof_id).len(), 1);
+    assert_eq!(chain.state().nonce(&dev_address(0), None).unwrap(), 1);
+}
+
+#[test]
+fn restarted_submit_reconciles_a_transaction_mined_meanwhile() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+    let tx_hash = kill_after_broadcast(&chain, verifier);
+
+    chain.mine();
+    chain.set_automine(true);
+    let out = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["submit", SAMPLE_V2]));
+    assert_eq!(out["tx_hash"], format!("{tx_hash:?}"));
+    assert_eq!(out["onchain_proof_id"], out["proof_id"]);
+    assert!(chain.pending().is_empty());
+    assert_eq!(chain.state().nonce(&dev_address(0), None).unwrap(), 1);
+
+    let outbox = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["outbox", "list"]));
+    assert_eq!(outbox["entries"].as_array().unwrap().len(), 1);
+    assert_eq!(outbox["entries"][0]["state"], "confirmed");
+    assert_eq!(outbox["entries"][0]["block_number"], out["block_number"]);
+}
+
+#[test]
+fn batch_submit_anchors_the_root_and_receipts_verify() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+    let receipts = scratch_dir("batch");
+    std::fs::create_dir_all(&receipts).unwrap();
+
+    // Attested by a key that is not trusted, so it fails verification.
+    let key = ethers::core::k256::ecdsa::SigningKey::from_slice(&[7; 32]).unwrap();
+    let mut untrusted = ProofJson::new("vericortex-demo", Hash32([1; 32]), Hash32([2; 32]));
+    untrusted.proof_type = Some("signed-attestation".into());
+    untrusted.evidence = Some(json!(Attestation::sign(&untrusted, &key)));
+    let untrusted_path = receipts.join("untrusted.proof");
+    std::fs::write(&untrusted_path, untrusted.to_json_pretty().unwrap()).unwrap();
+
+    let args = [
+        "batch",
+        SAMPLE_V2,
+        untrusted_path.to_str().unwrap(),
+        SAMPLE_V1,
+        "--attester",
+        &format!("0x{}", "11".repeat(20)),
+        "--model-id",
+        "vericortex-demo",
+        "--model-version",
+        "v0.1.0",
+        "--submit",
+        "--receipts",
+        receipts.to_str().unwrap(),
+    ];
+    let out = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &args));
+    // One bad proof does not invalidate the others: it is batched on its own.
+    let batches = out["batches"].as_array().unwrap();
+    assert_eq!(batches.iter().map(|b| (b["size"].as_u64(), b["valid"].as_bool())).collect::<Vec<_>>(), [
+        (Some(2), Some(true)),
+        (Some(1), Some(false))
+    ]);
+
+    for batch in batches {
+        let root: Hash32 = batch["batch_root"].as_str().unwrap().parse().unwrap();
+        let proof_id = batch["proof_id"].as_str().unwrap();
+        let anchored = logs_named(&chain, ProofAnchoredFilter::signature(), proof_id);
+        assert_eq!(anchored.len(), 1);
+        let record = chain.proof(verifier, proof_id.parse::<H256>().unwrap().0).expect("batch stored");
+        assert_eq!((record.proof_hash, Some(record.valid)), (root.0, batch["valid"].as_bool()));
+    }
+
+    let untrusted_receipt = receipts.join(format!("{}.json", untrusted.bundle_hash()));
+    let receipt: Value = serde_json::from_str(&std::fs::read_to_string(&untrusted_receipt).unwrap()).unwrap();
+    assert_eq!(receipt["anchor"]["proof_id"], batches[1]["proof_id"]);
+    for entry in std::fs::read_dir(&receipts).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|e| e != "json") {
+            continue;
+        }
+        let out = prover(&chain, verifier, DEV_KEYS[0], &["verify-receipt", path.to_str().unwrap()]);
+        assert!(out.status.success());
+    }
+
+    // --model-id alone records the proofs' common version, and refuses a batch without one.
+    let named = |proofs: &[&str]| {
+        let mut args = vec!["batch", "--model-id", "renamed", "--receipts", receipts.to_str().unwrap()];
+        args.extend(proofs);
+        prover(&chain, verifier, "", &args)
+    };
+    let out = stdout_json(&named(&[SAMPLE_V2]));
+    assert_eq!((&out["batches"][0]["model_id"], &out["batches"][0]["model_version"]), (&json!("renamed"), &json!("v0.1.0")));
+    let out = named(&[SAMPLE_V2, SAMPLE_V1]);
+    assert_eq!(out.status.code(), Some(6));
+    let err: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(err["message"].as_str().unwrap().contains("--model-version"), "{err}");
+
+    // Their receipts would overwrite each other.
+    let out = prover(&chain, verifier, "", &["batch", SAMPLE_V2, SAMPLE_V2, "--receipts", receipts.to_str().unwrap()]);
+    assert_eq!(out.status.code(), Some(6));
+    let err: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(err["message"].as_str().unwrap().starts_with("Proofs 1 and 2 have the same bundle hash"), "{err}");
+    std::fs::remove_dir_all(&receipts).unwrap();
+}
+
+#[test]
+fn read_and_is_valid_decode_the_full_record() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+    let submitted = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["submit", SAMPLE_V2]));
+    let proof_id = submitted["proof_id"].as_str().unwrap();
+
+    let record = stdout_json(&prover(&chain, verifier, "", &["read", proof_id]));
+    let proof = ProofJson::from_json(&std::fs::read_to_string(SAMPLE_V2).unwrap()).unwrap();
+    assert_eq!(record["proof_id"], proof_id);
+    assert_eq!(record["model_id"], "vericortex-demo");
+    assert_eq!(record["version"], "v0.1.0");
+    assert_eq!(record["input_hash"], proof.input_hash.digest.to_string());
+    assert_eq!(record["output_hash"], proof.output_hash.digest.to_string());
+    assert_eq!(record["trace_hash"], Hash32::default().to_string());
+    assert_eq!(record["proof_hash"], proof.bundle_hash().to_string());
+    assert_eq!(record["submitter"], format!("{:?}", dev_address(0)));
+    assert_eq!(record["valid"], true);
+    assert_eq!(record["locked"], false);
+    assert!(record["timestamp"].as_u64().unwrap() > 0);
+
+    let valid = stdout_json(&prover(&chain, verifier, "", &["is-valid", proof_id]));
+    assert_eq!(valid["valid"], true);
+
+    let unknown = format!("0x{}", "ab".repeat(32));
+    let out = prover(&chain, verifier, "", &["read", &unknown]);
+    assert_eq!(out.status.code(), Some(4));
+    assert_eq!(prover(&chain, verifier, "", &["is-valid", &unknown]).status.code(), Some(1));
+    // A short proofId is a usage error, rejected by the argument parser.
+    assert_eq!(prover(&chain, verifier, "", &["read", "0x1234"]).status.code(), Some(2));
+}
+
+#[test]
+fn listen_replays_and_follows_contract_events() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+    let submitted = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["submit", SAMPLE_V2]));
+    let proof_id: H256 = submitted["proof_id"].as_str().unwrap().parse().unwrap();
+
+    let listener = Command::new(env!("CARGO_BIN_EXE_verifier_offchain"))
+        .args(["listen", "--from-block", "0", "--count", "3", "--rpc-url", &chain.url()])
+        .args(["--contract", &format!("{verifier:?}")])
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Let the listener replay history and install its filter before updating.
+    std::thread::sleep(std::time::Duration::from_millis(800));
+
+    tokio::runtime::Runtime::new().unwrap().block_on(async {
+        use ethers::prelude::*;
+        let provider = Provider::<Http>::try_from(chain.url()).unwrap().interval(std::time::Duration::from_millis(50));
+        let wallet = vericortex_devchain::dev_wallet(0).with_chain_id(chain.chain_id());
+        let client = std::sync::Arc::new(SignerMiddleware::new(provider, wallet));
+        let contract = vericortex_sdk::contract::ProofCortexVerifier::new(verifier, client);
+        contract.update_proof_result(proof_id.0, false).send().await.unwrap().await.unwrap();
+    });
+
+    let out = listener.wait_with_output().unwrap();
+    assert!(out.status.success());
+    let events: Vec<Value> = String::from_utf8(out.stdout)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    let names: Vec<&str> = events.iter().map(|e| e["event"].as_str().unwrap()).collect();
+    assert_eq!(names, ["ProofSubmitted", "ProofAnchored", "ProofUpdated"]);
+    for event in &events {
+        assert_eq!(event["proof_id"], submitted["proof_id"]);
+    }
+    assert_eq!(events[0]["model_id"], "vericortex-demo");
+    assert_eq!(events[2]["valid"], false);
+}
+
+/// Mempool transaction (or mined one) with hash `hash`, as the dev chain holds it.
+fn chain_tx(chain: &DevChain, hash: &str) -> ethers::types::Transaction {
+    chain.state().transaction(&hash.parse().unwrap()).expect("tx known to the chain")
+}
+
+#[test]
+fn submissions_use_eip1559_fees_and_an_estimated_gas_limit() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+
+    let out = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["submit", SAMPLE_V2]));
+    let tx = chain_tx(&chain, out["tx_hash"].as_str().unwrap());
+    assert_eq!(tx.transaction_type, Some(2.into()));
+    // 2 * base fee (1 gwei) + the node's suggested tip
+    assert_eq!(tx.max_fee_per_gas, Some(U256::from(2 * GWEI + DEFAULT_PRIORITY_FEE)));
+    assert_eq!(tx.max_priority_fee_per_gas, Some(DEFAULT_PRIORITY_FEE.into()));
+
+    let receipt = chain.state().receipt(&tx.hash).unwrap();
+    let used = receipt.gas_used.unwrap();
+    assert!(tx.gas > used && tx.gas <= used * 12 / 10, "gas limit {} for {} used", tx.gas, used);
+}
+
+#[test]
+fn fee_limits_refuse_to_send() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+
+    let out = prover_cmd(&chain, verifier, DEV_KEYS[0])
+        .args(["submit", SAMPLE_V2])
+        .env("MAX_FEE_PER_TX", "0.0001")
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(3));
+    let err: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(err["message"].as_str().unwrap().contains("exceeds MAX_FEE_PER_TX 0.0001"), "{err}");
+    assert!(chain.pending().is_empty());
+    assert_eq!(chain.state().nonce(&dev_address(0), None).unwrap(), 0);
+    let outbox = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["outbox", "list"]));
+    assert_eq!(outbox["entries"][0]["state"], "failed");
+
+    // Without the cap the failed entry is retried; a budget of one worst-case
+    // tx then lets exactly that one through.
+    let first = stdout_json(&prover(&chain, verifier, DEV_KEYS[0], &["submit", SAMPLE_V2]));
+    let tx = chain_tx(&chain, first["tx_hash"].as_str().unwrap());
+    let budget = format_ether(tx.gas * tx.max_fee_per_gas.unwrap() + 1);
+    let out = prover_cmd(&chain, verifier, DEV_KEYS[0])
+        .args(["submit", SAMPLE_V1])
+        .env("DAILY_FEE_BUDGET", &budget)
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(3));
+    let err: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(err["message"].as_str().unwrap().contains("daily fee budget exhausted"), "{err}");
+    assert_eq!(chain.state().nonce(&dev_address(0), None).unwrap(), 1);
+}
+
+#[test]
+fn stuck_transaction_is_replaced_with_higher_fees() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+    chain.set_automine(false);
+    let child = prover_cmd(&chain, verifier, DEV_KEYS[0])
+        .args(["submit", SAMPLE_V2])
+        .env("FEE_BUMP_SECS", "1")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("start prover");
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let stuck = loop {
+        if let Some(tx) = chain.pending().first() {
+            break tx.clone();
+        }
+        assert!(Instant::now() < deadline, "prover never broadcast");
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    // The base fee jumps past the tx's fee cap: it can no longer be mined.
+    chain.set_base_fee(U256::from(10 * GWEI));
+    chain.set_automine(true);
+    let out = child.wait_with_output().unwrap();
+    let out = stdout_json(&out);
+
+    let tx_hash = out["tx_hash"].as_str().unwrap();
+    assert_ne!(tx_hash, format!("{:?}", stuck.hash));
+    let replacement = chain_tx(&chain, tx_hash);
+    assert_eq!(replacement.nonce, stuck.nonce);
+    assert!(replacement.max_fee_per_gas.unwrap() >= U256::from(20 * GWEI + DEFAULT_PRIORITY_FEE));
+    assert!(chain.state().receipt(&stuck.hash).is_none());
+    assert_eq!(chain.state().nonce(&dev_address(0), None).unwrap(), 1);
+    assert_eq!(out["onchain_proof_id"], out["proof_id"]);
+}
+
+#[test]
+fn submit_signs_with_an_encrypted_keystore() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+    let dir = scratch_dir("keystore");
+    std::fs::create_dir_all(&dir).unwrap();
+    let key = DEV_KEYS[0].trim_start_matches("0x");
+    let mut rng = ethers::core::rand::thread_rng();
+    LocalWallet::encrypt_keystore(&dir, &mut rng, ethers::utils::hex::decode(key).unwrap(), "correct horse", Some("submitter.json"))
+        .unwrap();
+    let password = dir.join("password");
+    std::fs::write(&password, "correct horse\n").unwrap();
+
+    let keystore = |password_file: &PathBuf| {
+        let mut cmd = prover_cmd(&chain, verifier, DEV_KEYS[0]);
+        cmd.env_remove("SUBMITTER_PRIVATE_KEY")
+            .env("KEYSTORE_PATH", dir.join("submitter.json"))
+            .env("KEYSTORE_PASSWORD_FILE", password_file);
+        cmd
+    };
+    let out = stdout_json(&keystore(&password).args(["submit", SAMPLE_V2]).output().unwrap());
+    assert_eq!(out["submitter"], format!("{:?}", dev_address(0)));
+    assert!(chain.proof(verifier, out["proof_id"].as_str().unwrap().parse::<H256>().unwrap().0).is_some());
+
+    let wrong = dir.join("wrong");
+    std::fs::write(&wrong, "battery staple").unwrap();
+    let out = keystore(&wrong).args(["submit", SAMPLE_V1]).output().unwrap();
+    assert_eq!(out.status.code(), Some(3));
+    let err: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(err["message"].as_str().unwrap().contains("keystore"), "{err}");
+    assert_eq!(chain.state().nonce(&dev_address(0), None).unwrap(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn submit_signs_through_a_remote_signer() {
+    let chain = DevChain::spawn().unwrap();
+    let verifier = chain.deploy_verifier(dev_address(0));
+    let signer = MockRemoteSigner::spawn(vec![dev_wallet(1), dev_wallet(0)]).unwrap();
+
+    let remote = |address: Option<Address>| {
+        let mut cmd = prover_cmd(&chain, verifier, DEV_KEYS[0]);
+        cmd.env_remove("SUBMITTER_PRIVATE_KEY").env("SIGNER_BACKEND", "remote").env("REMOTE_SIGNER_URL", signer.url());
+        if let Some(address) = address {
+            cmd.env("REMOTE_SIGNER_ADDRESS", format!("{address:?}"));
+        }
+        cmd
+    };
+
+    // Two keys on the signer and none chosen: refuse rather than guess.
+    let out = remote(None).args(["submit", SAMPLE_V2]).output().unwrap();
+    assert_eq!(out.status.code(), Some(3));
+    let err: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(err["message"].as_str().unwrap().contains("holds 2 keys"), "{err}");
+
+    let out = stdout_json(&remote(Some(dev_address(0))).args(["submit", SAMPLE_V2]).output().unwrap());
+    assert_eq!(out["submitter"], format!("{:?}", dev_address(0)));
+    let tx = chain_tx(&chain, out["tx_hash"].as_str().unwrap());
+    assert_eq!(tx.from, dev_address(0));
+    assert_eq!(tx.transaction_type, Some(2.into()));
+    assert_eq!(signer.signatures(), 1);
+}
+
+#[test]
+fn hash_input_and_output_canonicalize_payloads() {
+    let dir = scratch_dir("payloads");
+    std::fs::create_dir_all(&dir).unwrap();
+    let hash = |args: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_verifier_offchain")).args(args).output().unwrap();
+        (out.status.code(), serde_json::from_slice::<Value>(&out.stdout).unwrap())
+    };
+
+    // Member order and whitespace do not matter; the hash is SHA-256 of the RFC 8785 text.
+    std::fs::write(dir.join("a.json"), r#"{ "prompt": "hi", "temperature": 0.50, "max_tokens": 16 }"#).unwrap();
+    std::fs::write(dir.join("b.json"), r#"{"max_tokens":16,"prompt":"hi","temperature":0.5}"#).unwrap();
+    let (_, a) = hash(&["hash-input", "--format", "json", dir.join("a.json").to_str().unwrap()]);
+    let (_, b) = hash(&["hash-input", "--format", "json", dir.join("b.json").to_str().unwrap()]);
+    assert_eq!(a["input_hash"], b["input_hash"]);
+    let jcs = Payload::Bytes(br#"{"max_tokens":16,"prompt":"hi","temperature":0.5}"#.to_vec());
+    assert_eq!(a["input_hash"], json!(jcs.digest(HashAlg::Sha2_256).unwrap()));
+    assert_eq!(a["input_hash"].as_str().unwrap()[6..], jcs.hash().unwrap().to_string()[2..]);
+    let (_, c) = hash(&["hash-input", "--format", "json", "--alg", "blake3", dir.join("a.json").to_str().unwrap()]);
+    assert_eq!(c["input_hash"], json!(jcs.digest(HashAlg::Blake3).unwrap()));
+    assert_eq!(c["algorithm"], "blake3");
+
+    // A big-endian raw buffer and nested JSON arrays give the same tensor hash.
+    let be: Vec<u8> = [1.5f32, -2.0, 3.25, 0.0].iter().flat_map(|v| v.to_be_bytes()).collect();
+    std::fs::write(dir.join("t.bin"), be).unwrap();
+    std::fs::write(dir.join("t.json"), "[[1.5, -2], [3.25, 0]]").unwrap();
+    let t = dir.join("t.bin");
+    let (_, raw) = hash(&["hash-output", "--format", "tensor", "--dtype", "f32", "--shape", "2,2", "--endian", "big", t.to_str().unwrap()]);
+    let (_, nested) = hash(&["hash-output", "--format", "tensor-json", "--dtype", "float32", dir.join("t.json").to_str().unwrap()]);
+    assert_eq!(raw["output_hash"], nested["output_hash"]);
+    assert_eq!(raw["shape"], json!([2, 2]));
+
+    let (code, out) = hash(&["hash-output", "--format", "tensor", "--dtype", "f32", "--shape", "3", t.to_str().unwrap()]);
+    assert_eq!(code, Some(6));
+    assert_eq!(out["error"], "malformed_input");
+    let (code, out) = hash(&["hash-input", "--format", "json", "--proof", SAMPLE_V2, dir.join("a.json").to_str().unwrap()]);
+    assert_eq!(code, Some(1));
+    assert_eq!(out["match"], false);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn verify_selects_the_algorithm_from_the_digest() {
+    let dir = scratch_dir("digests");
+    std::fs::create_dir_all(&dir).unwrap();
+    let run = |args: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_verifier_offchain")).args(args).output().unwrap();
+        (out.status.code(), serde_json::from_slice::<Value>(&out.stdout).unwrap())
+    };
+
+    let input = br#"{"prompt":"hi"}"#;
+    std::fs::write(dir.join("input.json"), input).unwrap();
+    for alg in [HashAlg::Keccak256, HashAlg::Blake3, HashAlg::Sha3_256] {
+        let input_hash = Payload::Bytes(input.to_vec()).digest(alg).unwrap();
+        let output_hash = recompute_output_hash(&"vericortex-demo".into(), &input_hash, alg);
+        let path = dir.join(format!("{alg}.json"));
+        let proof = ProofJson::new("vericortex-demo", input_hash, output_hash);
+        std::fs::write(&path, proof.to_json_pretty().unwrap()).unwrap();
+
+        let (code, out) = run(&["verify", path.to_str().unwrap()]);
+        assert_eq!(code, Some(0), "{alg}: {out}");
+        assert_eq!(out["algorithm"], alg.name());
+        assert_eq!(out["recomputed_output_hash"], json!(output_hash));
+
+        // hash-input checks the payload with the proof's algorithm, whatever --alg says.
+        let (code, out) = run(&["hash-input", "--format", "json", "--proof", path.to_str().unwrap(), dir.join("input.json").to_str().unwrap()]);
+        assert_eq!(code, Some(0), "{alg}: {out}");
+        assert_eq!(out["algorithm"], alg.name());
+    }
+
+    // The legacy sample still verifies as SHA-256.
+    let (code, out) = run(&["verify", SAMPLE_V1]);
+    assert_eq!(code, Some(0), "{out}");
+    assert_eq!(out["algorithm"], "sha2-256");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn fingerprint_checks_a_model_against_its_proof_and_registry_entry() {
+    let dir = scratch_dir("fingerprint");
+    let model = dir.join("model");
+    std::fs::create_dir_all(&model).unwrap();
+    std::fs::write(model.join("config.json"), "{}").unwrap();
+    std::fs::write(model.join("weights.bin"), [7u8; 64]).unwrap();
+    let run = |args: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_verifier_offchain")).args(args).output().unwrap();
+        (out.status.code(), serde_json::from_slice::<Value>(&out.stdout).unwrap())
+    };
+
+    let (code, out) = run(&["fingerprint", "--leaves", model.to_str().unwrap()]);
+    assert_eq!(code, Some(0), "{out}");
+    let summary = vericortex_sdk::fingerprint::fingerprint(&model, None, HashAlg::Blake3).unwrap().summary();
+    assert_eq!(out["model_fingerprint"], json!(summary));
+    assert_eq!(out["leaves"][1]["name"], "weights.bin");
+
+    // The proof envelope and a registry entry both record the fingerprint.
+    let mut proof = ProofJson::from_json(&std::fs::read_to_string(SAMPLE_V2).unwrap()).unwrap();
+    proof.model_fingerprint = Some(summary);
+    let proof_path = dir.join("proof.json");
+    std::fs::write(&proof_path, proof.to_json_pretty().unwrap()).unwrap();
+    let entry_path = dir.join("entry.json");
+    std::fs::write(&entry_path, json!({ "modelId": "m", "name": "demo", "fingerprint": summary }).to_string()).unwrap();
+    for flag in ["--proof", "--entry"] {
+        let against = if flag == "--proof" { &proof_path } else { &entry_path };
+        let (code, out) = run(&["fingerprint", flag, against.to_str().unwrap(), model.to_str().unwrap()]);
+        assert_eq!((code, &out["match"]), (Some(0), &json!(true)), "{flag}: {out}");
+    }
+
+    std::fs::write(model.join("weights.bin"), [8u8; 64]).unwrap();
+    let (code, out) = run(&["fingerprint", "--proof", proof_path.to_str().unwrap(), model.to_str().unwrap()]);
+    assert_eq!((code, &out["match"]), (Some(1), &json!(false)), "{out}");
+    let (code, out) = run(&["fingerprint", "--proof", SAMPLE_V2, model.to_str().unwrap()]);
+    assert_eq!(code, Some(6), "a proof without a fingerprint: {out}");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Upper-cases ASCII input, writing the output after it.
+const UPPERCASE_WAT: &str = r#"(module
+    (memory (export "memory") 1)
+    (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+    (func (export "infer") (param $ptr i32) (param $len i32) (result i64)
+        (local $i i32) (local $c i32)
+        (block $done (loop $next
+            (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+            (local.set $c (i32.load8_u (i32.add (local.get $ptr) (local.get $i))))
+            (if (i32.lt_u (i32.sub (local.get $c) (i32.const 97)) (i32.const 26))
+                (then (local.set $c (i32.sub (local.get $c) (i32.const 32)))))
+            (i32.store8 (i32.add (i32.add (local.get $ptr) (local.get $len)) (local.get $i)) (local.get $c))
+            (local.set $i (i32.add (local.get $i) (i32.const 1)))
+            (br $next)))
+        (i64.or
+            (i64.shl (i64.extend_i32_u (i32.add (local.get $ptr) (local.get $len))) (i64.const 32))
+            (i64.extend_i32_u (local.get $len)))))"#;
+
+#[test]
+fn verify_reexecutes_a_wasm_model() {
+    let dir = scratch_dir("wasm");
+    std::fs::create_dir_all(&dir).unwrap();
+    let run = |args: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_verifier_offchain")).args(args).output().unwrap();
+        (out.status.code(), serde_json::from_slice::<Value>(&out.stdout).unwrap())
+    };
+    let model = dir.join("model.wasm");
+    std::fs::write(&model, wat::parse_str(UPPERCASE_WAT).unwrap()).unwrap();
+    let input = dir.join("input.txt");
+    std::fs::write(&input, "hello, world").unwrap();
+    let write_proof = |name: &str, output: &[u8]| {
+        let proof = ProofJson::new("vericortex-demo", HashAlg::Blake3.digest(b"hello, world"), HashAlg::Blake3.digest(output));
+        let path = dir.join(name);
+        std::fs::write(&path, proof.to_json_pretty().unwrap()).unwrap();
+        (path.to_str().unwrap().to_string(), proof)
+    };
+    let replay = ["--wasm", model.to_str().unwrap(), "--input", input.to_str().unwrap()];
+
+    let (honest, proof) = write_proof("honest.json", b"HELLO, WORLD");
+    let (code, out) = run(&[&["verify", honest.as_str()][..], &replay].concat());
+    assert_eq!(code, Some(0), "{out}");
+    assert_eq!(out["proof_type"], "wasm");
+    assert_eq!(out["recomputed_output_hash"], json!(proof.output_hash));
+    let fuel = out["fuel_consumed"].as_u64().unwrap();
+    let (code, again) = run(&[&["verify", honest.as_str()][..], &replay].concat());
+    assert_eq!((code, again["fuel_consumed"].as_u64()), (Some(0), Some(fuel)), "re-execution is deterministic");
+
+    // Without --wasm only the hash-only recompute runs, which this proof fails.
+    let (code, out) = run(&["verify", honest.as_str()]);
+    assert_eq!((code, &out["proof_type"]), (Some(1), &json!("hash-recompute")), "{out}");
+
+    let (forged, _) = write_proof("forged.json", b"HELLO, MARS");
+    let (code, out) = run(&[&["verify", forged.as_str()][..], &replay].concat());
+    assert_eq!((code, &out["match"]), (Some(1), &json!(false)), "{out}");
+
+    std::fs::write(&input, "goodbye").unwrap();
+    let (code, out) = run(&[&["verify", honest.as_str()][..], &replay].concat());
+    assert_eq!(code, Some(1), "{out}");
+    assert_eq!(out["input_hash"], json!(proof.input_hash));
+
+    std::fs::write(&input, "hello, world").unwrap();
+    let (code, out) = run(&[&["verify", honest.as_str()][..], &replay, &["--fuel", "10"]].concat());
+    assert_eq!(code, Some(6), "out of fuel: {out}");
+    std::fs::write(&model, b"not wasm").unwrap();
+    let (code, out) = run(&[&["verify", honest.as_str()][..], &replay].concat());
+    assert_eq!(code, Some(6), "{out}");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn verify_dispatches_on_the_proof_type() {
+    let dir = scratch_dir("adapters");
+    std::fs::create_dir_all(&dir).unwrap();
+    let run = |args: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_verifier_offchain")).args(args).output().unwrap();
+        (out.status.code(), serde_json::from_slice::<Value>(&out.stdout).unwrap())
+    };
+    let key = ethers::core::k256::ecdsa::SigningKey::from_slice(&[7; 32]).unwrap();
+    let mut proof = ProofJson::new("vericortex-demo", Hash32([1; 32]), Hash32([2; 32]));
+    proof.proof_type = Some("signed-attestation".into());
+    let attestation = Attestation::sign(&proof, &key);
+    proof.evidence = Some(json!(attestation));
+    let attested = dir.join("attested.json");
+    std::fs::write(&attested, proof.to_json_pretty().unwrap()).unwrap();
+    let attested = attested.to_str().unwrap();
+    let signer = attestation.signer.to_string();
+
+    let (code, out) = run(&["verify", attested, "--attester", &signer]);
+    assert_eq!((code, &out["proof_type"]), (Some(0), &json!("signed-attestation")), "{out}");
+    assert_eq!(out["signer"], json!(signer));
+    let (code, out) = run(&["verify", attested, "--attester", &format!("0x{}", "11".repeat(20))]);
+    assert_eq!((code, &out["match"]), (Some(1), &json!(false)), "untrusted signer: {out}");
+    let (code, _) = run(&["verify", attested]);
+    assert_eq!(code, Some(6), "no adapter for the proof type");
+
+    proof.proof_type = Some("ezkl".into());
+    let external = dir.join("external.json");
+    std::fs::write(&external, proof.to_json_pretty().unwrap()).unwrap();
+    let script = dir.join("verify.sh");
+    std::fs::write(&script, r#"grep -q '"ezkl"' && echo '{"valid": true, "reason": "checked"}'"#).unwrap();
+    let verifier = format!("ezkl=sh {}", script.display());
+    let (code, out) = run(&["verify", external.to_str().unwrap(), "--external", &verifier]);
+    assert_eq!((code, &out["reason"]), (Some(0), &json!("checked")), "{out}");
+
+    // A Groth16 proof generated offline by the SDK's examples/groth16_fixture.rs.
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/../sdk/core/rust/tests/fixtures/groth16");
+    let snark = format!("{fixtures}/proof.json");
+    let vk = format!("groth16-demo={fixtures}/vk.bin");
+    let (code, out) = run(&["verify", &snark, "--groth16-vk", &vk]);
+    assert_eq!((code, &out["proof_type"]), (Some(0), &json!("groth16")), "{out}");
+    let mut forged = ProofJson::from_json(&std::fs::read_to_string(&snark).unwrap()).unwrap();
+    forged.output_hash = Hash32([2; 32]).into();
+    let forged_path = dir.join("forged.json");
+    std::fs::write(&forged_path, forged.to_json_pretty().unwrap()).unwrap();
+    let (code, out) = run(&["verify", forged_path.to_str().unwrap(), "--groth16-vk", &vk]);
+    assert_eq!((code, &out["match"]), (Some(1), &json!(false)), "{out}");
+    let (code, _) = run(&["verify", &snark, "--groth16-vk", "groth16-demo=Cargo.toml"]);
+    assert_eq!(code, Some(6), "not a verifying key");
+
+    // An SEV-SNP report recorded by the SDK's examples/tee_fixture.rs under a test ARK.
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/../sdk/core/rust/tests/fixtures/tee");
+    let report = format!("{fixtures}/sev-snp.json");
+    let roots = format!("{fixtures}/test_ark.pem");
+    let measurement = format!("tee-demo=0x{}", "33".repeat(48));
+    let accept = ["--tee-accept-unknown-revocation", "--tee-accept-unevaluated-tcb"];
+    let (code, out) = run(&[&["verify", &report, "--tee-roots", &roots, "--tee-measurement", &measurement][..], &accept].concat());
+    assert_eq!((code, &out["platform"], &out["revocation"]), (Some(0), &json!("sev-snp"), &json!("unknown")), "{out}");
+    let (code, out) = run(&["verify", &report, "--tee-roots", &roots, "--tee-measurement", &measurement]);
+    assert_eq!((code, &out["match"]), (Some(1), &json!(false)), "fails closed on unchecked revocation: {out}");
+    let elsewhere = format!("other-model=0x{}", "33".repeat(48));
+    let (code, out) = run(&[&["verify", &report, "--tee-roots", &roots, "--tee-measurement", &elsewhere][..], &accept].concat());
+    assert_eq!((code, &out["match"]), (Some(1), &json!(false)), "{out}");
+    assert!(out["reason"].as_str().unwrap().contains("not allowlisted"), "{out}");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn verify_refuses_a_hash_with_a_missing_digit() {
+    let out = Command::new(env!("CARGO_BIN_EXE_verifier_offchain")).args(["verify", MALFORMED_HASH]).output().unwrap();
+    assert_eq!(out.status.code(), Some(6));
+    let out: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(out["error"], "malformed_input");
+    assert!(out["message"].as_str().unwrap().contains("output_hash"), "{out}");
+}
@@ -0,0 +1,68 @@
+//! Regenerates `tests/fixtures/groth16/`: a verifying key and a `groth16`
+//! proof over a toy circuit, made once and committed so the tests only ever
+//! verify.
+//!
+//! ```text
+//! cargo run --example groth16_fixture --features groth16 -- tests/fixtures/groth16
+//! ```
+//!
+//! The circuit takes the four public inputs of [`Groth16Verifier::public_inputs`]
+//! and proves knowledge of `w` with `(input_hi + input_lo) * w = output_hi + output_lo`.
+//! The setup uses a fixed seed so the files come out byte for byte the same;
+//! never do that for a real key.
+
+use std::path::PathBuf;
+
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::Groth16;
+use ark_relations::lc;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::rngs::StdRng;
+use ark_std::rand::SeedableRng;
+use serde_json::json;
+use vericortex_sdk::adapter::{Groth16Evidence, Groth16Verifier};
+use vericortex_sdk::{HashAlg, ProofJson};
+
+struct Toy {
+    inputs: [Fr; Groth16Verifier::PUBLIC_INPUTS],
+}
+
+impl ConstraintSynthesizer<Fr> for Toy {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let [in_hi, in_lo, out_hi, out_lo] = self.inputs;
+        let mut public = Vec::new();
+        for x in self.inputs {
+            public.push(cs.new_input_variable(|| Ok(x))?);
+        }
+        let w = cs.new_witness_variable(|| {
+            let sum = in_hi + in_lo;
+            if sum == Fr::from(0u8) {
+                return Err(SynthesisError::DivisionByZero);
+            }
+            Ok((out_hi + out_lo) / sum)
+        })?;
+        cs.enforce_constraint(lc!() + public[0] + public[1], lc!() + w, lc!() + public[2] + public[3])
+    }
+}
+
+fn main() {
+    let dir = PathBuf::from(std::env::args().nth(1).expect("usage: groth16_fixture <dir>"));
+    let mut proof = ProofJson::new("groth16-demo", HashAlg::Sha2_256.digest(b"input"), HashAlg::Sha2_256.digest(b"output"));
+    proof.proof_type = Some(Groth16Verifier::PROOF_TYPE.into());
+    let inputs = Groth16Verifier::public_inputs(&proof).map(Fr::from);
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let pk = Groth16::<Bn254>::generate_random_parameters_with_reduction(Toy { inputs }, &mut rng).unwrap();
+    let snark = Groth16::<Bn254>::create_random_proof_with_reduction(Toy { inputs }, &pk, &mut rng).unwrap();
+
+    let mut vk = Vec::new();
+    pk.vk.serialize_compressed(&mut vk).unwrap();
+    let mut bytes = Vec::new();
+    snark.serialize_compressed(&mut bytes).unwrap();
+    proof.evidence = Some(json!(Groth16Evidence { proof: format!("0x{}", hex::encode(bytes)) }));
+
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("vk.bin"), vk).unwrap();
+    std::fs::write(dir.join("proof.json"), proof.to_json_pretty().unwrap() + "\n").unwrap();
+}
@@ -0,0 +1,228 @@
+//! Regenerates `tests/fixtures/tee/`: one `tee-attestation` proof per
+//! platform, with an SGX v3 quote, a TDX v4 quote and a SEV-SNP report laid
+//! out byte for byte as the hardware does, plus the roots they chain to and
+//! CRLs for them.
+//!
+//! ```text
+//! cargo run --example tee_fixture --features tee -- tests/fixtures/tee
+//! ```
+//!
+//! Real quotes need real hardware, so the vendor hierarchies are stood in
+//! for by test CAs ("Test SGX Root CA", "Test ARK-Milan") with the same
+//! shape: ECDSA P-256 from the root down to the PCK for Intel, RSA-PSS
+//! (SHA-384) from the ARK through the ASK to a P-384 VCEK for AMD. The
+//! measurements are `0x11…` (MRENCLAVE), `0x22…` (MRTD) and `0x33…`
+//! (MEASUREMENT). `test_sgx_crls.pem` covers the whole Intel chain and
+//! `test_sgx_pck_revoked.pem` revokes its PCK; as with AMD, `test_ark_crl.pem`
+//! covers the ASK but nothing covers the VCEK. A fixed seed makes the files
+//! come out the same each run.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use p256::ecdsa::signature::Signer;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rsa::signature::{RandomizedSigner, SignatureEncoding};
+use rsa::RsaPrivateKey;
+use serde_json::json;
+use sha2::{Digest, Sha256, Sha384};
+use vericortex_sdk::adapter::{Platform, TeeEvidence, TeeVerifier};
+use vericortex_sdk::{HashAlg, ProofJson};
+use x509_cert::builder::{Builder, CertificateBuilder, Profile};
+use x509_cert::crl::{CertificateList, RevokedCert, TbsCertList};
+use x509_cert::der::asn1::{BitString, UtcTime};
+use x509_cert::der::{pem::LineEnding, Encode, EncodePem};
+use x509_cert::name::Name;
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::spki::{AlgorithmIdentifierOwned, DynSignatureAlgorithmIdentifier, SubjectPublicKeyInfoOwned};
+use x509_cert::time::{Time, Validity};
+use x509_cert::{Certificate, Version};
+
+/// Intel's QE vendor ID.
+const QE_VENDOR_ID: [u8; 16] = [0x93, 0x9a, 0x72, 0x33, 0xf7, 0x9c, 0x4c, 0xa9, 0x94, 0x0a, 0x0d, 0xb3, 0x95, 0x7f, 0x06, 0x07];
+
+fn name(cn: &str) -> Name {
+    Name::from_str(&format!("CN={cn},O=VeriCortex test fixtures")).unwrap()
+}
+
+fn at(secs: u64) -> Time {
+    Time::UtcTime(UtcTime::from_unix_duration(Duration::from_secs(secs)).unwrap())
+}
+
+/// 2024-01-01 to 2049-12-31, the last instant UTCTime can express.
+fn validity() -> Validity {
+    Validity { not_before: at(1_704_067_200), not_after: at(2_524_607_999) }
+}
+
+fn pem(certs: &[&Certificate]) -> String {
+    certs.iter().map(|c| c.to_pem(LineEnding::LF).unwrap()).collect()
+}
+
+fn ecdsa_cert(profile: Profile, serial: u32, cn: &str, key: SubjectPublicKeyInfoOwned, signer: &p256::ecdsa::SigningKey) -> Certificate {
+    CertificateBuilder::new(profile, SerialNumber::from(serial), validity(), name(cn), key, signer)
+        .unwrap()
+        .build::<p256::ecdsa::DerSignature>()
+        .unwrap()
+}
+
+fn pss_cert(profile: Profile, serial: u32, cn: &str, key: SubjectPublicKeyInfoOwned, signer: &RsaPrivateKey, rng: &mut StdRng) -> Certificate {
+    let signer = rsa::pss::SigningKey::<Sha384>::new(signer.clone());
+    CertificateBuilder::new(profile, SerialNumber::from(serial), validity(), name(cn), key, &signer)
+        .unwrap()
+        .build_with_rng::<rsa::pss::Signature>(rng)
+        .unwrap()
+}
+
+/// A CRL by `issuer`, current over the same window as the certificates,
+/// revoking `revoked` serials; `sign` signs its DER with `algorithm`.
+fn crl(issuer: &str, revoked: &[u32], algorithm: AlgorithmIdentifierOwned, sign: impl FnOnce(&[u8]) -> Vec<u8>) -> String {
+    let revoked = revoked
+        .iter()
+        .map(|&serial| RevokedCert { serial_number: SerialNumber::from(serial), revocation_date: at(1_704_067_200), crl_entry_extensions: None })
+        .collect::<Vec<_>>();
+    let tbs_cert_list = TbsCertList {
+        version: Version::V2,
+        signature: algorithm.clone(),
+        issuer: name(issuer),
+        this_update: at(1_704_067_200),
+        next_update: Some(at(2_524_607_999)),
+        revoked_certificates: (!revoked.is_empty()).then_some(revoked),
+        crl_extensions: None,
+    };
+    let signature = sign(&tbs_cert_list.to_der().unwrap());
+    let crl = CertificateList { tbs_cert_list, signature_algorithm: algorithm, signature: BitString::from_bytes(&signature).unwrap() };
+    x509_cert::der::pem::encode_string("X509 CRL", LineEnding::LF, &crl.to_der().unwrap()).unwrap()
+}
+
+fn ecdsa_crl(issuer: &str, revoked: &[u32], signer: &p256::ecdsa::SigningKey) -> String {
+    crl(issuer, revoked, signer.signature_algorithm_identifier().unwrap(), |tbs| {
+        let sig: p256::ecdsa::Signature = signer.sign(tbs);
+        sig.to_der().as_bytes().to_vec()
+    })
+}
+
+fn spki(key: impl x509_cert::spki::EncodePublicKey) -> SubjectPublicKeyInfoOwned {
+    SubjectPublicKeyInfoOwned::from_key(key).unwrap()
+}
+
+fn sub_ca(issuer: &str) -> Profile {
+    Profile::SubCA { issuer: name(issuer), path_len_constraint: Some(0) }
+}
+
+fn leaf(issuer: &str) -> Profile {
+    Profile::Leaf { issuer: name(issuer), enable_key_agreement: false, enable_key_encipherment: false }
+}
+
+/// `report_data`: the proof commitment, then a nonce.
+fn report_data(proof: &ProofJson) -> Vec<u8> {
+    [TeeVerifier::report_data(proof), [0xaa; 32]].concat()
+}
+
+fn write(dir: &Path, file: &str, proof: &ProofJson, evidence: TeeEvidence) {
+    let mut proof = proof.clone();
+    proof.evidence = Some(json!(evidence));
+    std::fs::write(dir.join(file), proof.to_json_pretty().unwrap() + "\n").unwrap();
+}
+
+fn main() {
+    let dir = PathBuf::from(std::env::args().nth(1).expect("usage: tee_fixture <dir>"));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut proof = ProofJson::new("tee-demo", HashAlg::Sha2_256.digest(b"input"), HashAlg::Sha2_256.digest(b"output"));
+    proof.proof_type = Some(TeeVerifier::PROOF_TYPE.into());
+
+    // Intel: root -> PCK platform CA -> PCK, which signs the QE report.
+    let root_key = p256::ecdsa::SigningKey::random(&mut rng);
+    let ca_key = p256::ecdsa::SigningKey::random(&mut rng);
+    let pck_key = p256::ecdsa::SigningKey::random(&mut rng);
+    let root = ecdsa_cert(Profile::Root, 1, "Test SGX Root CA", spki(*root_key.verifying_key()), &root_key);
+    let ca = ecdsa_cert(sub_ca("Test SGX Root CA"), 2, "Test SGX PCK Platform CA", spki(*ca_key.verifying_key()), &root_key);
+    let pck = ecdsa_cert(leaf("Test SGX PCK Platform CA"), 3, "Test SGX PCK Certificate", spki(*pck_key.verifying_key()), &ca_key);
+    let pck_chain = pem(&[&pck, &ca, &root]);
+    std::fs::write(dir.join("test_sgx_root.pem"), pem(&[&root])).unwrap();
+    let root_crl = ecdsa_crl("Test SGX Root CA", &[], &root_key);
+    std::fs::write(dir.join("test_sgx_crls.pem"), root_crl + &ecdsa_crl("Test SGX PCK Platform CA", &[], &ca_key)).unwrap();
+    std::fs::write(dir.join("test_sgx_pck_revoked.pem"), ecdsa_crl("Test SGX PCK Platform CA", &[3], &ca_key)).unwrap();
+
+    let attest_key = p256::ecdsa::SigningKey::random(&mut rng);
+    let attest_pub = attest_key.verifying_key().to_encoded_point(false).as_bytes()[1..].to_vec();
+    let qe_auth: Vec<u8> = (0..32).collect();
+    let mut qe_report = vec![0u8; 384];
+    qe_report[320..352].copy_from_slice(&Sha256::digest([attest_pub.as_slice(), &qe_auth].concat()));
+    let qe_sig: p256::ecdsa::Signature = pck_key.sign(&qe_report);
+
+    let header = |version: u16, tee_type: u32| {
+        let mut h = Vec::new();
+        h.extend(version.to_le_bytes());
+        h.extend(2u16.to_le_bytes());
+        h.extend(tee_type.to_le_bytes());
+        h.extend([0u8; 4]);
+        h.extend(QE_VENDOR_ID);
+        h.extend([0u8; 20]);
+        h
+    };
+    let cert_data = |kind: u16, data: &[u8]| [&kind.to_le_bytes()[..], &(data.len() as u32).to_le_bytes(), data].concat();
+    let qe_data = [
+        &qe_report[..],
+        &qe_sig.to_bytes(),
+        &(qe_auth.len() as u16).to_le_bytes(),
+        &qe_auth,
+        &cert_data(5, pck_chain.as_bytes()),
+    ]
+    .concat();
+    let quote = |signed: Vec<u8>, v4: bool| {
+        let sig: p256::ecdsa::Signature = attest_key.sign(&signed);
+        let qe = if v4 { cert_data(6, &qe_data) } else { qe_data.clone() };
+        let sig_data = [&sig.to_bytes()[..], &attest_pub, &qe].concat();
+        let quote = [signed, (sig_data.len() as u32).to_le_bytes().to_vec(), sig_data].concat();
+        format!("0x{}", hex::encode(quote))
+    };
+
+    // SGX v3: ATTRIBUTES 64-bit mode only, MRENCLAVE, REPORTDATA.
+    let mut body = vec![0u8; 384];
+    body[48] = 0x04;
+    body[64..96].fill(0x11);
+    body[320..384].copy_from_slice(&report_data(&proof));
+    let sgx = quote([header(3, 0), body].concat(), false);
+    write(&dir, "sgx.json", &proof, TeeEvidence { platform: Platform::Sgx, quote: sgx, cert_chain: None });
+
+    // TDX v4: MRTD and REPORTDATA of a TD report.
+    let mut body = vec![0u8; 584];
+    body[136..184].fill(0x22);
+    body[520..584].copy_from_slice(&report_data(&proof));
+    let tdx = quote([header(4, 0x81), body].concat(), true);
+    write(&dir, "tdx.json", &proof, TeeEvidence { platform: Platform::Tdx, quote: tdx, cert_chain: None });
+
+    // AMD: ARK -> ASK -> VCEK, which signs the report.
+    let ark_key = RsaPrivateKey::new(&mut rng, 2048).unwrap();
+    let ask_key = RsaPrivateKey::new(&mut rng, 2048).unwrap();
+    let vcek_key = p384::ecdsa::SigningKey::random(&mut rng);
+    let ark = pss_cert(Profile::Root, 1, "Test ARK-Milan", spki(ark_key.to_public_key()), &ark_key, &mut rng);
+    let ask = pss_cert(sub_ca("Test ARK-Milan"), 2, "Test SEV-Milan", spki(ask_key.to_public_key()), &ark_key, &mut rng);
+    let vcek = pss_cert(leaf("Test SEV-Milan"), 3, "Test SEV-VCEK", spki(*vcek_key.verifying_key()), &ask_key, &mut rng);
+    std::fs::write(dir.join("test_ark.pem"), pem(&[&ark])).unwrap();
+    let ark_signer = rsa::pss::SigningKey::<Sha384>::new(ark_key.clone());
+    let ark_crl = crl("Test ARK-Milan", &[], ark_signer.signature_algorithm_identifier().unwrap(), |tbs| {
+        ark_signer.sign_with_rng(&mut rng, tbs).to_vec()
+    });
+    std::fs::write(dir.join("test_ark_crl.pem"), ark_crl).unwrap();
+
+    let mut report = vec![0u8; 0x4a0];
+    report[0x00..0x04].copy_from_slice(&2u32.to_le_bytes());
+    report[0x08..0x10].copy_from_slice(&0x30000u64.to_le_bytes());
+    report[0x34..0x38].copy_from_slice(&1u32.to_le_bytes());
+    report[0x50..0x90].copy_from_slice(&report_data(&proof));
+    report[0x90..0xc0].fill(0x33);
+    let sig: p384::ecdsa::Signature = vcek_key.sign(&report[..0x2a0]);
+    let (r, s) = sig.split_bytes();
+    report[0x2a0..0x2a0 + 48].copy_from_slice(&r.iter().rev().copied().collect::<Vec<_>>());
+    report[0x2e8..0x2e8 + 48].copy_from_slice(&s.iter().rev().copied().collect::<Vec<_>>());
+    let evidence = TeeEvidence {
+        platform: Platform::SevSnp,
+        quote: format!("0x{}", hex::encode(report)),
+        cert_chain: Some(pem(&[&vcek, &ask])),
+    };
+    write(&dir, "sev-snp.json", &proof, evidence);
+}
@@ -0,0 +1,110 @@
+use std::collections::BTreeSet;
+
+use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use super::{ProofAdapter, Verdict};
+use crate::error::AdapterError;
+use crate::proof::ProofJson;
+use crate::types::{parse_hex_exact, Address, Hash32};
+
+/// `evidence` of a `signed-attestation` proof: an EIP-191 `personal_sign`
+/// signature by `signer` over the proof's derived bundle hash, so any
+/// Ethereum wallet or signer can attest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Attestation {
+    pub signer: Address,
+    /// `r || s || v` as `0x` hex, with a low `s` and `v` 27/28 (or 0/1).
+    pub signature: String,
+}
+
+impl Attestation {
+    /// The digest an attester signs for `proof`:
+    /// `keccak256("\x19Ethereum Signed Message:\n32" || derived_bundle_hash)`.
+    /// A recorded `proof_hash` is not what gets signed: it is the caller's
+    /// claim, not a commitment to the model, input and output.
+    pub fn message(proof: &ProofJson) -> Hash32 {
+        let mut hasher = Keccak256::new();
+        hasher.update(b"\x19Ethereum Signed Message:\n32");
+        hasher.update(proof.derived_bundle_hash().as_bytes());
+        Hash32(hasher.finalize().into())
+    }
+
+    pub fn sign(proof: &ProofJson, key: &SigningKey) -> Self {
+        let (signature, recovery) = key
+            .sign_prehash_recoverable(Self::message(proof).as_bytes())
+            .expect("a 32-byte prehash is always signable");
+        let mut bytes = signature.to_bytes().to_vec();
+        bytes.push(27 + recovery.to_byte());
+        Self { signer: eth_address(key.verifying_key()), signature: format!("0x{}", hex::encode(bytes)) }
+    }
+
+    /// The address whose key produced `signature` over `proof`.
+    pub fn recover(&self, proof: &ProofJson) -> Result<Address, String> {
+        let bytes = parse_hex_exact::<65>(&self.signature).map_err(|e| format!("signature: {e}"))?;
+        let signature = Signature::from_slice(&bytes[..64]).map_err(|e| format!("signature: {e}"))?;
+        if signature.normalize_s().is_some() {
+            return Err("signature: high s value".into());
+        }
+        let recovery = match bytes[64] {
+            v @ (0 | 1) => RecoveryId::from_byte(v),
+            v @ (27 | 28) => RecoveryId::from_byte(v - 27),
+            _ => None,
+        }
+        .ok_or_else(|| format!("signature: bad recovery byte {}, expected 27/28 (or 0/1)", bytes[64]))?;
+        VerifyingKey::recover_from_prehash(Self::message(proof).as_bytes(), &signature, recovery)
+            .map(|key| eth_address(&key))
+            .map_err(|e| format!("signature: {e}"))
+    }
+}
+
+/// The EVM address of a secp256k1 key: the last 20 bytes of the Keccak-256
+/// of its uncompressed point.
+pub(crate) fn eth_address(key: &VerifyingKey) -> Address {
+    let point = key.to_encoded_point(false);
+    let hash = Keccak256::digest(&point.as_bytes()[1..]);
+    Address(hash[12..].try_into().expect("20 bytes"))
+}
+
+/// Accepts a proof when one of a fixed set of attesters signed it.
+#[derive(Debug, Clone, Default)]
+pub struct SignedAttestation {
+    trusted: BTreeSet<Address>,
+}
+
+impl SignedAttestation {
+    pub const PROOF_TYPE: &'static str = "signed-attestation";
+
+    pub fn new(trusted: impl IntoIterator<Item = Address>) -> Self {
+        Self { trusted: trusted.into_iter().collect() }
+    }
+}
+
+impl ProofAdapter for SignedAttestation {
+    fn proof_type(&self) -> &str {
+        Self::PROOF_TYPE
+    }
+
+    fn verify(&self, proof: &ProofJson) -> Result<Verdict, AdapterError> {
+        let malformed = |message: String| AdapterError::Evidence { proof_type: Self::PROOF_TYPE.into(), message };
+        let evidence = proof.evidence.clone().ok_or_else(|| malformed("missing".into()))?;
+        let attestation: Attestation = serde_json::from_value(evidence).map_err(|e| malformed(e.to_string()))?;
+        let recovered = attestation.recover(proof).map_err(malformed)?;
+
+        let derived = proof.derived_bundle_hash();
+        let (valid, reason) = if proof.proof_hash.is_some_and(|h| h != derived) {
+            (false, format!("recorded proof_hash does not match the attested bundle hash {derived}"))
+        } else if recovered != attestation.signer {
+            (false, format!("signature is by {recovered}, not the claimed signer {}", attestation.signer))
+        } else if !self.trusted.contains(&recovered) {
+            (false, format!("{recovered} is not a trusted attester"))
+        } else {
+            (true, format!("attested by {recovered}"))
+        };
+        Ok(Verdict::new(Self::PROOF_TYPE, valid, reason)
+            .with("signer", recovered)
+            .with("message", Attestation::message(proof)))
+    }
+}
@@ -0,0 +1,116 @@
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use super::{ProofAdapter, Verdict};
+use crate::error::AdapterError;
+use crate::proof::ProofJson;
+
+/// Hands proofs of one type to another program, e.g. a zkML verifier CLI.
+///
+/// The program gets the proof JSON (evidence included) on stdin and must
+/// print `{"valid": bool, "reason": "...", "details": {...}}` on stdout,
+/// with `reason` and `details` optional. A non-zero exit, any other output
+/// or running past the timeout is an error, not an invalid proof.
+#[derive(Debug, Clone)]
+pub struct ExternalVerifier {
+    proof_type: String,
+    program: PathBuf,
+    args: Vec<String>,
+    timeout: Duration,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Reply {
+    valid: bool,
+    #[serde(default)]
+    reason: String,
+    #[serde(default)]
+    details: Map<String, Value>,
+}
+
+impl ExternalVerifier {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+    pub fn new(proof_type: impl Into<String>, program: impl Into<PathBuf>, args: Vec<String>) -> Self {
+        Self { proof_type: proof_type.into(), program: program.into(), args, timeout: Self::DEFAULT_TIMEOUT }
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn backend(&self, message: impl Into<String>) -> AdapterError {
+        AdapterError::Backend { proof_type: self.proof_type.clone(), message: message.into() }
+    }
+
+    /// Run the program on `input`, returning its stdout.
+    fn run(&self, input: String) -> Result<Vec<u8>, AdapterError> {
+        let program = self.program.display();
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| self.backend(format!("cannot run {program}: {e}")))?;
+
+        // Feed and drain the pipes on their own threads so a verifier that
+        // writes before it has read everything cannot deadlock.
+        let mut stdin = child.stdin.take().expect("piped");
+        let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+        let drain = |mut pipe: Box<dyn Read + Send>| {
+            thread::spawn(move || {
+                let mut buf = Vec::new();
+                pipe.read_to_end(&mut buf).map(|_| buf)
+            })
+        };
+        let stdout = drain(Box::new(child.stdout.take().expect("piped")));
+        let stderr = drain(Box::new(child.stderr.take().expect("piped")));
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            match child.try_wait().map_err(|e| self.backend(format!("{program}: {e}")))? {
+                Some(status) => break status,
+                None if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(self.backend(format!("{program} timed out after {:?}", self.timeout)));
+                }
+                None => thread::sleep(Duration::from_millis(10)),
+            }
+        };
+        // A verifier may exit without reading its input; that is its call.
+        let _ = writer.join();
+        let stdout = stdout.join().expect("reader thread").map_err(|e| self.backend(format!("{program}: {e}")))?;
+        let stderr = stderr.join().expect("reader thread").unwrap_or_default();
+        if !status.success() {
+            let stderr = String::from_utf8_lossy(&stderr);
+            return Err(self.backend(format!("{program} exited with {status}: {}", stderr.trim())));
+        }
+        Ok(stdout)
+    }
+}
+
+impl ProofAdapter for ExternalVerifier {
+    fn proof_type(&self) -> &str {
+        &self.proof_type
+    }
+
+    fn verify(&self, proof: &ProofJson) -> Result<Verdict, AdapterError> {
+        let input = proof.to_json_pretty().map_err(|e| self.backend(e.to_string()))?;
+        let stdout = self.run(input)?;
+        let reply: Reply = serde_json::from_slice(&stdout)
+            .map_err(|e| self.backend(format!("{} printed no verdict: {e}", self.program.display())))?;
+        let mut verdict = Verdict::new(&self.proof_type, reply.valid, reply.reason);
+        verdict.details = reply.details;
+        Ok(verdict)
+    }
+}
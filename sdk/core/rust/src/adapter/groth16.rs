@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::{prepare_verifying_key, Groth16, PreparedVerifyingKey, Proof, VerifyingKey};
+use ark_serialize::CanonicalDeserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{ProofAdapter, Verdict};
+use crate::error::AdapterError;
+use crate::proof::ProofJson;
+use crate::types::{Hash32, ModelId};
+
+/// `evidence` of a `groth16` proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Groth16Evidence {
+    /// An arkworks `ark_groth16::Proof<Bn254>` in `CanonicalSerialize`
+    /// form, compressed (128 bytes) or uncompressed (256), as `0x` hex.
+    pub proof: String,
+}
+
+#[derive(Clone, Debug)]
+struct Key {
+    prepared: PreparedVerifyingKey<Bn254>,
+    /// SHA-256 of the key as loaded, so verdicts say which key was used.
+    digest: Hash32,
+}
+
+/// Checks Groth16 proofs over BN254 against a verifying key per model.
+///
+/// The circuit's public inputs are, in order, the high and low 128 bits of
+/// the `input_hash` digest and then of the `output_hash` digest (see
+/// [`Groth16Verifier::public_inputs`]), so a proof only verifies for the
+/// hashes it was made for. The hash algorithm is the circuit's business and
+/// is not an input.
+#[derive(Clone, Debug, Default)]
+pub struct Groth16Verifier {
+    keys: BTreeMap<ModelId, Key>,
+}
+
+impl Groth16Verifier {
+    pub const PROOF_TYPE: &'static str = "groth16";
+    /// Public inputs a circuit exposes: the `input_hash` and `output_hash`
+    /// digests, each split into two big-endian 128-bit halves.
+    pub const PUBLIC_INPUTS: usize = 4;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust `vk`, an arkworks `ark_groth16::VerifyingKey<Bn254>` in
+    /// `CanonicalSerialize` form (compressed or not), for `model_id`'s proofs.
+    pub fn add_key(&mut self, model_id: ModelId, vk: &[u8]) -> Result<&mut Self, AdapterError> {
+        let key: VerifyingKey<Bn254> = decode(vk).map_err(|e| backend(format!("verifying key: {e}")))?;
+        if key.gamma_abc_g1.len() != Self::PUBLIC_INPUTS + 1 {
+            return Err(backend(format!(
+                "verifying key has {} public inputs, expected {}",
+                key.gamma_abc_g1.len().saturating_sub(1),
+                Self::PUBLIC_INPUTS
+            )));
+        }
+        let digest = Hash32(Sha256::digest(vk).into());
+        self.keys.insert(model_id, Key { prepared: prepare_verifying_key(&key), digest });
+        Ok(self)
+    }
+
+    /// The public inputs `proof`'s hashes give the circuit:
+    /// `[input_hi, input_lo, output_hi, output_lo]`.
+    pub fn public_inputs(proof: &ProofJson) -> [u128; Self::PUBLIC_INPUTS] {
+        let halves = |digest: &Hash32| {
+            let (hi, lo) = digest.as_bytes().split_at(16);
+            [hi, lo].map(|half| u128::from_be_bytes(half.try_into().expect("16 bytes")))
+        };
+        let [input_hi, input_lo] = halves(&proof.input_hash.digest);
+        let [output_hi, output_lo] = halves(&proof.output_hash.digest);
+        [input_hi, input_lo, output_hi, output_lo]
+    }
+}
+
+fn backend(message: String) -> AdapterError {
+    AdapterError::Backend { proof_type: Groth16Verifier::PROOF_TYPE.into(), message }
+}
+
+/// Read an arkworks value, compressed or not, that must use all of `bytes`.
+/// Points are checked to be on the curve and in the right subgroup.
+fn decode<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T, String> {
+    let whole = |compressed: bool| {
+        let mut reader = bytes;
+        let value = if compressed {
+            T::deserialize_compressed(&mut reader)
+        } else {
+            T::deserialize_uncompressed(&mut reader)
+        };
+        value.ok().filter(|_| reader.is_empty())
+    };
+    whole(true).or_else(|| whole(false)).ok_or_else(|| format!("not an arkworks BN254 encoding ({} bytes)", bytes.len()))
+}
+
+impl ProofAdapter for Groth16Verifier {
+    fn proof_type(&self) -> &str {
+        Self::PROOF_TYPE
+    }
+
+    fn verify(&self, proof: &ProofJson) -> Result<Verdict, AdapterError> {
+        let malformed = |message: String| AdapterError::Evidence { proof_type: Self::PROOF_TYPE.into(), message };
+        let key = self
+            .keys
+            .get(&proof.model_id)
+            .ok_or_else(|| backend(format!("no verifying key for model {}", proof.model_id.as_str())))?;
+        let evidence = proof.evidence.clone().ok_or_else(|| malformed("missing".into()))?;
+        let evidence: Groth16Evidence = serde_json::from_value(evidence).map_err(|e| malformed(e.to_string()))?;
+        let bytes = hex::decode(evidence.proof.strip_prefix("0x").unwrap_or(&evidence.proof))
+            .map_err(|e| malformed(format!("proof: {e}")))?;
+        let snark: Proof<Bn254> = decode(&bytes).map_err(|e| malformed(format!("proof: {e}")))?;
+
+        let inputs = Self::public_inputs(proof);
+        let scalars = inputs.map(Fr::from);
+        let valid = Groth16::<Bn254>::verify_proof(&key.prepared, &snark, &scalars).map_err(|e| backend(e.to_string()))?;
+        let reason = if valid {
+            format!("Groth16 proof verifies for model {}", proof.model_id.as_str())
+        } else {
+            "Groth16 proof does not verify for these input and output hashes".to_string()
+        };
+        Ok(Verdict::new(Self::PROOF_TYPE, valid, reason)
+            .with("verifying_key", key.digest)
+            .with("public_inputs", inputs.map(|x| format!("0x{x:032x}"))))
+    }
+}
@@ -0,0 +1,32 @@
+use super::{ProofAdapter, Verdict};
+use crate::error::AdapterError;
+use crate::proof::ProofJson;
+
+/// The hash-only recompute of [`ProofJson::recomputed_output_hash`]. It
+/// needs no evidence and does not run the model.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HashRecompute;
+
+impl HashRecompute {
+    pub const PROOF_TYPE: &'static str = "hash-recompute";
+}
+
+impl ProofAdapter for HashRecompute {
+    fn proof_type(&self) -> &str {
+        Self::PROOF_TYPE
+    }
+
+    fn verify(&self, proof: &ProofJson) -> Result<Verdict, AdapterError> {
+        let recomputed = proof.recomputed_output_hash();
+        let valid = recomputed == proof.output_hash;
+        let reason = if valid {
+            "output_hash matches the hash-only recompute; the model was not re-run"
+        } else {
+            "output_hash differs from the hash-only recompute"
+        };
+        Ok(Verdict::new(Self::PROOF_TYPE, valid, reason)
+            .with("algorithm", recomputed.alg)
+            .with("output_hash", proof.output_hash)
+            .with("recomputed_output_hash", recomputed))
+    }
+}
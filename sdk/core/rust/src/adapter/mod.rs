@@ -0,0 +1,127 @@
+//! Proof adapters: the verification backends that decide a proof's `valid`
+//! flag.
+//!
+//! A proof names its backend in `proof_type` and carries what that backend
+//! needs in `evidence` (proof format v5). An [`AdapterRegistry`] maps each
+//! type to a [`ProofAdapter`] and returns its [`Verdict`], whose `valid` is
+//! what gets anchored on-chain. Proofs that name no type go to the
+//! registry's default adapter, [`HashRecompute`] unless changed.
+//!
+//! Built in: [`HashRecompute`], [`SignedAttestation`],
+//! [`ExternalVerifier`] and, with the `groth16` and `tee` features,
+//! `Groth16Verifier` for zk-SNARK proofs of inference and `TeeVerifier` for
+//! hardware attestation quotes. WebAssembly re-execution implements the trait in
+//! the `vericortex-wasm-runtime` crate.
+
+mod attestation;
+mod external;
+#[cfg(feature = "groth16")]
+mod groth16;
+mod hash;
+#[cfg(feature = "tee")]
+mod tee;
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::AdapterError;
+use crate::proof::ProofJson;
+
+pub use attestation::{Attestation, SignedAttestation};
+pub use external::ExternalVerifier;
+#[cfg(feature = "groth16")]
+pub use groth16::{Groth16Evidence, Groth16Verifier};
+pub use hash::HashRecompute;
+#[cfg(feature = "tee")]
+pub use tee::{Platform, TeeEvidence, TeeVerifier, AMD_ARKS, INTEL_SGX_ROOT_CA};
+
+/// A verification backend for one proof type.
+pub trait ProofAdapter: Send + Sync {
+    /// The `proof_type` this adapter verifies.
+    fn proof_type(&self) -> &str;
+
+    /// Check `proof`. `Err` means it could not be checked (missing or
+    /// malformed evidence, a backend that did not run), not that it is
+    /// invalid.
+    fn verify(&self, proof: &ProofJson) -> Result<Verdict, AdapterError>;
+}
+
+/// The outcome of checking one proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Verdict {
+    pub proof_type: String,
+    /// Anchored as the verifier contract's `valid` flag.
+    pub valid: bool,
+    /// What was established, or why the proof failed.
+    pub reason: String,
+    /// Adapter-specific facts, such as a recomputed hash or the signer.
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub details: Map<String, Value>,
+}
+
+impl Verdict {
+    pub fn new(proof_type: impl Into<String>, valid: bool, reason: impl Into<String>) -> Self {
+        Self { proof_type: proof_type.into(), valid, reason: reason.into(), details: Map::new() }
+    }
+
+    /// Add a detail.
+    pub fn with(mut self, key: &str, value: impl Serialize) -> Self {
+        let value = serde_json::to_value(value).expect("verdict details serialize to JSON");
+        self.details.insert(key.into(), value);
+        self
+    }
+}
+
+/// Adapters by proof type.
+pub struct AdapterRegistry {
+    adapters: BTreeMap<String, Box<dyn ProofAdapter>>,
+    default: String,
+}
+
+impl Default for AdapterRegistry {
+    /// Only [`HashRecompute`], which is also the default.
+    fn default() -> Self {
+        let mut registry = Self { adapters: BTreeMap::new(), default: HashRecompute::PROOF_TYPE.into() };
+        registry.register(HashRecompute);
+        registry
+    }
+}
+
+impl AdapterRegistry {
+    /// Add `adapter`, replacing any registered for the same type.
+    pub fn register(&mut self, adapter: impl ProofAdapter + 'static) -> &mut Self {
+        self.adapters.insert(adapter.proof_type().to_string(), Box::new(adapter));
+        self
+    }
+
+    /// The type used for proofs that name none.
+    pub fn set_default(&mut self, proof_type: impl Into<String>) -> &mut Self {
+        self.default = proof_type.into();
+        self
+    }
+
+    pub fn get(&self, proof_type: &str) -> Option<&dyn ProofAdapter> {
+        self.adapters.get(proof_type).map(|a| a.as_ref())
+    }
+
+    pub fn proof_types(&self) -> impl Iterator<Item = &str> {
+        self.adapters.keys().map(String::as_str)
+    }
+
+    /// The type `proof` will be checked as.
+    pub fn proof_type_of<'a>(&'a self, proof: &'a ProofJson) -> &'a str {
+        proof.proof_type.as_deref().unwrap_or(&self.default)
+    }
+
+    /// Check `proof` with the adapter for its type.
+    pub fn verify(&self, proof: &ProofJson) -> Result<Verdict, AdapterError> {
+        let proof_type = self.proof_type_of(proof);
+        let adapter = self.get(proof_type).ok_or_else(|| AdapterError::UnknownType {
+            proof_type: proof_type.into(),
+            registered: self.proof_types().collect::<Vec<_>>().join(", "),
+        })?;
+        adapter.verify(proof)
+    }
+}
@@ -0,0 +1,139 @@
+//! Intel DCAP ECDSA quotes: SGX (v3 and v4) and TDX (v4).
+//!
+//! The quote is signed by an attestation key, which the Quoting Enclave
+//! vouches for in its own report (`report_data` = SHA-256 of the key and
+//! the QE authentication data), which is in turn signed by the platform's
+//! PCK certificate, chained to the Intel SGX Root CA.
+
+use p256::ecdsa::signature::Verifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use p256::EncodedPoint;
+use sha2::{Digest, Sha256};
+
+use super::x509::{self, Anchors, Chained, PublicKey};
+use super::{Failure, Platform, Quoted};
+
+const HEADER_LEN: usize = 48;
+const SGX_BODY_LEN: usize = 384;
+const TDX_BODY_LEN: usize = 584;
+/// `att_key_type` of ECDSA-256-with-P-256.
+const ECDSA_P256: u16 = 2;
+const TEE_SGX: u32 = 0x00;
+const TEE_TDX: u32 = 0x81;
+/// Certification data types.
+const PCK_CERT_CHAIN: u16 = 5;
+const QE_REPORT: u16 = 6;
+
+/// Reads a quote front to back.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Failure> {
+        if self.0.len() < n {
+            return Err(Failure::Malformed("quote is truncated".into()));
+        }
+        let (head, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn u16(&mut self) -> Result<u16, Failure> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().expect("2 bytes")))
+    }
+
+    fn u32(&mut self) -> Result<u32, Failure> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().expect("4 bytes")))
+    }
+
+    /// A `type: u16, size: u32, data` certification data block.
+    fn cert_data(&mut self, expected: u16) -> Result<&'a [u8], Failure> {
+        let kind = self.u16()?;
+        let size = self.u32()? as usize;
+        if kind != expected {
+            return Err(Failure::Malformed(format!("certification data type {kind}, expected {expected}")));
+        }
+        self.take(size)
+    }
+}
+
+fn signature(raw: &[u8]) -> Result<Signature, Failure> {
+    Signature::from_slice(raw).map_err(|e| Failure::Malformed(format!("signature: {e}")))
+}
+
+pub(super) fn verify<'r>(
+    quote: &[u8],
+    platform: Platform,
+    anchors: &Anchors<'r>,
+) -> Result<(Quoted, Chained<'r>), Failure> {
+    let mut reader = Reader(quote);
+    let header = reader.take(HEADER_LEN)?;
+    let version = u16::from_le_bytes([header[0], header[1]]);
+    let key_type = u16::from_le_bytes([header[2], header[3]]);
+    let tee_type = u32::from_le_bytes(header[4..8].try_into().expect("4 bytes"));
+    let body_len = match (platform, version, tee_type) {
+        (Platform::Sgx, 3, _) | (Platform::Sgx, 4, TEE_SGX) => SGX_BODY_LEN,
+        (Platform::Tdx, 4, TEE_TDX) => TDX_BODY_LEN,
+        _ => {
+            let message = format!("not a {platform:?} quote (version {version}, TEE type {tee_type:#x})");
+            return Err(Failure::Malformed(message));
+        }
+    };
+    if key_type != ECDSA_P256 {
+        return Err(Failure::Malformed(format!("attestation key type {key_type} is not ECDSA P-256")));
+    }
+    let body = reader.take(body_len)?;
+    let signed = &quote[..HEADER_LEN + body_len];
+
+    let sig_len = reader.u32()? as usize;
+    let mut sig_data = Reader(reader.take(sig_len)?);
+    let quote_sig = signature(sig_data.take(64)?)?;
+    let attest_key = sig_data.take(64)?;
+    // v4 wraps the QE report in certification data of its own.
+    let mut qe = if version == 3 { sig_data } else { Reader(sig_data.cert_data(QE_REPORT)?) };
+    let qe_report = qe.take(SGX_BODY_LEN)?;
+    let qe_sig = signature(qe.take(64)?)?;
+    let auth_len = qe.u16()? as usize;
+    let qe_auth = qe.take(auth_len)?;
+    // Quoting enclaves NUL-terminate the chain, and some pad it further.
+    let pck_pem = qe.cert_data(PCK_CERT_CHAIN)?;
+    let pck_pem = &pck_pem[..pck_pem.iter().rposition(|&b| b != 0).map_or(0, |end| end + 1)];
+    let pck_chain = x509::parse_pem(pck_pem).map_err(Failure::Malformed)?;
+
+    let chained = x509::verify_chain(&pck_chain, anchors)?;
+    let PublicKey::P256(pck) = PublicKey::of(&pck_chain[0]).map_err(Failure::Rejected)? else {
+        return Err(Failure::Rejected("PCK certificate key is not P-256".into()));
+    };
+    pck.verify(qe_report, &qe_sig)
+        .map_err(|_| Failure::Rejected("QE report is not signed by the PCK certificate".into()))?;
+    let mut expected = Sha256::new();
+    expected.update(attest_key);
+    expected.update(qe_auth);
+    // The QE report's report_data: the key hash, then 32 zero bytes.
+    if qe_report[320..352] != expected.finalize()[..] || qe_report[352..384].iter().any(|&b| b != 0) {
+        return Err(Failure::Rejected("QE report does not vouch for the attestation key".into()));
+    }
+    let point = EncodedPoint::from_untagged_bytes(attest_key.into());
+    let attest_key = VerifyingKey::from_encoded_point(&point)
+        .map_err(|_| Failure::Malformed("attestation key is not a P-256 point".into()))?;
+    attest_key
+        .verify(signed, &quote_sig)
+        .map_err(|_| Failure::Rejected("quote signature does not verify".into()))?;
+
+    let quoted = match platform {
+        // Report body: ATTRIBUTES at 48 (DEBUG is bit 1), MRENCLAVE at 64,
+        // REPORTDATA at 320.
+        Platform::Sgx => Quoted {
+            measurement: body[64..96].to_vec(),
+            report_data: body[320..384].try_into().expect("64 bytes"),
+            debug: body[48] & 0x02 != 0,
+        },
+        // TD report: TDATTRIBUTES at 120 (DEBUG is bit 0), MRTD at 136,
+        // REPORTDATA at 520.
+        _ => Quoted {
+            measurement: body[136..184].to_vec(),
+            report_data: body[520..584].try_into().expect("64 bytes"),
+            debug: body[120] & 0x01 != 0,
+        },
+    };
+    Ok((quoted, chained))
+}
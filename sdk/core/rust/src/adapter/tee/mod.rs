@@ -0,0 +1,283 @@
+//! TEE attestation: a proof backed by a hardware quote that the model ran
+//! inside an enclave allowlisted for it and reported exactly this bundle.
+
+mod dcap;
+mod snp;
+mod x509;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use x509_cert::crl::CertificateList;
+use x509_cert::Certificate;
+
+use self::x509::{Anchors, Chained, Revocation};
+use super::{ProofAdapter, Verdict};
+use crate::error::AdapterError;
+use crate::proof::ProofJson;
+use crate::types::ModelId;
+
+/// The Intel SGX Root CA, which every SGX and TDX PCK chain ends at (SHA-256
+/// fingerprint `44:A0:19:6B:…:B6:74:D3`). [`TeeVerifier::new`] trusts it.
+pub const INTEL_SGX_ROOT_CA: &str = include_str!("roots/intel_sgx_root_ca.pem");
+
+/// The AMD Root Keys of the Milan, Genoa and Turin product lines, which
+/// SEV-SNP VCEK chains end at. [`TeeVerifier::new`] trusts them.
+pub const AMD_ARKS: [&str; 3] = [
+    include_str!("roots/amd_ark_milan.pem"),
+    include_str!("roots/amd_ark_genoa.pem"),
+    include_str!("roots/amd_ark_turin.pem"),
+];
+
+/// The TEE that produced a quote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Platform {
+    /// An Intel SGX enclave; the measurement is MRENCLAVE.
+    Sgx,
+    /// An Intel TDX trust domain; the measurement is MRTD.
+    Tdx,
+    /// An AMD SEV-SNP guest; the measurement is the launch MEASUREMENT.
+    SevSnp,
+}
+
+/// `evidence` of a `tee-attestation` proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TeeEvidence {
+    pub platform: Platform,
+    /// The raw DCAP quote (v3 or v4) or SEV-SNP attestation report, as `0x`
+    /// hex.
+    pub quote: String,
+    /// PEM certificates, leaf first. SEV-SNP needs the VCEK and ASK here;
+    /// DCAP quotes carry their PCK chain themselves.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cert_chain: Option<String>,
+}
+
+/// Why a quote was not accepted. Malformed evidence cannot be checked at
+/// all; a rejected quote was checked and failed.
+enum Failure {
+    Malformed(String),
+    Rejected(String),
+}
+
+/// What a verified quote says.
+struct Quoted {
+    measurement: Vec<u8>,
+    report_data: [u8; 64],
+    debug: bool,
+}
+
+/// Checks DCAP quotes and SEV-SNP reports against trusted vendor roots and
+/// revocation lists, the measurements allowlisted for the proof's model, and
+/// the proof's bundle hash.
+///
+/// The quote's `report_data` must start with
+/// [`TeeVerifier::report_data`] of the proof; the other 32 bytes are left to
+/// the enclave (a nonce, say). Debug enclaves are always rejected.
+///
+/// Quotes are rejected unless revocation was checked: every certificate
+/// below the root must be covered by a current CRL from its issuer (see
+/// [`TeeVerifier::add_crls`]). TCB levels are never evaluated, so quotes are
+/// also rejected until [`TeeVerifier::accept_unevaluated_tcb`] is set.
+#[derive(Clone, Debug)]
+pub struct TeeVerifier {
+    roots: Vec<Certificate>,
+    crls: Vec<CertificateList>,
+    measurements: BTreeMap<ModelId, BTreeSet<Vec<u8>>>,
+    accept_unknown_revocation: bool,
+    accept_unevaluated_tcb: bool,
+}
+
+impl Default for TeeVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TeeVerifier {
+    pub const PROOF_TYPE: &'static str = "tee-attestation";
+
+    /// A verifier trusting [`INTEL_SGX_ROOT_CA`] and [`AMD_ARKS`].
+    pub fn new() -> Self {
+        let mut verifier = Self {
+            roots: Vec::new(),
+            crls: Vec::new(),
+            measurements: BTreeMap::new(),
+            accept_unknown_revocation: false,
+            accept_unevaluated_tcb: false,
+        };
+        for root in [INTEL_SGX_ROOT_CA].iter().chain(&AMD_ARKS) {
+            verifier.add_roots(root.as_bytes()).expect("bundled roots are valid roots");
+        }
+        verifier
+    }
+
+    fn backend(message: String) -> AdapterError {
+        AdapterError::Backend { proof_type: Self::PROOF_TYPE.into(), message }
+    }
+
+    /// Trust the self-signed CA certificates in `pem` as well.
+    pub fn add_roots(&mut self, pem: &[u8]) -> Result<&mut Self, AdapterError> {
+        for root in x509::parse_pem(pem).map_err(Self::backend)? {
+            x509::check_root(&root).map_err(Self::backend)?;
+            self.roots.push(root);
+        }
+        Ok(self)
+    }
+
+    /// Check chains against the CRLs in `crls` (PEM `X509 CRL` blocks, or
+    /// one DER CRL), such as Intel's root and PCK CRLs. A CRL is only used
+    /// for certificates its signer issued; one issued by a trusted root must
+    /// verify against it here.
+    pub fn add_crls(&mut self, crls: &[u8]) -> Result<&mut Self, AdapterError> {
+        for crl in x509::parse_crls(crls).map_err(Self::backend)? {
+            let issuer = &crl.tbs_cert_list.issuer;
+            let mut roots = self.roots.iter().filter(|root| &root.tbs_certificate.subject == issuer).peekable();
+            if roots.peek().is_some() && !roots.any(|root| x509::check_crl_signature(&crl, root).is_ok()) {
+                return Err(Self::backend(format!("CRL of {issuer} is not signed by that root")));
+            }
+            self.crls.push(crl);
+        }
+        Ok(self)
+    }
+
+    /// Accept quotes whose chain no current CRL covers, reporting
+    /// `revocation: unknown`. AMD publishes no CRL for VCEKs, so SEV-SNP
+    /// needs this.
+    pub fn accept_unknown_revocation(&mut self) -> &mut Self {
+        self.accept_unknown_revocation = true;
+        self
+    }
+
+    /// Accept quotes without evaluating the platform's TCB level (Intel TCB
+    /// Info, AMD `reported_tcb`), reporting `tcb: not-evaluated`.
+    pub fn accept_unevaluated_tcb(&mut self) -> &mut Self {
+        self.accept_unevaluated_tcb = true;
+        self
+    }
+
+    /// Accept proofs of `model_id` quoted by an enclave, TD or guest with
+    /// this measurement.
+    pub fn allow_measurement(&mut self, model_id: impl Into<ModelId>, measurement: &[u8]) -> &mut Self {
+        self.measurements.entry(model_id.into()).or_default().insert(measurement.to_vec());
+        self
+    }
+
+    /// What the first 32 bytes of `report_data` must be for `proof`: its
+    /// [`ProofJson::derived_bundle_hash`], which commits to the model,
+    /// version and hashes.
+    pub fn report_data(proof: &ProofJson) -> [u8; 32] {
+        proof.derived_bundle_hash().0
+    }
+
+    /// Verify a quote on its own: its chain, signatures, revocation and TCB
+    /// policy, and that it is not from a debug enclave. The verdict's details
+    /// carry the `measurement` and `report_data` for the caller to check;
+    /// [`ProofAdapter::verify`] checks them against a proof.
+    pub fn verify_quote(&self, evidence: &TeeEvidence) -> Result<Verdict, AdapterError> {
+        let checked = self.quote(evidence).and_then(|(quoted, chained)| {
+            let (root, revocation) = self.policy(chained)?;
+            Ok((quoted, root, revocation, "a genuine quote"))
+        });
+        Self::verdict(evidence, checked)
+    }
+
+    /// The quote's claims, once its chain and signatures check out.
+    fn quote<'a>(&'a self, evidence: &TeeEvidence) -> Result<(Quoted, Chained<'a>), Failure> {
+        let quote = hex::decode(evidence.quote.strip_prefix("0x").unwrap_or(&evidence.quote))
+            .map_err(|e| Failure::Malformed(format!("quote: {e}")))?;
+        let chain = evidence
+            .cert_chain
+            .as_deref()
+            .map(|pem| x509::parse_pem(pem.as_bytes()))
+            .transpose()
+            .map_err(Failure::Malformed)?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("clock after 1970");
+        let anchors = Anchors { roots: &self.roots, crls: &self.crls, now };
+
+        let (quoted, chained) = match evidence.platform {
+            Platform::Sgx | Platform::Tdx => dcap::verify(&quote, evidence.platform, &anchors)?,
+            Platform::SevSnp => {
+                let chain = chain.ok_or_else(|| Failure::Malformed("SEV-SNP needs cert_chain (VCEK, ASK)".into()))?;
+                snp::verify(&quote, &chain, &anchors)?
+            }
+        };
+        if quoted.debug {
+            return Err(Failure::Rejected("the quote is from a debug enclave".into()));
+        }
+        Ok((quoted, chained))
+    }
+
+    /// The chain's root, and whether revocation was checked, if the policy
+    /// accepts what could not be checked.
+    fn policy(&self, chained: Chained<'_>) -> Result<(String, &'static str), Failure> {
+        let revocation = match chained.revocation {
+            Revocation::Checked => "checked",
+            Revocation::Unknown(_) if self.accept_unknown_revocation => "unknown",
+            Revocation::Unknown(why) => return Err(Failure::Rejected(format!("revocation unchecked: {why}"))),
+        };
+        if !self.accept_unevaluated_tcb {
+            return Err(Failure::Rejected("the platform's TCB level is not evaluated".into()));
+        }
+        Ok((x509::subject(chained.root), revocation))
+    }
+
+    /// A genuine quote that commits to `proof`, from an enclave allowlisted
+    /// for its model.
+    fn check(&self, evidence: &TeeEvidence, proof: &ProofJson) -> Result<(Quoted, String, &'static str), Failure> {
+        let (quoted, chained) = self.quote(evidence)?;
+        let bundle = Self::report_data(proof);
+        if proof.proof_hash.is_some_and(|recorded| recorded.0 != bundle) {
+            return Err(Failure::Rejected("recorded proof_hash does not match the quoted bundle hash".into()));
+        }
+        if quoted.report_data[..32] != bundle {
+            return Err(Failure::Rejected("report_data does not commit to the proof's bundle hash".into()));
+        }
+        let allowed = self.measurements.get(&proof.model_id);
+        if !allowed.is_some_and(|allowed| allowed.contains(&quoted.measurement)) {
+            let measurement = hex::encode(&quoted.measurement);
+            return Err(Failure::Rejected(format!(
+                "measurement 0x{measurement} is not allowlisted for model {}",
+                proof.model_id.as_str()
+            )));
+        }
+        let (root, revocation) = self.policy(chained)?;
+        Ok((quoted, root, revocation))
+    }
+
+    fn verdict(
+        evidence: &TeeEvidence,
+        checked: Result<(Quoted, String, &'static str, &str), Failure>,
+    ) -> Result<Verdict, AdapterError> {
+        let verdict = match checked {
+            Ok((quoted, root, revocation, what)) => Verdict::new(Self::PROOF_TYPE, true, format!("{what} under {root}"))
+                .with("measurement", format!("0x{}", hex::encode(&quoted.measurement)))
+                .with("report_data", format!("0x{}", hex::encode(quoted.report_data)))
+                .with("root", root)
+                .with("revocation", revocation)
+                .with("tcb", "not-evaluated"),
+            Err(Failure::Rejected(reason)) => Verdict::new(Self::PROOF_TYPE, false, reason),
+            Err(Failure::Malformed(message)) => {
+                return Err(AdapterError::Evidence { proof_type: Self::PROOF_TYPE.into(), message })
+            }
+        };
+        Ok(verdict.with("platform", evidence.platform))
+    }
+}
+
+impl ProofAdapter for TeeVerifier {
+    fn proof_type(&self) -> &str {
+        Self::PROOF_TYPE
+    }
+
+    fn verify(&self, proof: &ProofJson) -> Result<Verdict, AdapterError> {
+        let malformed = |message: String| AdapterError::Evidence { proof_type: Self::PROOF_TYPE.into(), message };
+        let evidence = proof.evidence.clone().ok_or_else(|| malformed("missing".into()))?;
+        let evidence: TeeEvidence = serde_json::from_value(evidence).map_err(|e| malformed(e.to_string()))?;
+        let checked = self.check(&evidence, proof);
+        Self::verdict(&evidence, checked.map(|(quoted, root, revocation)| (quoted, root, revocation, "quoted by an allowlisted enclave")))
+    }
+}
@@ -0,0 +1,68 @@
+//! AMD SEV-SNP attestation reports, signed by the chip's VCEK, which the
+//! AMD SEV Key (ASK) certifies under the AMD Root Key (ARK).
+
+use p384::ecdsa::signature::Verifier;
+use p384::ecdsa::Signature;
+use x509_cert::Certificate;
+
+use super::x509::{self, Anchors, Chained, PublicKey};
+use super::{Failure, Quoted};
+
+const REPORT_LEN: usize = 0x4a0;
+/// Everything before the signature is signed.
+const SIGNED_LEN: usize = 0x2a0;
+/// `signature_algo` of ECDSA P-384 with SHA-384.
+const ECDSA_P384_SHA384: u32 = 1;
+/// Policy bit allowing the guest to be debugged.
+const POLICY_DEBUG: u64 = 1 << 19;
+
+fn u32_at(report: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(report[at..at + 4].try_into().expect("4 bytes"))
+}
+
+/// The report stores `r` and `s` little-endian, zero-padded to 72 bytes.
+fn signature(raw: &[u8]) -> Result<Signature, Failure> {
+    let malformed = || Failure::Malformed("report signature is not a P-384 signature".into());
+    let scalar = |le: &[u8]| -> Result<Vec<u8>, Failure> {
+        if le[48..72].iter().any(|&b| b != 0) {
+            return Err(malformed());
+        }
+        Ok(le[..48].iter().rev().copied().collect())
+    };
+    let (r, s) = (scalar(&raw[..72])?, scalar(&raw[72..144])?);
+    Signature::from_scalars(<[u8; 48]>::try_from(r).expect("48 bytes"), <[u8; 48]>::try_from(s).expect("48 bytes"))
+        .map_err(|_| malformed())
+}
+
+pub(super) fn verify<'r>(
+    report: &[u8],
+    chain: &[Certificate],
+    anchors: &Anchors<'r>,
+) -> Result<(Quoted, Chained<'r>), Failure> {
+    if report.len() != REPORT_LEN {
+        return Err(Failure::Malformed(format!("SEV-SNP report is {} bytes, expected {REPORT_LEN}", report.len())));
+    }
+    let version = u32_at(report, 0x00);
+    if !(2..=3).contains(&version) {
+        return Err(Failure::Malformed(format!("SEV-SNP report version {version} is not supported")));
+    }
+    let algo = u32_at(report, 0x34);
+    if algo != ECDSA_P384_SHA384 {
+        return Err(Failure::Malformed(format!("SEV-SNP signature algorithm {algo} is not ECDSA P-384")));
+    }
+
+    let chained = x509::verify_chain(chain, anchors)?;
+    let PublicKey::P384(vcek) = PublicKey::of(&chain[0]).map_err(Failure::Rejected)? else {
+        return Err(Failure::Rejected("VCEK certificate key is not P-384".into()));
+    };
+    vcek.verify(&report[..SIGNED_LEN], &signature(&report[SIGNED_LEN..SIGNED_LEN + 144])?)
+        .map_err(|_| Failure::Rejected("report signature does not verify against the VCEK".into()))?;
+
+    let policy = u64::from_le_bytes(report[0x08..0x10].try_into().expect("8 bytes"));
+    let quoted = Quoted {
+        measurement: report[0x90..0xc0].to_vec(),
+        report_data: report[0x50..0x90].try_into().expect("64 bytes"),
+        debug: policy & POLICY_DEBUG != 0,
+    };
+    Ok((quoted, chained))
+}
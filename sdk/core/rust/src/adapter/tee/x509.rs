@@ -0,0 +1,249 @@
+//! Just enough X.509 to walk a vendor attestation chain: the signature
+//! schemes Intel (ECDSA P-256) and AMD (RSA-PSS, ECDSA P-384) use, validity
+//! windows, CA flags and revocation lists.
+
+use std::time::Duration;
+
+use p256::pkcs8::DecodePublicKey;
+use rsa::pkcs1::RsaPssParams;
+use rsa::signature::Verifier;
+use rsa::RsaPublicKey;
+use sha2::{Sha256, Sha384};
+use x509_cert::crl::CertificateList;
+use x509_cert::der::asn1::{BitString, ObjectIdentifier};
+use x509_cert::der::{Decode, Encode, Error as DerError};
+use x509_cert::ext::pkix::BasicConstraints;
+use x509_cert::spki::AlgorithmIdentifierOwned;
+use x509_cert::Certificate;
+
+use super::Failure;
+
+const ECDSA_SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.2");
+const ECDSA_SHA384: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.3");
+const RSA_PSS: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.10");
+const RSA_SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.11");
+const RSA_SHA384: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.12");
+const SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.1");
+const SHA384: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.2");
+
+/// A certificate's subject key, in the forms attestation chains use.
+pub(super) enum PublicKey {
+    P256(p256::ecdsa::VerifyingKey),
+    P384(p384::ecdsa::VerifyingKey),
+    Rsa(RsaPublicKey),
+}
+
+impl PublicKey {
+    pub(super) fn of(cert: &Certificate) -> Result<Self, String> {
+        let der = cert.tbs_certificate.subject_public_key_info.to_der().map_err(|e| e.to_string())?;
+        if let Ok(key) = p256::ecdsa::VerifyingKey::from_public_key_der(&der) {
+            return Ok(Self::P256(key));
+        }
+        if let Ok(key) = p384::ecdsa::VerifyingKey::from_public_key_der(&der) {
+            return Ok(Self::P384(key));
+        }
+        RsaPublicKey::from_public_key_der(&der)
+            .map(Self::Rsa)
+            .map_err(|_| format!("{}: unsupported public key", subject(cert)))
+    }
+}
+
+pub(super) fn subject(cert: &Certificate) -> String {
+    cert.tbs_certificate.subject.to_string()
+}
+
+/// Every certificate in a PEM bundle.
+pub(super) fn parse_pem(pem: &[u8]) -> Result<Vec<Certificate>, String> {
+    let certs = Certificate::load_pem_chain(pem).map_err(|e| format!("certificates: {e}"))?;
+    if certs.is_empty() {
+        return Err("certificates: none found".into());
+    }
+    Ok(certs)
+}
+
+/// Check that `cert` carries a valid signature by `issuer`'s key.
+fn check_signature(cert: &Certificate, issuer: &Certificate) -> Result<(), String> {
+    let who = format!("{} (issued by {})", subject(cert), subject(issuer));
+    let tbs = cert.tbs_certificate.to_der().map_err(|e| e.to_string())?;
+    check_signed(&who, &tbs, &cert.signature_algorithm, &cert.signature, issuer)
+}
+
+/// Check that `crl` carries a valid signature by `issuer`'s key.
+pub(super) fn check_crl_signature(crl: &CertificateList, issuer: &Certificate) -> Result<(), String> {
+    let who = format!("CRL of {}", subject(issuer));
+    let tbs = crl.tbs_cert_list.to_der().map_err(|e| e.to_string())?;
+    check_signed(&who, &tbs, &crl.signature_algorithm, &crl.signature, issuer)
+}
+
+/// Check a signature by `issuer`'s key over the DER `tbs`.
+fn check_signed(
+    who: &str,
+    tbs: &[u8],
+    algorithm: &AlgorithmIdentifierOwned,
+    signature: &BitString,
+    issuer: &Certificate,
+) -> Result<(), String> {
+    let signature = signature.as_bytes().ok_or_else(|| format!("{who}: bad signature encoding"))?;
+    let verified = match (algorithm.oid, PublicKey::of(issuer)?) {
+        (ECDSA_SHA256, PublicKey::P256(key)) => p256::ecdsa::DerSignature::from_bytes(signature)
+            .map_err(|e| e.to_string())
+            .and_then(|sig| key.verify(tbs, &sig).map_err(|e| e.to_string())),
+        (ECDSA_SHA384, PublicKey::P384(key)) => p384::ecdsa::DerSignature::from_bytes(signature)
+            .map_err(|e| e.to_string())
+            .and_then(|sig| key.verify(tbs, &sig).map_err(|e| e.to_string())),
+        (RSA_PSS, PublicKey::Rsa(key)) => {
+            let params: RsaPssParams<'_> = algorithm
+                .parameters
+                .as_ref()
+                .ok_or(DerError::from(x509_cert::der::ErrorKind::Failed))
+                .and_then(|any| any.decode_as())
+                .map_err(|e| format!("{who}: RSA-PSS parameters: {e}"))?;
+            let sig = rsa::pss::Signature::try_from(signature).map_err(|e| e.to_string())?;
+            let salt = params.salt_len as usize;
+            match params.hash.oid {
+                SHA256 => rsa::pss::VerifyingKey::<Sha256>::new_with_salt_len(key, salt).verify(tbs, &sig),
+                SHA384 => rsa::pss::VerifyingKey::<Sha384>::new_with_salt_len(key, salt).verify(tbs, &sig),
+                other => return Err(format!("{who}: unsupported RSA-PSS hash {other}")),
+            }
+            .map_err(|e| e.to_string())
+        }
+        (RSA_SHA256 | RSA_SHA384, PublicKey::Rsa(key)) => {
+            let sig = rsa::pkcs1v15::Signature::try_from(signature).map_err(|e| e.to_string())?;
+            if algorithm.oid == RSA_SHA256 {
+                rsa::pkcs1v15::VerifyingKey::<Sha256>::new(key).verify(tbs, &sig)
+            } else {
+                rsa::pkcs1v15::VerifyingKey::<Sha384>::new(key).verify(tbs, &sig)
+            }
+            .map_err(|e| e.to_string())
+        }
+        (oid, _) => return Err(format!("{who}: unsupported signature algorithm {oid} for the issuer's key")),
+    };
+    verified.map_err(|e| format!("{who}: bad signature: {e}"))
+}
+
+fn check_validity(cert: &Certificate, now: Duration) -> Result<(), String> {
+    let validity = &cert.tbs_certificate.validity;
+    if now < validity.not_before.to_unix_duration() || now > validity.not_after.to_unix_duration() {
+        return Err(format!("{} is outside its validity period", subject(cert)));
+    }
+    Ok(())
+}
+
+fn check_ca(cert: &Certificate) -> Result<(), String> {
+    match cert.tbs_certificate.get::<BasicConstraints>() {
+        Ok(Some((_, constraints))) if constraints.ca => Ok(()),
+        _ => Err(format!("{} is not a CA but issues certificates", subject(cert))),
+    }
+}
+
+/// A trust anchor: a self-signed CA certificate.
+pub(super) fn check_root(root: &Certificate) -> Result<(), String> {
+    if root.tbs_certificate.issuer != root.tbs_certificate.subject {
+        return Err(format!("root {} is not self-issued", subject(root)));
+    }
+    check_ca(root)?;
+    check_signature(root, root)
+}
+
+/// Every CRL in a PEM bundle (`X509 CRL` blocks), or one DER CRL.
+pub(super) fn parse_crls(bytes: &[u8]) -> Result<Vec<CertificateList>, String> {
+    if bytes.first() == Some(&0x30) {
+        return CertificateList::from_der(bytes).map(|crl| vec![crl]).map_err(|e| format!("CRL: {e}"));
+    }
+    let text = std::str::from_utf8(bytes).map_err(|_| "CRL: neither DER nor PEM".to_string())?;
+    let mut crls = Vec::new();
+    for block in text.split_inclusive("-----END X509 CRL-----") {
+        let Some(start) = block.find("-----BEGIN X509 CRL-----") else { continue };
+        let (_, der) = x509_cert::der::pem::decode_vec(&block.as_bytes()[start..]).map_err(|e| format!("CRL: {e}"))?;
+        crls.push(CertificateList::from_der(&der).map_err(|e| format!("CRL: {e}"))?);
+    }
+    if crls.is_empty() {
+        return Err("CRL: none found".into());
+    }
+    Ok(crls)
+}
+
+/// Whether a chain's certificates are known not to be revoked.
+pub(super) enum Revocation {
+    /// Every certificate below the root is covered by a current CRL from its
+    /// issuer.
+    Checked,
+    /// No current CRL covers this certificate.
+    Unknown(String),
+}
+
+/// Where a chain led.
+pub(super) struct Chained<'r> {
+    pub(super) root: &'r Certificate,
+    pub(super) revocation: Revocation,
+}
+
+/// What chains are checked against.
+pub(super) struct Anchors<'a> {
+    pub(super) roots: &'a [Certificate],
+    pub(super) crls: &'a [CertificateList],
+    pub(super) now: Duration,
+}
+
+impl Anchors<'_> {
+    /// Reject `cert` if a CRL its issuer signed lists it, however old the
+    /// CRL; otherwise say whether a current one covers it.
+    fn revocation(&self, cert: &Certificate, issuer: &Certificate) -> Result<bool, Failure> {
+        let mut current = false;
+        for crl in self.crls {
+            let list = &crl.tbs_cert_list;
+            if list.issuer != issuer.tbs_certificate.subject || check_crl_signature(crl, issuer).is_err() {
+                continue;
+            }
+            let serial = &cert.tbs_certificate.serial_number;
+            if list.revoked_certificates.iter().flatten().any(|revoked| &revoked.serial_number == serial) {
+                return Err(Failure::Rejected(format!("{} is revoked", subject(cert))));
+            }
+            let fresh_until = list.next_update.map_or(Duration::ZERO, |t| t.to_unix_duration());
+            current |= list.this_update.to_unix_duration() <= self.now && self.now <= fresh_until;
+        }
+        Ok(current)
+    }
+}
+
+/// Walk `chain` (leaf first) up to one of the trusted roots, checking each
+/// certificate against the CRLs.
+/// The chain may end with the root itself or with a certificate it issued.
+pub(super) fn verify_chain<'r>(chain: &[Certificate], anchors: &Anchors<'r>) -> Result<Chained<'r>, Failure> {
+    let last = chain.last().ok_or_else(|| Failure::Malformed("empty certificate chain".into()))?;
+    for pair in chain.windows(2) {
+        let (cert, issuer) = (&pair[0], &pair[1]);
+        if cert.tbs_certificate.issuer != issuer.tbs_certificate.subject {
+            return Err(Failure::Rejected(format!("{} was not issued by {}", subject(cert), subject(issuer))));
+        }
+        check_ca(issuer).map_err(Failure::Rejected)?;
+        check_signature(cert, issuer).map_err(Failure::Rejected)?;
+    }
+    for cert in chain {
+        check_validity(cert, anchors.now).map_err(Failure::Rejected)?;
+    }
+
+    let last_der = last.to_der().map_err(|e| Failure::Malformed(e.to_string()))?;
+    let root = anchors.roots.iter().find_map(|root| {
+        if root.to_der().is_ok_and(|der| der == last_der) {
+            Some((root, chain.len() - 1))
+        } else if root.tbs_certificate.subject == last.tbs_certificate.issuer && check_signature(last, root).is_ok() {
+            Some((root, chain.len()))
+        } else {
+            None
+        }
+    });
+    let Some((root, below_root)) = root else {
+        return Err(Failure::Rejected(format!("{} does not chain to a trusted root", subject(last))));
+    };
+    check_validity(root, anchors.now).map_err(Failure::Rejected)?;
+
+    let mut revocation = Revocation::Checked;
+    for (i, cert) in chain[..below_root].iter().enumerate() {
+        let issuer = chain.get(i + 1).unwrap_or(root);
+        if !anchors.revocation(cert, issuer)? && matches!(revocation, Revocation::Checked) {
+            revocation = Revocation::Unknown(format!("no current CRL from {} covers {}", subject(issuer), subject(cert)));
+        }
+    }
+    Ok(Chained { root, revocation })
+}
@@ -0,0 +1,104 @@
+//! Batches of proofs anchored on-chain as a single Merkle root.
+//!
+//! Each proof contributes its [`ProofJson::bundle_hash`] as a leaf; the root
+//! is submitted as the batch record's `proofHash`. Every proof gets a
+//! [`BatchReceipt`] holding its Merkle path, so it can be proven against the
+//! anchored root on its own.
+
+use serde::{Deserialize, Serialize};
+
+use crate::merkle::{MerkleProof, MerkleTree};
+use crate::proof::ProofJson;
+use crate::types::{Hash32, ModelId, ProofId};
+
+#[derive(Debug, Clone)]
+pub struct ProofBatch {
+    proofs: Vec<ProofJson>,
+    tree: MerkleTree,
+}
+
+impl ProofBatch {
+    pub fn new(proofs: Vec<ProofJson>) -> Self {
+        let leaves: Vec<Hash32> = proofs.iter().map(ProofJson::bundle_hash).collect();
+        let tree = MerkleTree::from_leaves(&leaves);
+        Self { proofs, tree }
+    }
+
+    pub fn proofs(&self) -> &[ProofJson] {
+        &self.proofs
+    }
+
+    pub fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proofs.is_empty()
+    }
+
+    /// The value anchored as the batch record's `proofHash`.
+    pub fn root(&self) -> Hash32 {
+        self.tree.root()
+    }
+
+    /// The model shared by every proof in the batch, if there is exactly one.
+    pub fn common_model(&self) -> Option<(&ModelId, &str)> {
+        let first = self.proofs.first()?;
+        self.proofs
+            .iter()
+            .all(|p| p.model_id == first.model_id && p.model_version == first.model_version)
+            .then_some((&first.model_id, first.model_version.as_str()))
+    }
+
+    /// The model version shared by every proof in the batch, if there is
+    /// exactly one.
+    pub fn common_version(&self) -> Option<&str> {
+        let first = self.proofs.first()?;
+        self.proofs
+            .iter()
+            .all(|p| p.model_version == first.model_version)
+            .then_some(first.model_version.as_str())
+    }
+
+    /// One receipt per proof, in batch order. `anchor` is filled in once the
+    /// root has been submitted.
+    pub fn receipts(&self, anchor: Option<&BatchAnchor>) -> Vec<BatchReceipt> {
+        self.proofs
+            .iter()
+            .enumerate()
+            .map(|(i, proof)| BatchReceipt {
+                proof: proof.clone(),
+                batch_root: self.root(),
+                path: self.tree.proof(i).expect("index within batch"),
+                anchor: anchor.cloned(),
+            })
+            .collect()
+    }
+}
+
+/// Where a batch root was anchored on-chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchAnchor {
+    pub proof_id: ProofId,
+    pub tx_hash: Hash32,
+    pub block_number: Option<u64>,
+}
+
+/// Evidence that one proof is part of an anchored batch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchReceipt {
+    pub proof: ProofJson,
+    pub batch_root: Hash32,
+    pub path: MerkleProof,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<BatchAnchor>,
+}
+
+impl BatchReceipt {
+    /// Check the proof's bundle hash against the receipt's batch root. Pair
+    /// with a `getProof(anchor.proof_id).proofHash == batch_root` lookup to
+    /// prove the inference against the chain.
+    pub fn verify(&self) -> bool {
+        self.path.verify(&self.proof.bundle_hash(), &self.batch_root)
+    }
+}
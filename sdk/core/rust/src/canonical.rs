@@ -0,0 +1,677 @@
+//! Canonical encodings of raw inference payloads, and the hashes recorded as
+//! `input_hash` / `output_hash`.
+//!
+//! A payload hash is `SHA-256(canonical bytes)`, or any other
+//! [`HashAlg`] through [`Payload::digest`], where the canonical bytes are:
+//!
+//! - JSON: the RFC 8785 (JCS) serialization. Object members are sorted by the
+//!   UTF-16 code units of their names, there is no insignificant whitespace,
+//!   strings use the minimal JSON escapes and numbers are printed the way
+//!   ECMAScript prints an IEEE 754 double. Duplicate object keys and integers
+//!   beyond ±2^53 are rejected instead of being silently collapsed or rounded
+//! - raw bytes: the bytes unchanged
+//! - tensors: [`TENSOR_MAGIC`], a version byte, the dtype tag, the rank as
+//!   `u32` and each dimension as `u64`, then the elements, all little-endian.
+//!   Booleans must be `0` or `1` and every float NaN becomes the canonical
+//!   quiet NaN, so the same values hash the same on any platform
+//!
+//! Any JCS library plus SHA-256 reproduces the JSON and byte hashes; tensors
+//! follow the layout above.
+
+use std::cell::Cell;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Number, Value};
+use sha2::{Digest, Sha256};
+
+use crate::error::CanonicalError;
+use crate::multihash::{HashAlg, Multihash};
+use crate::types::Hash32;
+
+/// Leading bytes of every canonical tensor encoding.
+pub const TENSOR_MAGIC: &[u8; 8] = b"VCTENSOR";
+/// Version byte following [`TENSOR_MAGIC`].
+pub const TENSOR_VERSION: u8 = 1;
+
+/// Largest integer every JSON implementation holds exactly (2^53).
+const MAX_SAFE_INTEGER: u64 = 1 << 53;
+
+/// A raw inference input or output, before canonicalization.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Payload {
+    Json(Value),
+    Bytes(Vec<u8>),
+    Tensor(Tensor),
+}
+
+impl Payload {
+    /// Parse JSON text, rejecting duplicate object keys.
+    pub fn json(text: &str) -> Result<Self, CanonicalError> {
+        parse_json(text).map(Self::Json)
+    }
+
+    pub fn canonical_bytes(&self) -> Result<Vec<u8>, CanonicalError> {
+        match self {
+            Payload::Json(value) => canonical_json(value).map(String::into_bytes),
+            Payload::Bytes(bytes) => Ok(bytes.clone()),
+            Payload::Tensor(tensor) => Ok(tensor.encode()),
+        }
+    }
+
+    /// `SHA-256` of the canonical bytes.
+    pub fn hash(&self) -> Result<Hash32, CanonicalError> {
+        Ok(Hash32(Sha256::digest(self.canonical_bytes()?).into()))
+    }
+
+    /// The canonical bytes hashed with `alg`.
+    pub fn digest(&self, alg: HashAlg) -> Result<Multihash, CanonicalError> {
+        Ok(alg.digest(&self.canonical_bytes()?))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// JSON (RFC 8785)
+// ---------------------------------------------------------------------------
+
+/// Deserializes a `Value` whose objects have no duplicate keys, recording
+/// the first duplicate it meets in `duplicate`.
+#[derive(Clone, Copy)]
+struct Strict<'a> {
+    duplicate: &'a Cell<Option<String>>,
+}
+
+impl<'de> DeserializeSeed<'de> for Strict<'_> {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Strict<'_> {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Value, E> {
+        Number::from_f64(v).map(Value::Number).ok_or_else(|| E::custom("non-finite number"))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element_seed(self)? {
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut members = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let value = map.next_value_seed(self)?;
+            if members.contains_key(&key) {
+                self.duplicate.set(Some(key));
+                return Err(de::Error::custom("duplicate object key"));
+            }
+            members.insert(key, value);
+        }
+        Ok(Value::Object(members))
+    }
+}
+
+/// Parse JSON text, rejecting duplicate object keys (RFC 8785 §3.1 needs
+/// I-JSON input, where member names are unique) and integers beyond ±2^53.
+pub fn parse_json(text: &str) -> Result<Value, CanonicalError> {
+    let duplicate = Cell::new(None);
+    let mut de = serde_json::Deserializer::from_str(text);
+    let value = Strict { duplicate: &duplicate }
+        .deserialize(&mut de)
+        .and_then(|v| de.end().map(|_| v))
+        .map_err(|e| duplicate.take().map_or_else(|| CanonicalError::Json(e.to_string()), CanonicalError::DuplicateKey))?;
+    check_integer_literals(text)?;
+    Ok(value)
+}
+
+/// Reject integer literals beyond ±2^53 in well-formed JSON `text`. Those
+/// beyond `u64` parse as doubles, where [`format_number`] can no longer tell
+/// them from a literal such as `1e30`.
+fn check_integer_literals(text: &str) -> Result<(), CanonicalError> {
+    let bytes = text.as_bytes();
+    let (mut i, mut in_string) = (0, false);
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if in_string => i += 1,
+            b'"' => in_string = !in_string,
+            b'-' | b'0'..=b'9' if !in_string => {
+                let len = bytes[i..].iter().take_while(|b| matches!(b, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')).count();
+                let literal = &text[i..i + len];
+                let digits = literal.trim_start_matches('-');
+                if digits.bytes().all(|b| b.is_ascii_digit())
+                    && digits.parse::<u64>().map_or(true, |magnitude| magnitude > MAX_SAFE_INTEGER)
+                {
+                    return Err(CanonicalError::UnsafeInteger(literal.to_string()));
+                }
+                i += len - 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    Ok(())
+}
+
+/// The RFC 8785 serialization of `value`.
+pub fn canonical_json(value: &Value) -> Result<String, CanonicalError> {
+    let mut out = String::new();
+    write_value(&mut out, value)?;
+    Ok(out)
+}
+
+fn write_value(out: &mut String, value: &Value) -> Result<(), CanonicalError> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => out.push_str(&format_number(n)?),
+        Value::String(s) => write_string(out, s),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, item)?;
+            }
+            out.push(']');
+        }
+        Value::Object(members) => {
+            let mut sorted: Vec<(&String, &Value)> = members.iter().collect();
+            sorted.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (key, item)) in sorted.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(out, key);
+                out.push(':');
+                write_value(out, item)?;
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{0c}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Integers beyond ±2^53 are rejected. One beyond `u64` is already a double
+/// here, indistinguishable from a float literal; [`parse_json`] rejects those
+/// from the text.
+fn format_number(n: &Number) -> Result<String, CanonicalError> {
+    if let Some(i) = n.as_i64() {
+        if i.unsigned_abs() > MAX_SAFE_INTEGER {
+            return Err(CanonicalError::UnsafeInteger(i.to_string()));
+        }
+        return Ok(i.to_string());
+    }
+    if let Some(u) = n.as_u64() {
+        return Err(CanonicalError::UnsafeInteger(u.to_string()));
+    }
+    Ok(format_double(n.as_f64().unwrap_or_default()))
+}
+
+/// ECMAScript `Number.prototype.toString` for a finite double
+/// (ECMA-262 §6.1.6.1.20), which RFC 8785 §3.2.2.3 adopts.
+pub fn format_double(v: f64) -> String {
+    if v == 0.0 {
+        return "0".into();
+    }
+    let (digits, n) = shortest_digits(v.abs());
+    let k = digits.len() as i32;
+
+    let mut out = String::new();
+    if v < 0.0 {
+        out.push('-');
+    }
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', (-n) as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push('e');
+        out.push(if n - 1 < 0 { '-' } else { '+' });
+        out.push_str(&(n - 1).abs().to_string());
+    }
+    out
+}
+
+/// Significant digits `s` and exponent `n` with `v = 0.s × 10^n`, `s` as short
+/// as possible and, between two equally short and equally close candidates,
+/// even (Rust's `{:e}` rounds that tie up instead).
+fn shortest_digits(v: f64) -> (String, i32) {
+    let split = |sci: String| {
+        let (mantissa, exp) = sci.split_once('e').expect("LowerExp has an exponent");
+        let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+        (digits, exp.parse::<i32>().expect("LowerExp exponent is an integer") + 1)
+    };
+    let (digits, n) = split(format!("{v:e}"));
+    let k = digits.len();
+    // Two k-digit candidates can only both round-trip when decimal spacing at
+    // k digits is below one ulp, which needs k >= 15.
+    if k < 15 {
+        return (digits, n);
+    }
+    // The exact value (at most 767 significant digits) decides ties.
+    let (exact, exact_n) = split(format!("{v:.800e}"));
+    let tail = &exact[k..];
+    if exact_n != n || !tail.starts_with('5') || tail[1..].bytes().any(|b| b != b'0') {
+        return (digits, n);
+    }
+    let low: u64 = exact[..k].parse().expect("at most 17 digits");
+    let high = low + 1;
+    let chosen: u64 = digits.parse().expect("at most 17 digits");
+    let other = if chosen == low { high } else { low };
+    let round_trips = format!("{other}e{}", n - k as i32).parse::<f64>() == Ok(v);
+    if chosen % 2 == 1 && other % 2 == 0 && other.to_string().len() == k && round_trips {
+        (other.to_string().trim_end_matches('0').to_string(), n)
+    } else {
+        (digits, n)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tensors
+// ---------------------------------------------------------------------------
+
+/// Element type of a tensor. The tag is part of the canonical encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    F16,
+    BF16,
+    F32,
+    F64,
+}
+
+impl DType {
+    pub const ALL: [DType; 13] = [
+        DType::Bool,
+        DType::U8,
+        DType::I8,
+        DType::U16,
+        DType::I16,
+        DType::U32,
+        DType::I32,
+        DType::U64,
+        DType::I64,
+        DType::F16,
+        DType::BF16,
+        DType::F32,
+        DType::F64,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DType::Bool => "bool",
+            DType::U8 => "u8",
+            DType::I8 => "i8",
+            DType::U16 => "u16",
+            DType::I16 => "i16",
+            DType::U32 => "u32",
+            DType::I32 => "i32",
+            DType::U64 => "u64",
+            DType::I64 => "i64",
+            DType::F16 => "f16",
+            DType::BF16 => "bf16",
+            DType::F32 => "f32",
+            DType::F64 => "f64",
+        }
+    }
+
+    /// Byte written after the version in the canonical encoding.
+    pub fn tag(self) -> u8 {
+        match self {
+            DType::Bool => 0x01,
+            DType::U8 => 0x02,
+            DType::I8 => 0x03,
+            DType::U16 => 0x04,
+            DType::I16 => 0x05,
+            DType::U32 => 0x06,
+            DType::I32 => 0x07,
+            DType::U64 => 0x08,
+            DType::I64 => 0x09,
+            DType::F16 => 0x0a,
+            DType::BF16 => 0x0b,
+            DType::F32 => 0x0c,
+            DType::F64 => 0x0d,
+        }
+    }
+
+    /// Bytes per element.
+    pub fn size(self) -> usize {
+        match self {
+            DType::Bool | DType::U8 | DType::I8 => 1,
+            DType::U16 | DType::I16 | DType::F16 | DType::BF16 => 2,
+            DType::U32 | DType::I32 | DType::F32 => 4,
+            DType::U64 | DType::I64 | DType::F64 => 8,
+        }
+    }
+
+    /// Canonical quiet NaN, little-endian, for float types.
+    fn canonical_nan(self) -> Option<&'static [u8]> {
+        match self {
+            DType::F16 => Some(&[0x00, 0x7e]),
+            DType::BF16 => Some(&[0xc0, 0x7f]),
+            DType::F32 => Some(&[0x00, 0x00, 0xc0, 0x7f]),
+            DType::F64 => Some(&[0, 0, 0, 0, 0, 0, 0xf8, 0x7f]),
+            _ => None,
+        }
+    }
+
+    /// Whether little-endian `bytes` of this float type are a NaN.
+    fn is_nan(self, le: &[u8]) -> bool {
+        match self {
+            DType::F16 => {
+                let bits = u16::from_le_bytes([le[0], le[1]]);
+                bits & 0x7c00 == 0x7c00 && bits & 0x03ff != 0
+            }
+            DType::BF16 => {
+                let bits = u16::from_le_bytes([le[0], le[1]]);
+                bits & 0x7f80 == 0x7f80 && bits & 0x007f != 0
+            }
+            DType::F32 => f32::from_le_bytes(le.try_into().expect("4 bytes")).is_nan(),
+            DType::F64 => f64::from_le_bytes(le.try_into().expect("8 bytes")).is_nan(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for DType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for DType {
+    type Err = CanonicalError;
+
+    fn from_str(s: &str) -> Result<Self, CanonicalError> {
+        let lower = s.to_ascii_lowercase();
+        let name = match lower.as_str() {
+            "float16" | "half" => "f16",
+            "bfloat16" => "bf16",
+            "float32" | "float" => "f32",
+            "float64" | "double" => "f64",
+            "uint8" => "u8",
+            "int8" => "i8",
+            "uint16" => "u16",
+            "int16" => "i16",
+            "uint32" => "u32",
+            "int32" => "i32",
+            "uint64" => "u64",
+            "int64" => "i64",
+            other => other,
+        };
+        DType::ALL.into_iter().find(|d| d.name() == name).ok_or_else(|| CanonicalError::UnknownDType(s.to_string()))
+    }
+}
+
+/// Byte order of a raw tensor buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endian {
+    #[default]
+    Little,
+    Big,
+}
+
+impl fmt::Display for Endian {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Endian::Little => "little",
+            Endian::Big => "big",
+        })
+    }
+}
+
+impl FromStr for Endian {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "little" | "le" => Ok(Endian::Little),
+            "big" | "be" => Ok(Endian::Big),
+            other => Err(format!("unknown byte order {other:?} (expected little or big)")),
+        }
+    }
+}
+
+/// A dense tensor with its elements in canonical little-endian form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tensor {
+    dtype: DType,
+    shape: Vec<u64>,
+    data: Vec<u8>,
+}
+
+impl Tensor {
+    /// Take a raw row-major buffer in `endian` byte order.
+    pub fn from_bytes(dtype: DType, shape: &[u64], bytes: &[u8], endian: Endian) -> Result<Self, CanonicalError> {
+        let length_err = || CanonicalError::TensorLength {
+            shape: shape.to_vec(),
+            dtype: dtype.name(),
+            expected: shape.iter().try_fold(dtype.size() as u64, |acc, &d| acc.checked_mul(d)).unwrap_or(u64::MAX),
+            got: bytes.len() as u64,
+        };
+        let elements = shape.iter().try_fold(1u64, |acc, &d| acc.checked_mul(d)).ok_or_else(length_err)?;
+        if elements.checked_mul(dtype.size() as u64) != Some(bytes.len() as u64) {
+            return Err(length_err());
+        }
+
+        let mut data = Vec::with_capacity(bytes.len());
+        for (index, chunk) in bytes.chunks_exact(dtype.size()).enumerate() {
+            let start = data.len();
+            data.extend_from_slice(chunk);
+            if endian == Endian::Big {
+                data[start..].reverse();
+            }
+            canonicalize(dtype, &mut data[start..], index)?;
+        }
+        Ok(Self { dtype, shape: shape.to_vec(), data })
+    }
+
+    /// Take nested JSON arrays (a bare number is a rank-0 tensor). Floats
+    /// accept `"NaN"`, `"Infinity"` and `"-Infinity"`; half-precision tensors
+    /// must be given as raw bytes.
+    pub fn from_json(dtype: DType, value: &Value) -> Result<Self, CanonicalError> {
+        let mut shape = Vec::new();
+        let mut probe = value;
+        while let Value::Array(items) = probe {
+            shape.push(items.len() as u64);
+            match items.first() {
+                Some(first) => probe = first,
+                None => break,
+            }
+        }
+        let mut data = Vec::new();
+        let mut index = 0;
+        flatten(dtype, value, &shape, &mut data, &mut index)?;
+        Ok(Self { dtype, shape, data })
+    }
+
+    pub fn dtype(&self) -> DType {
+        self.dtype
+    }
+
+    pub fn shape(&self) -> &[u64] {
+        &self.shape
+    }
+
+    /// Elements in canonical little-endian form.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// `VCTENSOR || version || dtype tag || rank_le32 || dims_le64... || data`.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(14 + 8 * self.shape.len() + self.data.len());
+        out.extend_from_slice(TENSOR_MAGIC);
+        out.push(TENSOR_VERSION);
+        out.push(self.dtype.tag());
+        out.extend_from_slice(&(self.shape.len() as u32).to_le_bytes());
+        for dim in &self.shape {
+            out.extend_from_slice(&dim.to_le_bytes());
+        }
+        out.extend_from_slice(&self.data);
+        out
+    }
+}
+
+/// Enforce the canonical form of one little-endian element.
+fn canonicalize(dtype: DType, le: &mut [u8], index: usize) -> Result<(), CanonicalError> {
+    if dtype == DType::Bool && le[0] > 1 {
+        return Err(CanonicalError::TensorElement { index, message: format!("bool byte {:#04x} is not 0 or 1", le[0]) });
+    }
+    if let Some(nan) = dtype.canonical_nan() {
+        if dtype.is_nan(le) {
+            le.copy_from_slice(nan);
+        }
+    }
+    Ok(())
+}
+
+fn flatten(dtype: DType, value: &Value, shape: &[u64], out: &mut Vec<u8>, index: &mut usize) -> Result<(), CanonicalError> {
+    match (value, shape.split_first()) {
+        (Value::Array(items), Some((&len, rest))) => {
+            if items.len() as u64 != len {
+                return Err(CanonicalError::Ragged(format!(
+                    "element {} has {} entries where {len} were expected",
+                    *index,
+                    items.len()
+                )));
+            }
+            items.iter().try_for_each(|item| flatten(dtype, item, rest, out, index))
+        }
+        (Value::Array(_), None) => Err(CanonicalError::Ragged(format!("element {} is nested deeper than the rest", *index))),
+        (_, Some(_)) => Err(CanonicalError::Ragged(format!("element {} is a scalar where an array was expected", *index))),
+        (scalar, None) => {
+            let start = out.len();
+            encode_scalar(dtype, scalar, out)
+                .map_err(|message| CanonicalError::TensorElement { index: *index, message })?;
+            canonicalize(dtype, &mut out[start..], *index)?;
+            *index += 1;
+            Ok(())
+        }
+    }
+}
+
+fn encode_scalar(dtype: DType, value: &Value, out: &mut Vec<u8>) -> Result<(), String> {
+    let int = || -> Result<i128, String> {
+        match value {
+            Value::Number(n) => n
+                .as_i64()
+                .map(i128::from)
+                .or_else(|| n.as_u64().map(i128::from))
+                .ok_or_else(|| format!("{n} is not an integer")),
+            other => Err(format!("{other} is not a number")),
+        }
+    };
+    let float = || -> Result<f64, String> {
+        match value {
+            Value::Number(n) => Ok(n.as_f64().unwrap_or_default()),
+            Value::String(s) if s == "NaN" => Ok(f64::NAN),
+            Value::String(s) if s == "Infinity" => Ok(f64::INFINITY),
+            Value::String(s) if s == "-Infinity" => Ok(f64::NEG_INFINITY),
+            other => Err(format!("{other} is not a number")),
+        }
+    };
+    macro_rules! int_as {
+        ($t:ty) => {{
+            let v = int()?;
+            let v = <$t>::try_from(v).map_err(|_| format!("{v} is out of range for {}", dtype))?;
+            out.extend_from_slice(&v.to_le_bytes());
+        }};
+    }
+    match dtype {
+        DType::Bool => match value {
+            Value::Bool(b) => out.push(*b as u8),
+            other => return Err(format!("{other} is not a boolean")),
+        },
+        DType::U8 => int_as!(u8),
+        DType::I8 => int_as!(i8),
+        DType::U16 => int_as!(u16),
+        DType::I16 => int_as!(i16),
+        DType::U32 => int_as!(u32),
+        DType::I32 => int_as!(i32),
+        DType::U64 => int_as!(u64),
+        DType::I64 => int_as!(i64),
+        DType::F32 => out.extend_from_slice(&(float()? as f32).to_le_bytes()),
+        DType::F64 => out.extend_from_slice(&float()?.to_le_bytes()),
+        DType::F16 | DType::BF16 => {
+            return Err(format!("{dtype} tensors must be given as raw bytes; JSON numbers would need rounding"))
+        }
+    }
+    Ok(())
+}
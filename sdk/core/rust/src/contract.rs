@@ -0,0 +1,156 @@
+//! ethers-rs bindings for the verifier contracts.
+//!
+//! Generated from the ABI files checked in next to the Solidity sources:
+//! `verifier/abi/ProofCortexVerifier.json` (`verifier/BlockDAG_Verifier.sol`)
+//! and `sdk/blockchain/solidity/abi/VeriCortexVerifier.json`, both written by
+//! `tools/build-contracts.sh`; CI fails when they drift from the sources.
+
+use ethers::abi::{self, ParamType, Token};
+use ethers::contract::{abigen, ContractError, EthLogDecode};
+use ethers::providers::Middleware;
+use ethers::types::{TransactionReceipt, U256};
+
+use crate::types::{Address, Hash32, ModelId, ProofId};
+
+abigen!(
+    ProofCortexVerifier,
+    "../../../verifier/abi/ProofCortexVerifier.json",
+    derives(serde::Serialize, serde::Deserialize)
+);
+
+pub mod legacy {
+    //! Bindings for the legacy `VeriCortexVerifier` contract.
+    ethers::contract::abigen!(
+        VeriCortexVerifier,
+        "../../../sdk/blockchain/solidity/abi/VeriCortexVerifier.json"
+    );
+}
+
+/// `ProofCortexVerifier.ProofRecord` with SDK types in place of raw ABI values.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct OnChainProof {
+    pub model_id: ModelId,
+    pub version: String,
+    pub input_hash: Hash32,
+    pub output_hash: Hash32,
+    pub trace_hash: Hash32,
+    pub proof_hash: Hash32,
+    pub submitter: Address,
+    pub valid: bool,
+    pub timestamp: u64,
+    pub locked: bool,
+}
+
+impl OnChainProof {
+    /// The contract returns a zeroed record for unknown ids.
+    pub fn exists(&self) -> bool {
+        self.submitter != Address::default()
+    }
+}
+
+/// A contract timestamp beyond `u64`, which no real chain produces.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("timestamp {0} does not fit in u64")]
+pub struct TimestampOverflow(pub U256);
+
+/// A `uint256` timestamp from a record or event, as seconds.
+pub fn timestamp(value: U256) -> Result<u64, TimestampOverflow> {
+    u64::try_from(value).map_err(|_| TimestampOverflow(value))
+}
+
+impl TryFrom<ProofRecord> for OnChainProof {
+    type Error = TimestampOverflow;
+
+    fn try_from(r: ProofRecord) -> Result<Self, TimestampOverflow> {
+        Ok(Self {
+            model_id: ModelId(r.model_id),
+            version: r.version,
+            input_hash: Hash32(r.input_hash),
+            output_hash: Hash32(r.output_hash),
+            trace_hash: Hash32(r.trace_hash),
+            proof_hash: Hash32(r.proof_hash),
+            submitter: Address(r.submitter.0),
+            valid: r.valid,
+            timestamp: timestamp(r.timestamp)?,
+            locked: r.locked,
+        })
+    }
+}
+
+/// Decode every `ProofCortexVerifier` event emitted in `receipt`.
+pub fn receipt_events(receipt: &TransactionReceipt) -> Vec<ProofCortexVerifierEvents> {
+    receipt
+        .logs
+        .iter()
+        .filter_map(|log| ProofCortexVerifierEvents::decode_log(&log.clone().into()).ok())
+        .collect()
+}
+
+/// The proofId assigned by the `ProofSubmitted` event in `receipt`, if any.
+pub fn submitted_proof_id(receipt: &TransactionReceipt) -> Option<ProofId> {
+    receipt_events(receipt).into_iter().find_map(|ev| match ev {
+        ProofCortexVerifierEvents::ProofSubmittedFilter(ev) => Some(ProofId(ev.proof_id)),
+        _ => None,
+    })
+}
+
+/// Selector of the ABI `Error(string)` revert payload.
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// A `require` failure of `ProofCortexVerifier`, decoded from its revert string.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum VerifierRevert {
+    /// `submitProof` for a proofId that is already stored.
+    #[error("Proof exists")]
+    ProofExists,
+    /// The sender is not the contract's trusted submitter.
+    #[error("Not authorized")]
+    NotAuthorized,
+    /// `updateProofResult` for an unknown proofId. `lockProof` does not
+    /// check, and locks the empty record.
+    #[error("Not found")]
+    NotFound,
+    /// `updateProofResult` on a locked proof.
+    #[error("Immutable")]
+    Immutable,
+    /// Any other revert string.
+    #[error("{0}")]
+    Other(String),
+}
+
+impl VerifierRevert {
+    pub fn from_reason(reason: &str) -> Self {
+        match reason {
+            "Proof exists" => Self::ProofExists,
+            "Not authorized" => Self::NotAuthorized,
+            "Not found" => Self::NotFound,
+            "Immutable" => Self::Immutable,
+            other => Self::Other(other.to_string()),
+        }
+    }
+
+    /// Decode ABI-encoded `Error(string)` revert data.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let payload = data.strip_prefix(&ERROR_STRING_SELECTOR)?;
+        match abi::decode(&[ParamType::String], payload).ok()?.pop()? {
+            Token::String(reason) => Some(Self::from_reason(&reason)),
+            _ => None,
+        }
+    }
+
+    /// The revert of a failed contract call, if the contract reverted with a reason.
+    pub fn from_contract_error<M: Middleware>(e: &ContractError<M>) -> Option<Self> {
+        e.as_revert().and_then(|data| Self::decode(data))
+    }
+
+    /// Stable snake_case name, for JSON error output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ProofExists => "proof_exists",
+            Self::NotAuthorized => "not_authorized",
+            Self::NotFound => "not_found",
+            Self::Immutable => "immutable",
+            Self::Other(_) => "other",
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::types::KeyId;
+
+/// Why a `0x`-prefixed fixed-width hex value was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum HexError {
+    #[error("missing 0x prefix")]
+    BadPrefix,
+
+    #[error("odd number of hex digits ({0})")]
+    OddLength(usize),
+
+    #[error("non-hex character {ch:?} at position {index}")]
+    NonHex { index: usize, ch: char },
+
+    #[error("expected {expected} bytes, got {got}")]
+    WrongLength { expected: usize, got: usize },
+}
+
+/// Why a payload could not be canonicalized (see [`crate::canonical`]).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CanonicalError {
+    #[error("invalid JSON: {0}")]
+    Json(String),
+
+    #[error("duplicate object key {0:?}")]
+    DuplicateKey(String),
+
+    #[error("integer {0} is beyond ±2^53 and has no exact IEEE 754 double; encode it as a string")]
+    UnsafeInteger(String),
+
+    #[error("unknown tensor dtype {0:?}")]
+    UnknownDType(String),
+
+    #[error("tensor of shape {shape:?} and dtype {dtype} needs {expected} bytes, got {got}")]
+    TensorLength { shape: Vec<u64>, dtype: &'static str, expected: u64, got: u64 },
+
+    #[error("tensor element {index}: {message}")]
+    TensorElement { index: usize, message: String },
+
+    #[error("tensor is not rectangular: {0}")]
+    Ragged(String),
+}
+
+/// Why a self-describing digest was rejected (see [`crate::multihash`]).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DigestError {
+    #[error(transparent)]
+    Hex(#[from] HexError),
+
+    #[error("unknown hash algorithm code {0:#x}")]
+    UnknownCode(u64),
+
+    #[error("unknown hash algorithm {0:?} (expected sha2-256, sha3-256, keccak-256 or blake3)")]
+    UnknownName(String),
+
+    #[error("malformed multihash varint")]
+    Varint,
+
+    #[error("{alg} digest must be 32 bytes, header says {declared} and {got} follow")]
+    WrongLength { alg: &'static str, declared: u64, got: usize },
+}
+
+/// Why a model artifact could not be fingerprinted (see [`crate::fingerprint`]).
+#[derive(Debug, Error)]
+pub enum FingerprintError {
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+
+    #[error("{}: not a valid {format} file: {message}", path.display())]
+    Malformed { path: PathBuf, format: &'static str, message: String },
+
+    #[error("{}: cannot tell the model format from the file; name it explicitly", .0.display())]
+    UnknownFormat(PathBuf),
+
+    #[error("unknown artifact format {0:?} (expected safetensors, onnx, gguf or directory)")]
+    UnknownFormatName(String),
+}
+
+/// Why a proof could not be checked by its adapter (see [`crate::adapter`]).
+/// A proof that is checked and fails is an invalid verdict, not an error.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AdapterError {
+    #[error("no adapter for proof type {proof_type:?} (registered: {registered})")]
+    UnknownType { proof_type: String, registered: String },
+
+    #[error("{proof_type}: malformed evidence: {message}")]
+    Evidence { proof_type: String, message: String },
+
+    #[error("{proof_type}: {message}")]
+    Backend { proof_type: String, message: String },
+}
+
+/// Why an inference receipt was not accepted (see [`crate::receipt`]).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ReceiptError {
+    #[error("proof cannot be canonicalized for signing: {0}")]
+    Canonical(#[from] CanonicalError),
+
+    #[error("key_id {claimed} does not match the public key, whose ID is {derived}")]
+    KeyIdMismatch { claimed: KeyId, derived: KeyId },
+
+    #[error("malformed signature: {0}")]
+    Signature(String),
+
+    #[error("signature does not verify against key {0}")]
+    BadSignature(KeyId),
+
+    #[error("key {0} is not a trusted agent")]
+    Untrusted(KeyId),
+}
+
+/// Errors produced while parsing or hashing proof data.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Hex(#[from] HexError),
+
+    #[error("invalid {field}: {error}")]
+    Field { field: &'static str, error: HexError },
+
+    #[error("invalid {field}: {error}")]
+    Digest { field: &'static str, error: DigestError },
+
+    #[error("unknown contract schema {0:?} (expected `vericortex` or `proofcortex`)")]
+    UnknownSchema(String),
+
+    #[error("unsupported proof format version {0}")]
+    UnsupportedFormat(u32),
+
+    #[error(transparent)]
+    Canonical(#[from] CanonicalError),
+
+    #[error("invalid proof JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
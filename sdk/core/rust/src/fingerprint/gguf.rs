@@ -0,0 +1,225 @@
+//! GGUF (versions 2 and 3): a header, typed key/value metadata, tensor
+//! infos, then the tensor data at `general.alignment` boundaries. All
+//! integers are little-endian.
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+use super::{element_count, Leaf, LeafKind, Source};
+use crate::error::FingerprintError;
+use crate::multihash::Hasher;
+
+pub(super) const MAGIC: &[u8; 4] = b"GGUF";
+
+const DEFAULT_ALIGNMENT: u64 = 32;
+/// Longest key, string value or tensor name accepted.
+const MAX_STRING: u64 = 1 << 24;
+const MAX_DIMS: u32 = 8;
+
+const TYPE_UINT32: u32 = 4;
+const TYPE_STRING: u32 = 8;
+const TYPE_ARRAY: u32 = 9;
+
+/// `(name, elements per block, bytes per block)` by ggml type id.
+fn ggml_type(id: u32) -> Option<(&'static str, u64, u64)> {
+    Some(match id {
+        0 => ("F32", 1, 4),
+        1 => ("F16", 1, 2),
+        2 => ("Q4_0", 32, 18),
+        3 => ("Q4_1", 32, 20),
+        6 => ("Q5_0", 32, 22),
+        7 => ("Q5_1", 32, 24),
+        8 => ("Q8_0", 32, 34),
+        9 => ("Q8_1", 32, 36),
+        10 => ("Q2_K", 256, 84),
+        11 => ("Q3_K", 256, 110),
+        12 => ("Q4_K", 256, 144),
+        13 => ("Q5_K", 256, 176),
+        14 => ("Q6_K", 256, 210),
+        15 => ("Q8_K", 256, 292),
+        16 => ("IQ2_XXS", 256, 66),
+        17 => ("IQ2_XS", 256, 74),
+        18 => ("IQ3_XXS", 256, 98),
+        19 => ("IQ1_S", 256, 50),
+        20 => ("IQ4_NL", 32, 18),
+        21 => ("IQ3_S", 256, 110),
+        22 => ("IQ2_S", 256, 82),
+        23 => ("IQ4_XS", 256, 136),
+        24 => ("I8", 1, 1),
+        25 => ("I16", 1, 2),
+        26 => ("I32", 1, 4),
+        27 => ("I64", 1, 8),
+        28 => ("F64", 1, 8),
+        29 => ("IQ1_M", 256, 56),
+        30 => ("BF16", 1, 2),
+        34 => ("TQ1_0", 256, 54),
+        35 => ("TQ2_0", 256, 66),
+        _ => return None,
+    })
+}
+
+/// Reads the header and metadata, hashing every byte it consumes so the
+/// `gguf.metadata` leaf covers them exactly.
+struct Reader<'a, R> {
+    inner: R,
+    hasher: &'a mut Hasher,
+    pos: u64,
+}
+
+impl<R: Read> Reader<'_, R> {
+    fn bytes<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut buf = [0u8; N];
+        self.inner.read_exact(&mut buf)?;
+        self.hasher.update(&buf);
+        self.pos += N as u64;
+        Ok(buf)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        self.bytes().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        self.bytes().map(u64::from_le_bytes)
+    }
+
+    fn skip(&mut self, len: u64) -> io::Result<()> {
+        super::copy_exact(&mut self.inner, len, self.hasher)?;
+        self.pos += len;
+        Ok(())
+    }
+
+    fn string(&mut self) -> io::Result<Vec<u8>> {
+        let len = self.u64()?;
+        if len > MAX_STRING {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("string of {len} bytes")));
+        }
+        let mut buf = vec![0u8; len as usize];
+        self.inner.read_exact(&mut buf)?;
+        self.hasher.update(&buf);
+        self.pos += len;
+        Ok(buf)
+    }
+
+    /// Skip a value of type `ty`, returning it when it is a `u32`.
+    fn value(&mut self, ty: u32, depth: u8) -> io::Result<Option<u32>> {
+        let width = match ty {
+            0 | 1 | 7 => 1,
+            2 | 3 => 2,
+            TYPE_UINT32 => return self.u32().map(Some),
+            5 | 6 => 4,
+            10..=12 => 8,
+            TYPE_STRING => {
+                let len = self.u64()?;
+                self.skip(len)?;
+                return Ok(None);
+            }
+            TYPE_ARRAY if depth == 0 => {
+                let item = self.u32()?;
+                let count = self.u64()?;
+                for _ in 0..count {
+                    self.value(item, depth + 1)?;
+                }
+                return Ok(None);
+            }
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData, format!("unknown value type {ty}"))),
+        };
+        self.skip(width)?;
+        Ok(None)
+    }
+}
+
+struct TensorInfo {
+    name: String,
+    dims: Vec<u64>,
+    ty: u32,
+    offset: u64,
+}
+
+pub(super) fn leaves(source: &Source) -> Result<Vec<Leaf>, FingerprintError> {
+    let mut file = source.open()?;
+    let file_len = file.get_ref().metadata().map_err(|e| source.io(e))?.len();
+    let bad = |message: String| source.malformed(message);
+    let read_err = |e: io::Error| match e.kind() {
+        io::ErrorKind::InvalidData => source.malformed(e.to_string()),
+        _ => source.io(e),
+    };
+
+    let mut hasher = source.alg.hasher();
+    let mut r = Reader { inner: &mut file, hasher: &mut hasher, pos: 0 };
+    if &r.bytes::<4>().map_err(read_err)? != MAGIC {
+        return Err(bad("missing GGUF magic".into()));
+    }
+    let version = r.u32().map_err(read_err)?;
+    if !(2..=3).contains(&version) {
+        return Err(bad(format!("unsupported GGUF version {version}")));
+    }
+    let tensor_count = r.u64().map_err(read_err)?;
+    let kv_count = r.u64().map_err(read_err)?;
+
+    let mut alignment = DEFAULT_ALIGNMENT;
+    for _ in 0..kv_count {
+        let key = r.string().map_err(read_err)?;
+        let ty = r.u32().map_err(read_err)?;
+        let value = r.value(ty, 0).map_err(read_err)?;
+        if key == b"general.alignment" {
+            alignment = value.map(u64::from).filter(|a| a.is_power_of_two()).ok_or_else(|| {
+                bad("general.alignment must be a power-of-two u32".into())
+            })?;
+        }
+    }
+    let metadata_len = r.pos;
+    let metadata = hasher.finalize();
+
+    // Tensor infos are not hashed on their own: every field of them is in
+    // the tensor leaves.
+    let mut scratch = source.alg.hasher();
+    let mut r = Reader { inner: &mut file, hasher: &mut scratch, pos: metadata_len };
+    let mut infos = Vec::new();
+    for _ in 0..tensor_count {
+        let name = String::from_utf8(r.string().map_err(read_err)?).map_err(|_| bad("tensor name is not UTF-8".into()))?;
+        let n_dims = r.u32().map_err(read_err)?;
+        if n_dims > MAX_DIMS {
+            return Err(bad(format!("{name}: {n_dims} dimensions")));
+        }
+        let dims = (0..n_dims).map(|_| r.u64()).collect::<io::Result<Vec<_>>>().map_err(read_err)?;
+        let ty = r.u32().map_err(read_err)?;
+        let offset = r.u64().map_err(read_err)?;
+        infos.push(TensorInfo { name, dims, ty, offset });
+    }
+    let data_start = r.pos.next_multiple_of(alignment);
+
+    let mut leaves = vec![Leaf {
+        kind: LeafKind::Metadata,
+        name: "gguf.metadata".into(),
+        dtype: None,
+        shape: None,
+        size: metadata_len,
+        digest: metadata,
+    }];
+    for info in infos {
+        let (dtype, block, block_size) =
+            ggml_type(info.ty).ok_or_else(|| bad(format!("{}: unknown ggml type {}", info.name, info.ty)))?;
+        let elements = element_count(&info.dims).ok_or_else(|| bad(format!("{}: shape overflows", info.name)))?;
+        if info.dims.first().is_some_and(|d| d % block != 0) {
+            return Err(bad(format!("{}: first dimension is not a multiple of the {dtype} block size {block}", info.name)));
+        }
+        let size = (elements / block)
+            .checked_mul(block_size)
+            .ok_or_else(|| bad(format!("{}: {elements} {dtype} elements overflow the size", info.name)))?;
+        let start = data_start.checked_add(info.offset);
+        if info.offset % alignment != 0 || start.and_then(|s| s.checked_add(size)).is_none_or(|end| end > file_len) {
+            return Err(bad(format!("{}: data at offset {} is misaligned or past the end", info.name, info.offset)));
+        }
+        file.seek(SeekFrom::Start(data_start + info.offset)).map_err(|e| source.io(e))?;
+        let digest = source.digest(&mut file, size)?;
+        leaves.push(Leaf {
+            kind: LeafKind::Tensor,
+            name: info.name,
+            dtype: Some(dtype.into()),
+            shape: Some(info.dims),
+            size,
+            digest,
+        });
+    }
+    Ok(leaves)
+}
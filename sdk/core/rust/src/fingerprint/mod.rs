@@ -0,0 +1,307 @@
+//! Model artifact fingerprints.
+//!
+//! A fingerprint is a Merkle commitment over what a model artifact is made
+//! of, so a proof can name the exact weights that ran rather than only a
+//! free-form `model_id`:
+//!
+//! - safetensors: one leaf per tensor, plus `__metadata__` when present
+//! - ONNX: one leaf per graph initializer (inline or external data), plus
+//!   `onnx.graph`, the model with the initializers left out, so the operators
+//!   are committed to as well
+//! - GGUF: one leaf per tensor, plus `gguf.metadata`, the header and
+//!   key/value section (hyperparameters, tokenizer)
+//! - a plain directory: one leaf per file, named by its `/`-separated path
+//!
+//! Files are streamed; no tensor is ever held in memory. Each leaf is
+//! `alg(kind || name || dtype || shape || size || digest)` where `digest` is
+//! the [`Multihash`] of the leaf's bytes (see [`Leaf::hash`]). Leaves are
+//! sorted by kind and name and the root is a [`MerkleTree`] root, so one tensor
+//! can later be proven against the root without the rest of the file.
+//!
+//! Tensor leaves commit to the format's own dtype name (`F32`, `FLOAT`,
+//! `Q4_K`), so the same weights converted to another format fingerprint
+//! differently.
+
+mod gguf;
+mod onnx;
+mod safetensors;
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use crate::error::FingerprintError;
+use crate::merkle::MerkleTree;
+use crate::multihash::{HashAlg, Hasher, Multihash};
+use crate::types::Hash32;
+
+/// What kind of artifact was fingerprinted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtifactFormat {
+    Safetensors,
+    Onnx,
+    Gguf,
+    Directory,
+}
+
+impl ArtifactFormat {
+    pub fn name(self) -> &'static str {
+        match self {
+            ArtifactFormat::Safetensors => "safetensors",
+            ArtifactFormat::Onnx => "onnx",
+            ArtifactFormat::Gguf => "gguf",
+            ArtifactFormat::Directory => "directory",
+        }
+    }
+
+    /// Directories by type, GGUF by its magic, the others by file extension.
+    pub fn detect(path: &Path) -> Result<Self, FingerprintError> {
+        let io = |source| FingerprintError::Io { path: path.to_path_buf(), source };
+        if std::fs::metadata(path).map_err(io)?.is_dir() {
+            return Ok(ArtifactFormat::Directory);
+        }
+        let mut magic = [0u8; 4];
+        let read = File::open(path).and_then(|mut f| f.read(&mut magic)).map_err(io)?;
+        if read == 4 && &magic == gguf::MAGIC {
+            return Ok(ArtifactFormat::Gguf);
+        }
+        match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("safetensors") => Ok(ArtifactFormat::Safetensors),
+            Some("onnx") => Ok(ArtifactFormat::Onnx),
+            Some("gguf") => Ok(ArtifactFormat::Gguf),
+            _ => Err(FingerprintError::UnknownFormat(path.to_path_buf())),
+        }
+    }
+}
+
+impl fmt::Display for ArtifactFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ArtifactFormat {
+    type Err = FingerprintError;
+
+    fn from_str(s: &str) -> Result<Self, FingerprintError> {
+        match s.to_ascii_lowercase().as_str() {
+            "safetensors" => Ok(ArtifactFormat::Safetensors),
+            "onnx" => Ok(ArtifactFormat::Onnx),
+            "gguf" => Ok(ArtifactFormat::Gguf),
+            "directory" | "dir" => Ok(ArtifactFormat::Directory),
+            _ => Err(FingerprintError::UnknownFormatName(s.to_string())),
+        }
+    }
+}
+
+/// What a leaf commits to. Leaves sort by kind first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeafKind {
+    Tensor = 1,
+    Metadata = 2,
+    File = 3,
+}
+
+/// One committed tensor, metadata section or file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Leaf {
+    pub kind: LeafKind,
+    pub name: String,
+    /// The format's own element type name; tensors only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dtype: Option<String>,
+    /// Tensors only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shape: Option<Vec<u64>>,
+    /// Number of bytes hashed into `digest`.
+    pub size: u64,
+    pub digest: Multihash,
+}
+
+impl Leaf {
+    /// `digest.alg` over `kind || u32 len || name || u32 len || dtype ||
+    /// u32 rank || u64 dims || u64 size || digest as a multihash`, integers
+    /// little-endian and an absent dtype or shape empty.
+    pub fn hash(&self) -> Hash32 {
+        let mut hasher = self.digest.alg.hasher();
+        hasher.update(&[self.kind as u8]);
+        let dtype = self.dtype.as_deref().unwrap_or_default();
+        for text in [self.name.as_str(), dtype] {
+            hasher.update(&(text.len() as u32).to_le_bytes());
+            hasher.update(text.as_bytes());
+        }
+        let shape = self.shape.as_deref().unwrap_or_default();
+        hasher.update(&(shape.len() as u32).to_le_bytes());
+        for dim in shape {
+            hasher.update(&dim.to_le_bytes());
+        }
+        hasher.update(&self.size.to_le_bytes());
+        hasher.update(&self.digest.to_bytes());
+        hasher.finalize().digest
+    }
+}
+
+/// Every leaf of an artifact, in commitment order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    pub format: ArtifactFormat,
+    pub alg: HashAlg,
+    pub leaves: Vec<Leaf>,
+}
+
+impl Fingerprint {
+    fn new(format: ArtifactFormat, alg: HashAlg, mut leaves: Vec<Leaf>) -> Self {
+        leaves.sort_by(|a, b| (a.kind, &a.name).cmp(&(b.kind, &b.name)));
+        Self { format, alg, leaves }
+    }
+
+    pub fn tree(&self) -> MerkleTree {
+        MerkleTree::from_leaves(&self.leaves.iter().map(Leaf::hash).collect::<Vec<_>>())
+    }
+
+    pub fn root(&self) -> Hash32 {
+        self.tree().root()
+    }
+
+    /// The commitment a proof envelope or registry entry records.
+    pub fn summary(&self) -> ModelFingerprint {
+        ModelFingerprint { format: self.format, alg: self.alg, root: self.root(), leaves: self.leaves.len() as u64 }
+    }
+}
+
+/// A fingerprint as recorded in a proof (`model_fingerprint`) or a registry
+/// entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelFingerprint {
+    pub format: ArtifactFormat,
+    /// Algorithm of the content digests and leaf hashes.
+    pub alg: HashAlg,
+    /// Keccak-256 Merkle root over the leaf hashes.
+    pub root: Hash32,
+    pub leaves: u64,
+}
+
+impl ModelFingerprint {
+    /// `keccak256(keccak256(format) || keccak256(alg) || root)`, the value
+    /// [`crate::ProofJson::bundle_hash`] commits to.
+    pub fn commitment(&self) -> Hash32 {
+        let mut hasher = Keccak256::new();
+        hasher.update(Keccak256::digest(self.format.name().as_bytes()));
+        hasher.update(Keccak256::digest(self.alg.name().as_bytes()));
+        hasher.update(self.root.as_bytes());
+        Hash32(hasher.finalize().into())
+    }
+
+    /// Fingerprint `path` the same way and compare.
+    pub fn verify(&self, path: &Path) -> Result<bool, FingerprintError> {
+        Ok(fingerprint(path, Some(self.format), self.alg)?.summary() == *self)
+    }
+}
+
+/// Fingerprint the artifact at `path`, detecting its format unless given.
+pub fn fingerprint(path: &Path, format: Option<ArtifactFormat>, alg: HashAlg) -> Result<Fingerprint, FingerprintError> {
+    let format = match format {
+        Some(format) => format,
+        None => ArtifactFormat::detect(path)?,
+    };
+    let source = Source { path: path.to_path_buf(), format, alg };
+    let leaves = match format {
+        ArtifactFormat::Safetensors => safetensors::leaves(&source)?,
+        ArtifactFormat::Onnx => onnx::leaves(&source)?,
+        ArtifactFormat::Gguf => gguf::leaves(&source)?,
+        ArtifactFormat::Directory => directory_leaves(&source)?,
+    };
+    let mut names: Vec<_> = leaves.iter().map(|l| (l.kind, l.name.as_str())).collect();
+    names.sort();
+    if let Some(pair) = names.windows(2).find(|pair| pair[0] == pair[1]) {
+        return Err(source.malformed(format!("duplicate name {:?}", pair[0].1)));
+    }
+    Ok(Fingerprint::new(format, alg, leaves))
+}
+
+/// The file being read, for error reporting.
+struct Source {
+    path: PathBuf,
+    format: ArtifactFormat,
+    alg: HashAlg,
+}
+
+impl Source {
+    fn open(&self) -> Result<BufReader<File>, FingerprintError> {
+        File::open(&self.path).map(BufReader::new).map_err(|e| self.io(e))
+    }
+
+    /// A read error; running out of bytes means the file is truncated.
+    fn io(&self, source: io::Error) -> FingerprintError {
+        if source.kind() == io::ErrorKind::UnexpectedEof {
+            return self.malformed("unexpected end of file");
+        }
+        FingerprintError::Io { path: self.path.clone(), source }
+    }
+
+    fn malformed(&self, message: impl Into<String>) -> FingerprintError {
+        FingerprintError::Malformed { path: self.path.clone(), format: self.format.name(), message: message.into() }
+    }
+
+    /// Stream exactly `len` bytes of `reader` into a new hasher.
+    fn digest(&self, reader: &mut impl Read, len: u64) -> Result<Multihash, FingerprintError> {
+        let mut hasher = self.alg.hasher();
+        copy_exact(reader, len, &mut hasher).map_err(|e| self.io(e))?;
+        Ok(hasher.finalize())
+    }
+}
+
+fn copy_exact(reader: &mut impl Read, len: u64, hasher: &mut Hasher) -> io::Result<()> {
+    let copied = io::copy(&mut reader.take(len), hasher)?;
+    if copied < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+/// Number of elements of `shape`, or `None` on overflow.
+fn element_count(shape: &[u64]) -> Option<u64> {
+    shape.iter().try_fold(1u64, |n, &d| n.checked_mul(d))
+}
+
+fn directory_leaves(source: &Source) -> Result<Vec<Leaf>, FingerprintError> {
+    let mut leaves = Vec::new();
+    walk(source, &source.path, "", &mut leaves)?;
+    Ok(leaves)
+}
+
+/// Add every file under `dir`; symlinked files are followed, symlinked
+/// directories are refused so the walk cannot loop.
+fn walk(source: &Source, dir: &Path, prefix: &str, leaves: &mut Vec<Leaf>) -> Result<(), FingerprintError> {
+    let io = |path: &Path, source| FingerprintError::Io { path: path.to_path_buf(), source };
+    let entries = std::fs::read_dir(dir).map_err(|e| io(dir, e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| io(dir, e))?;
+        let path = entry.path();
+        let Some(name) = entry.file_name().to_str().map(|n| format!("{prefix}{n}")) else {
+            return Err(source.malformed(format!("{} is not a UTF-8 path", path.display())));
+        };
+        let link = entry.file_type().map_err(|e| io(&path, e))?.is_symlink();
+        let meta = std::fs::metadata(&path).map_err(|e| io(&path, e))?;
+        if meta.is_dir() {
+            if link {
+                return Err(source.malformed(format!("{name} is a symlinked directory")));
+            }
+            walk(source, &path, &format!("{name}/"), leaves)?;
+        } else {
+            let mut file = File::open(&path).map(BufReader::new).map_err(|e| io(&path, e))?;
+            let mut hasher = source.alg.hasher();
+            let size = io::copy(&mut file, &mut hasher).map_err(|e| io(&path, e))?;
+            leaves.push(Leaf { kind: LeafKind::File, name, dtype: None, shape: None, size, digest: hasher.finalize() });
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,405 @@
+//! ONNX: a protobuf `ModelProto`. The graph's initializers become tensor
+//! leaves; every other field of the model, re-encoded field by field with the
+//! initializers left out, is hashed into the `onnx.graph` leaf.
+//!
+//! The file is parsed as a stream, so `raw_data` of any size is hashed
+//! without being buffered. Tensors stored as `float_data` / `double_data`
+//! hash like the same values in `raw_data`; the integer fields are widened or
+//! narrowed to the element type first. External data is read from the file
+//! named by `location`, relative to the model.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Component, Path};
+
+use super::{element_count, Leaf, LeafKind, Source};
+use crate::error::FingerprintError;
+use crate::multihash::{HashAlg, Hasher};
+
+// Field numbers from onnx.proto.
+const MODEL_GRAPH: u64 = 7;
+const GRAPH_INITIALIZER: u64 = 5;
+const TENSOR_DIMS: u64 = 1;
+const TENSOR_DATA_TYPE: u64 = 2;
+const TENSOR_FLOAT_DATA: u64 = 4;
+const TENSOR_INT32_DATA: u64 = 5;
+const TENSOR_STRING_DATA: u64 = 6;
+const TENSOR_INT64_DATA: u64 = 7;
+const TENSOR_NAME: u64 = 8;
+const TENSOR_RAW_DATA: u64 = 9;
+const TENSOR_DOUBLE_DATA: u64 = 10;
+const TENSOR_UINT64_DATA: u64 = 11;
+const TENSOR_EXTERNAL_DATA: u64 = 13;
+const TENSOR_DATA_LOCATION: u64 = 14;
+const ENTRY_KEY: u64 = 1;
+const ENTRY_VALUE: u64 = 2;
+
+const WIRE_VARINT: u8 = 0;
+const WIRE_I64: u8 = 1;
+const WIRE_LEN: u8 = 2;
+const WIRE_I32: u8 = 5;
+
+const DATA_TYPE_STRING: i32 = 8;
+const MAX_DIMS: usize = 64;
+
+/// `TensorProto.DataType` name and element size in bytes (`0` for strings
+/// and sub-byte types).
+fn data_type(id: i32) -> (String, u64) {
+    let (name, size) = match id {
+        1 => ("FLOAT", 4),
+        2 => ("UINT8", 1),
+        3 => ("INT8", 1),
+        4 => ("UINT16", 2),
+        5 => ("INT16", 2),
+        6 => ("INT32", 4),
+        7 => ("INT64", 8),
+        8 => ("STRING", 0),
+        9 => ("BOOL", 1),
+        10 => ("FLOAT16", 2),
+        11 => ("DOUBLE", 8),
+        12 => ("UINT32", 4),
+        13 => ("UINT64", 8),
+        14 => ("COMPLEX64", 8),
+        15 => ("COMPLEX128", 16),
+        16 => ("BFLOAT16", 2),
+        17 => ("FLOAT8E4M3FN", 1),
+        18 => ("FLOAT8E4M3FNUZ", 1),
+        19 => ("FLOAT8E5M2", 1),
+        20 => ("FLOAT8E5M2FNUZ", 1),
+        21 => ("UINT4", 0),
+        22 => ("INT4", 0),
+        other => return (format!("DATA_TYPE_{other}"), 0),
+    };
+    (name.to_string(), size)
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn write_varint(hasher: &mut Hasher, value: u64) {
+    let mut buf = Vec::with_capacity(10);
+    push_varint(&mut buf, value);
+    hasher.update(&buf);
+}
+
+fn push_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// A protobuf reader that tracks its offset within the file.
+struct Proto<R> {
+    inner: R,
+    pos: u64,
+}
+
+impl<R: Read> Proto<R> {
+    fn byte(&mut self) -> io::Result<u8> {
+        let mut b = [0u8];
+        self.inner.read_exact(&mut b)?;
+        self.pos += 1;
+        Ok(b[0])
+    }
+
+    fn varint(&mut self) -> io::Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            value |= u64::from(b & 0x7f) << shift;
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("varint longer than 10 bytes"))
+    }
+
+    fn fixed<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut buf = [0u8; N];
+        self.inner.read_exact(&mut buf)?;
+        self.pos += N as u64;
+        Ok(buf)
+    }
+
+    /// The next `(field, wire type)` before `end`, or `None` at `end`.
+    fn tag(&mut self, end: u64) -> io::Result<Option<(u64, u8)>> {
+        if self.pos >= end {
+            return if self.pos == end { Ok(None) } else { Err(invalid("field overruns its message")) };
+        }
+        let tag = self.varint()?;
+        Ok(Some((tag >> 3, (tag & 7) as u8)))
+    }
+
+    /// A length prefix, checked against the enclosing message's `end`.
+    fn len(&mut self, end: u64) -> io::Result<u64> {
+        let len = self.varint()?;
+        match self.pos.checked_add(len) {
+            Some(stop) if stop <= end => Ok(len),
+            _ => Err(invalid("length-delimited field overruns its message")),
+        }
+    }
+
+    fn bytes(&mut self, len: u64) -> io::Result<Vec<u8>> {
+        let mut buf = vec![0u8; len as usize];
+        self.inner.read_exact(&mut buf)?;
+        self.pos += len;
+        Ok(buf)
+    }
+
+    fn string(&mut self, end: u64) -> io::Result<String> {
+        let len = self.len(end)?;
+        String::from_utf8(self.bytes(len)?).map_err(|_| invalid("string is not UTF-8"))
+    }
+
+    fn copy(&mut self, len: u64, hasher: &mut Hasher) -> io::Result<()> {
+        super::copy_exact(&mut self.inner, len, hasher)?;
+        self.pos += len;
+        Ok(())
+    }
+
+    /// Re-encode the field `(field, wire)` whose tag was just read into
+    /// `hasher`, or only skip it when `hasher` is `None`.
+    fn pass(&mut self, field: u64, wire: u8, end: u64, hasher: Option<&mut Hasher>) -> io::Result<()> {
+        let value = match wire {
+            WIRE_VARINT => {
+                let mut buf = Vec::new();
+                let value = self.varint()?;
+                push_varint(&mut buf, value);
+                buf
+            }
+            WIRE_I64 => self.fixed::<8>()?.to_vec(),
+            WIRE_I32 => self.fixed::<4>()?.to_vec(),
+            WIRE_LEN => {
+                let len = self.len(end)?;
+                let Some(h) = hasher else {
+                    if io::copy(&mut (&mut self.inner).take(len), &mut io::sink())? < len {
+                        return Err(io::ErrorKind::UnexpectedEof.into());
+                    }
+                    self.pos += len;
+                    return Ok(());
+                };
+                write_varint(h, field << 3 | u64::from(wire));
+                write_varint(h, len);
+                return self.copy(len, h);
+            }
+            _ => return Err(invalid(format!("unsupported wire type {wire} in field {field}"))),
+        };
+        if let Some(h) = hasher {
+            write_varint(h, field << 3 | u64::from(wire));
+            h.update(&value);
+        }
+        Ok(())
+    }
+}
+
+/// An initializer as read so far.
+struct Tensor {
+    name: Option<String>,
+    dims: Vec<u64>,
+    data_type: i32,
+    /// Values of `int32_data`, `int64_data` or `uint64_data`.
+    ints: Vec<u64>,
+    strings: u64,
+    location: Option<String>,
+    offset: u64,
+    length: Option<u64>,
+    external: bool,
+    /// Bytes hashed from `raw_data`, `float_data` or `double_data`.
+    streamed: u64,
+    data: Hasher,
+}
+
+impl Tensor {
+    fn new(alg: HashAlg) -> Self {
+        Self {
+            name: None,
+            dims: Vec::new(),
+            data_type: 0,
+            ints: Vec::new(),
+            strings: 0,
+            location: None,
+            offset: 0,
+            length: None,
+            external: false,
+            streamed: 0,
+            data: alg.hasher(),
+        }
+    }
+}
+
+pub(super) fn leaves(source: &Source) -> Result<Vec<Leaf>, FingerprintError> {
+    let file = source.open()?;
+    let file_len = file.get_ref().metadata().map_err(|e| source.io(e))?.len();
+    let mut p = Proto { inner: file, pos: 0 };
+    let read_err = |e| read_error(source, e);
+
+    let mut graph = source.alg.hasher();
+    // Bytes of the file outside the initializers.
+    let mut outside = file_len;
+    let mut leaves = Vec::new();
+    while let Some((field, wire)) = p.tag(file_len).map_err(read_err)? {
+        if field != MODEL_GRAPH || wire != WIRE_LEN {
+            p.pass(field, wire, file_len, Some(&mut graph)).map_err(read_err)?;
+            continue;
+        }
+        // The graph's own length changes with its initializers; leave it out.
+        write_varint(&mut graph, field << 3 | u64::from(wire));
+        let len = p.len(file_len).map_err(read_err)?;
+        let end = p.pos + len;
+        loop {
+            let tag_start = p.pos;
+            let Some((field, wire)) = p.tag(end).map_err(read_err)? else { break };
+            if field == GRAPH_INITIALIZER && wire == WIRE_LEN {
+                let len = p.len(end).map_err(read_err)?;
+                let stop = p.pos + len;
+                let tensor = read_tensor(&mut p, stop, source.alg).map_err(read_err)?;
+                leaves.push(finish(source, tensor)?);
+                outside -= p.pos - tag_start;
+            } else {
+                p.pass(field, wire, end, Some(&mut graph)).map_err(read_err)?;
+            }
+        }
+    }
+    leaves.push(Leaf {
+        kind: LeafKind::Metadata,
+        name: "onnx.graph".into(),
+        dtype: None,
+        shape: None,
+        size: outside,
+        digest: graph.finalize(),
+    });
+    Ok(leaves)
+}
+
+fn read_error(source: &Source, e: io::Error) -> FingerprintError {
+    match e.kind() {
+        io::ErrorKind::InvalidData => source.malformed(e.to_string()),
+        _ => source.io(e),
+    }
+}
+
+/// Read one `TensorProto` ending at `end`, hashing its inline data.
+fn read_tensor<R: Read>(p: &mut Proto<R>, end: u64, alg: HashAlg) -> io::Result<Tensor> {
+    let mut t = Tensor::new(alg);
+    while let Some((field, wire)) = p.tag(end)? {
+        match (field, wire) {
+            (TENSOR_DIMS, WIRE_VARINT) => t.dims.push(p.varint()?),
+            (TENSOR_DIMS, WIRE_LEN) => {
+                let len = p.len(end)?;
+                let stop = p.pos + len;
+                while p.pos < stop {
+                    t.dims.push(p.varint()?);
+                }
+            }
+            (TENSOR_DATA_TYPE, WIRE_VARINT) => t.data_type = p.varint()? as i32,
+            (TENSOR_FLOAT_DATA, WIRE_I32) => {
+                t.data.update(&p.fixed::<4>()?);
+                t.streamed += 4;
+            }
+            (TENSOR_DOUBLE_DATA, WIRE_I64) => {
+                t.data.update(&p.fixed::<8>()?);
+                t.streamed += 8;
+            }
+            (TENSOR_FLOAT_DATA | TENSOR_DOUBLE_DATA | TENSOR_RAW_DATA, WIRE_LEN) => {
+                let len = p.len(end)?;
+                p.copy(len, &mut t.data)?;
+                t.streamed += len;
+            }
+            (TENSOR_INT32_DATA | TENSOR_INT64_DATA | TENSOR_UINT64_DATA, WIRE_VARINT) => t.ints.push(p.varint()?),
+            (TENSOR_INT32_DATA | TENSOR_INT64_DATA | TENSOR_UINT64_DATA, WIRE_LEN) => {
+                let len = p.len(end)?;
+                let stop = p.pos + len;
+                while p.pos < stop {
+                    t.ints.push(p.varint()?);
+                }
+            }
+            (TENSOR_STRING_DATA, WIRE_LEN) => {
+                let len = p.len(end)?;
+                t.data.update(&len.to_le_bytes());
+                p.copy(len, &mut t.data)?;
+                t.strings += 1;
+            }
+            (TENSOR_NAME, WIRE_LEN) => t.name = Some(p.string(end)?),
+            (TENSOR_EXTERNAL_DATA, WIRE_LEN) => {
+                let len = p.len(end)?;
+                let stop = p.pos + len;
+                let (mut key, mut value) = (String::new(), String::new());
+                while let Some((field, wire)) = p.tag(stop)? {
+                    match (field, wire) {
+                        (ENTRY_KEY, WIRE_LEN) => key = p.string(stop)?,
+                        (ENTRY_VALUE, WIRE_LEN) => value = p.string(stop)?,
+                        _ => p.pass(field, wire, stop, None)?,
+                    }
+                }
+                let number = || value.parse::<u64>().map_err(|_| invalid(format!("external_data {key}={value:?}")));
+                match key.as_str() {
+                    "offset" => t.offset = number()?,
+                    "length" => t.length = Some(number()?),
+                    "location" => t.location = Some(value),
+                    _ => {}
+                }
+            }
+            (TENSOR_DATA_LOCATION, WIRE_VARINT) => t.external = p.varint()? == 1,
+            _ => p.pass(field, wire, end, None)?,
+        }
+        if t.dims.len() > MAX_DIMS {
+            return Err(invalid("too many dimensions"));
+        }
+    }
+    Ok(t)
+}
+
+/// Check a tensor against its shape and turn it into a leaf, reading its
+/// external data if it has any.
+fn finish(source: &Source, t: Tensor) -> Result<Leaf, FingerprintError> {
+    let Tensor { name, dims, data_type: ty, ints, strings, location, offset, length, external, streamed, mut data } = t;
+    let name = name.ok_or_else(|| source.malformed("initializer without a name"))?;
+    let bad = |message: String| source.malformed(format!("{name}: {message}"));
+    let (dtype, width) = data_type(ty);
+    let stored = [streamed > 0, !ints.is_empty(), strings > 0, external].into_iter().filter(|&s| s).count();
+    if stored > 1 {
+        return Err(bad("data is stored in more than one field".into()));
+    }
+
+    let mut size = streamed;
+    if !ints.is_empty() {
+        if width == 0 {
+            return Err(bad(format!("{dtype} cannot be stored as integers")));
+        }
+        for value in &ints {
+            data.update(&value.to_le_bytes()[..width as usize]);
+        }
+        size = ints.len() as u64 * width;
+    } else if external {
+        let location = location.ok_or_else(|| bad("external data without a location".into()))?;
+        let path = Path::new(&location);
+        if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(bad(format!("external data location {location:?} leaves the model directory")));
+        }
+        let path = source.path.parent().unwrap_or(Path::new("")).join(path);
+        let io = |e: io::Error| FingerprintError::Io { path: path.clone(), source: e };
+        let mut file = File::open(&path).map(BufReader::new).map_err(io)?;
+        let file_len = file.get_ref().metadata().map_err(io)?.len();
+        let length = length.unwrap_or(file_len.saturating_sub(offset));
+        if offset.checked_add(length).is_none_or(|stop| stop > file_len) {
+            return Err(bad(format!("external data [{offset}, +{length}) is past the end of {location}")));
+        }
+        file.seek(SeekFrom::Start(offset)).map_err(io)?;
+        super::copy_exact(&mut file, length, &mut data).map_err(io)?;
+        size = length;
+    }
+
+    let elements = element_count(&dims).ok_or_else(|| bad("shape overflows".into()))?;
+    if ty == DATA_TYPE_STRING {
+        if strings != elements {
+            return Err(bad(format!("{strings} strings for {elements} elements")));
+        }
+        size = strings;
+    } else if width > 0 && elements.checked_mul(width) != Some(size) {
+        return Err(bad(format!("{dtype} {dims:?} needs {} bytes, found {size}", elements.saturating_mul(width))));
+    }
+    Ok(Leaf { kind: LeafKind::Tensor, name, dtype: Some(dtype), shape: Some(dims), size, digest: data.finalize() })
+}
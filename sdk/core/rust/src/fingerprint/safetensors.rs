@@ -0,0 +1,85 @@
+//! safetensors: `u64 LE header length || JSON header || data`, where the
+//! header maps each tensor name to its dtype, shape and byte range.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Seek, SeekFrom};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{element_count, Leaf, LeafKind, Source};
+use crate::canonical::canonical_json;
+use crate::error::FingerprintError;
+
+/// Headers larger than this are refused rather than read into memory.
+const MAX_HEADER: u64 = 100 << 20;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    dtype: String,
+    shape: Vec<u64>,
+    data_offsets: (u64, u64),
+}
+
+fn dtype_size(dtype: &str) -> Option<u64> {
+    Some(match dtype {
+        "BOOL" | "U8" | "I8" | "F8_E5M2" | "F8_E4M3" => 1,
+        "U16" | "I16" | "F16" | "BF16" => 2,
+        "U32" | "I32" | "F32" => 4,
+        "U64" | "I64" | "F64" => 8,
+        _ => return None,
+    })
+}
+
+pub(super) fn leaves(source: &Source) -> Result<Vec<Leaf>, FingerprintError> {
+    let mut file = source.open()?;
+    let file_len = file.get_ref().metadata().map_err(|e| source.io(e))?.len();
+    let mut len = [0u8; 8];
+    file.read_exact(&mut len).map_err(|e| source.io(e))?;
+    let header_len = u64::from_le_bytes(len);
+    if header_len > MAX_HEADER || 8 + header_len > file_len {
+        return Err(source.malformed(format!("header length {header_len} does not fit the file")));
+    }
+    let mut header = vec![0u8; header_len as usize];
+    file.read_exact(&mut header).map_err(|e| source.io(e))?;
+    let mut header: BTreeMap<String, Value> =
+        serde_json::from_slice(&header).map_err(|e| source.malformed(format!("header: {e}")))?;
+    let data_start = 8 + header_len;
+
+    let mut leaves = Vec::new();
+    if let Some(metadata) = header.remove("__metadata__") {
+        let text = canonical_json(&metadata).map_err(|e| source.malformed(format!("__metadata__: {e}")))?;
+        leaves.push(Leaf {
+            kind: LeafKind::Metadata,
+            name: "__metadata__".into(),
+            dtype: None,
+            shape: None,
+            size: text.len() as u64,
+            digest: source.alg.digest(text.as_bytes()),
+        });
+    }
+    for (name, entry) in header {
+        let entry: Entry = serde_json::from_value(entry).map_err(|e| source.malformed(format!("{name}: {e}")))?;
+        let (begin, end) = entry.data_offsets;
+        if begin > end || data_start.checked_add(end).is_none_or(|end| end > file_len) {
+            return Err(source.malformed(format!("{name}: data_offsets [{begin}, {end}] outside the data")));
+        }
+        let size = end - begin;
+        if let Some(width) = dtype_size(&entry.dtype) {
+            let expected = element_count(&entry.shape).and_then(|n| n.checked_mul(width));
+            if expected != Some(size) {
+                return Err(source.malformed(format!(
+                    "{name}: {} {:?} needs {} bytes, data_offsets give {size}",
+                    entry.dtype,
+                    entry.shape,
+                    expected.map_or("too many".into(), |n| n.to_string())
+                )));
+            }
+        }
+        file.seek(SeekFrom::Start(data_start + begin)).map_err(|e| source.io(e))?;
+        let digest = source.digest(&mut file, size)?;
+        leaves.push(Leaf { kind: LeafKind::Tensor, name, dtype: Some(entry.dtype), shape: Some(entry.shape), size, digest });
+    }
+    Ok(leaves)
+}
@@ -0,0 +1,28 @@
+//! Local output recomputation.
+
+use sha2::{Digest, Sha256};
+
+use crate::multihash::{HashAlg, Multihash};
+use crate::types::{Hash32, ModelId};
+
+/// Compute deterministic recomputed hash: SHA256(model_id || ":" || input_hash)
+///
+/// `input_hash` is hashed in its canonical text form (lower-case `0x` hex).
+pub fn compute_recomputed_output_hash(model_id: &ModelId, input_hash: &Hash32) -> Hash32 {
+    let mut hasher = Sha256::new();
+    hasher.update(model_id.as_str().as_bytes());
+    hasher.update(b":");
+    hasher.update(input_hash.to_hex().as_bytes());
+    Hash32(hasher.finalize().into())
+}
+
+/// [`compute_recomputed_output_hash`] under any algorithm:
+/// `alg(model_id || ":" || input digest)`, the digest again in `0x` hex and
+/// without its multihash header. With `sha2-256` the two agree.
+pub fn recompute_output_hash(model_id: &ModelId, input_hash: &Multihash, alg: HashAlg) -> Multihash {
+    let mut hasher = alg.hasher();
+    hasher.update(model_id.as_str().as_bytes());
+    hasher.update(b":");
+    hasher.update(input_hash.digest.to_hex().as_bytes());
+    hasher.finalize()
+}
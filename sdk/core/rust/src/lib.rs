@@ -1,3 +1,39 @@
+//! VeriCortex Rust SDK.
+//!
+//! Canonical proof types, hashing and proofId derivation shared by the
+//! off-chain prover, the public API server and the dashboard.
+
+pub mod adapter;
+pub mod batch;
+pub mod canonical;
+#[cfg(feature = "contract")]
+pub mod contract;
+pub mod error;
+pub mod fingerprint;
+pub mod hash;
+pub mod merkle;
+pub mod multihash;
+pub mod proof;
+pub mod proof_id;
+pub mod receipt;
+#[cfg(feature = "signer")]
+pub mod signer;
+pub mod trace;
+pub mod types;
+
+pub use adapter::{AdapterRegistry, ProofAdapter, Verdict};
+pub use batch::{BatchAnchor, BatchReceipt, ProofBatch};
+pub use canonical::Payload;
+pub use error::{AdapterError, CanonicalError, DigestError, Error, FingerprintError, HexError, ReceiptError, Result};
+pub use fingerprint::{ArtifactFormat, ModelFingerprint};
+pub use hash::{compute_recomputed_output_hash, recompute_output_hash};
+pub use multihash::{HashAlg, Multihash};
+pub use proof::{ProofJson, PROOF_FORMAT_VERSION};
+pub use proof_id::{compute_proof_id, ContractSchema, ProofIdPreimage};
+pub use receipt::{AgentKey, AgentPublicKey, InferenceReceipt};
+pub use trace::{ExecutionStep, StepProof, Trace};
+pub use types::{parse_hex_exact, Address, Hash32, KeyId, ModelId, ProofId};
+
 pub fn hello() {
     println!("VeriCortex Rust SDK ready.");
 }
@@ -0,0 +1,117 @@
+//! Binary Keccak-256 Merkle tree with inclusion proofs.
+//!
+//! Leaves and interior nodes are domain-separated (RFC 6962 style) so a leaf
+//! can never be passed off as an interior node:
+//!
+//! - leaf: `keccak256(0x00 || leaf)`
+//! - node: `keccak256(0x01 || left || right)`
+//!
+//! A node without a sibling is promoted to the next level unchanged. The root
+//! of an empty tree is the zero hash, matching an unset `traceHash`.
+
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use crate::types::Hash32;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+pub fn hash_leaf(leaf: &Hash32) -> Hash32 {
+    let mut hasher = Keccak256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(leaf.as_bytes());
+    Hash32(hasher.finalize().into())
+}
+
+pub fn hash_node(left: &Hash32, right: &Hash32) -> Hash32 {
+    let mut hasher = Keccak256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left.as_bytes());
+    hasher.update(right.as_bytes());
+    Hash32(hasher.finalize().into())
+}
+
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    /// `levels[0]` are the hashed leaves, the last level is the root.
+    levels: Vec<Vec<Hash32>>,
+}
+
+impl MerkleTree {
+    pub fn from_leaves(leaves: &[Hash32]) -> Self {
+        let mut levels = vec![leaves.iter().map(hash_leaf).collect::<Vec<_>>()];
+        while levels.last().is_some_and(|l| l.len() > 1) {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_node(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    pub fn root(&self) -> Hash32 {
+        self.levels.last().and_then(|l| l.first()).copied().unwrap_or_default()
+    }
+
+    /// Inclusion proof for the leaf at `index`, or `None` if out of range.
+    pub fn proof(&self, index: usize) -> Option<MerkleProof> {
+        if index >= self.leaf_count() {
+            return None;
+        }
+        let mut siblings = Vec::new();
+        let mut i = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(i ^ 1) {
+                siblings.push(*sibling);
+            }
+            i /= 2;
+        }
+        Some(MerkleProof { index, leaf_count: self.leaf_count(), siblings })
+    }
+}
+
+/// The sibling path from one leaf up to the root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub index: usize,
+    pub leaf_count: usize,
+    pub siblings: Vec<Hash32>,
+}
+
+impl MerkleProof {
+    /// Recompute the root from `leaf`; `None` if the proof is malformed.
+    pub fn root_from(&self, leaf: &Hash32) -> Option<Hash32> {
+        if self.index >= self.leaf_count {
+            return None;
+        }
+        let mut node = hash_leaf(leaf);
+        let mut siblings = self.siblings.iter();
+        let (mut i, mut width) = (self.index, self.leaf_count);
+        while width > 1 {
+            let has_sibling = i ^ 1 < width;
+            if has_sibling {
+                let sibling = siblings.next()?;
+                node = if i % 2 == 0 { hash_node(&node, sibling) } else { hash_node(sibling, &node) };
+            }
+            i /= 2;
+            width = width.div_ceil(2);
+        }
+        siblings.next().is_none().then_some(node)
+    }
+
+    pub fn verify(&self, leaf: &Hash32, root: &Hash32) -> bool {
+        self.root_from(leaf).as_ref() == Some(root)
+    }
+}
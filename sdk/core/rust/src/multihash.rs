@@ -0,0 +1,304 @@
+//! Self-describing digests for the hashes a proof records.
+//!
+//! A [`Multihash`] is the [multihash](https://multiformats.io/multihash/)
+//! encoding `varint(code) || varint(length) || digest`, written as `0x` hex.
+//! The code names the algorithm, so a verifier picks the hash function from
+//! the value itself and new algorithms can be adopted without breaking
+//! proofs made with the old ones:
+//!
+//! | algorithm    | code   | example prefix |
+//! |--------------|--------|----------------|
+//! | `sha2-256`   | `0x12` | `0x1220…`      |
+//! | `sha3-256`   | `0x16` | `0x1620…`      |
+//! | `keccak-256` | `0x1b` | `0x1b20…`      |
+//! | `blake3`     | `0x1e` | `0x1e20…`      |
+//!
+//! A bare 32-byte `0x` hash, as written before digests were self-describing,
+//! reads as `sha2-256`. Every supported digest is 32 bytes, which is what the
+//! verifier contract stores; the algorithm itself stays off-chain.
+
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::Sha256;
+use sha3::{Digest, Keccak256, Sha3_256};
+
+use crate::error::{DigestError, HexError};
+use crate::types::Hash32;
+
+/// A hash function a [`Multihash`] can name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum HashAlg {
+    /// The algorithm of every digest recorded before algorithms were named.
+    #[default]
+    Sha2_256,
+    Sha3_256,
+    Keccak256,
+    Blake3,
+}
+
+impl HashAlg {
+    pub const ALL: [HashAlg; 4] = [HashAlg::Sha2_256, HashAlg::Sha3_256, HashAlg::Keccak256, HashAlg::Blake3];
+
+    /// The multicodec code.
+    pub fn code(self) -> u64 {
+        match self {
+            HashAlg::Sha2_256 => 0x12,
+            HashAlg::Sha3_256 => 0x16,
+            HashAlg::Keccak256 => 0x1b,
+            HashAlg::Blake3 => 0x1e,
+        }
+    }
+
+    pub fn from_code(code: u64) -> Option<Self> {
+        Self::ALL.into_iter().find(|alg| alg.code() == code)
+    }
+
+    /// The multicodec name, e.g. `keccak-256`.
+    pub fn name(self) -> &'static str {
+        match self {
+            HashAlg::Sha2_256 => "sha2-256",
+            HashAlg::Sha3_256 => "sha3-256",
+            HashAlg::Keccak256 => "keccak-256",
+            HashAlg::Blake3 => "blake3",
+        }
+    }
+
+    pub fn hasher(self) -> Hasher {
+        Hasher::new(self)
+    }
+
+    pub fn digest(self, data: &[u8]) -> Multihash {
+        let mut hasher = self.hasher();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+impl fmt::Display for HashAlg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for HashAlg {
+    type Err = DigestError;
+
+    fn from_str(s: &str) -> Result<Self, DigestError> {
+        match s.to_ascii_lowercase().as_str() {
+            "sha2-256" | "sha256" | "sha-256" => Ok(HashAlg::Sha2_256),
+            "sha3-256" | "sha3" => Ok(HashAlg::Sha3_256),
+            "keccak-256" | "keccak256" | "keccak" => Ok(HashAlg::Keccak256),
+            "blake3" => Ok(HashAlg::Blake3),
+            _ => Err(DigestError::UnknownName(s.to_string())),
+        }
+    }
+}
+
+impl Serialize for HashAlg {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for HashAlg {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Incremental hashing under any [`HashAlg`]; also an [`io::Write`] sink.
+#[derive(Clone)]
+pub enum Hasher {
+    Sha2_256(Sha256),
+    Sha3_256(Sha3_256),
+    Keccak256(Keccak256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    pub fn new(alg: HashAlg) -> Self {
+        match alg {
+            HashAlg::Sha2_256 => Hasher::Sha2_256(Sha256::new()),
+            HashAlg::Sha3_256 => Hasher::Sha3_256(Sha3_256::new()),
+            HashAlg::Keccak256 => Hasher::Keccak256(Keccak256::new()),
+            HashAlg::Blake3 => Hasher::Blake3(Box::default()),
+        }
+    }
+
+    pub fn alg(&self) -> HashAlg {
+        match self {
+            Hasher::Sha2_256(_) => HashAlg::Sha2_256,
+            Hasher::Sha3_256(_) => HashAlg::Sha3_256,
+            Hasher::Keccak256(_) => HashAlg::Keccak256,
+            Hasher::Blake3(_) => HashAlg::Blake3,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha2_256(h) => h.update(data),
+            Hasher::Sha3_256(h) => h.update(data),
+            Hasher::Keccak256(h) => h.update(data),
+            Hasher::Blake3(h) => {
+                h.update(data);
+            }
+        }
+    }
+
+    pub fn finalize(self) -> Multihash {
+        let alg = self.alg();
+        let digest: [u8; 32] = match self {
+            Hasher::Sha2_256(h) => h.finalize().into(),
+            Hasher::Sha3_256(h) => h.finalize().into(),
+            Hasher::Keccak256(h) => h.finalize().into(),
+            Hasher::Blake3(h) => h.finalize().into(),
+        };
+        Multihash::new(alg, Hash32(digest))
+    }
+}
+
+impl fmt::Debug for Hasher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Hasher({})", self.alg())
+    }
+}
+
+impl io::Write for Hasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A 32-byte digest tagged with the algorithm that produced it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Multihash {
+    pub alg: HashAlg,
+    /// The raw digest; this is what goes on-chain as `bytes32`.
+    pub digest: Hash32,
+}
+
+/// Length of the encoded form: one code byte, one length byte, the digest.
+const ENCODED_LEN: usize = 2 + Hash32::LEN;
+
+impl Multihash {
+    pub fn new(alg: HashAlg, digest: Hash32) -> Self {
+        Self { alg, digest }
+    }
+
+    /// `varint(code) || varint(32) || digest`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(ENCODED_LEN);
+        write_varint(&mut out, self.alg.code());
+        write_varint(&mut out, Hash32::LEN as u64);
+        out.extend_from_slice(self.digest.as_bytes());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DigestError> {
+        let (code, rest) = read_varint(bytes)?;
+        let (len, digest) = read_varint(rest)?;
+        let alg = HashAlg::from_code(code).ok_or(DigestError::UnknownCode(code))?;
+        if len != Hash32::LEN as u64 || digest.len() != Hash32::LEN {
+            return Err(DigestError::WrongLength { alg: alg.name(), declared: len, got: digest.len() });
+        }
+        Ok(Self::new(alg, Hash32(digest.try_into().expect("checked above"))))
+    }
+
+    /// Parse the `0x` hex of an encoded multihash, or a bare 32-byte hash as
+    /// `sha2-256`.
+    pub fn from_hex(s: &str) -> Result<Self, DigestError> {
+        let digits = s.strip_prefix("0x").ok_or(HexError::BadPrefix)?;
+        if let Some((i, ch)) = digits.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+            return Err(HexError::NonHex { index: i + 2, ch }.into());
+        }
+        if digits.len() % 2 != 0 {
+            return Err(HexError::OddLength(digits.len()).into());
+        }
+        if digits.len() == Hash32::LEN * 2 {
+            return Ok(Hash32::from_hex(s)?.into());
+        }
+        Self::from_bytes(&hex::decode(digits).expect("validated above"))
+    }
+
+    pub fn to_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.to_bytes()))
+    }
+
+    /// Hash `data` with this digest's algorithm and compare.
+    pub fn matches(&self, data: &[u8]) -> bool {
+        self.alg.digest(data) == *self
+    }
+}
+
+impl From<Hash32> for Multihash {
+    /// A bare hash, taken to be `sha2-256`.
+    fn from(digest: Hash32) -> Self {
+        Self::new(HashAlg::Sha2_256, digest)
+    }
+}
+
+impl FromStr for Multihash {
+    type Err = DigestError;
+
+    fn from_str(s: &str) -> Result<Self, DigestError> {
+        Self::from_hex(s)
+    }
+}
+
+impl fmt::Display for Multihash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl fmt::Debug for Multihash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Multihash({}:{})", self.alg, self.digest.to_hex())
+    }
+}
+
+impl Serialize for Multihash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for Multihash {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::from_hex(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Unsigned LEB128, at most nine bytes and minimally encoded, as multiformats
+/// requires.
+fn read_varint(bytes: &[u8]) -> Result<(u64, &[u8]), DigestError> {
+    let mut value = 0u64;
+    for (i, &byte) in bytes.iter().enumerate().take(9) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            if byte == 0 && i > 0 {
+                return Err(DigestError::Varint);
+            }
+            return Ok((value, &bytes[i + 1..]));
+        }
+    }
+    Err(DigestError::Varint)
+}
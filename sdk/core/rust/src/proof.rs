@@ -0,0 +1,214 @@
+//! The proof envelope produced by inference services and read by the prover.
+//!
+//! Format history:
+//! - v1: `model_id`, `input_hash`, `output_hash` only (no `format_version` field)
+//! - v2: adds the fields `ProofCortexVerifier.ProofRecord` stores on-chain –
+//!   `model_version`, `trace_hash`, `proof_hash` – plus `timestamp` and `submitter`
+//! - v3: `input_hash` and `output_hash` are self-describing [`Multihash`]
+//!   digests that name their algorithm; v1 and v2 hashes read as `sha2-256`
+//! - v4: adds `model_fingerprint`, the [`ModelFingerprint`] of the model
+//!   artifact that ran
+//! - v5: adds `proof_type`, the [`crate::adapter`] backend that checks the
+//!   proof, and `evidence`, whatever that backend needs (a signature, a
+//!   SNARK, an attestation report). Neither is part of the bundle hash,
+//!   which evidence such as an attestation signs
+//!
+//! `trace_hash` and `proof_hash` stay plain Keccak-256 hashes: their
+//! algorithm is fixed by the trace Merkle tree and the verifier contract.
+//!
+//! Older files are migrated on load, so a v1 `sample_proof.json` still parses.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha3::{Digest, Keccak256};
+
+use crate::error::{DigestError, Error, HexError, Result};
+use crate::fingerprint::ModelFingerprint;
+use crate::hash::recompute_output_hash;
+use crate::multihash::Multihash;
+use crate::types::{Address, Hash32, ModelId};
+
+/// The envelope version written by this crate.
+pub const PROOF_FORMAT_VERSION: u32 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "ProofFile")]
+pub struct ProofJson {
+    pub format_version: u32,
+    pub model_id: ModelId,
+    /// Model version or commit; empty for migrated v1 files.
+    pub model_version: String,
+    pub input_hash: Multihash,
+    /// The algorithm of this digest is also the one the output is recomputed
+    /// with; see [`ProofJson::recomputed_output_hash`].
+    pub output_hash: Multihash,
+    /// Merkle root of the execution trace.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_hash: Option<Hash32>,
+    /// Combined proof bundle hash; see [`ProofJson::bundle_hash`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof_hash: Option<Hash32>,
+    /// Unix seconds at which the inference was proven.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    /// Account expected to anchor the proof.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submitter: Option<Address>,
+    /// Commitment to the weights (and graph) that produced the output.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_fingerprint: Option<ModelFingerprint>,
+    /// The adapter that checks this proof; `None` leaves it to the
+    /// verifier's default, the hash recompute.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof_type: Option<String>,
+    /// Input for that adapter, in the shape it defines.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<Value>,
+}
+
+/// On-disk shape accepted for every format version. Hashes stay strings here
+/// so a malformed value is reported against the field it came from.
+#[derive(Deserialize)]
+struct ProofFile {
+    #[serde(default = "legacy_format_version")]
+    format_version: u32,
+    model_id: ModelId,
+    #[serde(default)]
+    model_version: String,
+    input_hash: String,
+    output_hash: String,
+    #[serde(default)]
+    trace_hash: Option<String>,
+    #[serde(default)]
+    proof_hash: Option<String>,
+    #[serde(default)]
+    timestamp: Option<u64>,
+    #[serde(default)]
+    submitter: Option<String>,
+    #[serde(default)]
+    model_fingerprint: Option<ModelFingerprint>,
+    #[serde(default)]
+    proof_type: Option<String>,
+    #[serde(default)]
+    evidence: Option<Value>,
+}
+
+fn legacy_format_version() -> u32 {
+    1
+}
+
+fn parse_field<T: std::str::FromStr<Err = HexError>>(value: &str, field: &'static str) -> Result<T> {
+    value.parse().map_err(|error| Error::Field { field, error })
+}
+
+fn parse_digest(value: &str, field: &'static str) -> Result<Multihash> {
+    value.parse().map_err(|error| match error {
+        DigestError::Hex(error) => Error::Field { field, error },
+        error => Error::Digest { field, error },
+    })
+}
+
+/// A v1/v2 hash, which was always a bare `sha2-256` digest.
+fn parse_legacy_digest(value: &str, field: &'static str) -> Result<Multihash> {
+    parse_field::<Hash32>(value, field).map(Multihash::from)
+}
+
+impl TryFrom<ProofFile> for ProofJson {
+    type Error = Error;
+
+    fn try_from(f: ProofFile) -> Result<Self> {
+        let parse = if f.format_version < 3 { parse_legacy_digest } else { parse_digest };
+        let input_hash = parse(&f.input_hash, "input_hash")?;
+        let output_hash = parse(&f.output_hash, "output_hash")?;
+        match f.format_version {
+            // v1 had no envelope fields; anything else in the file is ignored.
+            1 => Ok(ProofJson::new(f.model_id, input_hash, output_hash)),
+            2..=5 => Ok(ProofJson {
+                format_version: PROOF_FORMAT_VERSION,
+                model_id: f.model_id,
+                model_version: f.model_version,
+                input_hash,
+                output_hash,
+                trace_hash: f.trace_hash.as_deref().map(|h| parse_field(h, "trace_hash")).transpose()?,
+                proof_hash: f.proof_hash.as_deref().map(|h| parse_field(h, "proof_hash")).transpose()?,
+                timestamp: f.timestamp,
+                submitter: f.submitter.as_deref().map(|a| parse_field(a, "submitter")).transpose()?,
+                model_fingerprint: f.model_fingerprint.filter(|_| f.format_version >= 4),
+                proof_type: f.proof_type.filter(|_| f.format_version >= 5),
+                evidence: f.evidence.filter(|_| f.format_version >= 5),
+            }),
+            v => Err(Error::UnsupportedFormat(v)),
+        }
+    }
+}
+
+impl ProofJson {
+    /// A current-format envelope with only the v1 fields set. Bare [`Hash32`]
+    /// values are taken as `sha2-256` digests.
+    pub fn new(model_id: impl Into<ModelId>, input_hash: impl Into<Multihash>, output_hash: impl Into<Multihash>) -> Self {
+        Self {
+            format_version: PROOF_FORMAT_VERSION,
+            model_id: model_id.into(),
+            model_version: String::new(),
+            input_hash: input_hash.into(),
+            output_hash: output_hash.into(),
+            trace_hash: None,
+            proof_hash: None,
+            timestamp: None,
+            submitter: None,
+            model_fingerprint: None,
+            proof_type: None,
+            evidence: None,
+        }
+    }
+
+    /// Parse any supported format version, migrating it to the current one.
+    /// Every hash and address is validated here, before anything is hashed.
+    pub fn from_json(raw: &str) -> Result<Self> {
+        let file: ProofFile = serde_json::from_str(raw)?;
+        file.try_into()
+    }
+
+    pub fn to_json_pretty(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// `trace_hash`, zero when the proof has no trace.
+    pub fn trace_hash32(&self) -> Hash32 {
+        self.trace_hash.unwrap_or_default()
+    }
+
+    /// The local output recompute, under the algorithm named by `output_hash`.
+    pub fn recomputed_output_hash(&self) -> Multihash {
+        recompute_output_hash(&self.model_id, &self.input_hash, self.output_hash.alg)
+    }
+
+    /// Whether [`ProofJson::recomputed_output_hash`] reproduces `output_hash`.
+    pub fn output_matches(&self) -> bool {
+        self.recomputed_output_hash() == self.output_hash
+    }
+
+    /// The proof bundle hash anchored as `proofHash`: the recorded value if
+    /// present, otherwise [`ProofJson::derived_bundle_hash`].
+    pub fn bundle_hash(&self) -> Hash32 {
+        self.proof_hash.unwrap_or_else(|| self.derived_bundle_hash())
+    }
+
+    /// The bundle hash of the envelope's own fields, ignoring any recorded
+    /// `proof_hash`:
+    /// `keccak256(keccak256(model_id) || keccak256(model_version) || input_hash || output_hash || trace_hash)`,
+    /// over the raw 32-byte digests, followed by
+    /// [`ModelFingerprint::commitment`] when the proof has a fingerprint.
+    pub fn derived_bundle_hash(&self) -> Hash32 {
+        let mut hasher = Keccak256::new();
+        hasher.update(Keccak256::digest(self.model_id.as_str().as_bytes()));
+        hasher.update(Keccak256::digest(self.model_version.as_bytes()));
+        hasher.update(self.input_hash.digest.as_bytes());
+        hasher.update(self.output_hash.digest.as_bytes());
+        hasher.update(self.trace_hash32().as_bytes());
+        if let Some(fingerprint) = &self.model_fingerprint {
+            hasher.update(fingerprint.commitment().as_bytes());
+        }
+        Hash32(hasher.finalize().into())
+    }
+}
@@ -0,0 +1,104 @@
+//! proofId derivation, versioned by the verifier contract it must agree with.
+//!
+//! Both contracts derive the id as `keccak256(abi.encodePacked(...))` over a
+//! schema-specific preimage followed by `msg.sender`:
+//!
+//! - `VeriCortexVerifier` (legacy): `modelId, inputHash, outputHash, submitter`
+//! - `ProofCortexVerifier`: `modelId, version, proofHash, submitter`
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use crate::error::Error;
+use crate::types::{Address, Hash32, ModelId, ProofId};
+
+/// The verifier contract whose proofId encoding should be reproduced.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContractSchema {
+    /// `sdk/blockchain/solidity/contracts/VeriCortexVerifier.sol`
+    VeriCortex,
+    /// `verifier/BlockDAG_Verifier.sol`
+    #[default]
+    ProofCortex,
+}
+
+impl ContractSchema {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContractSchema::VeriCortex => "vericortex",
+            ContractSchema::ProofCortex => "proofcortex",
+        }
+    }
+}
+
+impl fmt::Display for ContractSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ContractSchema {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s.to_ascii_lowercase().as_str() {
+            "vericortex" | "legacy" => Ok(ContractSchema::VeriCortex),
+            "proofcortex" => Ok(ContractSchema::ProofCortex),
+            _ => Err(Error::UnknownSchema(s.to_string())),
+        }
+    }
+}
+
+/// The contract-specific fields hashed into a proofId, before the submitter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofIdPreimage<'a> {
+    VeriCortex {
+        model_id: &'a ModelId,
+        input_hash: &'a Hash32,
+        output_hash: &'a Hash32,
+    },
+    ProofCortex {
+        model_id: &'a ModelId,
+        version: &'a str,
+        proof_hash: &'a Hash32,
+    },
+}
+
+impl ProofIdPreimage<'_> {
+    pub fn schema(&self) -> ContractSchema {
+        match self {
+            ProofIdPreimage::VeriCortex { .. } => ContractSchema::VeriCortex,
+            ProofIdPreimage::ProofCortex { .. } => ContractSchema::ProofCortex,
+        }
+    }
+
+    /// `abi.encodePacked(<preimage>, submitter)`: strings as raw UTF-8,
+    /// `bytes32` as 32 bytes and the address as 20 bytes, no padding.
+    pub fn encode_packed(&self, submitter: &Address) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self {
+            ProofIdPreimage::VeriCortex { model_id, input_hash, output_hash } => {
+                out.extend_from_slice(model_id.as_str().as_bytes());
+                out.extend_from_slice(input_hash.as_bytes());
+                out.extend_from_slice(output_hash.as_bytes());
+            }
+            ProofIdPreimage::ProofCortex { model_id, version, proof_hash } => {
+                out.extend_from_slice(model_id.as_str().as_bytes());
+                out.extend_from_slice(version.as_bytes());
+                out.extend_from_slice(proof_hash.as_bytes());
+            }
+        }
+        out.extend_from_slice(submitter.as_bytes());
+        out
+    }
+}
+
+/// Compute the proofId the contract selected by `preimage` will assign when
+/// `submitter` is `msg.sender`.
+pub fn compute_proof_id(preimage: &ProofIdPreimage<'_>, submitter: &Address) -> ProofId {
+    ProofId(Keccak256::digest(preimage.encode_packed(submitter)).into())
+}
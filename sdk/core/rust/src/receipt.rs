@@ -0,0 +1,223 @@
+//! Inference receipts: a proof signed by the agent that produced it, so a
+//! peer can check who vouches for an output offline, before (or without)
+//! anything being anchored on-chain.
+//!
+//! The signature covers the whole envelope, `proof_type` and `evidence`
+//! included: [`InferenceReceipt::message`] is
+//! `SHA-256("VeriCortex inference receipt v1\n" || JCS(proof))`, where
+//! `JCS(proof)` is the RFC 8785 serialization of the current-format proof
+//! JSON. Ed25519 keys sign those 32 bytes as the message (RFC 8032);
+//! secp256k1 keys sign them as an ECDSA prehash, normalized to low-S.
+//!
+//! A public key is written as `0x` hex of its multicodec tag and bytes, as in
+//! `did:key`: `ed01` and the 32-byte Ed25519 key, or `e701` and the 33-byte
+//! compressed secp256k1 point. Its [`KeyId`] is SHA-256 of that encoding, so
+//! IDs of different algorithms never collide and can be handed out ahead of
+//! time as an allowlist.
+
+use std::fmt;
+use std::str::FromStr;
+
+use ed25519_dalek::Signer;
+use k256::ecdsa::signature::hazmat::{PrehashSigner, PrehashVerifier};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+
+use crate::canonical::canonical_json;
+use crate::error::{CanonicalError, ReceiptError};
+use crate::proof::ProofJson;
+use crate::types::{Hash32, KeyId};
+
+const DOMAIN: &[u8] = b"VeriCortex inference receipt v1\n";
+/// Multicodec `ed25519-pub` and `secp256k1-pub`, as varints.
+const ED25519_PUB: [u8; 2] = [0xed, 0x01];
+const SECP256K1_PUB: [u8; 2] = [0xe7, 0x01];
+
+/// An agent's signing key.
+#[derive(Clone, Debug)]
+pub enum AgentKey {
+    Ed25519(ed25519_dalek::SigningKey),
+    Secp256k1(k256::ecdsa::SigningKey),
+}
+
+impl AgentKey {
+    pub fn public_key(&self) -> AgentPublicKey {
+        match self {
+            AgentKey::Ed25519(key) => AgentPublicKey::Ed25519(key.verifying_key()),
+            AgentKey::Secp256k1(key) => AgentPublicKey::Secp256k1(*key.verifying_key()),
+        }
+    }
+
+    pub fn key_id(&self) -> KeyId {
+        self.public_key().key_id()
+    }
+
+    fn sign(&self, message: &Hash32) -> [u8; 64] {
+        match self {
+            AgentKey::Ed25519(key) => key.sign(message.as_bytes()).to_bytes(),
+            AgentKey::Secp256k1(key) => {
+                let signature: k256::ecdsa::Signature =
+                    key.sign_prehash(message.as_bytes()).expect("a 32-byte prehash is always signable");
+                signature.to_bytes().into()
+            }
+        }
+    }
+}
+
+/// The public half of an [`AgentKey`], serialized as tagged `0x` hex.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AgentPublicKey {
+    Ed25519(ed25519_dalek::VerifyingKey),
+    Secp256k1(k256::ecdsa::VerifyingKey),
+}
+
+impl AgentPublicKey {
+    /// `ed25519` or `secp256k1`.
+    pub fn algorithm(&self) -> &'static str {
+        match self {
+            AgentPublicKey::Ed25519(_) => "ed25519",
+            AgentPublicKey::Secp256k1(_) => "secp256k1",
+        }
+    }
+
+    /// The multicodec tag followed by the key bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            AgentPublicKey::Ed25519(key) => [&ED25519_PUB[..], key.as_bytes()].concat(),
+            AgentPublicKey::Secp256k1(key) => [&SECP256K1_PUB[..], key.to_encoded_point(true).as_bytes()].concat(),
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        match bytes.split_at_checked(2) {
+            Some((tag, key)) if tag == ED25519_PUB => {
+                let key: [u8; 32] =
+                    key.try_into().map_err(|_| format!("Ed25519 key is {} bytes, expected 32", key.len()))?;
+                ed25519_dalek::VerifyingKey::from_bytes(&key)
+                    .map(Self::Ed25519)
+                    .map_err(|_| "not an Ed25519 point".to_string())
+            }
+            Some((tag, key)) if tag == SECP256K1_PUB => {
+                if key.len() != 33 {
+                    return Err(format!("secp256k1 key is {} bytes, expected a 33-byte compressed point", key.len()));
+                }
+                k256::ecdsa::VerifyingKey::from_sec1_bytes(key)
+                    .map(Self::Secp256k1)
+                    .map_err(|_| "not a secp256k1 point".to_string())
+            }
+            _ => Err("unknown key type (expected multicodec ed25519-pub or secp256k1-pub)".to_string()),
+        }
+    }
+
+    /// SHA-256 of [`AgentPublicKey::to_bytes`].
+    pub fn key_id(&self) -> KeyId {
+        KeyId(Sha256::digest(self.to_bytes()).into())
+    }
+
+    fn verify(&self, message: &Hash32, signature: &str) -> Result<(), ReceiptError> {
+        let bytes = hex::decode(signature.strip_prefix("0x").unwrap_or(signature))
+            .map_err(|e| ReceiptError::Signature(e.to_string()))?;
+        let verified = match self {
+            AgentPublicKey::Ed25519(key) => {
+                let signature = ed25519_dalek::Signature::from_slice(&bytes)
+                    .map_err(|_| ReceiptError::Signature(format!("{} bytes, expected 64", bytes.len())))?;
+                key.verify_strict(message.as_bytes(), &signature).is_ok()
+            }
+            AgentPublicKey::Secp256k1(key) => {
+                let signature = k256::ecdsa::Signature::from_slice(&bytes)
+                    .map_err(|_| ReceiptError::Signature("not a 64-byte r || s ECDSA signature".into()))?;
+                key.verify_prehash(message.as_bytes(), &signature).is_ok()
+            }
+        };
+        if verified {
+            Ok(())
+        } else {
+            Err(ReceiptError::BadSignature(self.key_id()))
+        }
+    }
+}
+
+impl fmt::Display for AgentPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl FromStr for AgentPublicKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let digits = s.strip_prefix("0x").ok_or("missing 0x prefix")?;
+        Self::from_bytes(&hex::decode(digits).map_err(|e| e.to_string())?)
+    }
+}
+
+impl Serialize for AgentPublicKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for AgentPublicKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A proof and an agent's signature over it.
+///
+/// A valid receipt says who vouches for the proof, not that the proof holds:
+/// check that with an [`crate::AdapterRegistry`] as well.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InferenceReceipt {
+    pub proof: ProofJson,
+    pub key_id: KeyId,
+    pub public_key: AgentPublicKey,
+    /// 64 bytes as `0x` hex: Ed25519 `R || S`, or ECDSA `r || s`.
+    pub signature: String,
+}
+
+impl InferenceReceipt {
+    /// The digest an agent signs for `proof`. Fails only when `evidence`
+    /// holds a value JCS cannot represent, such as an integer beyond 2^53.
+    pub fn message(proof: &ProofJson) -> Result<Hash32, CanonicalError> {
+        let value = serde_json::to_value(proof).expect("a proof serializes to JSON");
+        let mut hasher = Sha256::new();
+        hasher.update(DOMAIN);
+        hasher.update(canonical_json(&value)?);
+        Ok(Hash32(hasher.finalize().into()))
+    }
+
+    pub fn sign(proof: ProofJson, key: &AgentKey) -> Result<Self, CanonicalError> {
+        let signature = key.sign(&Self::message(&proof)?);
+        Ok(Self {
+            proof,
+            key_id: key.key_id(),
+            public_key: key.public_key(),
+            signature: format!("0x{}", hex::encode(signature)),
+        })
+    }
+
+    /// Check that `key_id` names `public_key` and that its signature covers
+    /// `proof`, returning the signer's ID. Any well-formed key passes; see
+    /// [`InferenceReceipt::verify_trusted`].
+    pub fn verify(&self) -> Result<KeyId, ReceiptError> {
+        let derived = self.public_key.key_id();
+        if derived != self.key_id {
+            return Err(ReceiptError::KeyIdMismatch { claimed: self.key_id, derived });
+        }
+        self.public_key.verify(&Self::message(&self.proof)?, &self.signature)?;
+        Ok(derived)
+    }
+
+    /// [`InferenceReceipt::verify`], and the signer must be one of `trusted`.
+    pub fn verify_trusted<'a>(&self, trusted: impl IntoIterator<Item = &'a KeyId>) -> Result<KeyId, ReceiptError> {
+        let signer = self.verify()?;
+        if trusted.into_iter().any(|id| *id == signer) {
+            Ok(signer)
+        } else {
+            Err(ReceiptError::Untrusted(signer))
+        }
+    }
+}
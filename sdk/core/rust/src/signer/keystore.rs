@@ -0,0 +1,100 @@
+//! Encrypted JSON keystores and the passphrases that unlock them.
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use ethers::signers::LocalWallet;
+
+use super::SignerError;
+
+/// Where a passphrase or PIN comes from. Never an environment variable.
+#[derive(Clone)]
+pub enum Passphrase {
+    /// First line of a file (e.g. a mounted secret).
+    File(PathBuf),
+    /// Asked on the controlling terminal, without echo, with this prompt.
+    Prompt(String),
+    /// Already in memory; for tests and callers with their own secret store.
+    Value(String),
+}
+
+impl fmt::Debug for Passphrase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => f.debug_tuple("File").field(path).finish(),
+            Self::Prompt(_) => f.write_str("Prompt"),
+            Self::Value(_) => f.write_str("Value(..)"),
+        }
+    }
+}
+
+/// The passphrase, without its trailing newline.
+pub fn read_passphrase(source: &Passphrase) -> Result<String, SignerError> {
+    match source {
+        Passphrase::File(path) => {
+            let text = std::fs::read_to_string(path)
+                .map_err(|e| SignerError::Passphrase(format!("{}: {e}", path.display())))?;
+            Ok(text.lines().next().unwrap_or_default().to_string())
+        }
+        Passphrase::Prompt(prompt) => prompt_tty(prompt),
+        Passphrase::Value(value) => Ok(value.clone()),
+    }
+}
+
+/// Restores the terminal mode when dropped, even if reading fails.
+struct EchoOff<'a> {
+    tty: &'a File,
+    saved: libc::termios,
+}
+
+impl<'a> EchoOff<'a> {
+    fn new(tty: &'a File) -> std::io::Result<Self> {
+        // SAFETY: tcgetattr/tcsetattr only read and write the termios struct
+        // passed in, for a descriptor that stays open while `tty` is borrowed.
+        unsafe {
+            let mut saved: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(tty.as_raw_fd(), &mut saved) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            let mut quiet = saved;
+            quiet.c_lflag &= !libc::ECHO;
+            quiet.c_lflag |= libc::ECHONL;
+            if libc::tcsetattr(tty.as_raw_fd(), libc::TCSANOW, &quiet) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(Self { tty, saved })
+        }
+    }
+}
+
+impl Drop for EchoOff<'_> {
+    fn drop(&mut self) {
+        // SAFETY: as in `new`.
+        unsafe {
+            libc::tcsetattr(self.tty.as_raw_fd(), libc::TCSANOW, &self.saved);
+        }
+    }
+}
+
+fn prompt_tty(prompt: &str) -> Result<String, SignerError> {
+    let no_tty = |e: std::io::Error| {
+        SignerError::Passphrase(format!("no terminal to prompt on ({e}); provide a passphrase file instead"))
+    };
+    let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty").map_err(no_tty)?;
+    tty.write_all(prompt.as_bytes()).and_then(|_| tty.flush()).map_err(no_tty)?;
+
+    let _echo = EchoOff::new(&tty).map_err(no_tty)?;
+    let mut line = String::new();
+    BufReader::new(&tty).read_line(&mut line).map_err(no_tty)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Decrypt a Web3 Secret Storage keystore (scrypt or pbkdf2, AES-128-CTR).
+pub(super) fn unlock(path: &Path, passphrase: &Passphrase) -> Result<LocalWallet, SignerError> {
+    let passphrase = read_passphrase(passphrase)?;
+    LocalWallet::decrypt_keystore(path, passphrase)
+        .map_err(|e| SignerError::Keystore { path: path.to_path_buf(), message: e.to_string() })
+}
@@ -0,0 +1,271 @@
+//! Transaction signers for the trusted submitter.
+//!
+//! [`SubmitterSigner`] implements the ethers [`Signer`] trait on top of one of
+//! several key backends, so the prover and the API build the same
+//! `SignerMiddleware` whatever holds the key:
+//!
+//! - `key`: a raw hex private key (development only)
+//! - `keystore`: an encrypted JSON (Web3 Secret Storage) keystore file, unlocked
+//!   with a passphrase read from a file or prompted on the terminal
+//! - `remote`: a web3signer-style HTTP signer
+//! - `pkcs11`: a secp256k1 key on a PKCS#11 token (HSM, SoftHSM)
+//!
+//! Every backend signs `keccak256(payload)` for a payload built here (RLP of
+//! the transaction, EIP-191 message, EIP-712 encoding), and the recovered
+//! address is checked before a signature is handed out.
+
+mod keystore;
+mod pkcs11;
+mod remote;
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ethers::core::k256::ecdsa::Signature as EcdsaSignature;
+use ethers::signers::{to_eip155_v, LocalWallet, Signer};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::types::{Address, Signature, H256, U256};
+use ethers::utils::keccak256;
+
+pub use keystore::{read_passphrase, Passphrase};
+pub use pkcs11::Pkcs11Key;
+pub use remote::RemoteSigner;
+
+/// Why a signer could not be configured, unlocked or used.
+#[derive(Debug, thiserror::Error)]
+pub enum SignerError {
+    #[error("invalid signer configuration: {0}")]
+    Config(String),
+
+    #[error("cannot read passphrase: {0}")]
+    Passphrase(String),
+
+    #[error("keystore {path}: {message}")]
+    Keystore { path: PathBuf, message: String },
+
+    #[error("remote signer: {0}")]
+    Remote(String),
+
+    #[error("PKCS#11: {0}")]
+    Pkcs11(String),
+
+    #[error("signature does not recover to the signer address {0:?}")]
+    WrongKey(Address),
+
+    #[error("signature has v = {0}, which is not 0/1, 27/28 or EIP-155 for this chain")]
+    RecoveryId(u64),
+
+    #[error("EIP-712 encoding failed: {0}")]
+    Eip712(String),
+}
+
+/// Which backend holds the submitter key, and how to reach it.
+#[derive(Clone)]
+pub enum SignerConfig {
+    /// Raw hex private key. Kept for local development.
+    PrivateKey(String),
+    Keystore { path: PathBuf, passphrase: Passphrase },
+    Remote {
+        url: String,
+        /// Key to use when the signer holds several.
+        address: Option<Address>,
+    },
+    Pkcs11 {
+        /// Path of the PKCS#11 module (`libsofthsm2.so`, vendor library).
+        module: PathBuf,
+        token_label: String,
+        /// `CKA_LABEL` of the private key and its public key object.
+        key_label: String,
+        pin: Passphrase,
+    },
+}
+
+// Never print key material.
+impl fmt::Debug for SignerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PrivateKey(_) => f.write_str("PrivateKey(..)"),
+            Self::Keystore { path, passphrase } => {
+                f.debug_struct("Keystore").field("path", path).field("passphrase", passphrase).finish()
+            }
+            Self::Remote { url, address } => f.debug_struct("Remote").field("url", url).field("address", address).finish(),
+            Self::Pkcs11 { module, token_label, key_label, pin } => f
+                .debug_struct("Pkcs11")
+                .field("module", module)
+                .field("token_label", token_label)
+                .field("key_label", key_label)
+                .field("pin", pin)
+                .finish(),
+        }
+    }
+}
+
+impl SignerConfig {
+    /// Short name of the backend, for logs.
+    pub fn backend(&self) -> &'static str {
+        match self {
+            Self::PrivateKey(_) => "key",
+            Self::Keystore { .. } => "keystore",
+            Self::Remote { .. } => "remote",
+            Self::Pkcs11 { .. } => "pkcs11",
+        }
+    }
+
+    /// Unlock or reach the key. May prompt for a passphrase or PIN.
+    pub async fn connect(&self, chain_id: u64) -> Result<SubmitterSigner, SignerError> {
+        let backend = match self {
+            Self::PrivateKey(key) => Backend::Local(
+                key.parse::<LocalWallet>().map_err(|e| SignerError::Config(format!("invalid private key: {e}")))?,
+            ),
+            Self::Keystore { path, passphrase } => Backend::Local(keystore::unlock(path, passphrase)?),
+            Self::Remote { url, address } => Backend::Remote(RemoteSigner::connect(url, *address).await?),
+            Self::Pkcs11 { module, token_label, key_label, pin } => {
+                let pin = read_passphrase(pin)?;
+                Backend::Pkcs11(Arc::new(Pkcs11Key::open(module, token_label, key_label, &pin)?))
+            }
+        };
+        Ok(SubmitterSigner { address: backend.address(), backend, chain_id })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Backend {
+    Local(LocalWallet),
+    Remote(RemoteSigner),
+    Pkcs11(Arc<Pkcs11Key>),
+}
+
+impl Backend {
+    fn address(&self) -> Address {
+        match self {
+            Self::Local(wallet) => wallet.address(),
+            Self::Remote(remote) => remote.address(),
+            Self::Pkcs11(key) => key.address(),
+        }
+    }
+
+    /// Sign `keccak256(payload)`; `v` is 27 or 28.
+    async fn sign_payload(&self, payload: &[u8]) -> Result<Signature, SignerError> {
+        match self {
+            Self::Local(wallet) => wallet
+                .sign_hash(H256(keccak256(payload)))
+                .map_err(|e| SignerError::Config(format!("signing failed: {e}"))),
+            Self::Remote(remote) => remote.sign(payload).await,
+            Self::Pkcs11(key) => key.sign_hash(&keccak256(payload)),
+        }
+    }
+}
+
+/// The submitter key, behind whichever backend [`SignerConfig`] chose.
+#[derive(Debug, Clone)]
+pub struct SubmitterSigner {
+    backend: Backend,
+    address: Address,
+    chain_id: u64,
+}
+
+impl SubmitterSigner {
+    pub fn backend(&self) -> &'static str {
+        match self.backend {
+            Backend::Local(_) => "local",
+            Backend::Remote(_) => "remote",
+            Backend::Pkcs11(_) => "pkcs11",
+        }
+    }
+
+    /// Sign `keccak256(payload)` and check the signature recovers to
+    /// [`Signer::address`]; `v` comes back as 27 or 28.
+    async fn sign_checked(&self, payload: &[u8], chain_id: u64) -> Result<Signature, SignerError> {
+        let mut signature = self.backend.sign_payload(payload).await?;
+        signature.v = 27 + recovery_id(signature.v, chain_id)? as u64;
+        match signature.recover(H256(keccak256(payload))) {
+            Ok(signer) if signer == self.address => Ok(signature),
+            _ => Err(SignerError::WrongKey(self.address)),
+        }
+    }
+}
+
+#[async_trait]
+impl Signer for SubmitterSigner {
+    type Error = SignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(&self, message: S) -> Result<Signature, Self::Error> {
+        let message = message.as_ref();
+        let mut payload = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+        payload.extend_from_slice(message);
+        self.sign_checked(&payload, self.chain_id).await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        // Same chain id rules as `LocalWallet`: the RLP and `v` must agree.
+        let chain_id = tx.chain_id().map(|id| id.as_u64()).unwrap_or(self.chain_id);
+        let mut tx = tx.clone();
+        tx.set_chain_id(chain_id);
+        let mut signature = self.sign_checked(&tx.rlp(), chain_id).await?;
+        signature.v = to_eip155_v(recovery_id(signature.v, chain_id)?, chain_id);
+        Ok(signature)
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(&self, payload: &T) -> Result<Signature, Self::Error> {
+        let domain = payload.domain().map_err(|e| SignerError::Eip712(e.to_string()))?;
+        let struct_hash = payload.struct_hash().map_err(|e| SignerError::Eip712(e.to_string()))?;
+        let mut encoded = vec![0x19, 0x01];
+        encoded.extend_from_slice(&domain.separator());
+        encoded.extend_from_slice(&struct_hash);
+        self.sign_checked(&encoded, self.chain_id).await
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+/// The recovery id (0 or 1) of a backend's `v`: bare 0/1, 27/28, or EIP-155
+/// `chain_id * 2 + 35/36` for `chain_id`. Anything else is an error.
+pub fn recovery_id(v: u64, chain_id: u64) -> Result<u8, SignerError> {
+    match v {
+        0 | 1 => Ok(v as u8),
+        27 | 28 => Ok((v - 27) as u8),
+        _ if v >= 35 && (v - 35) / 2 == chain_id => Ok(((v - 35) % 2) as u8),
+        _ => Err(SignerError::RecoveryId(v)),
+    }
+}
+
+/// Address of an uncompressed secp256k1 public key (`x || y`, optionally
+/// prefixed with `0x04`).
+pub(crate) fn public_key_address(key: &[u8]) -> Option<Address> {
+    let xy = match key.len() {
+        64 => key,
+        65 if key[0] == 0x04 => &key[1..],
+        _ => return None,
+    };
+    Some(Address::from_slice(&keccak256(xy)[12..]))
+}
+
+/// Turn a raw 64-byte `r || s` ECDSA signature over `hash` into an Ethereum
+/// signature for `address`: low-s normalized, with the recovery id found by
+/// trial recovery.
+pub(crate) fn recoverable(raw: &[u8], hash: &[u8; 32], address: Address) -> Result<Signature, SignerError> {
+    let signature = EcdsaSignature::from_slice(raw).map_err(|e| SignerError::Pkcs11(format!("bad signature: {e}")))?;
+    let signature = signature.normalize_s().unwrap_or(signature);
+    let (r, s) = signature.split_bytes();
+    for id in [0u8, 1] {
+        let candidate = Signature { r: U256::from_big_endian(&r), s: U256::from_big_endian(&s), v: 27 + id as u64 };
+        if candidate.recover(H256(*hash)).ok() == Some(address) {
+            return Ok(candidate);
+        }
+    }
+    Err(SignerError::WrongKey(address))
+}
@@ -0,0 +1,112 @@
+//! secp256k1 keys on a PKCS#11 token.
+//!
+//! Talks to the vendor module through the `cryptoki` crate: find the token by
+//! label, log in as the user, look up the private and public key objects
+//! sharing `CKA_LABEL`, and sign hashes with `CKM_ECDSA`. The token returns a
+//! bare `r || s`; the recovery id is found by trial recovery against the
+//! address derived from `CKA_EC_POINT`.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use cryptoki::context::{CInitializeArgs, CInitializeFlags, Pkcs11};
+use cryptoki::error::{Error as CryptokiError, RvError};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+use ethers::types::{Address, Signature};
+
+use super::{public_key_address, recoverable, SignerError};
+
+fn pkcs11_err(e: CryptokiError) -> SignerError {
+    SignerError::Pkcs11(e.to_string())
+}
+
+/// A signing key on a PKCS#11 token.
+pub struct Pkcs11Key {
+    /// `Session` is `Send` but not `Sync`; calls through it are serialized.
+    session: Mutex<(Session, ObjectHandle)>,
+    address: Address,
+}
+
+impl std::fmt::Debug for Pkcs11Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pkcs11Key").field("address", &self.address).finish_non_exhaustive()
+    }
+}
+
+/// The uncompressed point inside `CKA_EC_POINT`, which modules return either
+/// DER-wrapped in an OCTET STRING or bare.
+fn ec_point(value: &[u8]) -> &[u8] {
+    match value {
+        [0x04, 0x41, point @ ..] if point.len() == 65 => point,
+        _ => value,
+    }
+}
+
+/// Objects of `class` labelled `label`.
+fn find(session: &Session, class: ObjectClass, label: &str) -> Result<Vec<ObjectHandle>, SignerError> {
+    session
+        .find_objects(&[Attribute::Class(class), Attribute::Label(label.as_bytes().to_vec())])
+        .map_err(pkcs11_err)
+}
+
+impl Pkcs11Key {
+    /// Load `module`, log in to the token `token_label` with `pin` and find
+    /// the key pair labelled `key_label`.
+    pub fn open(module: &Path, token_label: &str, key_label: &str, pin: &str) -> Result<Self, SignerError> {
+        let context = Pkcs11::new(module)
+            .map_err(|e| SignerError::Pkcs11(format!("cannot load module {}: {e}", module.display())))?;
+        match context.initialize(CInitializeArgs::new(CInitializeFlags::OS_LOCKING_OK)) {
+            Ok(()) | Err(CryptokiError::Pkcs11(RvError::CryptokiAlreadyInitialized, _)) => {}
+            Err(e) => return Err(pkcs11_err(e)),
+        }
+
+        let mut slot = None;
+        for candidate in context.get_slots_with_initialized_token().map_err(pkcs11_err)? {
+            // Labels are blank-padded to 32 bytes.
+            if context.get_token_info(candidate).map_err(pkcs11_err)?.label().trim_end() == token_label {
+                slot = Some(candidate);
+                break;
+            }
+        }
+        let slot = slot.ok_or_else(|| SignerError::Pkcs11(format!("no token labelled {token_label:?}")))?;
+        let session = context.open_rw_session(slot).map_err(pkcs11_err)?;
+        match session.login(UserType::User, Some(&AuthPin::from(pin))) {
+            Ok(()) | Err(CryptokiError::Pkcs11(RvError::UserAlreadyLoggedIn, _)) => {}
+            Err(e) => return Err(pkcs11_err(e)),
+        }
+
+        let private_key = match find(&session, ObjectClass::PRIVATE_KEY, key_label)?.as_slice() {
+            [key] => *key,
+            [] => return Err(SignerError::Pkcs11(format!("no private key labelled {key_label:?}"))),
+            _ => return Err(SignerError::Pkcs11(format!("several private keys labelled {key_label:?}"))),
+        };
+        let public_key = *find(&session, ObjectClass::PUBLIC_KEY, key_label)?
+            .first()
+            .ok_or_else(|| SignerError::Pkcs11(format!("no public key labelled {key_label:?}")))?;
+        let point = match session.get_attributes(public_key, &[AttributeType::EcPoint]).map_err(pkcs11_err)?.as_slice() {
+            [Attribute::EcPoint(point)] => point.clone(),
+            _ => return Err(SignerError::Pkcs11(format!("public key {key_label:?} has no CKA_EC_POINT"))),
+        };
+        let address = public_key_address(ec_point(&point))
+            .ok_or_else(|| SignerError::Pkcs11(format!("{key_label:?} is not an uncompressed secp256k1 key")))?;
+
+        Ok(Self { session: Mutex::new((session, private_key)), address })
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Sign a 32-byte hash; `v` is 27 or 28.
+    pub fn sign_hash(&self, hash: &[u8; 32]) -> Result<Signature, SignerError> {
+        let raw = {
+            let guard = self.session.lock().map_err(|_| SignerError::Pkcs11("session lock poisoned".into()))?;
+            let (session, private_key) = &*guard;
+            session.sign(&Mechanism::Ecdsa, *private_key, hash).map_err(pkcs11_err)?
+        };
+        recoverable(&raw, hash, self.address)
+    }
+}
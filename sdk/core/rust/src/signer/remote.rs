@@ -0,0 +1,94 @@
+//! A web3signer-style remote signer over HTTP.
+//!
+//! Uses the `eth1` endpoints of the web3signer REST API:
+//!
+//! - `GET  {url}/api/v1/eth1/publicKeys` lists the uncompressed secp256k1
+//!   public keys the signer holds
+//! - `POST {url}/api/v1/eth1/sign/{publicKey}` with `{"data": "0x…"}` returns
+//!   the 65-byte `r || s || v` signature of `keccak256(data)`
+
+use ethers::types::{Address, Signature, U256};
+
+use super::{public_key_address, SignerError};
+
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    http: reqwest::Client,
+    url: String,
+    public_key: String,
+    address: Address,
+}
+
+fn remote_err(e: impl std::fmt::Display) -> SignerError {
+    SignerError::Remote(e.to_string())
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    hex::decode(value.trim().trim_matches('"').strip_prefix("0x")?).ok()
+}
+
+impl RemoteSigner {
+    /// List the signer's keys and pick `address`, or the only key when `None`.
+    pub async fn connect(url: &str, address: Option<Address>) -> Result<Self, SignerError> {
+        let http = reqwest::Client::new();
+        let url = url.trim_end_matches('/').to_string();
+        let keys: Vec<String> = http
+            .get(format!("{url}/api/v1/eth1/publicKeys"))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(remote_err)?
+            .json()
+            .await
+            .map_err(remote_err)?;
+
+        let mut held = keys.into_iter().filter_map(|key| {
+            let addr = decode_hex(&key).as_deref().and_then(public_key_address)?;
+            Some((key, addr))
+        });
+        let (public_key, address) = match address {
+            Some(wanted) => held
+                .find(|(_, addr)| *addr == wanted)
+                .ok_or_else(|| SignerError::Remote(format!("no key for {wanted:?} at {url}")))?,
+            None => {
+                let all: Vec<_> = held.collect();
+                match <[_; 1]>::try_from(all) {
+                    Ok([only]) => only,
+                    Err(all) => {
+                        return Err(SignerError::Remote(format!(
+                            "{url} holds {} keys; set the address to use",
+                            all.len()
+                        )))
+                    }
+                }
+            }
+        };
+        Ok(Self { http, url, public_key, address })
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Ask the signer for the signature of `keccak256(payload)`.
+    pub async fn sign(&self, payload: &[u8]) -> Result<Signature, SignerError> {
+        let body = serde_json::json!({ "data": format!("0x{}", hex::encode(payload)) });
+        let response = self
+            .http
+            .post(format!("{}/api/v1/eth1/sign/{}", self.url, self.public_key))
+            .json(&body)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(remote_err)?
+            .text()
+            .await
+            .map_err(remote_err)?;
+
+        let raw = decode_hex(&response)
+            .filter(|raw| raw.len() == 65)
+            .ok_or_else(|| SignerError::Remote(format!("unexpected signature {response:?}")))?;
+        // `v` is normalized, or rejected, by the caller.
+        Ok(Signature { r: U256::from_big_endian(&raw[..32]), s: U256::from_big_endian(&raw[32..64]), v: raw[64] as u64 })
+    }
+}
@@ -0,0 +1,105 @@
+//! Execution traces committed to on-chain as `traceHash`.
+//!
+//! A trace is the ordered list of steps an inference went through. Each step
+//! becomes one Merkle leaf, so a single step can later be shown to belong to
+//! an anchored `traceHash` without revealing the rest of the trace.
+
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use crate::merkle::{MerkleProof, MerkleTree};
+use crate::types::Hash32;
+
+/// One step of an inference, identified by hashes of what it produced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExecutionStep {
+    /// Output of a model layer (or any named intermediate tensor).
+    LayerOutput { layer: String, output_hash: Hash32 },
+    /// A call out to an external tool made while producing the output.
+    ToolCall { tool: String, input_hash: Hash32, output_hash: Hash32 },
+}
+
+fn update_str(hasher: &mut Keccak256, s: &str) {
+    hasher.update((s.len() as u32).to_be_bytes());
+    hasher.update(s.as_bytes());
+}
+
+impl ExecutionStep {
+    /// Leaf value for this step at position `index`:
+    /// `keccak256(index_be64 || kind || len_be32(name) || name || hashes...)`
+    /// where `kind` is `0x00` for a layer output and `0x01` for a tool call.
+    pub fn leaf(&self, index: u64) -> Hash32 {
+        let mut hasher = Keccak256::new();
+        hasher.update(index.to_be_bytes());
+        match self {
+            ExecutionStep::LayerOutput { layer, output_hash } => {
+                hasher.update([0x00]);
+                update_str(&mut hasher, layer);
+                hasher.update(output_hash.as_bytes());
+            }
+            ExecutionStep::ToolCall { tool, input_hash, output_hash } => {
+                hasher.update([0x01]);
+                update_str(&mut hasher, tool);
+                hasher.update(input_hash.as_bytes());
+                hasher.update(output_hash.as_bytes());
+            }
+        }
+        Hash32(hasher.finalize().into())
+    }
+}
+
+/// An ordered execution trace.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Trace {
+    pub steps: Vec<ExecutionStep>,
+}
+
+impl Trace {
+    pub fn new(steps: Vec<ExecutionStep>) -> Self {
+        Self { steps }
+    }
+
+    fn tree(&self) -> MerkleTree {
+        let leaves: Vec<Hash32> = self.steps.iter().enumerate().map(|(i, s)| s.leaf(i as u64)).collect();
+        MerkleTree::from_leaves(&leaves)
+    }
+
+    /// The value anchored as `traceHash`; zero for an empty trace.
+    pub fn root(&self) -> Hash32 {
+        self.tree().root()
+    }
+
+    /// Inclusion proofs for the steps at `indices`, built from one tree.
+    pub fn prove(&self, indices: &[usize]) -> Option<Vec<StepProof>> {
+        let tree = self.tree();
+        indices
+            .iter()
+            .map(|&i| {
+                Some(StepProof {
+                    step: self.steps.get(i)?.clone(),
+                    path: tree.proof(i)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Evidence that one step is part of a trace with a given root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepProof {
+    pub step: ExecutionStep,
+    pub path: MerkleProof,
+}
+
+impl StepProof {
+    pub fn index(&self) -> usize {
+        self.path.index
+    }
+
+    /// Check the step against an anchored `traceHash`.
+    pub fn verify(&self, trace_hash: &Hash32) -> bool {
+        self.path.verify(&self.step.leaf(self.path.index as u64), trace_hash)
+    }
+}
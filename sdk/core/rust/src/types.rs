@@ -0,0 +1,149 @@
+//! Typed wrappers for the values that make up a proof.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::HexError;
+
+/// Strictly decode a `0x`-prefixed hex string of exactly `N` bytes.
+///
+/// Upper- and lower-case digits are accepted; nothing is padded or truncated.
+pub fn parse_hex_exact<const N: usize>(s: &str) -> Result<[u8; N], HexError> {
+    let digits = s.strip_prefix("0x").ok_or(HexError::BadPrefix)?;
+    if let Some((i, ch)) = digits.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+        return Err(HexError::NonHex { index: i + 2, ch });
+    }
+    if digits.len() % 2 != 0 {
+        return Err(HexError::OddLength(digits.len()));
+    }
+    if digits.len() != N * 2 {
+        return Err(HexError::WrongLength { expected: N, got: digits.len() / 2 });
+    }
+    let mut arr = [0u8; N];
+    hex::decode_to_slice(digits, &mut arr).expect("validated above");
+    Ok(arr)
+}
+
+macro_rules! hex_newtype {
+    ($(#[$meta:meta])* $name:ident, $len:expr) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+        pub struct $name(pub [u8; $len]);
+
+        impl $name {
+            pub const LEN: usize = $len;
+
+            pub fn from_hex(s: &str) -> Result<Self, HexError> {
+                parse_hex_exact::<$len>(s).map(Self)
+            }
+
+            pub fn as_bytes(&self) -> &[u8; $len] {
+                &self.0
+            }
+
+            pub fn to_hex(&self) -> String {
+                format!("0x{}", hex::encode(self.0))
+            }
+        }
+
+        impl From<[u8; $len]> for $name {
+            fn from(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl From<$name> for [u8; $len] {
+            fn from(v: $name) -> Self {
+                v.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = HexError;
+
+            fn from_str(s: &str) -> Result<Self, HexError> {
+                Self::from_hex(s)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.to_hex())
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}({})", stringify!($name), self.to_hex())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.to_hex())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                Self::from_hex(&s).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+hex_newtype!(
+    /// A 32-byte hash (`bytes32` on-chain), rendered as `0x`-prefixed hex.
+    Hash32, 32
+);
+
+hex_newtype!(
+    /// The `bytes32` key the verifier contract stores a proof under.
+    ProofId, 32
+);
+
+hex_newtype!(
+    /// A 20-byte EVM account address.
+    Address, 20
+);
+
+hex_newtype!(
+    /// An agent signing key's ID; see [`crate::receipt::AgentPublicKey::key_id`].
+    KeyId, 32
+);
+
+/// Identifier of the model that produced an inference, e.g. `vericortex-demo`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ModelId(pub String);
+
+impl ModelId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for ModelId {
+    fn from(s: &str) -> Self {
+        Self(s.to_string())
+    }
+}
+
+impl From<String> for ModelId {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl fmt::Display for ModelId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
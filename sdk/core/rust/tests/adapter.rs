@@ -0,0 +1,146 @@
+//! Proof adapters and the registry that dispatches on `proof_type`.
+
+use std::time::Duration;
+
+use k256::ecdsa::SigningKey;
+use serde_json::json;
+use vericortex_sdk::adapter::{Attestation, ExternalVerifier, HashRecompute, SignedAttestation};
+use vericortex_sdk::{recompute_output_hash, AdapterError, AdapterRegistry, HashAlg, Hash32, ProofJson};
+
+fn proof() -> ProofJson {
+    let input_hash = HashAlg::Sha2_256.digest(b"input");
+    let output_hash = recompute_output_hash(&"vericortex-demo".into(), &input_hash, HashAlg::Sha2_256);
+    ProofJson::new("vericortex-demo", input_hash, output_hash)
+}
+
+#[test]
+fn registry_dispatches_on_proof_type() {
+    let registry = AdapterRegistry::default();
+    let mut proof = proof();
+    let verdict = registry.verify(&proof).unwrap();
+    assert_eq!((verdict.proof_type.as_str(), verdict.valid), (HashRecompute::PROOF_TYPE, true));
+    assert_eq!(verdict.details["recomputed_output_hash"], json!(proof.output_hash));
+
+    proof.output_hash = HashAlg::Sha2_256.digest(b"forged");
+    assert!(!registry.verify(&proof).unwrap().valid);
+
+    proof.proof_type = Some("groth16".into());
+    assert_eq!(
+        registry.verify(&proof).unwrap_err(),
+        AdapterError::UnknownType { proof_type: "groth16".into(), registered: "hash-recompute".into() }
+    );
+
+    // Type and evidence survive a round trip from v5 on, and are ignored before.
+    proof.evidence = Some(json!({ "anything": 1 }));
+    let text = proof.to_json_pretty().unwrap();
+    assert_eq!(ProofJson::from_json(&text).unwrap(), proof);
+    let v4 = text.replacen("\"format_version\": 5", "\"format_version\": 4", 1);
+    let old = ProofJson::from_json(&v4).unwrap();
+    assert_eq!((old.proof_type, old.evidence), (None, None));
+}
+
+#[test]
+fn signed_attestation_checks_the_signer() {
+    let attester = SigningKey::from_slice(&[7; 32]).unwrap();
+    let stranger = SigningKey::from_slice(&[9; 32]).unwrap();
+    let mut proof = proof();
+    proof.proof_type = Some(SignedAttestation::PROOF_TYPE.into());
+    let attestation = Attestation::sign(&proof, &attester);
+    proof.evidence = Some(json!(attestation));
+
+    let mut registry = AdapterRegistry::default();
+    registry.register(SignedAttestation::new([attestation.signer]));
+    let verdict = registry.verify(&proof).unwrap();
+    assert!(verdict.valid, "{}", verdict.reason);
+    assert_eq!(verdict.details["signer"], json!(attestation.signer));
+
+    // The signature covers the bundle hash, so changing the claim breaks it.
+    let mut altered = proof.clone();
+    altered.output_hash = HashAlg::Sha2_256.digest(b"forged");
+    assert!(!registry.verify(&altered).unwrap().valid);
+
+    // A recorded proof_hash is not signed: the signature cannot be replayed
+    // over other hashes by pinning the old bundle hash, nor can the anchored
+    // value be swapped.
+    let mut replayed = proof.clone();
+    replayed.proof_hash = Some(proof.bundle_hash());
+    replayed.input_hash = HashAlg::Sha2_256.digest(b"other input");
+    replayed.output_hash = HashAlg::Sha2_256.digest(b"other output");
+    assert!(!registry.verify(&replayed).unwrap().valid);
+    let mut repointed = proof.clone();
+    repointed.proof_hash = Some(Hash32([0x44; 32]));
+    let verdict = registry.verify(&repointed).unwrap();
+    assert!(!verdict.valid);
+    assert!(verdict.reason.contains("does not match"), "{}", verdict.reason);
+    let mut pinned = proof.clone();
+    pinned.proof_hash = Some(proof.bundle_hash());
+    assert!(registry.verify(&pinned).unwrap().valid);
+
+    let other = Attestation::sign(&proof, &stranger);
+    let mut untrusted = proof.clone();
+    untrusted.evidence = Some(json!(other));
+    let verdict = registry.verify(&untrusted).unwrap();
+    assert!(!verdict.valid);
+    assert!(verdict.reason.contains("not a trusted attester"), "{}", verdict.reason);
+
+    let mut impersonated = proof.clone();
+    impersonated.evidence = Some(json!({ "signer": attestation.signer, "signature": other.signature }));
+    assert!(!registry.verify(&impersonated).unwrap().valid);
+
+    // Only 27/28 or 0/1 recover, and only the low-s form of a signature.
+    let signed = hex::decode(&attestation.signature[2..]).unwrap();
+    let with_v = |v: u8| {
+        let mut bytes = signed.clone();
+        bytes[64] = v;
+        bytes
+    };
+    let mut bare = proof.clone();
+    bare.evidence = Some(json!({ "signer": attestation.signer, "signature": format!("0x{}", hex::encode(with_v(signed[64] - 27))) }));
+    assert!(registry.verify(&bare).unwrap().valid);
+    let high_s = {
+        let signature = k256::ecdsa::Signature::from_slice(&signed[..64]).unwrap();
+        let flipped = k256::ecdsa::Signature::from_scalars(signature.r(), -*signature.s()).unwrap();
+        let mut bytes = flipped.to_bytes().to_vec();
+        bytes.push(55 - signed[64]);
+        bytes
+    };
+    let mut rejected = vec![high_s];
+    rejected.extend([2, 3, 29, 30, 35, 255].map(with_v));
+    for bytes in rejected {
+        let mut malleated = proof.clone();
+        malleated.evidence = Some(json!({ "signer": attestation.signer, "signature": format!("0x{}", hex::encode(bytes)) }));
+        assert!(matches!(registry.verify(&malleated), Err(AdapterError::Evidence { .. })));
+    }
+
+    for evidence in [None, Some(json!({ "signer": attestation.signer, "signature": "0x1234" }))] {
+        let mut malformed = proof.clone();
+        malformed.evidence = evidence;
+        assert!(matches!(registry.verify(&malformed), Err(AdapterError::Evidence { .. })));
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn external_verifier_reads_a_verdict_from_stdout() {
+    let sh = |script: &str| ExternalVerifier::new("ezkl", "sh", vec!["-c".into(), script.into()]);
+    let mut proof = proof();
+    proof.proof_type = Some("ezkl".into());
+    proof.evidence = Some(json!({ "proof": "0xabcdef" }));
+
+    let mut registry = AdapterRegistry::default();
+    // The proof arrives on stdin, evidence included.
+    registry.register(sh(
+        r#"if grep -q 0xabcdef; then echo '{"valid": true, "reason": "snark ok", "details": {"constraints": 42}}';
+           else echo '{"valid": false}'; fi"#,
+    ));
+    let verdict = registry.verify(&proof).unwrap();
+    assert_eq!((verdict.proof_type.as_str(), verdict.valid, verdict.reason.as_str()), ("ezkl", true, "snark ok"));
+    assert_eq!(verdict.details["constraints"], 42);
+    proof.evidence = Some(json!({ "proof": "0x00" }));
+    assert!(!registry.verify(&proof).unwrap().valid);
+
+    for broken in [sh("echo not json"), sh("echo oops >&2; exit 3"), sh("sleep 5").timeout(Duration::from_millis(100))] {
+        registry.register(broken);
+        assert!(matches!(registry.verify(&proof), Err(AdapterError::Backend { .. })));
+    }
+}
@@ -0,0 +1,59 @@
+//! Batch roots and per-proof receipts.
+
+use vericortex_sdk::{BatchAnchor, Hash32, ProofBatch, ProofJson, ProofId};
+
+fn proof(model: &str, i: u8) -> ProofJson {
+    let mut p = ProofJson::new(model, Hash32([i; 32]), Hash32([i.wrapping_add(1); 32]));
+    p.model_version = "v1".into();
+    p
+}
+
+#[test]
+fn every_receipt_verifies_against_the_root() {
+    let batch = ProofBatch::new((0..7).map(|i| proof("fraud-v3", i)).collect());
+    let anchor = BatchAnchor { proof_id: ProofId([9; 32]), tx_hash: Hash32([8; 32]), block_number: Some(42) };
+
+    let receipts = batch.receipts(Some(&anchor));
+    assert_eq!(receipts.len(), 7);
+    for (i, receipt) in receipts.iter().enumerate() {
+        assert_eq!(receipt.path.index, i);
+        assert_eq!(receipt.batch_root, batch.root());
+        assert_eq!(receipt.anchor.as_ref(), Some(&anchor));
+        assert!(receipt.verify());
+    }
+}
+
+#[test]
+fn receipt_for_altered_proof_fails() {
+    let batch = ProofBatch::new((0..4).map(|i| proof("fraud-v3", i)).collect());
+    let mut receipt = batch.receipts(None).remove(1);
+    receipt.proof.output_hash = Hash32([0xee; 32]).into();
+    assert!(!receipt.verify());
+}
+
+#[test]
+fn receipt_survives_json_round_trip() {
+    let batch = ProofBatch::new((0..3).map(|i| proof("fraud-v3", i)).collect());
+    let receipt = batch.receipts(None).remove(2);
+    let json = serde_json::to_string(&receipt).unwrap();
+    assert!(!json.contains("anchor"));
+    let back: vericortex_sdk::BatchReceipt = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, receipt);
+    assert!(back.verify());
+}
+
+#[test]
+fn common_model_requires_matching_id_and_version() {
+    let batch = ProofBatch::new(vec![proof("a", 0), proof("a", 1)]);
+    assert_eq!(batch.common_model().map(|(m, v)| (m.as_str(), v)), Some(("a", "v1")));
+
+    let mixed = ProofBatch::new(vec![proof("a", 0), proof("b", 1)]);
+    assert!(mixed.common_model().is_none());
+    assert_eq!(mixed.common_version(), Some("v1"));
+
+    let mut other = proof("a", 1);
+    other.model_version = "v2".into();
+    assert!(ProofBatch::new(vec![proof("a", 0), other]).common_version().is_none());
+
+    assert!(ProofBatch::new(vec![]).common_model().is_none());
+}
@@ -0,0 +1,148 @@
+//! Payload canonicalization: RFC 8785 vectors, tensors and pinned hashes.
+
+use vericortex_sdk::canonical::{canonical_json, format_double, parse_json, DType, Endian, Tensor};
+use vericortex_sdk::{CanonicalError, Hash32, Payload};
+
+fn jcs(text: &str) -> String {
+    canonical_json(&parse_json(text).unwrap()).unwrap()
+}
+
+#[test]
+fn rfc8785_example_and_hash_are_pinned() {
+    let input = r#"{
+        "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+        "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+        "literals": [null, true, false]
+    }"#;
+    let expected = r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#;
+    assert_eq!(jcs(input), expected);
+    assert_eq!(
+        Payload::json(input).unwrap().hash().unwrap(),
+        "0x2d5e01a318d0f0879ab568c4be289c8b1f64ef8921a53c6277d5e069978baacb".parse::<Hash32>().unwrap()
+    );
+}
+
+#[test]
+fn members_sort_by_utf16_code_units() {
+    let input = r#"{"\u20ac":"Euro Sign","\r":"Carriage Return","\ufb33":"Dalet","1":"One","\ud83d\ude00":"Emoji","\u0080":"Control","\u00f6":"o"}"#;
+    assert_eq!(
+        jcs(input),
+        "{\"\\r\":\"Carriage Return\",\"1\":\"One\",\"\u{80}\":\"Control\",\"ö\":\"o\",\"€\":\"Euro Sign\",\"😀\":\"Emoji\",\"\u{fb33}\":\"Dalet\"}"
+    );
+    // Whitespace and member order do not change the hash.
+    let a = Payload::json(r#"{"b": [1, 2.0], "a": {"y": null, "x": "z"}}"#).unwrap();
+    let b = Payload::json(r#"{"a":{"x":"z","y":null},"b":[1,2]}"#).unwrap();
+    assert_eq!(a.hash().unwrap(), b.hash().unwrap());
+}
+
+#[test]
+fn numbers_follow_ecmascript_formatting() {
+    // RFC 8785 Appendix B.
+    let vectors: [(u64, &str); 23] = [
+        (0x0000000000000000, "0"),
+        (0x8000000000000000, "0"),
+        (0x0000000000000001, "5e-324"),
+        (0x8000000000000001, "-5e-324"),
+        (0x7fefffffffffffff, "1.7976931348623157e+308"),
+        (0xffefffffffffffff, "-1.7976931348623157e+308"),
+        (0x4340000000000000, "9007199254740992"),
+        (0xc340000000000000, "-9007199254740992"),
+        (0x4430000000000000, "295147905179352830000"),
+        (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+        (0x44b52d02c7e14af6, "1e+23"),
+        (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+        (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+        (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+        (0x444b1ae4d6e2ef50, "1e+21"),
+        (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+        (0x3eb0c6f7a0b5ed8d, "0.000001"),
+        (0x41b3de4355555553, "333333333.3333332"),
+        (0x41b3de4355555554, "333333333.33333325"),
+        (0x41b3de4355555555, "333333333.3333333"),
+        (0x41b3de4355555556, "333333333.3333334"),
+        (0xbecbf647612f3696, "-0.0000033333333333333333"),
+        (0x43143ff3c1cb0959, "1424953923781206.2"),
+    ];
+    for (bits, expected) in vectors {
+        assert_eq!(format_double(f64::from_bits(bits)), expected, "{bits:#018x}");
+    }
+}
+
+#[test]
+fn ambiguous_json_is_rejected() {
+    assert_eq!(parse_json(r#"{"a": 1, "a": 2}"#), Err(CanonicalError::DuplicateKey("a".into())));
+    assert_eq!(parse_json(r#"[{"k\"ey": 1, "k\"ey": 1}]"#), Err(CanonicalError::DuplicateKey("k\"ey".into())));
+    assert!(matches!(parse_json("[1,]"), Err(CanonicalError::Json(_))));
+    assert!(matches!(parse_json("\"\\ud800\""), Err(CanonicalError::Json(_))));
+
+    // A key that spells out the old error text is just a key.
+    let tricky = r#"{"duplicate object key \"a\"": 1}"#;
+    assert_eq!(jcs(tricky), r#"{"duplicate object key \"a\"":1}"#);
+    assert_eq!(parse_json(r#"{"a": {"b": 1, "b": 1}}"#), Err(CanonicalError::DuplicateKey("b".into())));
+
+    // Integers beyond ±2^53 are rejected whether they fit u64 or only a double.
+    for big in ["9007199254740993", "-9007199254740993", "18446744073709551617", "-100000000000000000000000"] {
+        assert_eq!(parse_json(&format!(r#"{{"n": [1, {big}]}}"#)), Err(CanonicalError::UnsafeInteger(big.into())));
+    }
+    let big = serde_json::json!([9007199254740993u64]);
+    assert_eq!(canonical_json(&big), Err(CanonicalError::UnsafeInteger("9007199254740993".into())));
+    assert_eq!(jcs("[-9007199254740992, 9007199254740992]"), "[-9007199254740992,9007199254740992]");
+    // Float literals keep their double, digits in strings are not numbers.
+    assert_eq!(jcs(r#"[1e30, 1.8446744073709552E19, "18446744073709551617"]"#), r#"[1e+30,18446744073709552000,"18446744073709551617"]"#);
+}
+
+#[test]
+fn tensors_encode_little_endian_with_canonical_nan() {
+    let json = parse_json(r#"[[1.0, -2.0], [0.5, "NaN"]]"#).unwrap();
+    let tensor = Tensor::from_json(DType::F32, &json).unwrap();
+    assert_eq!(tensor.shape(), &[2, 2]);
+    assert_eq!(
+        hex::encode(tensor.encode()),
+        "564354454e534f52010c02000000020000000000000002000000000000000000803f000000c00000003f0000c07f"
+    );
+    let hash = Payload::Tensor(tensor.clone()).hash().unwrap();
+    assert_eq!(hash, "0x0d9b525891514ffa56c0ace0c316857cecd793b0f6e007264ae0f36a457c600d".parse::<Hash32>().unwrap());
+
+    // The same values from a big-endian buffer, with a signalling NaN payload.
+    let be: Vec<u8> = [1.0f32.to_bits(), (-2.0f32).to_bits(), 0.5f32.to_bits(), 0x7f80_0001]
+        .iter()
+        .flat_map(|bits| bits.to_be_bytes())
+        .collect();
+    assert_eq!(Tensor::from_bytes(DType::F32, &[2, 2], &be, Endian::Big).unwrap(), tensor);
+
+    // Shape and dtype are committed to, not just the bytes.
+    let flat = Tensor::from_bytes(DType::F32, &[4], tensor.data(), Endian::Little).unwrap();
+    assert_ne!(Payload::Tensor(flat).hash().unwrap(), hash);
+    let ints = Tensor::from_bytes(DType::I32, &[2, 2], tensor.data(), Endian::Little).unwrap();
+    assert_ne!(Payload::Tensor(ints).hash().unwrap(), hash);
+}
+
+#[test]
+fn malformed_tensors_are_rejected() {
+    assert!(matches!(
+        Tensor::from_bytes(DType::F32, &[3], &[0; 8], Endian::Little),
+        Err(CanonicalError::TensorLength { expected: 12, got: 8, .. })
+    ));
+    assert!(matches!(
+        Tensor::from_bytes(DType::Bool, &[2], &[1, 2], Endian::Little),
+        Err(CanonicalError::TensorElement { index: 1, .. })
+    ));
+    assert!(matches!(
+        Tensor::from_json(DType::I32, &parse_json("[[1, 2], [3]]").unwrap()),
+        Err(CanonicalError::Ragged(_))
+    ));
+    assert!(matches!(
+        Tensor::from_json(DType::U8, &parse_json("[1, 256]").unwrap()),
+        Err(CanonicalError::TensorElement { index: 1, .. })
+    ));
+    assert!(matches!(
+        Tensor::from_json(DType::I64, &parse_json("[1.5]").unwrap()),
+        Err(CanonicalError::TensorElement { index: 0, .. })
+    ));
+    assert_eq!("float16".parse::<DType>(), Ok(DType::F16));
+    assert!("complex64".parse::<DType>().is_err());
+
+    let scalar = Tensor::from_json(DType::I64, &parse_json("7").unwrap()).unwrap();
+    assert!(scalar.shape().is_empty());
+    assert_eq!(scalar.data(), 7i64.to_le_bytes());
+}
@@ -0,0 +1,327 @@
+//! Model artifact fingerprints over hand-built safetensors, GGUF and ONNX
+//! files and plain directories.
+
+use std::path::{Path, PathBuf};
+
+use vericortex_sdk::fingerprint::{fingerprint, Fingerprint, LeafKind};
+use vericortex_sdk::{ArtifactFormat, FingerprintError, HashAlg, Hash32, ProofJson};
+
+/// A directory private to this test.
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vericortex-fingerprint-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn f32_bytes(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn leaf<'a>(fp: &'a Fingerprint, name: &str) -> &'a vericortex_sdk::fingerprint::Leaf {
+    fp.leaves.iter().find(|l| l.name == name).unwrap_or_else(|| panic!("no leaf {name}"))
+}
+
+fn run(path: &Path) -> Fingerprint {
+    fingerprint(path, None, HashAlg::Blake3).unwrap()
+}
+
+// safetensors ---------------------------------------------------------------
+
+fn safetensors(header: &str, data: &[u8]) -> Vec<u8> {
+    let mut out = (header.len() as u64).to_le_bytes().to_vec();
+    out.extend_from_slice(header.as_bytes());
+    out.extend_from_slice(data);
+    out
+}
+
+#[test]
+fn safetensors_commit_to_each_tensor_independent_of_layout() {
+    let dir = scratch("safetensors");
+    let (w, b) = (f32_bytes(&[1.0, 2.0, 3.0, 4.0]), f32_bytes(&[0.5, -0.5]));
+    let a = safetensors(
+        r#"{"__metadata__":{"format":"pt"},"w":{"dtype":"F32","shape":[2,2],"data_offsets":[0,16]},"b":{"dtype":"F32","shape":[2],"data_offsets":[16,24]}}"#,
+        &[w.clone(), b.clone()].concat(),
+    );
+    // The same tensors in the other order, with the header keys shuffled.
+    let b_first = safetensors(
+        r#"{"b":{"shape":[2],"dtype":"F32","data_offsets":[0,8]},"w":{"dtype":"F32","shape":[2,2],"data_offsets":[8,24]},"__metadata__":{"format":"pt"}}"#,
+        &[b.clone(), w.clone()].concat(),
+    );
+    std::fs::write(dir.join("a.safetensors"), &a).unwrap();
+    std::fs::write(dir.join("b.safetensors"), &b_first).unwrap();
+
+    let fp = run(&dir.join("a.safetensors"));
+    assert_eq!(fp.format, ArtifactFormat::Safetensors);
+    let names: Vec<_> = fp.leaves.iter().map(|l| (l.kind, l.name.as_str())).collect();
+    assert_eq!(names, [(LeafKind::Tensor, "b"), (LeafKind::Tensor, "w"), (LeafKind::Metadata, "__metadata__")]);
+    assert_eq!(leaf(&fp, "w").digest, HashAlg::Blake3.digest(&w));
+    assert_eq!(leaf(&fp, "w").shape.as_deref(), Some(&[2, 2][..]));
+    assert_eq!(leaf(&fp, "__metadata__").digest, HashAlg::Blake3.digest(br#"{"format":"pt"}"#));
+    assert_eq!(run(&dir.join("b.safetensors")).root(), fp.root());
+
+    // One changed weight changes one leaf and the root.
+    let mut tampered = a.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    std::fs::write(dir.join("t.safetensors"), &tampered).unwrap();
+    let other = run(&dir.join("t.safetensors"));
+    assert_ne!(other.root(), fp.root());
+    assert_eq!(leaf(&other, "w"), leaf(&fp, "w"));
+    assert_ne!(leaf(&other, "b"), leaf(&fp, "b"));
+
+    // The algorithm is part of the commitment.
+    let sha = fingerprint(&dir.join("a.safetensors"), None, HashAlg::Sha2_256).unwrap();
+    assert_ne!(sha.root(), fp.root());
+    assert!(fp.summary().verify(&dir.join("a.safetensors")).unwrap());
+    assert!(sha.summary().verify(&dir.join("a.safetensors")).unwrap());
+    assert!(!fp.summary().verify(&dir.join("t.safetensors")).unwrap());
+
+    let short = safetensors(r#"{"w":{"dtype":"F32","shape":[2,2],"data_offsets":[0,12]}}"#, &w[..12]);
+    std::fs::write(dir.join("short.safetensors"), short).unwrap();
+    let err = fingerprint(&dir.join("short.safetensors"), None, HashAlg::Blake3).unwrap_err();
+    assert!(matches!(err, FingerprintError::Malformed { .. }) && err.to_string().contains("needs 16 bytes"), "{err}");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+// GGUF ----------------------------------------------------------------------
+
+fn gguf_string(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u64).to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+/// A v3 file holding an F32 `[4]` and a Q8_0 `[32]` tensor.
+fn gguf(name: &str, q8: &[u8; 34]) -> Vec<u8> {
+    let mut out = b"GGUF".to_vec();
+    out.extend_from_slice(&3u32.to_le_bytes());
+    out.extend_from_slice(&2u64.to_le_bytes());
+    out.extend_from_slice(&3u64.to_le_bytes());
+    gguf_string(&mut out, "general.name");
+    out.extend_from_slice(&8u32.to_le_bytes());
+    gguf_string(&mut out, name);
+    gguf_string(&mut out, "general.alignment");
+    out.extend_from_slice(&4u32.to_le_bytes());
+    out.extend_from_slice(&64u32.to_le_bytes());
+    gguf_string(&mut out, "tokenizer.ggml.tokens");
+    out.extend_from_slice(&9u32.to_le_bytes());
+    out.extend_from_slice(&8u32.to_le_bytes());
+    out.extend_from_slice(&2u64.to_le_bytes());
+    gguf_string(&mut out, "<s>");
+    gguf_string(&mut out, "</s>");
+    let metadata_len = out.len();
+
+    for (tensor, ty, offset) in [("embd", 0u32, 0u64), ("blk.0.q", 8, 64)] {
+        gguf_string(&mut out, tensor);
+        out.extend_from_slice(&1u32.to_le_bytes());
+        out.extend_from_slice(&(if ty == 0 { 4u64 } else { 32 }).to_le_bytes());
+        out.extend_from_slice(&ty.to_le_bytes());
+        out.extend_from_slice(&offset.to_le_bytes());
+    }
+    out.resize(out.len().next_multiple_of(64), 0);
+    out.extend_from_slice(&f32_bytes(&[1.0, 2.0, 3.0, 4.0]));
+    out.resize(out.len() + 48, 0);
+    out.extend_from_slice(q8);
+    assert!(metadata_len < 200);
+    out
+}
+
+#[test]
+fn gguf_commits_to_tensors_and_metadata() {
+    let dir = scratch("gguf");
+    let q8: [u8; 34] = std::array::from_fn(|i| i as u8);
+    std::fs::write(dir.join("model.bin"), gguf("demo", &q8)).unwrap();
+
+    // Detected by its magic, whatever the extension.
+    let fp = run(&dir.join("model.bin"));
+    assert_eq!(fp.format, ArtifactFormat::Gguf);
+    assert_eq!(fp.leaves.len(), 3);
+    let q = leaf(&fp, "blk.0.q");
+    assert_eq!((q.dtype.as_deref(), q.size), (Some("Q8_0"), 34));
+    assert_eq!(q.digest, HashAlg::Blake3.digest(&q8));
+    assert_eq!(leaf(&fp, "embd").digest, HashAlg::Blake3.digest(&f32_bytes(&[1.0, 2.0, 3.0, 4.0])));
+
+    // Renaming the model in the metadata touches only the metadata leaf.
+    std::fs::write(dir.join("renamed.gguf"), gguf("omed", &q8)).unwrap();
+    let renamed = run(&dir.join("renamed.gguf"));
+    assert_ne!(renamed.root(), fp.root());
+    assert_ne!(leaf(&renamed, "gguf.metadata"), leaf(&fp, "gguf.metadata"));
+    assert_eq!(leaf(&renamed, "blk.0.q"), q);
+
+    let full = gguf("demo", &q8);
+    std::fs::write(dir.join("cut.gguf"), &full[..full.len() - 1]).unwrap();
+    let err = fingerprint(&dir.join("cut.gguf"), None, HashAlg::Blake3).unwrap_err();
+    assert!(err.to_string().contains("past the end"), "{err}");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn sizes_and_offsets_that_overflow_are_malformed() {
+    let dir = scratch("overflow");
+    let malformed = |name: &str, bytes: &[u8], expected: &str| {
+        std::fs::write(dir.join(name), bytes).unwrap();
+        let err = fingerprint(&dir.join(name), None, HashAlg::Blake3).unwrap_err();
+        assert!(matches!(err, FingerprintError::Malformed { .. }) && err.to_string().contains(expected), "{name}: {err}");
+    };
+
+    let far = format!(r#"{{"w":{{"dtype":"F32","shape":[1],"data_offsets":[0,{}]}}}}"#, u64::MAX);
+    malformed("far.safetensors", &safetensors(&far, &[0; 4]), "outside the data");
+
+    // Almost 2^59 Q8_0 blocks of 34 bytes each.
+    let mut huge = gguf("demo", &[0; 34]);
+    let at = huge.windows(7).position(|w| w == b"blk.0.q").unwrap() + 7 + 4;
+    huge[at..at + 8].copy_from_slice(&(!31u64).to_le_bytes());
+    malformed("huge.gguf", &huge, "overflow the size");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+// ONNX ----------------------------------------------------------------------
+
+fn varint(out: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        out.push(v as u8 | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+fn field(out: &mut Vec<u8>, number: u64, bytes: &[u8]) {
+    varint(out, number << 3 | 2);
+    varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn field_varint(out: &mut Vec<u8>, number: u64, value: u64) {
+    varint(out, number << 3);
+    varint(out, value);
+}
+
+/// `TensorProto` of FLOAT `dims`, with `data` written by `write`.
+fn tensor(name: &str, dims: &[u64], write: impl FnOnce(&mut Vec<u8>)) -> Vec<u8> {
+    let mut t = Vec::new();
+    for &d in dims {
+        field_varint(&mut t, 1, d);
+    }
+    field_varint(&mut t, 2, 1);
+    write(&mut t);
+    field(&mut t, 8, name.as_bytes());
+    t
+}
+
+fn onnx(op: &str, initializers: &[Vec<u8>]) -> Vec<u8> {
+    let mut node = Vec::new();
+    field(&mut node, 1, b"x");
+    field(&mut node, 1, b"w");
+    field(&mut node, 2, b"y");
+    field(&mut node, 4, op.as_bytes());
+    let mut graph = Vec::new();
+    field(&mut graph, 1, &node);
+    field(&mut graph, 2, b"main");
+    for init in initializers {
+        field(&mut graph, 5, init);
+    }
+    let mut model = Vec::new();
+    field_varint(&mut model, 1, 8);
+    field(&mut model, 7, &graph);
+    model
+}
+
+#[test]
+fn onnx_commits_to_initializers_and_the_graph() {
+    let dir = scratch("onnx");
+    let values = f32_bytes(&[1.5, -2.0]);
+    let raw = tensor("w", &[2], |t| field(t, 9, &values));
+    let packed = tensor("w", &[2], |t| field(t, 4, &values));
+    std::fs::write(dir.join("raw.onnx"), onnx("Add", &[raw])).unwrap();
+    std::fs::write(dir.join("packed.onnx"), onnx("Add", &[packed])).unwrap();
+    std::fs::write(dir.join("mul.onnx"), onnx("Mul", &[tensor("w", &[2], |t| field(t, 9, &values))])).unwrap();
+
+    let fp = run(&dir.join("raw.onnx"));
+    assert_eq!(fp.format, ArtifactFormat::Onnx);
+    let w = leaf(&fp, "w");
+    assert_eq!((w.dtype.as_deref(), w.size), (Some("FLOAT"), 8));
+    assert_eq!(w.digest, HashAlg::Blake3.digest(&values));
+    // float_data holds the same values as raw_data.
+    assert_eq!(run(&dir.join("packed.onnx")).root(), fp.root());
+    // A different operator with the same weights changes only the graph leaf.
+    let mul = run(&dir.join("mul.onnx"));
+    assert_eq!(leaf(&mul, "w"), w);
+    assert_ne!(leaf(&mul, "onnx.graph"), leaf(&fp, "onnx.graph"));
+
+    // External data is read from the file next to the model.
+    std::fs::write(dir.join("weights.bin"), [vec![0xff; 4], values.clone()].concat()).unwrap();
+    let external = tensor("w", &[2], |t| {
+        for (key, value) in [("location", "weights.bin"), ("offset", "4"), ("length", "8")] {
+            let mut entry = Vec::new();
+            field(&mut entry, 1, key.as_bytes());
+            field(&mut entry, 2, value.as_bytes());
+            field(t, 13, &entry);
+        }
+        field_varint(t, 14, 1);
+    });
+    std::fs::write(dir.join("external.onnx"), onnx("Add", &[external])).unwrap();
+    assert_eq!(leaf(&run(&dir.join("external.onnx")), "w"), w);
+
+    let escape = tensor("w", &[2], |t| {
+        let mut entry = Vec::new();
+        field(&mut entry, 1, b"location");
+        field(&mut entry, 2, b"../weights.bin");
+        field(t, 13, &entry);
+        field_varint(t, 14, 1);
+    });
+    std::fs::write(dir.join("escape.onnx"), onnx("Add", &[escape])).unwrap();
+    let err = fingerprint(&dir.join("escape.onnx"), None, HashAlg::Blake3).unwrap_err();
+    assert!(err.to_string().contains("leaves the model directory"), "{err}");
+
+    let short = tensor("w", &[3], |t| field(t, 9, &values));
+    std::fs::write(dir.join("short.onnx"), onnx("Add", &[short])).unwrap();
+    let err = fingerprint(&dir.join("short.onnx"), None, HashAlg::Blake3).unwrap_err();
+    assert!(err.to_string().contains("needs 12 bytes"), "{err}");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+// Directories and proofs ----------------------------------------------------
+
+#[test]
+fn directories_commit_to_every_file_by_path() {
+    let dir = scratch("directory");
+    std::fs::create_dir_all(dir.join("tokenizer")).unwrap();
+    std::fs::write(dir.join("config.json"), "{}").unwrap();
+    std::fs::write(dir.join("tokenizer/vocab.txt"), "a\nb\n").unwrap();
+
+    let fp = run(&dir);
+    assert_eq!(fp.format, ArtifactFormat::Directory);
+    let names: Vec<_> = fp.leaves.iter().map(|l| l.name.as_str()).collect();
+    assert_eq!(names, ["config.json", "tokenizer/vocab.txt"]);
+    assert_eq!(leaf(&fp, "tokenizer/vocab.txt").size, 4);
+
+    // Moving a file is a different model even though the bytes are the same.
+    std::fs::rename(dir.join("tokenizer/vocab.txt"), dir.join("vocab.txt")).unwrap();
+    assert_ne!(run(&dir).root(), fp.root());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn proofs_record_the_fingerprint() {
+    let dir = scratch("proof");
+    std::fs::write(dir.join("config.json"), "{}").unwrap();
+    let summary = run(&dir).summary();
+    assert_eq!(summary.leaves, 1);
+
+    let mut proof = ProofJson::new("vericortex-demo", Hash32([1; 32]), Hash32([2; 32]));
+    let without = proof.bundle_hash();
+    proof.model_fingerprint = Some(summary);
+    assert_ne!(proof.bundle_hash(), without, "the anchored bundle hash commits to the fingerprint");
+
+    let json = proof.to_json_pretty().unwrap();
+    assert!(json.contains("\"format\": \"directory\""), "{json}");
+    assert_eq!(ProofJson::from_json(&json).unwrap(), proof);
+    assert!(proof.model_fingerprint.unwrap().verify(&dir).unwrap());
+
+    std::fs::write(dir.join("config.json"), "{\"layers\": 2}").unwrap();
+    assert!(!proof.model_fingerprint.unwrap().verify(&dir).unwrap());
+    assert!(matches!(
+        fingerprint(&dir.join("config.json"), None, HashAlg::Blake3),
+        Err(FingerprintError::UnknownFormat(_))
+    ));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
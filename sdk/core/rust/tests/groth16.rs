@@ -0,0 +1,81 @@
+#![cfg(feature = "groth16")]
+//! The `groth16` adapter against a proof and key generated offline by
+//! `examples/groth16_fixture.rs`.
+
+use ark_bn254::Bn254;
+use ark_groth16::Proof;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde_json::json;
+use vericortex_sdk::adapter::{Groth16Evidence, Groth16Verifier};
+use vericortex_sdk::{AdapterError, AdapterRegistry, HashAlg, ProofJson};
+
+const VK: &[u8] = include_bytes!("fixtures/groth16/vk.bin");
+const PROOF: &str = include_str!("fixtures/groth16/proof.json");
+
+fn registry() -> AdapterRegistry {
+    let mut verifier = Groth16Verifier::new();
+    verifier.add_key("groth16-demo".into(), VK).unwrap();
+    let mut registry = AdapterRegistry::default();
+    registry.register(verifier);
+    registry
+}
+
+fn evidence(proof: &ProofJson) -> Groth16Evidence {
+    serde_json::from_value(proof.evidence.clone().unwrap()).unwrap()
+}
+
+#[test]
+fn offline_proof_verifies_for_its_hashes_only() {
+    let registry = registry();
+    let proof = ProofJson::from_json(PROOF).unwrap();
+    let verdict = registry.verify(&proof).unwrap();
+    assert!(verdict.valid, "{}", verdict.reason);
+    assert_eq!(verdict.proof_type, Groth16Verifier::PROOF_TYPE);
+    assert_eq!(
+        verdict.details["public_inputs"],
+        json!([
+            "0xc96c6d5be8d08a12e7b5cdc1b207fa6b",
+            "0x2430974c86803d8891675e76fd992c20",
+            "0xe0ee8bb50685e05fa0f47ed04203ae95",
+            "0x3fdfd055f5bd2892ea186504254f8c3a",
+        ])
+    );
+
+    let mut altered = proof.clone();
+    altered.output_hash = HashAlg::Sha2_256.digest(b"forged");
+    let verdict = registry.verify(&altered).unwrap();
+    assert!(!verdict.valid);
+    let mut altered = proof.clone();
+    altered.input_hash = HashAlg::Sha2_256.digest(b"other input");
+    assert!(!registry.verify(&altered).unwrap().valid);
+
+    // The uncompressed arkworks encoding of the same proof is accepted too.
+    let compressed = hex::decode(&evidence(&proof).proof[2..]).unwrap();
+    let snark = Proof::<Bn254>::deserialize_compressed(compressed.as_slice()).unwrap();
+    let mut uncompressed = Vec::new();
+    snark.serialize_uncompressed(&mut uncompressed).unwrap();
+    let mut long = proof.clone();
+    long.evidence = Some(json!(Groth16Evidence { proof: format!("0x{}", hex::encode(uncompressed)) }));
+    assert!(registry.verify(&long).unwrap().valid);
+}
+
+#[test]
+fn unusable_keys_and_evidence_are_errors() {
+    let registry = registry();
+    let proof = ProofJson::from_json(PROOF).unwrap();
+
+    let mut flipped = hex::decode(&evidence(&proof).proof[2..]).unwrap();
+    flipped[5] ^= 1;
+    for bad in [None, Some(json!({ "proof": "0xzz" })), Some(json!({ "proof": format!("0x{}", hex::encode(flipped)) }))] {
+        let mut malformed = proof.clone();
+        malformed.evidence = bad;
+        assert!(matches!(registry.verify(&malformed), Err(AdapterError::Evidence { .. })));
+    }
+
+    let mut unknown = proof.clone();
+    unknown.model_id = "other-model".into();
+    assert!(matches!(registry.verify(&unknown), Err(AdapterError::Backend { .. })));
+
+    let mut verifier = Groth16Verifier::new();
+    assert!(verifier.add_key("groth16-demo".into(), &VK[..VK.len() - 1]).is_err());
+}
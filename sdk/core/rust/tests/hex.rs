@@ -0,0 +1,41 @@
+//! Strict fixed-width hex parsing.
+
+use vericortex_sdk::{Address, Hash32, HexError};
+
+const ZERO32: &str = "0x0000000000000000000000000000000000000000000000000000000000000000";
+
+#[test]
+fn accepts_exact_width_in_either_case() {
+    let lower = format!("0x{}", "ab".repeat(32));
+    let upper = format!("0x{}", "AB".repeat(32));
+    assert_eq!(lower.parse::<Hash32>().unwrap(), Hash32([0xab; 32]));
+    assert_eq!(upper.parse::<Hash32>().unwrap(), Hash32([0xab; 32]));
+    assert_eq!(ZERO32.parse::<Hash32>().unwrap().to_hex(), ZERO32);
+}
+
+#[test]
+fn rejects_missing_prefix() {
+    assert_eq!(ZERO32[2..].parse::<Hash32>(), Err(HexError::BadPrefix));
+    assert_eq!(format!("0X{}", &ZERO32[2..]).parse::<Hash32>(), Err(HexError::BadPrefix));
+}
+
+#[test]
+fn rejects_odd_length() {
+    assert_eq!(ZERO32[..65].parse::<Hash32>(), Err(HexError::OddLength(63)));
+}
+
+#[test]
+fn rejects_non_hex() {
+    let bad = format!("0x00g0{}", &ZERO32[6..]);
+    assert_eq!(bad.parse::<Hash32>(), Err(HexError::NonHex { index: 4, ch: 'g' }));
+}
+
+#[test]
+fn rejects_wrong_length_instead_of_padding() {
+    assert_eq!("0xaa".parse::<Hash32>(), Err(HexError::WrongLength { expected: 32, got: 1 }));
+    assert_eq!(
+        format!("{}00", ZERO32).parse::<Hash32>(),
+        Err(HexError::WrongLength { expected: 32, got: 33 })
+    );
+    assert_eq!(ZERO32.parse::<Address>(), Err(HexError::WrongLength { expected: 20, got: 32 }));
+}
@@ -0,0 +1,99 @@
+//! Self-describing digests and algorithm selection when verifying.
+
+use vericortex_sdk::{
+    compute_recomputed_output_hash, DigestError, Error, HashAlg, Hash32, Multihash, Payload, ProofJson,
+};
+
+const LEGACY: &str = r#"{
+  "format_version": 2,
+  "model_id": "vericortex-demo",
+  "input_hash": "0x00000000000000000000000000000000000000000000000000000000000000aa",
+  "output_hash": "0x1111111111111111111111111111111111111111111111111111111111111111"
+}"#;
+
+#[test]
+fn every_algorithm_matches_its_reference_vector() {
+    let vectors = [
+        (HashAlg::Sha2_256, "0x1220ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+        (HashAlg::Sha3_256, "0x16203a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"),
+        (HashAlg::Keccak256, "0x1b204e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"),
+        (HashAlg::Blake3, "0x1e206437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"),
+    ];
+    for (alg, expected) in vectors {
+        let digest = alg.digest(b"abc");
+        assert_eq!(digest.to_hex(), expected, "{alg}");
+        assert_eq!(expected.parse::<Multihash>().unwrap(), digest);
+        assert_eq!(alg.name().parse::<HashAlg>().unwrap(), alg);
+        assert!(digest.matches(b"abc"));
+        assert!(!digest.matches(b"abd"));
+
+        // Streaming in pieces gives the same digest.
+        let mut hasher = alg.hasher();
+        std::io::copy(&mut &b"abc"[..], &mut hasher).unwrap();
+        assert_eq!(hasher.finalize(), digest);
+    }
+}
+
+#[test]
+fn bare_hashes_read_as_sha2_256() {
+    let bare = "0x1111111111111111111111111111111111111111111111111111111111111111";
+    let digest: Multihash = bare.parse().unwrap();
+    assert_eq!(digest.alg, HashAlg::Sha2_256);
+    assert_eq!(digest.digest, bare.parse::<Hash32>().unwrap());
+    assert_eq!(serde_json::to_string(&digest).unwrap(), format!("\"0x1220{}\"", &bare[2..]));
+}
+
+#[test]
+fn malformed_digests_are_rejected() {
+    let digest = |s: &str| s.parse::<Multihash>();
+    let body = "11".repeat(32);
+    assert_eq!(digest(&format!("0x1340{body}{body}")), Err(DigestError::UnknownCode(0x13)));
+    assert!(matches!(digest(&format!("0x1e20{}", &body[2..])), Err(DigestError::WrongLength { got: 31, .. })));
+    assert!(matches!(digest(&format!("0x1e21{body}00")), Err(DigestError::WrongLength { declared: 33, .. })));
+    assert_eq!(digest("0x9e8000"), Err(DigestError::Varint), "non-minimal varint");
+    assert!(matches!(digest("1e20"), Err(DigestError::Hex(_))));
+    assert!(matches!("md5".parse::<HashAlg>(), Err(DigestError::UnknownName(_))));
+}
+
+#[test]
+fn legacy_proofs_keep_verifying_as_sha2_256() {
+    let mut proof = ProofJson::from_json(LEGACY).unwrap();
+    assert_eq!(proof.input_hash.alg, HashAlg::Sha2_256);
+    assert_eq!(
+        proof.recomputed_output_hash().digest,
+        compute_recomputed_output_hash(&proof.model_id, &proof.input_hash.digest)
+    );
+    proof.output_hash = proof.recomputed_output_hash();
+    assert!(proof.output_matches());
+
+    // v2 files never carried self-describing digests.
+    let raw = LEGACY.replace("0x1111", "0x12201111");
+    assert!(matches!(ProofJson::from_json(&raw), Err(Error::Field { field: "output_hash", .. })));
+}
+
+#[test]
+fn verifier_recomputes_with_the_algorithm_the_proof_names() {
+    let input = Payload::json(r#"{"prompt": "hi"}"#).unwrap();
+    for alg in HashAlg::ALL {
+        let input_hash = input.digest(alg).unwrap();
+        let output_hash = vericortex_sdk::recompute_output_hash(&"vericortex-demo".into(), &input_hash, alg);
+        let proof = ProofJson::new("vericortex-demo", input_hash, output_hash);
+
+        let json = proof.to_json_pretty().unwrap();
+        assert!(json.contains(&format!("\"output_hash\": \"0x{:02x}20", alg.code())), "{json}");
+        let loaded = ProofJson::from_json(&json).unwrap();
+        assert_eq!(loaded, proof);
+        assert!(loaded.output_matches(), "{alg}");
+
+        // The same digest under another algorithm's label no longer verifies.
+        let other = if alg == HashAlg::Blake3 { HashAlg::Sha2_256 } else { HashAlg::Blake3 };
+        let relabelled = ProofJson::new("vericortex-demo", input_hash, Multihash::new(other, output_hash.digest));
+        assert!(!relabelled.output_matches(), "{alg}");
+    }
+
+    let raw = LEGACY.replace("\"format_version\": 2", "\"format_version\": 3").replace("0x1111", "0x14201111");
+    assert!(matches!(
+        ProofJson::from_json(&raw),
+        Err(Error::Digest { field: "output_hash", error: DigestError::UnknownCode(0x14) })
+    ));
+}
@@ -0,0 +1,83 @@
+//! Proof envelope loading across format versions.
+
+use vericortex_sdk::{Error, Hash32, HexError, ProofJson, PROOF_FORMAT_VERSION};
+
+const LEGACY: &str = r#"{
+  "model_id": "vericortex-demo",
+  "input_hash": "0x00000000000000000000000000000000000000000000000000000000000000aa",
+  "output_hash": "0x1111111111111111111111111111111111111111111111111111111111111111"
+}"#;
+
+#[test]
+fn legacy_file_migrates_to_current_format() {
+    let proof = ProofJson::from_json(LEGACY).unwrap();
+
+    assert_eq!(proof.format_version, PROOF_FORMAT_VERSION);
+    assert_eq!(proof.model_id.as_str(), "vericortex-demo");
+    assert_eq!(proof.model_version, "");
+    assert_eq!(proof.trace_hash, None);
+    assert_eq!(proof.proof_hash, None);
+    assert_eq!(proof.trace_hash32(), Hash32::default());
+}
+
+#[test]
+fn current_format_round_trips() {
+    let mut proof = ProofJson::from_json(LEGACY).unwrap();
+    proof.model_version = "v2025.08".into();
+    proof.trace_hash = Some(Hash32([0x33; 32]));
+    proof.timestamp = Some(1_765_584_000);
+    proof.submitter = Some("0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266".parse().unwrap());
+
+    let json = proof.to_json_pretty().unwrap();
+    assert!(json.contains("\"format_version\": 5"));
+    assert_eq!(ProofJson::from_json(&json).unwrap(), proof);
+}
+
+#[test]
+fn bundle_hash_prefers_recorded_value() {
+    let mut proof = ProofJson::from_json(LEGACY).unwrap();
+    let derived = proof.bundle_hash();
+    assert_ne!(derived, Hash32::default());
+
+    proof.model_version = "v2".into();
+    assert_ne!(proof.bundle_hash(), derived);
+
+    proof.proof_hash = Some(Hash32([0x44; 32]));
+    assert_eq!(proof.bundle_hash(), Hash32([0x44; 32]));
+}
+
+#[test]
+fn future_format_is_rejected() {
+    let raw = LEGACY.replacen('{', "{\"format_version\": 99,", 1);
+    let err = ProofJson::from_json(&raw).unwrap_err();
+    assert!(matches!(err, Error::UnsupportedFormat(99)), "{err:?}");
+}
+
+#[test]
+fn malformed_hash_fails_at_load_with_field_name() {
+    let raw = LEGACY.replace("0x1111", "0x111");
+    match ProofJson::from_json(&raw).unwrap_err() {
+        Error::Field { field, error } => {
+            assert_eq!(field, "output_hash");
+            assert_eq!(error, HexError::OddLength(63));
+        }
+        err => panic!("unexpected error {err:?}"),
+    }
+
+    // The serde path used when a proof is embedded in another document
+    // rejects the same value.
+    let err = serde_json::from_str::<ProofJson>(&raw).unwrap_err();
+    assert!(err.to_string().contains("invalid output_hash"), "{err}");
+}
+
+#[test]
+fn prover_malformed_fixture_is_rejected() {
+    let raw = include_str!("../../../../prover/tests/malformed_hash_proof.json");
+    match ProofJson::from_json(raw).unwrap_err() {
+        Error::Field { field, error } => {
+            assert_eq!(field, "output_hash");
+            assert_eq!(error, HexError::OddLength(63));
+        }
+        err => panic!("unexpected error {err:?}"),
+    }
+}
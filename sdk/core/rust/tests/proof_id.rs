@@ -0,0 +1,74 @@
+//! Pins proofId derivation against vectors computed independently of this
+//! crate, so an encoding change can never silently diverge from the contracts.
+
+use vericortex_sdk::{compute_proof_id, Address, ContractSchema, Hash32, ModelId, ProofIdPreimage};
+
+fn h32(s: &str) -> Hash32 {
+    s.parse().unwrap()
+}
+
+fn addr(s: &str) -> Address {
+    s.parse().unwrap()
+}
+
+#[test]
+fn vericortex_vector() {
+    let model_id = ModelId::from("vericortex-demo");
+    let input_hash = h32("0x00000000000000000000000000000000000000000000000000000000000000aa");
+    let output_hash = h32("0x1111111111111111111111111111111111111111111111111111111111111111");
+    let preimage = ProofIdPreimage::VeriCortex {
+        model_id: &model_id,
+        input_hash: &input_hash,
+        output_hash: &output_hash,
+    };
+    let submitter = addr("0x00000000000000000000000000000000000000aa");
+
+    assert_eq!(preimage.schema(), ContractSchema::VeriCortex);
+    assert_eq!(preimage.encode_packed(&submitter).len(), 15 + 32 + 32 + 20);
+    assert_eq!(
+        compute_proof_id(&preimage, &submitter).to_hex(),
+        "0x3769d6117d3a855cccb1646daacecff400c55f5b3e84d7e9379dd26d61583ffb"
+    );
+}
+
+#[test]
+fn proofcortex_vector() {
+    let model_id = ModelId::from("llama-3.1-70b");
+    let proof_hash = h32("0x2222222222222222222222222222222222222222222222222222222222222222");
+    let preimage = ProofIdPreimage::ProofCortex {
+        model_id: &model_id,
+        version: "v2025.08",
+        proof_hash: &proof_hash,
+    };
+    let submitter = addr("0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+
+    assert_eq!(preimage.schema(), ContractSchema::ProofCortex);
+    assert_eq!(
+        compute_proof_id(&preimage, &submitter).to_hex(),
+        "0x1b58321c87a6220092e57784566437b5d361973b3b0ee14a06d5ed64afe1e570"
+    );
+}
+
+#[test]
+fn proofcortex_empty_version_and_zero_hashes() {
+    let model_id = ModelId::from("vericortex-demo");
+    let preimage = ProofIdPreimage::ProofCortex {
+        model_id: &model_id,
+        version: "",
+        proof_hash: &Hash32::default(),
+    };
+
+    assert_eq!(
+        compute_proof_id(&preimage, &Address::default()).to_hex(),
+        "0x1d3d40cee92f2d6cc2f19a8265dcef40121acf3fedbcf08e48a9de3411af2ecd"
+    );
+}
+
+#[test]
+fn schema_names_round_trip() {
+    for schema in [ContractSchema::VeriCortex, ContractSchema::ProofCortex] {
+        assert_eq!(schema.as_str().parse::<ContractSchema>().unwrap(), schema);
+    }
+    assert_eq!("legacy".parse::<ContractSchema>().unwrap(), ContractSchema::VeriCortex);
+    assert!("zk".parse::<ContractSchema>().is_err());
+}
@@ -0,0 +1,85 @@
+//! Inference receipts signed by Ed25519 and secp256k1 agent keys.
+
+use serde_json::json;
+use vericortex_sdk::{AgentKey, AgentPublicKey, HashAlg, InferenceReceipt, KeyId, ProofJson, ReceiptError};
+
+fn proof() -> ProofJson {
+    let mut proof = ProofJson::new("vericortex-demo", HashAlg::Sha2_256.digest(b"input"), HashAlg::Sha2_256.digest(b"output"));
+    proof.timestamp = Some(1_700_000_000);
+    proof
+}
+
+fn agents() -> [AgentKey; 2] {
+    [
+        AgentKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(&[7; 32])),
+        AgentKey::Secp256k1(k256::ecdsa::SigningKey::from_slice(&[7; 32]).unwrap()),
+    ]
+}
+
+#[test]
+fn receipts_round_trip_and_verify_for_both_key_types() {
+    for agent in agents() {
+        let receipt = InferenceReceipt::sign(proof(), &agent).unwrap();
+        let text = serde_json::to_string_pretty(&receipt).unwrap();
+        let parsed: InferenceReceipt = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed, receipt);
+        assert_eq!(parsed.verify().unwrap(), agent.key_id());
+        assert_eq!(parsed.verify_trusted(&[agent.key_id()]).unwrap(), agent.key_id());
+
+        // The key ID is SHA-256 of the tagged key, and the tag names the algorithm.
+        let encoded = receipt.public_key.to_string();
+        let tag = match receipt.public_key.algorithm() {
+            "ed25519" => "0xed01",
+            _ => "0xe701",
+        };
+        assert!(encoded.starts_with(tag), "{encoded}");
+        let digest: [u8; 32] = <sha2::Sha256 as sha2::Digest>::digest(hex::decode(&encoded[2..]).unwrap()).into();
+        assert_eq!(receipt.key_id, KeyId(digest));
+        assert_eq!(encoded.parse::<AgentPublicKey>().unwrap(), receipt.public_key);
+    }
+}
+
+#[test]
+fn tampered_or_untrusted_receipts_are_rejected() {
+    let [ed25519, secp256k1] = agents();
+    for agent in [&ed25519, &secp256k1] {
+        let receipt = InferenceReceipt::sign(proof(), agent).unwrap();
+
+        // Every envelope field is signed, evidence included.
+        let mut forged = receipt.clone();
+        forged.proof.output_hash = HashAlg::Sha2_256.digest(b"forged");
+        assert_eq!(forged.verify(), Err(ReceiptError::BadSignature(agent.key_id())));
+        let mut forged = receipt.clone();
+        forged.proof.evidence = Some(json!({ "note": "added later" }));
+        assert_eq!(forged.verify(), Err(ReceiptError::BadSignature(agent.key_id())));
+
+        let mut flipped = receipt.clone();
+        flipped.signature.replace_range(2..4, if &flipped.signature[2..4] == "00" { "01" } else { "00" });
+        assert!(flipped.verify().is_err());
+        let mut short = receipt.clone();
+        short.signature.truncate(20);
+        assert!(matches!(short.verify(), Err(ReceiptError::Signature(_))));
+
+        // The receipt must name the key that signed it.
+        let other = if agent.key_id() == ed25519.key_id() { &secp256k1 } else { &ed25519 };
+        let mut swapped = receipt.clone();
+        swapped.public_key = other.public_key();
+        assert_eq!(swapped.verify(), Err(ReceiptError::KeyIdMismatch { claimed: agent.key_id(), derived: other.key_id() }));
+        swapped.key_id = other.key_id();
+        assert_eq!(swapped.verify(), Err(ReceiptError::BadSignature(other.key_id())));
+
+        assert_eq!(receipt.verify_trusted(&[other.key_id()]), Err(ReceiptError::Untrusted(agent.key_id())));
+    }
+
+    // Evidence JCS cannot represent exactly is refused rather than signed lossily.
+    let mut proof = proof();
+    proof.evidence = Some(json!({ "nonce": 1u64 << 60 }));
+    assert!(InferenceReceipt::sign(proof, &ed25519).is_err());
+}
+
+#[test]
+fn public_keys_must_be_tagged_points() {
+    for bad in ["ed01", "0x1201aa", "0xed0100", "0xe70102", &format!("0xe701{}", "00".repeat(33))] {
+        assert!(bad.parse::<AgentPublicKey>().is_err(), "{bad}");
+    }
+}
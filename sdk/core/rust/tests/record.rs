@@ -0,0 +1,29 @@
+#![cfg(feature = "contract")]
+
+use ethers::types::{Address, U256};
+use vericortex_sdk::contract::{OnChainProof, ProofRecord, TimestampOverflow};
+
+fn record(timestamp: U256) -> ProofRecord {
+    ProofRecord {
+        model_id: "vericortex-demo".into(),
+        version: "v0.1.0".into(),
+        input_hash: [1; 32],
+        output_hash: [2; 32],
+        trace_hash: [0; 32],
+        proof_hash: [3; 32],
+        submitter: Address::repeat_byte(0x11),
+        valid: true,
+        timestamp,
+        locked: false,
+    }
+}
+
+#[test]
+fn timestamps_beyond_u64_are_refused_instead_of_truncated() {
+    let proof = OnChainProof::try_from(record(U256::from(1_700_000_000u64))).unwrap();
+    assert_eq!(proof.timestamp, 1_700_000_000);
+    assert!(proof.exists());
+
+    let beyond = U256::from(u64::MAX) + 1;
+    assert_eq!(OnChainProof::try_from(record(beyond)), Err(TimestampOverflow(beyond)));
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "contract")]
+
+use ethers::abi::{self, Token};
+use vericortex_sdk::contract::VerifierRevert;
+
+fn error_string(reason: &str) -> Vec<u8> {
+    let mut data = vec![0x08, 0xc3, 0x79, 0xa0];
+    data.extend(abi::encode(&[Token::String(reason.into())]));
+    data
+}
+
+#[test]
+fn known_revert_strings_decode_to_variants() {
+    for (reason, expected, kind) in [
+        ("Proof exists", VerifierRevert::ProofExists, "proof_exists"),
+        ("Not authorized", VerifierRevert::NotAuthorized, "not_authorized"),
+        ("Not found", VerifierRevert::NotFound, "not_found"),
+        ("Immutable", VerifierRevert::Immutable, "immutable"),
+    ] {
+        let revert = VerifierRevert::decode(&error_string(reason)).unwrap();
+        assert_eq!(revert, expected);
+        assert_eq!(revert.kind(), kind);
+        assert_eq!(revert.to_string(), reason);
+    }
+}
+
+#[test]
+fn unknown_strings_are_kept_and_other_payloads_rejected() {
+    let revert = VerifierRevert::decode(&error_string("paused")).unwrap();
+    assert_eq!(revert, VerifierRevert::Other("paused".into()));
+    assert_eq!(revert.kind(), "other");
+
+    // Panic(uint256) and truncated data are not revert strings.
+    let panic = [0x4e, 0x48, 0x7b, 0x71].iter().copied().chain(abi::encode(&[Token::Uint(1.into())])).collect::<Vec<_>>();
+    assert_eq!(VerifierRevert::decode(&panic), None);
+    assert_eq!(VerifierRevert::decode(&error_string("Proof exists")[..20]), None);
+    assert_eq!(VerifierRevert::decode(&[]), None);
+}
+
+#[test]
+fn every_require_in_the_contract_has_a_variant() {
+    let source = include_str!("../../../../verifier/BlockDAG_Verifier.sol");
+    let mut reasons: Vec<&str> = source
+        .lines()
+        .filter(|line| line.contains("require("))
+        .map(|line| line.rsplit('"').nth(1).expect("require without a reason string"))
+        .collect();
+    reasons.sort_unstable();
+    reasons.dedup();
+    assert_eq!(reasons, ["Immutable", "Not authorized", "Not found", "Proof exists"]);
+    for reason in reasons {
+        assert_ne!(VerifierRevert::from_reason(reason).kind(), "other", "{reason}");
+    }
+}
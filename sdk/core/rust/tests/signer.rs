@@ -0,0 +1,106 @@
+#![cfg(feature = "signer")]
+
+use std::path::PathBuf;
+
+use ethers::core::rand::thread_rng;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Eip1559TransactionRequest, TransactionRequest, H256};
+use ethers::utils::keccak256;
+use vericortex_sdk::signer::{recovery_id, Passphrase, SignerConfig, SignerError};
+
+const KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vericortex-signer-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn transactions() -> Vec<TypedTransaction> {
+    let to: Address = "0x5fbdb2315678afecb367f032d93f642f64180aa3".parse().unwrap();
+    vec![
+        TransactionRequest::new().to(to).data(vec![1, 2, 3]).nonce(7).gas(60_000).gas_price(5).into(),
+        Eip1559TransactionRequest::new()
+            .to(to)
+            .data(vec![4, 5])
+            .nonce(8)
+            .gas(60_000)
+            .max_fee_per_gas(3_000_000_000u64)
+            .max_priority_fee_per_gas(1_000_000_000u64)
+            .into(),
+    ]
+}
+
+#[tokio::test]
+async fn raw_key_signs_exactly_like_local_wallet() {
+    let wallet: LocalWallet = KEY.parse::<LocalWallet>().unwrap().with_chain_id(1337u64);
+    let signer = SignerConfig::PrivateKey(KEY.into()).connect(1337).await.unwrap();
+    assert_eq!(signer.address(), wallet.address());
+
+    for tx in transactions() {
+        assert_eq!(signer.sign_transaction(&tx).await.unwrap(), wallet.sign_transaction(&tx).await.unwrap());
+    }
+    assert_eq!(signer.sign_message("hello").await.unwrap(), wallet.sign_message("hello").await.unwrap());
+}
+
+#[tokio::test]
+async fn keystore_unlocks_with_a_passphrase_file() {
+    let dir = scratch_dir("keystore");
+    let secret = ethers::utils::hex::decode(KEY.trim_start_matches("0x")).unwrap();
+    let (wallet, _) =
+        LocalWallet::encrypt_keystore(&dir, &mut thread_rng(), secret, "s3cret", Some("key.json")).unwrap();
+    std::fs::write(dir.join("pass"), "s3cret\n").unwrap();
+
+    let config = SignerConfig::Keystore { path: dir.join("key.json"), passphrase: Passphrase::File(dir.join("pass")) };
+    let signer = config.connect(1).await.unwrap();
+    assert_eq!(signer.address(), wallet.address());
+    let signature = signer.sign_message("ping").await.unwrap();
+    assert_eq!(signature.recover("ping").unwrap(), wallet.address());
+
+    let wrong = SignerConfig::Keystore { path: dir.join("key.json"), passphrase: Passphrase::Value("nope".into()) };
+    assert!(matches!(wrong.connect(1).await, Err(SignerError::Keystore { .. })));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn recovery_ids_are_normalized_or_rejected() {
+    for (v, id) in [(0, 0), (1, 1), (27, 0), (28, 1), (1337 * 2 + 35, 0), (1337 * 2 + 36, 1)] {
+        assert_eq!(recovery_id(v, 1337).unwrap(), id, "v = {v}");
+    }
+    // Anything else, including EIP-155 for another chain, would have underflowed or been mislabelled.
+    for v in [2, 26, 29, 34, 35, 38, 1337 * 2 + 37] {
+        assert!(matches!(recovery_id(v, 1337), Err(SignerError::RecoveryId(bad)) if bad == v), "v = {v}");
+    }
+}
+
+#[test]
+fn debug_output_hides_secrets() {
+    let printed = format!(
+        "{:?} {:?}",
+        SignerConfig::PrivateKey(KEY.into()),
+        SignerConfig::Keystore { path: "k.json".into(), passphrase: Passphrase::Value("hunter2".into()) }
+    );
+    assert!(!printed.contains(&KEY[2..]) && !printed.contains("hunter2"), "{printed}");
+}
+
+/// SoftHSM (or any PKCS#11 module) holding a secp256k1 key pair; see the README.
+#[tokio::test]
+#[ignore = "needs SoftHSM; set PKCS11_TEST_*"]
+async fn pkcs11_token_signs_transactions() {
+    let var = |name: &str| std::env::var(name).unwrap_or_else(|_| panic!("{name} not set"));
+    let config = SignerConfig::Pkcs11 {
+        module: var("PKCS11_TEST_MODULE").into(),
+        token_label: var("PKCS11_TEST_TOKEN"),
+        key_label: var("PKCS11_TEST_KEY"),
+        pin: Passphrase::Value(var("PKCS11_TEST_PIN")),
+    };
+    let signer = config.connect(1337).await.unwrap();
+    for tx in transactions() {
+        let signature = signer.sign_transaction(&tx).await.unwrap();
+        let mut tx = tx.clone();
+        tx.set_chain_id(1337u64);
+        assert_eq!(signature.recover(H256(keccak256(tx.rlp()))).unwrap(), signer.address());
+    }
+}
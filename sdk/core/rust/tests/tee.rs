@@ -0,0 +1,268 @@
+#![cfg(feature = "tee")]
+//! The `tee-attestation` adapter against quotes recorded by
+//! `examples/tee_fixture.rs` under test roots, and the bundled vendor roots
+//! against quotes and CRLs recorded on real hardware (`fixtures/tee/recorded`).
+
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use vericortex_sdk::adapter::{Platform, TeeEvidence, TeeVerifier, AMD_ARKS, INTEL_SGX_ROOT_CA};
+use vericortex_sdk::{AdapterError, AdapterRegistry, Hash32, HashAlg, ProofJson};
+use x509_cert::der::{Decode, DecodePem, Encode, EncodePem};
+
+const TEST_SGX_ROOT: &str = include_str!("fixtures/tee/test_sgx_root.pem");
+const TEST_ARK: &str = include_str!("fixtures/tee/test_ark.pem");
+const TEST_SGX_CRLS: &str = include_str!("fixtures/tee/test_sgx_crls.pem");
+const TEST_SGX_PCK_REVOKED: &str = include_str!("fixtures/tee/test_sgx_pck_revoked.pem");
+const TEST_ARK_CRL: &str = include_str!("fixtures/tee/test_ark_crl.pem");
+const INTEL_ROOT_CRL: &[u8] = include_bytes!("fixtures/tee/recorded/intel_root_ca.crl");
+const INTEL_PCK_CRLS: [&[u8]; 2] = [
+    include_bytes!("fixtures/tee/recorded/intel_pck_processor_ca.crl"),
+    include_bytes!("fixtures/tee/recorded/intel_pck_platform_ca.crl"),
+];
+/// An SGX quote and a TDX quote from production Intel platforms.
+const INTEL_SGX_QUOTE: &[u8] = include_bytes!("fixtures/tee/recorded/intel_sgx_quote.bin");
+const INTEL_TDX_QUOTE: &[u8] = include_bytes!("fixtures/tee/recorded/intel_tdx_quote.bin");
+/// An SEV-SNP report from a Milan host, with the VCEK that signed it.
+const AMD_MILAN_REPORT: &str = include_str!("fixtures/tee/recorded/amd_milan_report.hex");
+const AMD_MILAN_VCEK: &[u8] = include_bytes!("fixtures/tee/recorded/amd_milan_vcek.der");
+const AMD_MILAN_ASK: &str = include_str!("fixtures/tee/recorded/amd_milan_ask.pem");
+
+fn fixture(name: &str) -> ProofJson {
+    let path = format!("{}/tests/fixtures/tee/{name}.json", env!("CARGO_MANIFEST_DIR"));
+    ProofJson::from_json(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn evidence(proof: &ProofJson) -> TeeEvidence {
+    serde_json::from_value(proof.evidence.clone().unwrap()).unwrap()
+}
+
+fn with_evidence(proof: &ProofJson, evidence: TeeEvidence) -> ProofJson {
+    let mut proof = proof.clone();
+    proof.evidence = Some(json!(evidence));
+    proof
+}
+
+/// `proof` with one byte of its quote flipped.
+fn tampered(proof: &ProofJson, at: usize) -> ProofJson {
+    let mut evidence = evidence(proof);
+    let mut quote = hex::decode(&evidence.quote[2..]).unwrap();
+    quote[at] ^= 1;
+    evidence.quote = format!("0x{}", hex::encode(quote));
+    with_evidence(proof, evidence)
+}
+
+/// Trusting both test roots and their CRLs, with every fixture measurement
+/// allowlisted for `tee-demo` and the TCB left unevaluated.
+fn test_verifier() -> TeeVerifier {
+    let mut tee = TeeVerifier::new();
+    tee.add_roots(TEST_SGX_ROOT.as_bytes()).unwrap().add_roots(TEST_ARK.as_bytes()).unwrap();
+    tee.add_crls(TEST_SGX_CRLS.as_bytes()).unwrap().add_crls(TEST_ARK_CRL.as_bytes()).unwrap();
+    tee.allow_measurement("tee-demo", &[0x11; 32])
+        .allow_measurement("tee-demo", &[0x22; 48])
+        .allow_measurement("tee-demo", &[0x33; 48])
+        .accept_unevaluated_tcb();
+    tee
+}
+
+fn registered(tee: TeeVerifier) -> AdapterRegistry {
+    let mut registry = AdapterRegistry::default();
+    registry.register(tee);
+    registry
+}
+
+fn reason(registry: &AdapterRegistry, proof: &ProofJson) -> String {
+    let verdict = registry.verify(proof).unwrap();
+    assert!(!verdict.valid);
+    verdict.reason
+}
+
+#[test]
+fn recorded_quotes_verify_on_every_platform() {
+    let mut tee = test_verifier();
+    tee.accept_unknown_revocation();
+    let registry = registered(tee);
+    for (name, measurement, revocation) in
+        [("sgx", "11".repeat(32), "checked"), ("tdx", "22".repeat(48), "checked"), ("sev-snp", "33".repeat(48), "unknown")]
+    {
+        let proof = fixture(name);
+        let verdict = registry.verify(&proof).unwrap();
+        assert!(verdict.valid, "{name}: {}", verdict.reason);
+        assert_eq!(verdict.details["platform"], name);
+        assert_eq!(verdict.details["measurement"], format!("0x{measurement}"));
+        assert_eq!(verdict.details["report_data"].as_str().unwrap()[2..66], hex::encode(TeeVerifier::report_data(&proof)));
+        assert_eq!(verdict.details["revocation"], revocation, "{name}");
+        assert_eq!(verdict.details["tcb"], "not-evaluated");
+    }
+}
+
+#[test]
+fn quotes_must_commit_to_the_bundle_chain_to_a_root_and_be_allowlisted_for_the_model() {
+    let registry = registered(test_verifier());
+
+    for name in ["sgx", "tdx", "sev-snp"] {
+        let mut altered = fixture(name);
+        altered.output_hash = HashAlg::Sha2_256.digest(b"forged");
+        assert!(reason(&registry, &altered).contains("does not commit"), "{name}: {}", reason(&registry, &altered));
+        // The model is part of the bundle, so a quote cannot be moved to another.
+        let mut moved = fixture(name);
+        moved.model_id = "other-model".into();
+        assert!(reason(&registry, &moved).contains("does not commit"), "{name}");
+    }
+    let mut repointed = fixture("sgx");
+    repointed.proof_hash = Some(Hash32([0xab; 32]));
+    assert!(reason(&registry, &repointed).contains("proof_hash"));
+
+    // Flip a bit of MRENCLAVE (after the 48-byte header) and of the SNP measurement.
+    assert!(reason(&registry, &tampered(&fixture("sgx"), 48 + 64)).contains("quote signature"));
+    assert!(reason(&registry, &tampered(&fixture("sev-snp"), 0x90)).contains("report signature"));
+
+    let mut intel_only = TeeVerifier::new();
+    intel_only.add_roots(TEST_SGX_ROOT.as_bytes()).unwrap();
+    intel_only.allow_measurement("tee-demo", &[0x33; 48]).accept_unevaluated_tcb().accept_unknown_revocation();
+    assert!(reason(&registered(intel_only), &fixture("sev-snp")).contains("does not chain to a trusted root"));
+
+    // Measurements are allowlisted per model.
+    let mut other_model = TeeVerifier::new();
+    other_model.add_roots(TEST_SGX_ROOT.as_bytes()).unwrap().add_crls(TEST_SGX_CRLS.as_bytes()).unwrap();
+    other_model.allow_measurement("other-model", &[0x22; 48]).accept_unevaluated_tcb();
+    assert!(reason(&registered(other_model), &fixture("tdx")).contains("is not allowlisted for model tee-demo"));
+}
+
+#[test]
+fn unchecked_revocation_and_tcb_fail_closed() {
+    // No CRL covers the VCEK, as AMD publishes none.
+    assert!(reason(&registered(test_verifier()), &fixture("sev-snp")).contains("revocation unchecked"));
+
+    let mut no_crls = TeeVerifier::new();
+    no_crls.add_roots(TEST_SGX_ROOT.as_bytes()).unwrap();
+    no_crls.allow_measurement("tee-demo", &[0x11; 32]).accept_unevaluated_tcb();
+    assert!(reason(&registered(no_crls), &fixture("sgx")).contains("revocation unchecked"));
+
+    let mut revoked = test_verifier();
+    revoked.add_crls(TEST_SGX_PCK_REVOKED.as_bytes()).unwrap().accept_unknown_revocation();
+    let revoked = registered(revoked);
+    for name in ["sgx", "tdx"] {
+        let why = reason(&revoked, &fixture(name));
+        assert!(why.contains("Test SGX PCK Certificate") && why.contains("is revoked"), "{why}");
+    }
+
+    let mut tcb_unaccepted = TeeVerifier::new();
+    tcb_unaccepted.add_roots(TEST_SGX_ROOT.as_bytes()).unwrap().add_crls(TEST_SGX_CRLS.as_bytes()).unwrap();
+    tcb_unaccepted.allow_measurement("tee-demo", &[0x11; 32]);
+    assert!(reason(&registered(tcb_unaccepted), &fixture("sgx")).contains("TCB level is not evaluated"));
+}
+
+#[test]
+fn the_bundled_roots_are_the_vendors_own() {
+    let fingerprint = |pem: &str| {
+        let root = x509_cert::Certificate::from_pem(pem).unwrap();
+        hex::encode(Sha256::digest(root.to_der().unwrap()))
+    };
+    assert_eq!(fingerprint(INTEL_SGX_ROOT_CA), "44a0196b2b99f889b8e149e95b807a350e7424964399e885a7cbb8ccfab674d3");
+    assert_eq!(
+        AMD_ARKS.map(fingerprint),
+        [
+            "69d063b45344d26a2e94e1f4210de49ef555308287d4c174445c95639a540bcd",
+            "4c6598d19c18719c5dfd4a7d335f674e5bfe1d8f800cea2cf270c10d103db2f1",
+            "1f084161a44bb6d93778a904877d4819cafa5d05ef4193b2ded9dd9c73dd3f6a",
+        ]
+    );
+
+    // Intel's root CRL verifies under the bundled root; a tampered copy does not.
+    TeeVerifier::new().add_crls(INTEL_ROOT_CRL).unwrap();
+    let mut forged = INTEL_ROOT_CRL.to_vec();
+    *forged.last_mut().unwrap() ^= 1;
+    assert!(matches!(TeeVerifier::new().add_crls(&forged), Err(AdapterError::Backend { .. })));
+}
+
+/// The bundled roots with Intel's recorded CRLs. Those CRLs have since passed
+/// their nextUpdate, and no verifier evaluates TCB, so both are accepted.
+fn real_verifier() -> TeeVerifier {
+    let mut tee = TeeVerifier::new();
+    tee.add_crls(INTEL_ROOT_CRL).unwrap();
+    for crl in INTEL_PCK_CRLS {
+        tee.add_crls(crl).unwrap();
+    }
+    tee.accept_unknown_revocation().accept_unevaluated_tcb();
+    tee
+}
+
+fn real_evidence(platform: Platform, quote: &[u8]) -> TeeEvidence {
+    let cert_chain = (platform == Platform::SevSnp).then(|| {
+        let vcek = x509_cert::Certificate::from_der(AMD_MILAN_VCEK).unwrap();
+        format!("{}\n{AMD_MILAN_ASK}", vcek.to_pem(Default::default()).unwrap().trim_end())
+    });
+    TeeEvidence { platform, quote: format!("0x{}", hex::encode(quote)), cert_chain }
+}
+
+#[test]
+fn quotes_recorded_on_real_hardware_verify_under_the_bundled_roots() {
+    let tee = real_verifier();
+    let milan = hex::decode(AMD_MILAN_REPORT.trim()).unwrap();
+    for (platform, quote, root) in [
+        (Platform::Sgx, INTEL_SGX_QUOTE, "Intel SGX Root CA"),
+        (Platform::Tdx, INTEL_TDX_QUOTE, "Intel SGX Root CA"),
+        (Platform::SevSnp, &milan[..], "ARK-Milan"),
+    ] {
+        let verdict = tee.verify_quote(&real_evidence(platform, quote)).unwrap();
+        assert!(verdict.valid, "{platform:?}: {}", verdict.reason);
+        assert!(verdict.details["root"].as_str().unwrap().contains(root), "{platform:?}");
+        assert_eq!(verdict.details["revocation"], "unknown");
+        assert_eq!(verdict.details["tcb"], "not-evaluated");
+
+        // Any flipped byte in the signed body breaks the signature.
+        let mut tampered = quote.to_vec();
+        tampered[0x40] ^= 1;
+        match tee.verify_quote(&real_evidence(platform, &tampered)) {
+            Ok(verdict) => assert!(!verdict.valid, "{platform:?}"),
+            Err(e) => assert!(matches!(e, AdapterError::Evidence { .. }), "{platform:?}: {e}"),
+        }
+    }
+
+    let measurement = |platform, quote| tee.verify_quote(&real_evidence(platform, quote)).unwrap().details["measurement"].clone();
+    assert_eq!(
+        measurement(Platform::Sgx, INTEL_SGX_QUOTE),
+        "0x33d8736db756ed4997e04ba358d27833188f1932ff7b1d156904d3f560452fbb"
+    );
+    assert_eq!(
+        measurement(Platform::Tdx, INTEL_TDX_QUOTE),
+        "0x91eb2b44d141d4ece09f0c75c2c53d247a3c68edd7fafe8a3520c942a604a407de03ae6dc5f87f27428b2538873118b7"
+    );
+
+    // Without accepting them, stale CRLs and unevaluated TCB fail closed.
+    let mut strict = TeeVerifier::new();
+    strict.add_crls(INTEL_ROOT_CRL).unwrap();
+    let verdict = strict.verify_quote(&real_evidence(Platform::Tdx, INTEL_TDX_QUOTE)).unwrap();
+    assert!(!verdict.valid && verdict.reason.contains("revocation unchecked"), "{}", verdict.reason);
+    strict.accept_unknown_revocation();
+    let verdict = strict.verify_quote(&real_evidence(Platform::Tdx, INTEL_TDX_QUOTE)).unwrap();
+    assert!(!verdict.valid && verdict.reason.contains("TCB level is not evaluated"), "{}", verdict.reason);
+}
+
+#[test]
+fn unreadable_evidence_roots_and_crls_are_errors() {
+    let registry = registered(test_verifier());
+    let sgx = fixture("sgx");
+    let snp = fixture("sev-snp");
+
+    let mut truncated = evidence(&sgx);
+    truncated.quote.truncate(400);
+    let mut mislabelled = evidence(&sgx);
+    mislabelled.platform = Platform::Tdx;
+    let mut unchained = evidence(&snp);
+    unchained.cert_chain = None;
+    for bad in [truncated, mislabelled, unchained] {
+        let proof = with_evidence(&sgx, bad);
+        assert!(matches!(registry.verify(&proof), Err(AdapterError::Evidence { .. })), "{:?}", evidence(&proof).platform);
+    }
+    let mut missing = sgx.clone();
+    missing.evidence = None;
+    assert!(matches!(registry.verify(&missing), Err(AdapterError::Evidence { .. })));
+
+    // The VCEK is not a self-signed CA, so it cannot be a root.
+    let vcek = evidence(&snp).cert_chain.unwrap();
+    assert!(TeeVerifier::new().add_roots(vcek.as_bytes()).is_err());
+    assert!(TeeVerifier::new().add_roots(b"not pem").is_err());
+    assert!(TeeVerifier::new().add_crls(b"not a CRL").is_err());
+    assert!(TeeVerifier::new().add_crls(TEST_SGX_ROOT.as_bytes()).is_err());
+}
@@ -0,0 +1,84 @@
+//! Execution trace roots and single-step inclusion proofs.
+
+use vericortex_sdk::merkle::MerkleTree;
+use vericortex_sdk::{ExecutionStep, Hash32, Trace};
+
+fn layer(name: &str, byte: u8) -> ExecutionStep {
+    ExecutionStep::LayerOutput { layer: name.into(), output_hash: Hash32([byte; 32]) }
+}
+
+fn sample_trace(len: usize) -> Trace {
+    let mut steps: Vec<ExecutionStep> = (0..len).map(|i| layer(&format!("layer.{i}"), i as u8)).collect();
+    if len > 2 {
+        steps[2] = ExecutionStep::ToolCall {
+            tool: "search".into(),
+            input_hash: Hash32([0xaa; 32]),
+            output_hash: Hash32([0xbb; 32]),
+        };
+    }
+    Trace::new(steps)
+}
+
+#[test]
+fn every_step_verifies_for_odd_and_even_sizes() {
+    for len in 1..=9 {
+        let trace = sample_trace(len);
+        let root = trace.root();
+        let indices: Vec<usize> = (0..len).collect();
+        for proof in trace.prove(&indices).unwrap() {
+            assert!(proof.verify(&root), "len {len} step {}", proof.index());
+        }
+    }
+}
+
+#[test]
+fn tampered_step_or_wrong_root_fails() {
+    let trace = sample_trace(5);
+    let root = trace.root();
+    let mut proof = trace.prove(&[3]).unwrap().remove(0);
+
+    assert!(!proof.verify(&Hash32([1; 32])));
+
+    proof.step = layer("layer.3", 0xff);
+    assert!(!proof.verify(&root));
+}
+
+#[test]
+fn step_order_is_committed() {
+    let trace = sample_trace(4);
+    let mut swapped = trace.clone();
+    swapped.steps.swap(0, 1);
+    assert_ne!(trace.root(), swapped.root());
+
+    // A proof for step 0 cannot be replayed at position 1.
+    let mut proof = trace.prove(&[0]).unwrap().remove(0);
+    proof.path.index = 1;
+    assert!(!proof.verify(&trace.root()));
+}
+
+#[test]
+fn empty_trace_has_zero_root_and_no_proofs() {
+    let trace = Trace::default();
+    assert_eq!(trace.root(), Hash32::default());
+    assert!(trace.prove(&[0]).is_none());
+}
+
+#[test]
+fn proof_with_extra_siblings_is_rejected() {
+    let leaves: Vec<Hash32> = (0..3u8).map(|i| Hash32([i; 32])).collect();
+    let tree = MerkleTree::from_leaves(&leaves);
+    let mut proof = tree.proof(2).unwrap();
+    assert!(proof.verify(&leaves[2], &tree.root()));
+
+    proof.siblings.push(Hash32::default());
+    assert!(!proof.verify(&leaves[2], &tree.root()));
+}
+
+#[test]
+fn steps_serialize_with_kind_tag() {
+    let json = serde_json::to_value(sample_trace(3)).unwrap();
+    assert_eq!(json[0]["kind"], "layer_output");
+    assert_eq!(json[2]["kind"], "tool_call");
+    let back: Trace = serde_json::from_value(json).unwrap();
+    assert_eq!(back, sample_trace(3));
+}
@@ -0,0 +1,155 @@
+//! Gas and fee selection for submission transactions, and the spending
+//! limits that stop a runaway job from draining the submitter wallet.
+
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::utils::{format_ether, parse_ether};
+use std::time::Duration;
+use vericortex_config::{Config, ConfigError};
+
+/// Headroom added on top of `eth_estimateGas` for the gas limit.
+const GAS_MARGIN_PERCENT: u64 = 20;
+
+/// Gas limit for an estimate, with [`GAS_MARGIN_PERCENT`] headroom.
+pub fn gas_limit(estimate: U256) -> U256 {
+    estimate * (100 + GAS_MARGIN_PERCENT) / 100
+}
+
+/// Gas price parameters of one transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fees {
+    /// Chains without a base fee.
+    Legacy { gas_price: U256 },
+    Eip1559 { max_fee_per_gas: U256, max_priority_fee_per_gas: U256 },
+}
+
+impl Fees {
+    /// The most one unit of gas can cost.
+    pub fn max_fee_per_gas(&self) -> U256 {
+        match *self {
+            Fees::Legacy { gas_price } => gas_price,
+            Fees::Eip1559 { max_fee_per_gas, .. } => max_fee_per_gas,
+        }
+    }
+
+    pub fn priority_fee(&self) -> Option<U256> {
+        match *self {
+            Fees::Legacy { .. } => None,
+            Fees::Eip1559 { max_priority_fee_per_gas, .. } => Some(max_priority_fee_per_gas),
+        }
+    }
+
+    /// Fees for a replacement: every component at least `percent` above
+    /// `self` (nodes refuse smaller bumps) and never below `market`.
+    pub fn bumped(&self, percent: u64, market: &Fees) -> Fees {
+        let bump = |v: U256| v * (100 + percent) / 100 + 1;
+        match *self {
+            Fees::Legacy { gas_price } => {
+                Fees::Legacy { gas_price: bump(gas_price).max(market.max_fee_per_gas()) }
+            }
+            Fees::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => {
+                let tip = bump(max_priority_fee_per_gas).max(market.priority_fee().unwrap_or_default());
+                Fees::Eip1559 {
+                    max_fee_per_gas: bump(max_fee_per_gas).max(market.max_fee_per_gas()).max(tip),
+                    max_priority_fee_per_gas: tip,
+                }
+            }
+        }
+    }
+
+    /// A call of `data` on `to` paying these fees.
+    pub fn request(&self, from: Address, to: Address, data: Bytes, nonce: u64) -> TypedTransaction {
+        match *self {
+            Fees::Legacy { gas_price } => {
+                TransactionRequest::new().from(from).to(to).data(data).nonce(nonce).gas_price(gas_price).into()
+            }
+            Fees::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => Eip1559TransactionRequest::new()
+                .from(from)
+                .to(to)
+                .data(data)
+                .nonce(nonce)
+                .max_fee_per_gas(max_fee_per_gas)
+                .max_priority_fee_per_gas(max_priority_fee_per_gas)
+                .into(),
+        }
+    }
+}
+
+/// Current fees: EIP-1559 when the latest block carries a base fee
+/// (`2 * next base fee + eth_maxPriorityFeePerGas`), else `eth_gasPrice`.
+pub async fn market_fees(provider: &Provider<Http>) -> Result<Fees> {
+    let latest = provider.get_block(BlockNumber::Latest).await.context("eth_getBlockByNumber failed")?;
+    if latest.and_then(|b| b.base_fee_per_gas).is_none() {
+        let gas_price = provider.get_gas_price().await.context("eth_gasPrice failed")?;
+        return Ok(Fees::Legacy { gas_price });
+    }
+    let history = provider.fee_history(1u64, BlockNumber::Latest, &[]).await.context("eth_feeHistory failed")?;
+    let next_base_fee = history.base_fee_per_gas.last().copied().unwrap_or_default();
+    let tip: U256 = provider
+        .request("eth_maxPriorityFeePerGas", ())
+        .await
+        .context("eth_maxPriorityFeePerGas failed")?;
+    Ok(Fees::Eip1559 { max_fee_per_gas: next_base_fee * 2 + tip, max_priority_fee_per_gas: tip })
+}
+
+/// Spending limits and replacement policy, from the `[prover]` settings.
+#[derive(Clone, Debug)]
+pub struct FeePolicy {
+    /// Largest worst-case cost (`gas limit * max fee per gas`) of one tx.
+    pub max_fee_per_tx: Option<U256>,
+    /// Largest total spent by this submitter over any 24 hours.
+    pub daily_budget: Option<U256>,
+    /// How long a tx may sit in the mempool before it is replaced with higher fees.
+    pub bump_after: Duration,
+    pub bump_percent: u64,
+}
+
+impl FeePolicy {
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        let prover = &config.prover;
+        // An amount in native token units (e.g. `0.05`), as wei.
+        let ether = |key: &str, amount: &Option<String>| {
+            amount
+                .as_deref()
+                .map(|v| {
+                    parse_ether(v).map_err(|e| ConfigError::Invalid {
+                        profile: config.profile.clone(),
+                        problems: vec![format!("prover.{key} {v:?}: {e}")],
+                    })
+                })
+                .transpose()
+        };
+        Ok(Self {
+            max_fee_per_tx: ether("max_fee_per_tx", &prover.max_fee_per_tx)?,
+            daily_budget: ether("daily_fee_budget", &prover.daily_fee_budget)?,
+            bump_after: Duration::from_secs(prover.fee_bump_secs),
+            bump_percent: prover.fee_bump_percent,
+        })
+    }
+
+    /// Why a tx costing at most `cost` may not be sent when `spent` has
+    /// already gone out in the last 24 hours, if it may not.
+    pub fn refusal(&self, cost: U256, spent: U256) -> Option<String> {
+        if let Some(cap) = self.max_fee_per_tx {
+            if cost > cap {
+                return Some(format!(
+                    "tx may cost up to {} which exceeds MAX_FEE_PER_TX {}",
+                    format_ether(cost),
+                    format_ether(cap)
+                ));
+            }
+        }
+        if let Some(budget) = self.daily_budget {
+            if spent + cost > budget {
+                return Some(format!(
+                    "daily fee budget exhausted: {} spent in the last 24h, tx may cost up to {}, DAILY_FEE_BUDGET {}",
+                    format_ether(spent),
+                    format_ether(cost),
+                    format_ether(budget)
+                ));
+            }
+        }
+        None
+    }
+}
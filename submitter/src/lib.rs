@@ -0,0 +1,18 @@
+//! VeriCortex transaction submission.
+//!
+//! Every transaction to the verifier contract goes through the [`Outbox`]:
+//! it is queued, signed and recorded before broadcast, then followed until
+//! it has `confirmations` blocks. A process killed at any point picks the
+//! entry up again on its next run. Fees follow [`fees`]: EIP-1559 where the
+//! chain has a base fee, a gas limit estimated per call, fee bumps for
+//! transactions stuck in the mempool, and the per-tx and daily spending caps.
+//! The prover and the API both submit through this crate, so one submitter
+//! wallet is held to the same limits on either path.
+
+pub mod fees;
+pub mod outbox;
+mod sender;
+
+pub use fees::{FeePolicy, Fees};
+pub use outbox::{Outbox, OutboxEntry, TxState};
+pub use sender::{revert_of, ChainConfig, Client, Sender};
@@ -0,0 +1,430 @@
+//! Durable record of every submission transaction.
+//!
+//! Each proof moves through `queued` (calldata known, nothing signed) →
+//! `sent` (signed; nonce, hash and raw bytes written *before* broadcast) →
+//! `mined` → `confirmed`, or ends in `failed`. Because the signed bytes are
+//! stored ahead of the broadcast, a restarted process can always rebroadcast
+//! the exact transaction it may already have sent instead of signing a
+//! second one, and entries are keyed by proof so the same proof is never
+//! queued twice.
+//!
+//! Every signed transaction of an entry, fee-bumped replacements included,
+//! is kept as an [`Attempt`]; the daily fee budget is counted from them.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result};
+use ethers::types::{Address, Bytes, H256, U256};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use vericortex_sdk::{ModelId, ProofId};
+
+use crate::fees::Fees;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS outbox (
+    id           INTEGER PRIMARY KEY,
+    chain_id     INTEGER NOT NULL,
+    contract     TEXT NOT NULL,
+    submitter    TEXT NOT NULL,
+    proof_id     TEXT NOT NULL,
+    model_id     TEXT NOT NULL,
+    calldata     BLOB NOT NULL,
+    state        TEXT NOT NULL,
+    nonce        INTEGER,
+    tx_hash      TEXT,
+    raw_tx       BLOB,
+    block_number INTEGER,
+    error        TEXT,
+    updated_at   INTEGER NOT NULL,
+    UNIQUE (chain_id, contract, submitter, proof_id)
+);
+CREATE TABLE IF NOT EXISTS attempts (
+    tx_hash      TEXT PRIMARY KEY,
+    entry_id     INTEGER NOT NULL REFERENCES outbox (id),
+    nonce        INTEGER NOT NULL,
+    gas_limit    TEXT NOT NULL,
+    max_fee      TEXT NOT NULL,
+    priority_fee TEXT,
+    raw_tx       BLOB NOT NULL,
+    sent_at      INTEGER NOT NULL,
+    paid         TEXT
+);
+CREATE INDEX IF NOT EXISTS attempts_by_entry ON attempts (entry_id, nonce);
+";
+
+const COLUMNS: &str = "id, chain_id, contract, submitter, proof_id, model_id, calldata, state, nonce, tx_hash, raw_tx, \
+                       block_number, error, updated_at";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TxState {
+    Queued,
+    Sent,
+    Mined,
+    Confirmed,
+    Failed,
+}
+
+impl TxState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TxState::Queued => "queued",
+            TxState::Sent => "sent",
+            TxState::Mined => "mined",
+            TxState::Confirmed => "confirmed",
+            TxState::Failed => "failed",
+        }
+    }
+}
+
+impl std::fmt::Display for TxState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TxState {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "queued" => TxState::Queued,
+            "sent" => TxState::Sent,
+            "mined" => TxState::Mined,
+            "confirmed" => TxState::Confirmed,
+            "failed" => TxState::Failed,
+            other => bail!("unknown outbox state {other:?}"),
+        })
+    }
+}
+
+/// What a new submission needs before anything is signed.
+pub struct NewEntry<'a> {
+    pub chain_id: u64,
+    pub contract: Address,
+    pub submitter: vericortex_sdk::Address,
+    pub proof_id: ProofId,
+    pub model_id: &'a ModelId,
+    pub calldata: Bytes,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct OutboxEntry {
+    pub id: i64,
+    pub chain_id: u64,
+    pub contract: Address,
+    pub submitter: vericortex_sdk::Address,
+    pub proof_id: ProofId,
+    pub model_id: ModelId,
+    #[serde(skip)]
+    pub calldata: Bytes,
+    pub state: TxState,
+    pub nonce: Option<u64>,
+    pub tx_hash: Option<H256>,
+    #[serde(skip)]
+    pub raw_tx: Option<Bytes>,
+    pub block_number: Option<u64>,
+    pub error: Option<String>,
+    /// Unix seconds of the last state change.
+    pub updated_at: u64,
+}
+
+fn parse_col<T: FromStr>(row: &Row<'_>, idx: usize) -> rusqlite::Result<T>
+where
+    T::Err: std::fmt::Display,
+{
+    let s: String = row.get(idx)?;
+    s.parse().map_err(|e: T::Err| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, e.to_string().into())
+    })
+}
+
+fn entry_row(row: &Row<'_>) -> rusqlite::Result<OutboxEntry> {
+    let tx_hash: Option<String> = row.get(9)?;
+    let raw_tx: Option<Vec<u8>> = row.get(10)?;
+    Ok(OutboxEntry {
+        id: row.get(0)?,
+        chain_id: row.get(1)?,
+        contract: parse_col(row, 2)?,
+        submitter: parse_col(row, 3)?,
+        proof_id: parse_col(row, 4)?,
+        model_id: ModelId(row.get(5)?),
+        calldata: Bytes::from(row.get::<_, Vec<u8>>(6)?),
+        state: parse_col(row, 7)?,
+        nonce: row.get(8)?,
+        tx_hash: match tx_hash {
+            Some(_) => Some(parse_col(row, 9)?),
+            None => None,
+        },
+        raw_tx: raw_tx.map(Bytes::from),
+        block_number: row.get(11)?,
+        error: row.get(12)?,
+        updated_at: row.get(13)?,
+    })
+}
+
+/// One signed transaction for an entry. Fee bumps add attempts with the
+/// same nonce; whichever is mined settles the entry.
+#[derive(Clone, Debug)]
+pub struct Attempt {
+    pub tx_hash: H256,
+    pub nonce: u64,
+    pub gas_limit: U256,
+    pub fees: Fees,
+    pub raw_tx: Bytes,
+    /// Unix seconds.
+    pub sent_at: u64,
+}
+
+fn parse_u256(row: &Row<'_>, idx: usize) -> rusqlite::Result<U256> {
+    let s: String = row.get(idx)?;
+    U256::from_dec_str(&s).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, e.to_string().into())
+    })
+}
+
+fn attempt_row(row: &Row<'_>) -> rusqlite::Result<Attempt> {
+    let max_fee = parse_u256(row, 3)?;
+    let priority_fee: Option<String> = row.get(4)?;
+    Ok(Attempt {
+        tx_hash: parse_col(row, 0)?,
+        nonce: row.get(1)?,
+        gas_limit: parse_u256(row, 2)?,
+        fees: match priority_fee {
+            Some(_) => Fees::Eip1559 { max_fee_per_gas: max_fee, max_priority_fee_per_gas: parse_u256(row, 4)? },
+            None => Fees::Legacy { gas_price: max_fee },
+        },
+        raw_tx: Bytes::from(row.get::<_, Vec<u8>>(5)?),
+        sent_at: row.get(6)?,
+    })
+}
+
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+pub struct Outbox {
+    conn: Connection,
+}
+
+impl Outbox {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let conn = Connection::open(path).with_context(|| format!("Failed to open outbox {}", path.display()))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "FULL")?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Queue a submission, or return the entry already recorded for the same
+    /// proof. A failed entry is reset to `queued` so it can be retried.
+    pub fn enqueue(&mut self, new: &NewEntry<'_>) -> Result<OutboxEntry> {
+        let tx = self.conn.transaction()?;
+        let key = params![
+            new.chain_id,
+            format!("{:?}", new.contract),
+            new.submitter.to_string(),
+            new.proof_id.to_string()
+        ];
+        let existing = tx
+            .query_row(
+                &format!(
+                    "SELECT {COLUMNS} FROM outbox
+                     WHERE chain_id = ?1 AND contract = ?2 AND submitter = ?3 AND proof_id = ?4"
+                ),
+                key,
+                entry_row,
+            )
+            .optional()?;
+        let id = match existing {
+            Some(entry) if entry.state != TxState::Failed => return Ok(entry),
+            Some(entry) => {
+                tx.execute(
+                    "UPDATE outbox SET state = 'queued', calldata = ?2, nonce = NULL, tx_hash = NULL, raw_tx = NULL,
+                     block_number = NULL, error = NULL, updated_at = ?3 WHERE id = ?1",
+                    params![entry.id, new.calldata.to_vec(), now()],
+                )?;
+                entry.id
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO outbox (chain_id, contract, submitter, proof_id, model_id, calldata, state, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'queued', ?7)",
+                    params![
+                        new.chain_id,
+                        format!("{:?}", new.contract),
+                        new.submitter.to_string(),
+                        new.proof_id.to_string(),
+                        new.model_id.as_str(),
+                        new.calldata.to_vec(),
+                        now()
+                    ],
+                )?;
+                tx.last_insert_rowid()
+            }
+        };
+        tx.commit()?;
+        self.entry(id)
+    }
+
+    pub fn entry(&self, id: i64) -> Result<OutboxEntry> {
+        self.conn
+            .query_row(&format!("SELECT {COLUMNS} FROM outbox WHERE id = ?1"), [id], entry_row)
+            .with_context(|| format!("outbox entry {id} not found"))
+    }
+
+    /// Every entry, oldest first.
+    pub fn list(&self) -> Result<Vec<OutboxEntry>> {
+        let mut stmt = self.conn.prepare(&format!("SELECT {COLUMNS} FROM outbox ORDER BY id"))?;
+        let rows = stmt.query_map([], entry_row)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Entries of `submitter` on one contract that are not confirmed or failed, oldest first.
+    pub fn unfinished(
+        &self,
+        chain_id: u64,
+        contract: Address,
+        submitter: &vericortex_sdk::Address,
+    ) -> Result<Vec<OutboxEntry>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {COLUMNS} FROM outbox
+             WHERE chain_id = ?1 AND contract = ?2 AND submitter = ?3 AND state IN ('queued', 'sent', 'mined')
+             ORDER BY id"
+        ))?;
+        let rows = stmt.query_map(params![chain_id, format!("{contract:?}"), submitter.to_string()], entry_row)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Highest nonce held by a sent or mined transaction of `submitter`.
+    pub fn max_nonce(&self, chain_id: u64, submitter: &vericortex_sdk::Address) -> Result<Option<u64>> {
+        Ok(self.conn.query_row(
+            "SELECT MAX(nonce) FROM outbox WHERE chain_id = ?1 AND submitter = ?2 AND state IN ('sent', 'mined')",
+            params![chain_id, submitter.to_string()],
+            |row| row.get(0),
+        )?)
+    }
+
+    fn update(&self, id: i64, sql: &str, values: &[&dyn rusqlite::ToSql]) -> Result<OutboxEntry> {
+        let mut all: Vec<&dyn rusqlite::ToSql> = vec![&id];
+        let updated_at = now();
+        all.push(&updated_at);
+        all.extend_from_slice(values);
+        self.conn.execute(&format!("UPDATE outbox SET updated_at = ?2, {sql} WHERE id = ?1"), all.as_slice())?;
+        self.entry(id)
+    }
+
+    /// Record a signed transaction (first send or fee-bumped replacement) as
+    /// the entry's current one. Must be called before it is broadcast.
+    pub fn record_sent(&self, id: i64, attempt: &Attempt) -> Result<OutboxEntry> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO attempts (tx_hash, entry_id, nonce, gas_limit, max_fee, priority_fee, raw_tx, sent_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                format!("{:?}", attempt.tx_hash),
+                id,
+                attempt.nonce,
+                attempt.gas_limit.to_string(),
+                attempt.fees.max_fee_per_gas().to_string(),
+                attempt.fees.priority_fee().map(|f| f.to_string()),
+                attempt.raw_tx.to_vec(),
+                attempt.sent_at
+            ],
+        )?;
+        tx.execute(
+            "UPDATE outbox SET state = 'sent', nonce = ?2, tx_hash = ?3, raw_tx = ?4, block_number = NULL,
+             updated_at = ?5 WHERE id = ?1",
+            params![id, attempt.nonce, format!("{:?}", attempt.tx_hash), attempt.raw_tx.to_vec(), now()],
+        )?;
+        tx.commit()?;
+        self.entry(id)
+    }
+
+    /// Forget an attempt the node refused outright; the previous attempt, if
+    /// any, becomes current again.
+    pub fn discard_attempt(&self, id: i64, tx_hash: H256) -> Result<OutboxEntry> {
+        self.conn.execute("DELETE FROM attempts WHERE tx_hash = ?1", [format!("{tx_hash:?}")])?;
+        match self.attempts(id)?.pop() {
+            Some(prev) => self.update(
+                id,
+                "tx_hash = ?3, raw_tx = ?4",
+                &[&format!("{:?}", prev.tx_hash), &prev.raw_tx.to_vec()],
+            ),
+            None => self.entry(id),
+        }
+    }
+
+    /// Signed transactions for the entry's current nonce, oldest first.
+    pub fn attempts(&self, id: i64) -> Result<Vec<Attempt>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.tx_hash, a.nonce, a.gas_limit, a.max_fee, a.priority_fee, a.raw_tx, a.sent_at
+             FROM attempts a JOIN outbox o ON o.id = a.entry_id AND o.nonce = a.nonce
+             WHERE a.entry_id = ?1 ORDER BY a.rowid",
+        )?;
+        let rows = stmt.query_map([id], attempt_row)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Fees spent by `submitter` on transactions first sent at or after
+    /// `since`: what was paid where one attempt per nonce was mined,
+    /// otherwise the worst case of the pending attempts.
+    pub fn spent_since(&self, chain_id: u64, submitter: &vericortex_sdk::Address, since: u64) -> Result<U256> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.entry_id, a.nonce, a.gas_limit, a.max_fee, a.paid FROM attempts a
+             JOIN outbox o ON o.id = a.entry_id
+             WHERE o.chain_id = ?1 AND o.submitter = ?2 AND a.sent_at >= ?3",
+        )?;
+        let mut per_nonce: HashMap<(i64, u64), (U256, Option<U256>)> = HashMap::new();
+        let rows = stmt.query_map(params![chain_id, submitter.to_string(), since], |row| {
+            let paid: Option<String> = row.get(4)?;
+            Ok((
+                (row.get::<_, i64>(0)?, row.get::<_, u64>(1)?),
+                parse_u256(row, 2)? * parse_u256(row, 3)?,
+                match paid {
+                    Some(_) => Some(parse_u256(row, 4)?),
+                    None => None,
+                },
+            ))
+        })?;
+        for row in rows {
+            let (key, worst, paid) = row?;
+            let slot = per_nonce.entry(key).or_default();
+            slot.0 = slot.0.max(worst);
+            slot.1 = slot.1.or(paid);
+        }
+        Ok(per_nonce.into_values().fold(U256::zero(), |sum, (worst, paid)| sum + paid.unwrap_or(worst)))
+    }
+
+    /// `tx_hash`, one of the entry's attempts, was mined and cost `paid`.
+    pub fn record_mined(&self, id: i64, block_number: u64, tx_hash: H256, paid: U256) -> Result<OutboxEntry> {
+        let hash = format!("{tx_hash:?}");
+        self.conn.execute("UPDATE attempts SET paid = ?2 WHERE tx_hash = ?1", params![hash, paid.to_string()])?;
+        self.update(
+            id,
+            "state = 'mined', block_number = ?3, tx_hash = ?4,
+             raw_tx = COALESCE((SELECT raw_tx FROM attempts WHERE tx_hash = ?4), raw_tx)",
+            &[&block_number, &hash],
+        )
+    }
+
+    /// The mined transaction was reorged out: it is pending (or lost) again.
+    pub fn record_unmined(&self, id: i64) -> Result<OutboxEntry> {
+        self.conn.execute("UPDATE attempts SET paid = NULL WHERE entry_id = ?1", [id])?;
+        self.update(id, "state = 'sent', block_number = NULL", &[])
+    }
+
+    pub fn record_confirmed(&self, id: i64) -> Result<OutboxEntry> {
+        self.update(id, "state = 'confirmed'", &[])
+    }
+
+    pub fn record_failed(&self, id: i64, error: &str) -> Result<OutboxEntry> {
+        self.update(id, "state = 'failed', error = ?3", &[&error])
+    }
+}
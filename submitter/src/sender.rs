@@ -0,0 +1,379 @@
+//! Drives outbox entries from `queued` to `confirmed` or `failed`.
+
+use anyhow::{anyhow, bail, Context, Result};
+use ethers::prelude::*;
+use ethers::providers::RpcError;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::utils::keccak256;
+use log::{info, warn};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use vericortex_config::{Config, ConfigError};
+use vericortex_sdk::contract::VerifierRevert;
+use vericortex_sdk::signer::{SignerConfig, SubmitterSigner};
+use vericortex_sdk::{ModelId, ProofId};
+
+use crate::fees::{self, FeePolicy, Fees};
+use crate::outbox::{self, Attempt, NewEntry, Outbox, OutboxEntry, TxState};
+
+/// How often pending transactions are polled.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Window of the daily fee budget, in seconds.
+const DAY: u64 = 24 * 60 * 60;
+
+pub type Client = SignerMiddleware<Provider<Http>, SubmitterSigner>;
+
+/// RPC endpoint, signer backend and contract address, from the shared configuration.
+#[derive(Clone, Debug)]
+pub struct ChainConfig {
+    pub rpc_url: String,
+    pub signer: SignerConfig,
+    pub contract_address: Address,
+    pub chain_id: u64,
+    /// SQLite outbox file (`prover.outbox`).
+    pub outbox: PathBuf,
+    /// Blocks, counting the inclusion block, before a tx is confirmed.
+    pub confirmations: u64,
+    /// How long one run waits on an unconfirmed tx before leaving it to `outbox resume`.
+    pub tx_timeout: Duration,
+    pub fees: FeePolicy,
+}
+
+impl ChainConfig {
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        let network = config.network()?;
+        Ok(Self {
+            rpc_url: network.rpc_url,
+            signer: config.signer()?,
+            contract_address: Address::from(network.verifier_contract.0),
+            chain_id: network.chain_id,
+            outbox: config.prover.outbox.clone(),
+            confirmations: network.confirmations.unwrap_or(1).max(1),
+            tx_timeout: Duration::from_secs(config.prover.tx_timeout_secs),
+            fees: FeePolicy::from_config(config)?,
+        })
+    }
+
+    /// Connect the signer to the endpoint, checking it serves `chain_id`.
+    pub async fn client(&self) -> Result<Arc<Client>> {
+        let provider = Provider::<Http>::try_from(self.rpc_url.as_str())?.interval(POLL_INTERVAL);
+        let chain_id = provider.get_chainid().await.context("eth_chainId failed")?;
+        if chain_id != U256::from(self.chain_id) {
+            bail!("{} serves chain {chain_id} but the configured chain_id is {}", self.rpc_url, self.chain_id);
+        }
+        let signer = self.signer.connect(self.chain_id).await.context("Signer unavailable")?;
+        info!("Signing as {:?} with the {} signer", signer.address(), self.signer.backend());
+        Ok(Arc::new(SignerMiddleware::new(provider, signer)))
+    }
+}
+
+/// The verifier's revert reason carried by a failed `eth_call` / `eth_estimateGas`.
+pub fn revert_of(e: &ProviderError) -> Option<VerifierRevert> {
+    RpcError::as_error_response(e).and_then(|resp| resp.as_revert_data()).and_then(|data| VerifierRevert::decode(&data))
+}
+
+/// What a mined tx cost its sender.
+fn paid(receipt: &TransactionReceipt) -> U256 {
+    receipt.gas_used.unwrap_or_default() * receipt.effective_gas_price.unwrap_or_default()
+}
+
+/// Node errors meaning the transaction (or one with its nonce) is already
+/// known, so the broadcast may well have happened before.
+fn already_broadcast(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    message.contains("already known") || message.contains("nonce too low")
+}
+
+/// A signer on one contract, driving outbox entries to a final state.
+///
+/// Methods take `&mut self`: the outbox connection is not `Sync`, and one
+/// sender must not price two transactions of the same wallet at once.
+pub struct Sender {
+    cfg: ChainConfig,
+    client: Arc<Client>,
+    submitter: vericortex_sdk::Address,
+    outbox: Outbox,
+    /// Stuck txs whose replacement the fee limits refused, warned about once.
+    bump_refused: HashSet<H256>,
+}
+
+impl Sender {
+    pub async fn connect(cfg: ChainConfig) -> Result<Self> {
+        let client = cfg.client().await?;
+        Ok(Self {
+            submitter: vericortex_sdk::Address(client.address().0),
+            outbox: Outbox::open(&cfg.outbox)?,
+            cfg,
+            client,
+            bump_refused: HashSet::new(),
+        })
+    }
+
+    pub fn config(&self) -> &ChainConfig {
+        &self.cfg
+    }
+
+    pub fn client(&self) -> &Arc<Client> {
+        &self.client
+    }
+
+    pub fn submitter(&self) -> vericortex_sdk::Address {
+        self.submitter
+    }
+
+    /// Queue `calldata` for `proof_id` on the contract, or pick up the entry
+    /// already queued for it, and drive it until it is confirmed. A failed
+    /// entry is an error.
+    pub async fn submit(&mut self, proof_id: ProofId, model_id: &ModelId, calldata: Bytes) -> Result<OutboxEntry> {
+        let entry = self.outbox.enqueue(&NewEntry {
+            chain_id: self.cfg.chain_id,
+            contract: self.cfg.contract_address,
+            submitter: self.submitter,
+            proof_id,
+            model_id,
+            calldata,
+        })?;
+        if entry.state != TxState::Queued {
+            info!("Proof {} already in the outbox as entry {} ({})", proof_id, entry.id, entry.state);
+        }
+        let entry = self.drive(entry).await?;
+        if entry.state == TxState::Failed {
+            bail!("submit tx failed: {}", entry.error.unwrap_or_default());
+        }
+        Ok(entry)
+    }
+
+    /// Drive every unfinished entry of this signer and contract, oldest first.
+    /// Failures are logged and leave the entry for the next run.
+    pub async fn resume(&mut self) -> Result<Vec<OutboxEntry>> {
+        let mut out = Vec::new();
+        for entry in self.outbox.unfinished(self.cfg.chain_id, self.cfg.contract_address, &self.submitter)? {
+            info!("Resuming outbox entry {} ({})", entry.id, entry.state);
+            let id = entry.id;
+            match self.drive(entry).await {
+                Ok(entry) => out.push(entry),
+                Err(e) => {
+                    warn!("Outbox entry {} not resolved: {:#}", id, e);
+                    out.push(self.outbox.entry(id)?);
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Drive `entry` until it is confirmed or failed, or `tx_timeout` passes.
+    async fn drive(&mut self, mut entry: OutboxEntry) -> Result<OutboxEntry> {
+        let deadline = Instant::now() + self.cfg.tx_timeout;
+        loop {
+            let next = match entry.state {
+                TxState::Confirmed | TxState::Failed => return Ok(entry),
+                TxState::Queued => Some(self.send(&entry).await?),
+                TxState::Sent => self.check_sent(&entry).await?,
+                TxState::Mined => self.check_mined(&entry).await?,
+            };
+            match next {
+                Some(next) => entry = next,
+                None if Instant::now() >= deadline => bail!(
+                    "tx {:?} still {} after {}s; run `outbox resume` later",
+                    entry.tx_hash.unwrap_or_default(),
+                    entry.state,
+                    self.cfg.tx_timeout.as_secs()
+                ),
+                None => tokio::time::sleep(POLL_INTERVAL).await,
+            }
+        }
+    }
+
+    /// Fees spent by this submitter over the last 24 hours.
+    fn spent_today(&self) -> Result<U256> {
+        self.outbox.spent_since(self.cfg.chain_id, &self.submitter, outbox::now().saturating_sub(DAY))
+    }
+
+    /// Price, estimate and sign the queued call within the fee limits, record
+    /// it as sent, then broadcast it.
+    async fn send(&mut self, entry: &OutboxEntry) -> Result<OutboxEntry> {
+        let from = self.client.address();
+        let pending = self.client.inner().get_transaction_count(from, Some(BlockNumber::Pending.into())).await?;
+        // Outbox transactions the node has lost still hold their nonces.
+        let nonce = match self.outbox.max_nonce(self.cfg.chain_id, &self.submitter)? {
+            Some(n) => pending.as_u64().max(n + 1),
+            None => pending.as_u64(),
+        };
+
+        // Nothing is signed or broadcast until the checks pass, so failing
+        // the entry here is safe; submitting the proof again retries it.
+        let fees = fees::market_fees(self.client.inner()).await?;
+        let tx = fees.request(from, self.cfg.contract_address, entry.calldata.clone(), nonce);
+        let gas_limit = match self.client.inner().estimate_gas(&tx, None).await {
+            Ok(estimate) => fees::gas_limit(estimate),
+            Err(e) => {
+                self.outbox.record_failed(entry.id, &e.to_string())?;
+                return Err(match revert_of(&e) {
+                    Some(revert) => anyhow!(revert),
+                    None => anyhow!(e).context("submit tx failed"),
+                });
+            }
+        };
+        let spent = self.spent_today()?;
+        if let Some(reason) = self.cfg.fees.refusal(gas_limit * fees.max_fee_per_gas(), spent) {
+            self.outbox.record_failed(entry.id, &reason)?;
+            bail!("submit refused: {reason}");
+        }
+
+        let (entry, accepted) = self.sign_and_broadcast(entry, tx, nonce, gas_limit, fees).await?;
+        match accepted {
+            Ok(()) => Ok(entry),
+            Err(reason) => {
+                let entry = self.outbox.record_failed(entry.id, &reason)?;
+                bail!("submit tx failed: {reason} (outbox entry {})", entry.id)
+            }
+        }
+    }
+
+    /// Sign `tx`, record the attempt, then broadcast it. A node refusal is
+    /// returned as `Err(reason)` with the attempt already discarded.
+    async fn sign_and_broadcast(
+        &mut self,
+        entry: &OutboxEntry,
+        mut tx: TypedTransaction,
+        nonce: u64,
+        gas_limit: U256,
+        fees: Fees,
+    ) -> Result<(OutboxEntry, std::result::Result<(), String>)> {
+        tx.set_gas(gas_limit);
+        tx.set_chain_id(self.cfg.chain_id);
+        let signature = self.client.signer().sign_transaction(&tx).await.context("signing failed")?;
+        let raw = tx.rlp_signed(&signature);
+        let tx_hash = H256(keccak256(&raw));
+        let attempt = Attempt { tx_hash, nonce, gas_limit, fees, raw_tx: raw.clone(), sent_at: outbox::now() };
+        let entry = self.outbox.record_sent(entry.id, &attempt)?;
+        info!("Submitting tx {:?} with nonce {} ({:?})", tx_hash, nonce, fees);
+
+        match self.client.inner().send_raw_transaction(raw).await {
+            Ok(_) => Ok((entry, Ok(()))),
+            Err(e) => match RpcError::as_error_response(&e) {
+                Some(resp) if !already_broadcast(&resp.message) => {
+                    // The node refused it outright, so it is not in any mempool.
+                    let entry = self.outbox.discard_attempt(entry.id, tx_hash)?;
+                    Ok((entry, Err(e.to_string())))
+                }
+                _ => {
+                    warn!("Broadcast of {:?} not acknowledged ({}); will reconcile", tx_hash, e);
+                    Ok((entry, Ok(())))
+                }
+            },
+        }
+    }
+
+    /// Replace the newest attempt with one paying `bump_percent` more, once
+    /// it has waited `bump_after` in the mempool and the fee limits allow.
+    async fn bump(&mut self, entry: &OutboxEntry, latest: &Attempt) -> Result<Option<OutboxEntry>> {
+        let (bump_after, bump_percent) = (self.cfg.fees.bump_after, self.cfg.fees.bump_percent);
+        if outbox::now() < latest.sent_at + bump_after.as_secs() {
+            return Ok(None);
+        }
+        let fees = latest.fees.bumped(bump_percent, &fees::market_fees(self.client.inner()).await?);
+        // The replacement takes over the reservation of the tx it replaces.
+        let spent = self.spent_today()?.saturating_sub(latest.gas_limit * latest.fees.max_fee_per_gas());
+        if let Some(reason) = self.cfg.fees.refusal(latest.gas_limit * fees.max_fee_per_gas(), spent) {
+            if self.bump_refused.insert(latest.tx_hash) {
+                warn!("Not replacing stuck tx {:?}: {}", latest.tx_hash, reason);
+            }
+            return Ok(None);
+        }
+
+        info!("Tx {:?} stuck for {}s; replacing it with higher fees", latest.tx_hash, bump_after.as_secs());
+        let tx = fees.request(self.client.address(), self.cfg.contract_address, entry.calldata.clone(), latest.nonce);
+        let (entry, accepted) = self.sign_and_broadcast(entry, tx, latest.nonce, latest.gas_limit, fees).await?;
+        if let Err(reason) = accepted {
+            warn!("Replacement for {:?} refused: {}", latest.tx_hash, reason);
+        }
+        Ok(Some(entry))
+    }
+
+    /// The receipt of whichever attempt was mined, if any.
+    async fn mined_attempt(&mut self, attempts: &[Attempt]) -> Result<Option<TransactionReceipt>> {
+        for attempt in attempts.iter().rev() {
+            if let Some(receipt) = self.client.inner().get_transaction_receipt(attempt.tx_hash).await? {
+                if receipt.block_number.is_some() {
+                    return Ok(Some(receipt));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// A sent tx is mined, still pending (and maybe due a fee bump), lost
+    /// (rebroadcast) or superseded.
+    async fn check_sent(&mut self, entry: &OutboxEntry) -> Result<Option<OutboxEntry>> {
+        let tx_hash = entry.tx_hash.context("sent entry without tx hash")?;
+        let attempts = self.outbox.attempts(entry.id)?;
+        if let Some(receipt) = self.mined_attempt(&attempts).await? {
+            let block = receipt.block_number.unwrap_or_default().as_u64();
+            info!("Tx {:?} mined in block {}", receipt.transaction_hash, block);
+            let entry = self.outbox.record_mined(entry.id, block, receipt.transaction_hash, paid(&receipt))?;
+            if receipt.status != Some(1.into()) {
+                let reason = format!("submitProof reverted in tx {:?}", receipt.transaction_hash);
+                return Ok(Some(self.outbox.record_failed(entry.id, &reason)?));
+            }
+            return Ok(Some(entry));
+        }
+        if self.client.inner().get_transaction(tx_hash).await?.is_some() {
+            return match attempts.last() {
+                Some(latest) => self.bump(entry, latest).await,
+                None => Ok(None),
+            };
+        }
+
+        let nonce = entry.nonce.context("sent entry without nonce")?;
+        let mined_nonce = self
+            .client
+            .inner()
+            .get_transaction_count(self.client.address(), Some(BlockNumber::Latest.into()))
+            .await?
+            .as_u64();
+        if mined_nonce > nonce {
+            // Mined between the two lookups, or the nonce went to another tx.
+            if self.mined_attempt(&attempts).await?.is_some() {
+                return Ok(None);
+            }
+            let reason = format!("nonce {nonce} was used by another transaction");
+            warn!("Tx {:?}: {}", tx_hash, reason);
+            return Ok(Some(self.outbox.record_failed(entry.id, &reason)?));
+        }
+
+        warn!("Tx {:?} is unknown to the node; rebroadcasting", tx_hash);
+        let raw = entry.raw_tx.clone().context("sent entry without raw tx")?;
+        if let Err(e) = self.client.inner().send_raw_transaction(raw).await {
+            match RpcError::as_error_response(&e) {
+                Some(resp) if already_broadcast(&resp.message) => {}
+                _ => warn!("Rebroadcast of {:?} failed: {}", tx_hash, e),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Count confirmations of a mined tx, noticing when a reorg removed it.
+    async fn check_mined(&mut self, entry: &OutboxEntry) -> Result<Option<OutboxEntry>> {
+        let tx_hash = entry.tx_hash.context("mined entry without tx hash")?;
+        let receipt = match self.client.inner().get_transaction_receipt(tx_hash).await? {
+            Some(receipt) if receipt.block_number.is_some() => receipt,
+            _ => {
+                warn!("Tx {:?} left the canonical chain; waiting for it again", tx_hash);
+                return Ok(Some(self.outbox.record_unmined(entry.id)?));
+            }
+        };
+        let block = receipt.block_number.unwrap_or_default().as_u64();
+        if entry.block_number != Some(block) {
+            return Ok(Some(self.outbox.record_mined(entry.id, block, tx_hash, paid(&receipt))?));
+        }
+        let head = self.client.inner().get_block_number().await?.as_u64();
+        if head + 1 >= block + self.cfg.confirmations {
+            info!("Tx {:?} confirmed", tx_hash);
+            return Ok(Some(self.outbox.record_confirmed(entry.id)?));
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+# Regenerate the checked-in contract ABIs from the Solidity sources and, with
+# $SOLC set, the compiled ProofCortexVerifier in verifier/out/.
+#
+# Needs solar 0.2.0 (`cargo install solar-compiler --version 0.2.0 --locked`),
+# or another binary given as $SOLAR, and jq. CI runs this and fails on a diff,
+# so commit its output together with any contract change.
+set -euo pipefail
+
+root="$(cd "$(dirname "$0")/.." && pwd)"
+solar="${SOLAR:-solar}"
+tmp="$(mktemp -d)"
+trap 'rm -rf "$tmp"' EXIT
+
+# abi <source> <output>
+abi() {
+    mkdir -p "$tmp/abi"
+    "$solar" "$root/$1" --emit abi --out-dir "$tmp/abi"
+    jq -S '.contracts[].abi' "$tmp/abi/combined.json" > "$root/$2"
+    rm -rf "$tmp/abi"
+}
+
+abi verifier/BlockDAG_Verifier.sol verifier/abi/ProofCortexVerifier.json
+abi sdk/blockchain/solidity/contracts/VeriCortexVerifier.sol sdk/blockchain/solidity/abi/VeriCortexVerifier.json
+
+# The compiled ProofCortexVerifier, which the devchain's tests/evm.rs checks
+# its native model against. solar's code generator is still experimental, so
+# this takes solc ($SOLC; CI pins 0.8.24) and is skipped without it.
+if [ -n "${SOLC:-}" ]; then
+    mkdir -p "$root/verifier/out"
+    (cd "$root/verifier" && "$SOLC" --combined-json abi,bin,bin-runtime BlockDAG_Verifier.sol) \
+        | jq -S '.contracts[] | {abi, bytecode: ("0x" + .bin), deployedBytecode: ("0x" + .["bin-runtime"])}' \
+        > "$root/verifier/out/ProofCortexVerifier.json"
+fi
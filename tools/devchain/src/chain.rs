@@ -0,0 +1,763 @@
+//! Chain state: accounts, verifier contracts, mempool and a block history
+//! that keeps one state snapshot per block so it can be rewound.
+
+use std::collections::HashMap;
+
+use ethers::abi::{self, ParamType, Token};
+
+use ethers::types::{
+    Address, Block, BlockId, BlockNumber, Bloom, Bytes, Filter, FilterBlockOption, Log,
+    Transaction, TransactionReceipt, ValueOrArray, H256, H64, U256, U64,
+};
+use ethers::utils::{get_contract_address, keccak256, rlp};
+
+use crate::evm::{self, Contract};
+use crate::verifier::Verifier;
+
+pub const BLOCK_GAS_LIMIT: u64 = 30_000_000;
+pub const BLOCK_TIME: u64 = 12;
+pub const GENESIS_TIMESTAMP: u64 = 1_765_584_000;
+pub const TRANSFER_GAS: u64 = 21_000;
+
+/// Gas charged for a reverted call to a native verifier.
+const REVERT_GAS: u64 = 30_000;
+
+/// Selector of the ABI `Error(string)` revert payload.
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// A log emitted by a contract, before block placement.
+#[derive(Debug, Clone)]
+pub struct EmittedLog {
+    pub topics: Vec<H256>,
+    pub data: Bytes,
+}
+
+/// Revert data of a failed call: the ABI-encoded `Error(string)` of a
+/// `require`, or empty for a bare revert or a halt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revert(pub Bytes);
+
+impl Revert {
+    /// A revert with `reason`, as a `require` raises it.
+    pub fn reason(reason: &str) -> Self {
+        let mut out = ERROR_STRING_SELECTOR.to_vec();
+        out.extend(abi::encode(&[Token::String(reason.into())]));
+        Self(out.into())
+    }
+
+    /// The `Error(string)` reason, if the data carries one.
+    pub fn message(&self) -> Option<String> {
+        let payload = self.0.strip_prefix(&ERROR_STRING_SELECTOR)?;
+        match abi::decode(&[ParamType::String], payload).ok()?.pop()? {
+            Token::String(reason) => Some(reason),
+            _ => None,
+        }
+    }
+}
+
+/// Errors surfaced to JSON-RPC clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainError {
+    /// `require` failed during `eth_call`/`eth_estimateGas`.
+    Reverted(Revert),
+    /// Transaction rejected before reaching the mempool.
+    Rejected(String),
+    InvalidParams(String),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Account {
+    pub nonce: u64,
+    pub balance: U256,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct World {
+    pub accounts: HashMap<Address, Account>,
+    pub verifiers: HashMap<Address, Verifier>,
+    /// Compiled contracts, executed on revm.
+    pub contracts: HashMap<Address, Contract>,
+}
+
+impl World {
+    fn nonce(&self, addr: &Address) -> u64 {
+        self.accounts.get(addr).map_or(0, |a| a.nonce)
+    }
+
+    pub(crate) fn balance(&self, addr: &Address) -> U256 {
+        self.accounts.get(addr).map_or_else(U256::zero, |a| a.balance)
+    }
+}
+
+/// A call or transaction, as far as execution is concerned.
+#[derive(Debug, Clone, Default)]
+pub struct CallRequest {
+    pub from: Address,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub data: Bytes,
+}
+
+/// A successful call.
+pub(crate) struct Executed {
+    pub output: Bytes,
+    pub logs: Vec<EmittedLog>,
+    /// Net of refunds, as the receipt reports it.
+    pub gas_used: u64,
+    pub gas_refunded: u64,
+}
+
+/// A failed call and the gas it burnt.
+pub(crate) struct Failure {
+    pub revert: Revert,
+    pub gas_used: u64,
+}
+
+#[derive(Debug, Clone)]
+struct MinedTx {
+    tx: Transaction,
+    receipt: TransactionReceipt,
+}
+
+#[derive(Debug, Clone)]
+struct BlockRecord {
+    header: Block<H256>,
+    txs: Vec<MinedTx>,
+    /// State after this block was applied.
+    world: World,
+}
+
+#[derive(Debug, Clone)]
+enum FilterKind {
+    Logs(Box<Filter>),
+    Blocks,
+}
+
+#[derive(Debug, Clone)]
+struct InstalledFilter {
+    kind: FilterKind,
+    /// Next block number not yet reported.
+    next_block: u64,
+}
+
+pub struct Chain {
+    chain_id: u64,
+    base_fee: U256,
+    automine: bool,
+    blocks: Vec<BlockRecord>,
+    pending: Vec<Transaction>,
+    filters: HashMap<U256, InstalledFilter>,
+    next_filter_id: u64,
+    deployments: u64,
+    /// Mixed into block hashes so a re-mined height never reuses an old hash.
+    seal_counter: u64,
+}
+
+fn effective_gas_price(tx: &Transaction, base_fee: U256) -> U256 {
+    match (tx.max_fee_per_gas, tx.max_priority_fee_per_gas) {
+        (Some(max_fee), Some(tip)) => max_fee.min(base_fee + tip),
+        _ => tx.gas_price.unwrap_or_default(),
+    }
+}
+
+/// Highest price per gas the sender agreed to pay.
+fn fee_cap(tx: &Transaction) -> U256 {
+    tx.max_fee_per_gas.or(tx.gas_price).unwrap_or_default()
+}
+
+fn tip_cap(tx: &Transaction) -> U256 {
+    tx.max_priority_fee_per_gas.or(tx.gas_price).unwrap_or_default()
+}
+
+/// geth's replacement rule: both fee caps must rise by at least 10%.
+fn is_bump(old: &Transaction, new: &Transaction) -> bool {
+    let bumped = |old: U256, new: U256| new >= old + old / 10 && new > old;
+    bumped(fee_cap(old), fee_cap(new)) && (tip_cap(old).is_zero() || bumped(tip_cap(old), tip_cap(new)))
+}
+
+/// Yellow paper M3:2048: three 11-bit indices from the item's keccak.
+fn accrue(bloom: &mut Bloom, item: &[u8]) {
+    let hash = keccak256(item);
+    for i in 0..3 {
+        let bit = (usize::from(hash[2 * i]) << 8 | usize::from(hash[2 * i + 1])) & 2047;
+        bloom.0[255 - bit / 8] |= 1 << (bit % 8);
+    }
+}
+
+fn bloom_of(logs: &[Log]) -> Bloom {
+    let mut bloom = Bloom::default();
+    for log in logs {
+        accrue(&mut bloom, log.address.as_bytes());
+        for topic in &log.topics {
+            accrue(&mut bloom, topic.as_bytes());
+        }
+    }
+    bloom
+}
+
+fn matches<T: PartialEq>(filter: &Option<ValueOrArray<T>>, value: &T) -> bool {
+    match filter {
+        None => true,
+        Some(ValueOrArray::Value(v)) => v == value,
+        Some(ValueOrArray::Array(vs)) => vs.is_empty() || vs.contains(value),
+    }
+}
+
+fn log_matches(filter: &Filter, log: &Log) -> bool {
+    if !matches(&filter.address, &log.address) {
+        return false;
+    }
+    filter.topics.iter().enumerate().all(|(i, topic)| match topic {
+        None => true,
+        Some(ValueOrArray::Value(None)) => true,
+        Some(ValueOrArray::Value(Some(t))) => log.topics.get(i) == Some(t),
+        Some(ValueOrArray::Array(ts)) => {
+            ts.is_empty() || ts.iter().any(|t| t.is_none() || log.topics.get(i) == t.as_ref())
+        }
+    })
+}
+
+impl Chain {
+    /// A chain whose genesis block funds `accounts` with `balance` each.
+    pub fn new(chain_id: u64, accounts: &[Address], balance: U256) -> Self {
+        let mut world = World::default();
+        for addr in accounts {
+            world.accounts.insert(*addr, Account { nonce: 0, balance });
+        }
+        let mut chain = Self {
+            chain_id,
+            base_fee: U256::from(1_000_000_000u64),
+            automine: true,
+            blocks: Vec::new(),
+            pending: Vec::new(),
+            filters: HashMap::new(),
+            next_filter_id: 1,
+            deployments: 0,
+            seal_counter: 0,
+        };
+        chain.seal(Vec::new(), world);
+        chain
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    pub fn base_fee(&self) -> U256 {
+        self.base_fee
+    }
+
+    pub fn set_base_fee(&mut self, base_fee: U256) {
+        self.base_fee = base_fee;
+    }
+
+    pub fn set_automine(&mut self, automine: bool) {
+        self.automine = automine;
+        if automine {
+            self.mine_pending();
+        }
+    }
+
+    pub fn block_number(&self) -> u64 {
+        self.blocks.len() as u64 - 1
+    }
+
+    fn latest(&self) -> &BlockRecord {
+        self.blocks.last().expect("genesis block always present")
+    }
+
+    /// Install a `ProofCortexVerifier` at a fresh address in the current state,
+    /// the way `anvil_setCode` would: no transaction, no block.
+    pub fn deploy_verifier(&mut self, trusted_submitter: Address) -> Address {
+        let address = get_contract_address(Address::zero(), self.deployments);
+        self.deployments += 1;
+        let world = &mut self.blocks.last_mut().expect("genesis block always present").world;
+        world.verifiers.insert(address, Verifier::new(trusted_submitter));
+        address
+    }
+
+    pub fn verifier(&self, address: &Address) -> Option<&Verifier> {
+        self.latest().world.verifiers.get(address)
+    }
+
+    /// Run `init` (creation code followed by the constructor arguments) and
+    /// install the contract it returns at a fresh address, like
+    /// `deploy_verifier`.
+    pub fn deploy_code(&mut self, init: &[u8]) -> Result<Address, ChainError> {
+        let contract = evm::create(init).map_err(ChainError::Reverted)?;
+        let address = get_contract_address(Address::zero(), self.deployments);
+        self.deployments += 1;
+        let world = &mut self.blocks.last_mut().expect("genesis block always present").world;
+        world.contracts.insert(address, contract);
+        Ok(address)
+    }
+
+    pub fn balance(&self, address: &Address, block: Option<BlockId>) -> Result<U256, ChainError> {
+        Ok(self.world_at(block)?.balance(address))
+    }
+
+    pub fn code(&self, address: &Address, block: Option<BlockId>) -> Result<Bytes, ChainError> {
+        let world = self.world_at(block)?;
+        if let Some(contract) = world.contracts.get(address) {
+            return Ok(contract.code.original_bytes().to_vec().into());
+        }
+        // Clients only test for non-empty code; a native verifier has no bytecode.
+        Ok(if world.verifiers.contains_key(address) { Bytes::from(vec![0xfe]) } else { Bytes::new() })
+    }
+
+    /// Account nonce; `pending` also counts queued transactions.
+    pub fn nonce(&self, address: &Address, block: Option<BlockId>) -> Result<u64, ChainError> {
+        if let Some(BlockId::Number(BlockNumber::Pending)) = block {
+            let mut nonce = self.latest().world.nonce(address);
+            while self.pending.iter().any(|tx| tx.from == *address && tx.nonce.as_u64() == nonce) {
+                nonce += 1;
+            }
+            return Ok(nonce);
+        }
+        Ok(self.world_at(block)?.nonce(address))
+    }
+
+    fn resolve_number(&self, number: BlockNumber) -> u64 {
+        match number {
+            BlockNumber::Earliest => 0,
+            BlockNumber::Number(n) => n.as_u64(),
+            _ => self.block_number(),
+        }
+    }
+
+    fn block_index(&self, block: Option<BlockId>) -> Result<usize, ChainError> {
+        let index = match block {
+            None => self.block_number(),
+            Some(BlockId::Number(n)) => self.resolve_number(n),
+            Some(BlockId::Hash(hash)) => self
+                .blocks
+                .iter()
+                .position(|b| b.header.hash == Some(hash))
+                .ok_or_else(|| ChainError::InvalidParams(format!("unknown block {hash:?}")))?
+                as u64,
+        };
+        if index > self.block_number() {
+            return Err(ChainError::InvalidParams(format!("block {index} not found")));
+        }
+        Ok(index as usize)
+    }
+
+    fn world_at(&self, block: Option<BlockId>) -> Result<&World, ChainError> {
+        Ok(&self.blocks[self.block_index(block)?].world)
+    }
+
+    /// Execute `req` against `world` with `gas_limit`, committing state only
+    /// on success.
+    fn execute(&self, world: &mut World, req: &CallRequest, gas_limit: u64, timestamp: u64) -> Result<Executed, Failure> {
+        let revert = |revert: Revert| Failure { revert, gas_used: REVERT_GAS.min(gas_limit) };
+        let Some(to) = req.to else {
+            return Err(revert(Revert::reason("contract creation is not supported")));
+        };
+        if world.balance(&req.from) < req.value {
+            return Err(revert(Revert::reason("insufficient balance")));
+        }
+        if world.contracts.contains_key(&to) {
+            return evm::call(world, req, gas_limit, timestamp);
+        }
+        let gas_used = if let Some(verifier) = world.verifiers.get(&to) {
+            if !req.value.is_zero() {
+                return Err(revert(Revert(Bytes::new())));
+            }
+            let mut verifier = verifier.clone();
+            let outcome = verifier.execute(req.from, &req.data, timestamp).map_err(revert)?;
+            if outcome.gas_used > gas_limit {
+                return Err(Failure { revert: Revert(Bytes::new()), gas_used: gas_limit });
+            }
+            world.verifiers.insert(to, verifier);
+            return Ok(Executed { output: outcome.output, logs: outcome.logs, gas_used: outcome.gas_used, gas_refunded: 0 });
+        } else {
+            TRANSFER_GAS
+        };
+        world.accounts.entry(req.from).or_default().balance -= req.value;
+        world.accounts.entry(to).or_default().balance += req.value;
+        Ok(Executed { output: Bytes::new(), logs: Vec::new(), gas_used, gas_refunded: 0 })
+    }
+
+    /// `eth_call`: run against a copy of the state at `block`.
+    pub fn call(&self, req: &CallRequest, block: Option<BlockId>) -> Result<Bytes, ChainError> {
+        let index = self.block_index(block)?;
+        let mut world = self.blocks[index].world.clone();
+        let timestamp = self.blocks[index].header.timestamp.as_u64();
+        self.execute(&mut world, req, BLOCK_GAS_LIMIT, timestamp)
+            .map(|e| e.output)
+            .map_err(|f| ChainError::Reverted(f.revert))
+    }
+
+    /// Gas the call burns before refunds are paid back, which is what its
+    /// transaction needs as a limit.
+    pub fn estimate_gas(&self, req: &CallRequest) -> Result<u64, ChainError> {
+        let mut world = self.latest().world.clone();
+        let timestamp = self.next_timestamp();
+        self.execute(&mut world, req, BLOCK_GAS_LIMIT, timestamp)
+            .map(|e| e.gas_used + e.gas_refunded)
+            .map_err(|f| ChainError::Reverted(f.revert))
+    }
+
+    fn next_timestamp(&self) -> u64 {
+        GENESIS_TIMESTAMP + (self.block_number() + 1) * BLOCK_TIME
+    }
+
+    /// `eth_sendRawTransaction`: validate, queue and (with automine) mine.
+    pub fn send_raw(&mut self, raw: &[u8]) -> Result<H256, ChainError> {
+        let mut tx: Transaction = rlp::decode(raw)
+            .map_err(|e| ChainError::InvalidParams(format!("invalid transaction rlp: {e}")))?;
+        let from = tx
+            .recover_from_mut()
+            .map_err(|e| ChainError::Rejected(format!("invalid signature: {e}")))?;
+        if let Some(id) = tx.chain_id {
+            if id.as_u64() != self.chain_id {
+                return Err(ChainError::Rejected(format!("invalid chain id {id}, expected {}", self.chain_id)));
+            }
+        }
+        if self.pending.iter().any(|p| p.hash == tx.hash) || self.find_mined(&tx.hash).is_some() {
+            return Err(ChainError::Rejected("already known".into()));
+        }
+        if tx.gas.as_u64() < TRANSFER_GAS {
+            return Err(ChainError::Rejected("intrinsic gas too low".into()));
+        }
+        let world = &self.latest().world;
+        if tx.nonce.as_u64() < world.nonce(&from) {
+            return Err(ChainError::Rejected("nonce too low".into()));
+        }
+        if tx.gas * fee_cap(&tx) + tx.value > world.balance(&from) {
+            return Err(ChainError::Rejected("insufficient funds for gas * price + value".into()));
+        }
+        if let Some(pos) = self.pending.iter().position(|p| p.from == from && p.nonce == tx.nonce) {
+            if !is_bump(&self.pending[pos], &tx) {
+                return Err(ChainError::Rejected("replacement transaction underpriced".into()));
+            }
+            self.pending.remove(pos);
+        }
+
+        let hash = tx.hash;
+        self.pending.push(tx);
+        if self.automine {
+            self.mine_pending();
+        }
+        Ok(hash)
+    }
+
+    pub fn pending(&self) -> &[Transaction] {
+        &self.pending
+    }
+
+    /// Forget every queued transaction, as a restarted node would.
+    pub fn drop_pending(&mut self) -> usize {
+        std::mem::take(&mut self.pending).len()
+    }
+
+    fn mine_pending(&mut self) {
+        if self.pending.iter().any(|tx| fee_cap(tx) >= self.base_fee) {
+            self.mine();
+        }
+    }
+
+    /// Mine one block with every includable pending transaction.
+    pub fn mine(&mut self) -> u64 {
+        let mut world = self.latest().world.clone();
+        let timestamp = self.next_timestamp();
+        let base_fee = self.base_fee;
+
+        let mut queue = std::mem::take(&mut self.pending);
+        queue.sort_by_key(|tx| (tx.from, tx.nonce));
+        let mut included: Vec<(Transaction, Executed, bool)> = Vec::new();
+        let mut block_gas = 0u64;
+        loop {
+            let next = queue.iter().position(|tx| {
+                tx.nonce.as_u64() == world.nonce(&tx.from)
+                    && fee_cap(tx) >= base_fee
+                    && block_gas + tx.gas.as_u64() <= BLOCK_GAS_LIMIT
+                    && tx.gas * effective_gas_price(tx, base_fee) + tx.value <= world.balance(&tx.from)
+            });
+            let Some(pos) = next else { break };
+            let tx = queue.remove(pos);
+
+            let req = CallRequest { from: tx.from, to: tx.to, value: tx.value, data: tx.input.clone() };
+            let mut scratch = world.clone();
+            let (executed, success) = match self.execute(&mut scratch, &req, tx.gas.as_u64(), timestamp) {
+                Ok(e) => {
+                    world = scratch;
+                    (e, true)
+                }
+                Err(f) => (Executed { output: Bytes::new(), logs: Vec::new(), gas_used: f.gas_used, gas_refunded: 0 }, false),
+            };
+            let account = world.accounts.entry(tx.from).or_default();
+            account.nonce += 1;
+            account.balance -= U256::from(executed.gas_used) * effective_gas_price(&tx, base_fee);
+            block_gas += executed.gas_used;
+            included.push((tx, executed, success));
+        }
+        // Stale nonces can never be mined; everything else stays queued.
+        queue.retain(|tx| tx.nonce.as_u64() >= world.nonce(&tx.from));
+        self.pending = queue;
+
+        self.seal(included, world)
+    }
+
+    fn seal(&mut self, included: Vec<(Transaction, Executed, bool)>, world: World) -> u64 {
+        let number = self.blocks.len() as u64;
+        let parent_hash = self.blocks.last().and_then(|b| b.header.hash).unwrap_or_default();
+        self.seal_counter += 1;
+
+        let mut preimage = Vec::new();
+        preimage.extend(parent_hash.as_bytes());
+        preimage.extend(number.to_be_bytes());
+        preimage.extend(self.seal_counter.to_be_bytes());
+        for (tx, _, _) in &included {
+            preimage.extend(tx.hash.as_bytes());
+        }
+        let hash = H256(keccak256(&preimage));
+        let timestamp = if number == 0 { GENESIS_TIMESTAMP } else { self.next_timestamp() };
+
+        let mut txs = Vec::with_capacity(included.len());
+        let mut cumulative = 0u64;
+        let mut log_index = 0u64;
+        let mut block_logs = Vec::new();
+        for (index, (mut tx, executed, success)) in included.into_iter().enumerate() {
+            cumulative += executed.gas_used;
+            let price = effective_gas_price(&tx, self.base_fee);
+            tx.block_hash = Some(hash);
+            tx.block_number = Some(number.into());
+            tx.transaction_index = Some((index as u64).into());
+            tx.gas_price = Some(price);
+
+            let logs: Vec<Log> = executed
+                .logs
+                .into_iter()
+                .enumerate()
+                .map(|(i, emitted)| {
+                    let log = Log {
+                        address: tx.to.unwrap_or_default(),
+                        topics: emitted.topics,
+                        data: emitted.data,
+                        block_hash: Some(hash),
+                        block_number: Some(number.into()),
+                        transaction_hash: Some(tx.hash),
+                        transaction_index: Some((index as u64).into()),
+                        log_index: Some(log_index.into()),
+                        transaction_log_index: Some(i.into()),
+                        log_type: None,
+                        removed: Some(false),
+                    };
+                    log_index += 1;
+                    log
+                })
+                .collect();
+            block_logs.extend(logs.iter().cloned());
+
+            let receipt = TransactionReceipt {
+                transaction_hash: tx.hash,
+                transaction_index: (index as u64).into(),
+                block_hash: Some(hash),
+                block_number: Some(number.into()),
+                from: tx.from,
+                to: tx.to,
+                cumulative_gas_used: cumulative.into(),
+                gas_used: Some(executed.gas_used.into()),
+                logs_bloom: bloom_of(&logs),
+                logs,
+                status: Some(U64::from(success as u64)),
+                transaction_type: tx.transaction_type,
+                effective_gas_price: Some(price),
+                ..Default::default()
+            };
+            txs.push(MinedTx { tx, receipt });
+        }
+
+        let header = Block {
+            hash: Some(hash),
+            parent_hash,
+            number: Some(number.into()),
+            timestamp: timestamp.into(),
+            gas_used: cumulative.into(),
+            gas_limit: BLOCK_GAS_LIMIT.into(),
+            base_fee_per_gas: Some(self.base_fee),
+            logs_bloom: Some(bloom_of(&block_logs)),
+            transactions: txs.iter().map(|m| m.tx.hash).collect(),
+            author: Some(Address::zero()),
+            difficulty: U256::zero(),
+            total_difficulty: Some(U256::zero()),
+            nonce: Some(H64::zero()),
+            mix_hash: Some(H256::zero()),
+            size: Some(U256::from(512 + 128 * txs.len())),
+            ..Default::default()
+        };
+        self.blocks.push(BlockRecord { header, txs, world });
+        number
+    }
+
+    /// Mine `count` blocks, including pending transactions as usual.
+    pub fn mine_blocks(&mut self, count: u64) -> u64 {
+        for _ in 0..count {
+            self.mine();
+        }
+        self.block_number()
+    }
+
+    /// Drop the newest `depth` blocks; their transactions are discarded, not
+    /// re-queued, so a following `mine_blocks` builds a competing fork.
+    pub fn rewind(&mut self, depth: u64) -> Vec<Transaction> {
+        let keep = (self.blocks.len() as u64).saturating_sub(depth).max(1) as usize;
+        let dropped: Vec<Transaction> =
+            self.blocks.drain(keep..).flat_map(|b| b.txs.into_iter().map(|m| m.tx)).collect();
+        let head = self.block_number();
+        for filter in self.filters.values_mut() {
+            filter.next_block = filter.next_block.min(head + 1);
+        }
+        dropped
+    }
+
+    /// Replace the newest `depth` blocks with `depth + 1` empty ones.
+    pub fn reorg(&mut self, depth: u64) -> u64 {
+        self.rewind(depth);
+        let pending = self.drop_pending();
+        debug_assert_eq!(pending, 0, "reorg with queued transactions");
+        self.mine_blocks(depth + 1)
+    }
+
+    fn find_mined(&self, hash: &H256) -> Option<&MinedTx> {
+        self.blocks.iter().flat_map(|b| b.txs.iter()).find(|m| m.tx.hash == *hash)
+    }
+
+    pub fn transaction(&self, hash: &H256) -> Option<Transaction> {
+        self.find_mined(hash)
+            .map(|m| m.tx.clone())
+            .or_else(|| self.pending.iter().find(|tx| tx.hash == *hash).cloned())
+    }
+
+    pub fn receipt(&self, hash: &H256) -> Option<TransactionReceipt> {
+        self.find_mined(hash).map(|m| m.receipt.clone())
+    }
+
+    pub fn block(&self, block: BlockId) -> Option<Block<H256>> {
+        let index = self.block_index(Some(block)).ok()?;
+        Some(self.blocks[index].header.clone())
+    }
+
+    pub fn block_with_txs(&self, block: BlockId) -> Option<Block<Transaction>> {
+        let index = self.block_index(Some(block)).ok()?;
+        let record = &self.blocks[index];
+        let h = record.header.clone();
+        Some(Block {
+            hash: h.hash,
+            parent_hash: h.parent_hash,
+            number: h.number,
+            timestamp: h.timestamp,
+            gas_used: h.gas_used,
+            gas_limit: h.gas_limit,
+            base_fee_per_gas: h.base_fee_per_gas,
+            logs_bloom: h.logs_bloom,
+            author: h.author,
+            difficulty: h.difficulty,
+            total_difficulty: h.total_difficulty,
+            nonce: h.nonce,
+            mix_hash: h.mix_hash,
+            size: h.size,
+            transactions: record.txs.iter().map(|m| m.tx.clone()).collect(),
+            ..Default::default()
+        })
+    }
+
+    /// `eth_feeHistory` over the newest `count` blocks ending at `newest`.
+    pub fn fee_history(&self, count: u64, newest: BlockNumber, percentiles: &[f64]) -> ethers::types::FeeHistory {
+        let newest = self.resolve_number(newest).min(self.block_number());
+        let count = count.clamp(1, newest + 1);
+        let oldest = newest + 1 - count;
+        let blocks = &self.blocks[oldest as usize..=newest as usize];
+        let mut base_fee_per_gas: Vec<U256> =
+            blocks.iter().map(|b| b.header.base_fee_per_gas.unwrap_or_default()).collect();
+        base_fee_per_gas.push(self.base_fee);
+        let gas_used_ratio =
+            blocks.iter().map(|b| b.header.gas_used.as_u64() as f64 / BLOCK_GAS_LIMIT as f64).collect();
+        let reward = blocks
+            .iter()
+            .map(|b| {
+                let base = b.header.base_fee_per_gas.unwrap_or_default();
+                let tip = b.txs.iter().map(|m| m.tx.gas_price.unwrap_or_default().saturating_sub(base)).max();
+                vec![tip.unwrap_or_default(); percentiles.len()]
+            })
+            .collect();
+        ethers::types::FeeHistory { base_fee_per_gas, gas_used_ratio, oldest_block: oldest.into(), reward }
+    }
+
+    fn logs_in(&self, filter: &Filter, from: u64, to: u64) -> Vec<Log> {
+        let to = to.min(self.block_number());
+        if from > to {
+            return Vec::new();
+        }
+        self.blocks[from as usize..=to as usize]
+            .iter()
+            .flat_map(|b| b.txs.iter().flat_map(|m| m.receipt.logs.iter()))
+            .filter(|log| log_matches(filter, log))
+            .cloned()
+            .collect()
+    }
+
+    /// `eth_getLogs`.
+    pub fn logs(&self, filter: &Filter) -> Result<Vec<Log>, ChainError> {
+        match filter.block_option {
+            FilterBlockOption::AtBlockHash(hash) => {
+                let index = self.block_index(Some(BlockId::Hash(hash)))? as u64;
+                Ok(self.logs_in(filter, index, index))
+            }
+            FilterBlockOption::Range { from_block, to_block } => {
+                let from = from_block.map_or(self.block_number(), |n| self.resolve_number(n));
+                let to = to_block.map_or(self.block_number(), |n| self.resolve_number(n));
+                Ok(self.logs_in(filter, from, to))
+            }
+        }
+    }
+
+    fn install(&mut self, kind: FilterKind) -> U256 {
+        let id = U256::from(self.next_filter_id);
+        self.next_filter_id += 1;
+        let next_block = self.block_number() + 1;
+        self.filters.insert(id, InstalledFilter { kind, next_block });
+        id
+    }
+
+    pub fn new_filter(&mut self, filter: Filter) -> U256 {
+        self.install(FilterKind::Logs(Box::new(filter)))
+    }
+
+    pub fn new_block_filter(&mut self) -> U256 {
+        self.install(FilterKind::Blocks)
+    }
+
+    pub fn uninstall_filter(&mut self, id: &U256) -> bool {
+        self.filters.remove(id).is_some()
+    }
+
+    /// `eth_getFilterChanges`: logs or block hashes since the previous poll.
+    pub fn filter_changes(&mut self, id: &U256) -> Result<serde_json::Value, ChainError> {
+        let head = self.block_number();
+        let filter = self.filters.get(id).cloned().ok_or_else(|| ChainError::InvalidParams("filter not found".into()))?;
+        let changes = match &filter.kind {
+            FilterKind::Logs(f) => serde_json::to_value(self.logs_in(f, filter.next_block, head)),
+            FilterKind::Blocks => {
+                let hashes: Vec<H256> = (filter.next_block..=head)
+                    .filter_map(|n| self.blocks.get(n as usize).and_then(|b| b.header.hash))
+                    .collect();
+                serde_json::to_value(hashes)
+            }
+        }
+        .expect("logs serialize");
+        if let Some(f) = self.filters.get_mut(id) {
+            f.next_block = head + 1;
+        }
+        Ok(changes)
+    }
+
+    /// `eth_getFilterLogs`: every log matching an installed log filter.
+    pub fn filter_logs(&self, id: &U256) -> Result<Vec<Log>, ChainError> {
+        match self.filters.get(id).map(|f| &f.kind) {
+            Some(FilterKind::Logs(f)) => self.logs(f),
+            _ => Err(ChainError::InvalidParams("filter not found".into())),
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Compiled contracts, executed on revm against the chain's own account and
+//! storage maps.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+use ethers::types::{Address, Bytes, H256, U256};
+use revm::primitives::{self as r, AccountInfo, Bytecode, ExecutionResult, Output, TxKind, B256};
+use revm::{DatabaseRef, Evm};
+
+use crate::chain::{CallRequest, EmittedLog, Executed, Failure, Revert, World, BLOCK_GAS_LIMIT};
+
+/// Code and storage of a deployed contract.
+#[derive(Debug, Clone)]
+pub struct Contract {
+    pub code: Bytecode,
+    pub storage: HashMap<r::U256, r::U256>,
+}
+
+fn to_revm_address(address: Address) -> r::Address {
+    r::Address::from(address.0)
+}
+
+fn from_revm_address(address: r::Address) -> Address {
+    Address::from(address.into_array())
+}
+
+fn to_revm_word(value: U256) -> r::U256 {
+    r::U256::from_limbs(value.0)
+}
+
+fn from_revm_word(value: r::U256) -> U256 {
+    U256(value.into_limbs())
+}
+
+impl DatabaseRef for World {
+    type Error = Infallible;
+
+    fn basic_ref(&self, address: r::Address) -> Result<Option<AccountInfo>, Infallible> {
+        let address = from_revm_address(address);
+        let account = self.accounts.get(&address);
+        let contract = self.contracts.get(&address);
+        if account.is_none() && contract.is_none() {
+            return Ok(None);
+        }
+        let code = contract.map(|c| c.code.clone()).unwrap_or_default();
+        let balance = to_revm_word(account.map(|a| a.balance).unwrap_or_default());
+        let nonce = account.map_or(0, |a| a.nonce);
+        Ok(Some(AccountInfo::new(balance, nonce, code.hash_slow(), code)))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Infallible> {
+        let code = self.contracts.values().map(|c| &c.code).find(|code| code.hash_slow() == code_hash);
+        Ok(code.cloned().unwrap_or_default())
+    }
+
+    fn storage_ref(&self, address: r::Address, index: r::U256) -> Result<r::U256, Infallible> {
+        let contract = self.contracts.get(&from_revm_address(address));
+        Ok(contract.and_then(|c| c.storage.get(&index).copied()).unwrap_or_default())
+    }
+
+    fn block_hash_ref(&self, _number: r::U256) -> Result<B256, Infallible> {
+        // Block hashes are not part of the world; no contract here reads them.
+        Ok(B256::ZERO)
+    }
+}
+
+/// Run `kind` with `data` from `from` on `world` without changing it. Fees
+/// and nonces are the chain's business, so gas is free and nonces unchecked.
+fn run(
+    world: &World,
+    from: Address,
+    kind: TxKind,
+    value: U256,
+    data: &[u8],
+    gas_limit: u64,
+    timestamp: u64,
+) -> (ExecutionResult, r::EvmState) {
+    let mut evm = Evm::builder()
+        .with_ref_db(world)
+        .modify_block_env(|block| {
+            block.timestamp = r::U256::from(timestamp);
+            block.gas_limit = r::U256::from(BLOCK_GAS_LIMIT);
+        })
+        .modify_tx_env(|tx| {
+            tx.caller = to_revm_address(from);
+            tx.transact_to = kind;
+            tx.value = to_revm_word(value);
+            tx.data = r::Bytes::copy_from_slice(data);
+            tx.gas_limit = gas_limit;
+            tx.nonce = None;
+        })
+        .build();
+    let result = evm.transact().expect("the world database cannot fail");
+    (result.result, result.state)
+}
+
+/// Execute `req`, a call to a deployed contract, with `gas_limit` in a block
+/// stamped `timestamp`; `world` changes only if it succeeds.
+pub(crate) fn call(world: &mut World, req: &CallRequest, gas_limit: u64, timestamp: u64) -> Result<Executed, Failure> {
+    let to = req.to.expect("calls have a target");
+    let (result, state) = run(world, req.from, TxKind::Call(to_revm_address(to)), req.value, &req.data, gas_limit, timestamp);
+    let (gas_used, gas_refunded, logs, output) = match result {
+        ExecutionResult::Success { gas_used, gas_refunded, logs, output, .. } => (gas_used, gas_refunded, logs, output),
+        ExecutionResult::Revert { gas_used, output } => {
+            return Err(Failure { revert: Revert(output.to_vec().into()), gas_used })
+        }
+        ExecutionResult::Halt { gas_used, .. } => return Err(Failure { revert: Revert(Bytes::new()), gas_used }),
+    };
+
+    for (address, account) in state {
+        if !account.is_touched() {
+            continue;
+        }
+        let address = from_revm_address(address);
+        let balance = from_revm_word(account.info.balance);
+        if world.balance(&address) != balance {
+            world.accounts.entry(address).or_default().balance = balance;
+        }
+        if let Some(contract) = world.contracts.get_mut(&address) {
+            for (index, slot) in account.storage.into_iter().filter(|(_, slot)| slot.is_changed()) {
+                if slot.present_value().is_zero() {
+                    contract.storage.remove(&index);
+                } else {
+                    contract.storage.insert(index, slot.present_value());
+                }
+            }
+        }
+    }
+
+    let logs = logs
+        .into_iter()
+        .map(|log| EmittedLog {
+            topics: log.data.topics().iter().map(|t| H256(t.0)).collect(),
+            data: log.data.data.to_vec().into(),
+        })
+        .collect();
+    Ok(Executed { output: output.into_data().to_vec().into(), logs, gas_used, gas_refunded })
+}
+
+/// Run `init` and return the contract it deploys.
+pub fn create(init: &[u8]) -> Result<Contract, Revert> {
+    let (result, mut state) =
+        run(&World::default(), Address::zero(), TxKind::Create, U256::zero(), init, BLOCK_GAS_LIMIT, 0);
+    let (code, address) = match result {
+        ExecutionResult::Success { output: Output::Create(code, Some(address)), .. } => (code, address),
+        ExecutionResult::Revert { output, .. } => return Err(Revert(output.to_vec().into())),
+        _ => return Err(Revert(Bytes::new())),
+    };
+    let account = state.remove(&address).expect("created account in state");
+    let storage = account.storage.into_iter().map(|(index, slot)| (index, slot.present_value())).collect();
+    Ok(Contract { code: Bytecode::new_raw(code), storage })
+}
@@ -0,0 +1,231 @@
+//! VeriCortex local dev chain.
+//!
+//! An in-process stand-in for anvil/hardhat: an Ethereum JSON-RPC endpoint
+//! whose only contract is `ProofCortexVerifier`, implemented natively in Rust
+//! with the storage, `require` messages and events of
+//! `verifier/BlockDAG_Verifier.sol`. It accepts real signed transactions, so
+//! the prover, API and tools are exercised through their normal ethers code
+//! paths without a network, a Solidity compiler or an EVM binary.
+//!
+//! It is a semantic model of the contract, not a bytecode interpreter: keep it
+//! in step with the Solidity source and the ABI in `verifier/abi/`. Compiled
+//! contracts deployed with [`DevChain::deploy_code`] run on revm instead,
+//! which is how `tests/evm.rs` checks the model against solc's bytecode.
+
+pub mod chain;
+pub mod evm;
+pub mod remote_signer;
+pub mod rpc;
+pub mod verifier;
+
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, Log, Transaction, U256};
+use tokio::sync::oneshot;
+use vericortex_sdk::contract::ProofRecord;
+
+pub use chain::{Chain, ChainError};
+pub use rpc::SharedChain;
+
+pub const DEFAULT_CHAIN_ID: u64 = 1337;
+
+/// Tip suggested by `eth_maxPriorityFeePerGas` (1 gwei).
+pub const DEFAULT_PRIORITY_FEE: u64 = 1_000_000_000;
+
+/// The well-known anvil/hardhat development keys; funded at genesis.
+pub const DEV_KEYS: [&str; 3] = [
+    "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+    "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d",
+    "0x5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a",
+];
+
+/// Wallet for `DEV_KEYS[index]`.
+pub fn dev_wallet(index: usize) -> LocalWallet {
+    DEV_KEYS[index].parse().expect("valid dev key")
+}
+
+pub fn dev_address(index: usize) -> Address {
+    dev_wallet(index).address()
+}
+
+/// Genesis state: every dev account holds 10 000 ETH.
+pub fn dev_chain(chain_id: u64) -> Chain {
+    let accounts: Vec<Address> = (0..DEV_KEYS.len()).map(dev_address).collect();
+    Chain::new(chain_id, &accounts, U256::exp10(22))
+}
+
+/// Serve `chain` on `listener` until `shutdown` resolves.
+pub async fn serve(
+    listener: tokio::net::TcpListener,
+    chain: SharedChain,
+    shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()> {
+    axum::serve(listener, rpc::router(chain)).with_graceful_shutdown(shutdown).await
+}
+
+/// An axum router served on its own thread and runtime; stops when dropped.
+struct Background {
+    addr: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Background {
+    fn spawn(name: &str, router: axum::Router) -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let (tx, rx) = oneshot::channel::<()>();
+
+        let label = name.to_string();
+        let thread = std::thread::Builder::new().name(name.into()).spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("background runtime");
+            runtime.block_on(async move {
+                let listener = tokio::net::TcpListener::from_std(listener).expect("background listener");
+                if let Err(e) = axum::serve(listener, router)
+                    .with_graceful_shutdown(async {
+                        let _ = rx.await;
+                    })
+                    .await
+                {
+                    log::error!("{label} server failed: {e}");
+                }
+            });
+        })?;
+
+        Ok(Self { addr, shutdown: Some(tx), thread: Some(thread) })
+    }
+
+    fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+}
+
+impl Drop for Background {
+    fn drop(&mut self) {
+        if let Some(tx) = self.shutdown.take() {
+            let _ = tx.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// A dev chain served on a background thread; stops when dropped.
+pub struct DevChain {
+    chain: SharedChain,
+    server: Background,
+}
+
+impl DevChain {
+    /// Start a chain with `DEFAULT_CHAIN_ID` on an ephemeral localhost port.
+    pub fn spawn() -> std::io::Result<Self> {
+        Self::spawn_with(dev_chain(DEFAULT_CHAIN_ID))
+    }
+
+    pub fn spawn_with(chain: Chain) -> std::io::Result<Self> {
+        let chain = Arc::new(Mutex::new(chain));
+        let server = Background::spawn("devchain", rpc::router(chain.clone()))?;
+        Ok(Self { chain, server })
+    }
+
+    /// HTTP JSON-RPC endpoint, e.g. `http://127.0.0.1:40123`.
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    /// Direct access to the chain state, for controls the RPC does not expose.
+    pub fn state(&self) -> MutexGuard<'_, Chain> {
+        self.chain.lock().expect("chain lock poisoned")
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.state().chain_id()
+    }
+
+    /// Deploy a verifier trusting `trusted_submitter`; returns its address.
+    pub fn deploy_verifier(&self, trusted_submitter: Address) -> Address {
+        self.state().deploy_verifier(trusted_submitter)
+    }
+
+    /// Deploy compiled `init` code (constructor arguments appended); returns
+    /// its address.
+    pub fn deploy_code(&self, init: &[u8]) -> Result<Address, ChainError> {
+        self.state().deploy_code(init)
+    }
+
+    pub fn set_automine(&self, automine: bool) {
+        self.state().set_automine(automine);
+    }
+
+    pub fn mine(&self) -> u64 {
+        self.state().mine()
+    }
+
+    pub fn mine_blocks(&self, count: u64) -> u64 {
+        self.state().mine_blocks(count)
+    }
+
+    pub fn block_number(&self) -> u64 {
+        self.state().block_number()
+    }
+
+    /// Replace the newest `depth` blocks with a longer fork of empty blocks.
+    pub fn reorg(&self, depth: u64) -> u64 {
+        self.state().reorg(depth)
+    }
+
+    pub fn set_base_fee(&self, base_fee: U256) {
+        self.state().set_base_fee(base_fee);
+    }
+
+    pub fn pending(&self) -> Vec<Transaction> {
+        self.state().pending().to_vec()
+    }
+
+    pub fn drop_pending(&self) -> usize {
+        self.state().drop_pending()
+    }
+
+    /// Every log emitted so far on the canonical chain.
+    pub fn logs(&self) -> Vec<Log> {
+        let filter = ethers::types::Filter::new().from_block(0u64);
+        self.state().logs(&filter).expect("block range is valid")
+    }
+
+    /// The stored record for `proof_id`, if it was ever submitted.
+    pub fn proof(&self, verifier: Address, proof_id: [u8; 32]) -> Option<ProofRecord> {
+        self.state().verifier(&verifier)?.proofs.get(&proof_id).filter(|p| p.submitter != Address::zero()).cloned()
+    }
+}
+
+/// A web3signer-style remote signer holding `wallets`, on an ephemeral
+/// localhost port; stops when dropped.
+pub struct MockRemoteSigner {
+    state: remote_signer::SignerState,
+    server: Background,
+}
+
+impl MockRemoteSigner {
+    pub fn spawn(wallets: Vec<LocalWallet>) -> std::io::Result<Self> {
+        let state = remote_signer::SignerState::new(wallets);
+        let server = Background::spawn("remote-signer", remote_signer::router(state.clone()))?;
+        Ok(Self { state, server })
+    }
+
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    /// Signatures handed out so far.
+    pub fn signatures(&self) -> usize {
+        self.state.signatures()
+    }
+}
@@ -0,0 +1,47 @@
+//! `devchain`: run the VeriCortex dev chain as a standalone JSON-RPC node.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use ethers::types::Address;
+use vericortex_devchain::{dev_address, dev_chain, serve, DEFAULT_CHAIN_ID, DEV_KEYS};
+
+#[derive(Parser)]
+#[command(name = "devchain", about = "Local ProofCortexVerifier chain for offline testing")]
+struct Cli {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8545")]
+    listen: SocketAddr,
+
+    #[arg(long, default_value_t = DEFAULT_CHAIN_ID)]
+    chain_id: u64,
+
+    /// Trusted submitter for the deployed verifier (default: dev account 0)
+    #[arg(long)]
+    trusted: Option<Address>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    let mut chain = dev_chain(cli.chain_id);
+    let verifier = chain.deploy_verifier(cli.trusted.unwrap_or_else(|| dev_address(0)));
+    let listener = tokio::net::TcpListener::bind(cli.listen).await.context("bind failed")?;
+
+    println!("RPC_URL=http://{}", listener.local_addr()?);
+    println!("CHAIN_ID={}", cli.chain_id);
+    println!("VERIFIER_CONTRACT_ADDRESS={verifier:?}");
+    for (i, key) in DEV_KEYS.iter().enumerate() {
+        println!("# account {i}: {:?} key {key}", dev_address(i));
+    }
+
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    serve(listener, Arc::new(Mutex::new(chain)), shutdown).await?;
+    Ok(())
+}
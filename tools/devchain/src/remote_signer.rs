@@ -0,0 +1,76 @@
+//! A web3signer stand-in: the `eth1` public key listing and signing endpoints,
+//! backed by in-memory wallets, for testing the SDK's remote signer backend.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use ethers::signers::LocalWallet;
+use ethers::types::H256;
+use ethers::utils::keccak256;
+use serde::Deserialize;
+
+/// Keys held by the mock and the number of signatures it produced.
+#[derive(Clone)]
+pub struct SignerState {
+    wallets: Arc<Vec<LocalWallet>>,
+    signatures: Arc<AtomicUsize>,
+}
+
+impl SignerState {
+    pub fn new(wallets: Vec<LocalWallet>) -> Self {
+        Self { wallets: Arc::new(wallets), signatures: Arc::default() }
+    }
+
+    pub fn signatures(&self) -> usize {
+        self.signatures.load(Ordering::SeqCst)
+    }
+}
+
+/// `0x`-prefixed uncompressed public key (`x || y`), as web3signer lists it.
+pub fn public_key(wallet: &LocalWallet) -> String {
+    let point = wallet.signer().verifying_key().to_encoded_point(false);
+    format!("0x{}", ethers::utils::hex::encode(&point.as_bytes()[1..]))
+}
+
+#[derive(Deserialize)]
+struct SignRequest {
+    data: String,
+}
+
+pub fn router(state: SignerState) -> Router {
+    Router::new()
+        .route("/upcheck", get(|| async { "OK" }))
+        .route("/api/v1/eth1/publicKeys", get(public_keys))
+        .route("/api/v1/eth1/sign/:identifier", post(sign))
+        .with_state(state)
+}
+
+async fn public_keys(State(state): State<SignerState>) -> Json<Vec<String>> {
+    Json(state.wallets.iter().map(public_key).collect())
+}
+
+async fn sign(
+    State(state): State<SignerState>,
+    Path(identifier): Path<String>,
+    Json(req): Json<SignRequest>,
+) -> Result<String, (StatusCode, String)> {
+    let wallet = state
+        .wallets
+        .iter()
+        .find(|w| public_key(w).eq_ignore_ascii_case(&identifier))
+        .ok_or((StatusCode::NOT_FOUND, format!("no key {identifier}")))?;
+    let data = req
+        .data
+        .strip_prefix("0x")
+        .and_then(|hex| ethers::utils::hex::decode(hex).ok())
+        .ok_or((StatusCode::BAD_REQUEST, "data must be 0x-prefixed hex".to_string()))?;
+    let signature = wallet
+        .sign_hash(H256(keccak256(&data)))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    state.signatures.fetch_add(1, Ordering::SeqCst);
+    Ok(format!("0x{}", ethers::utils::hex::encode(signature.to_vec())))
+}
@@ -0,0 +1,172 @@
+//! Ethereum JSON-RPC over HTTP: the subset ethers' `Provider`,
+//! `SignerMiddleware` and event filters use.
+
+use std::sync::{Arc, Mutex};
+
+use axum::{extract::State, routing::post, Json, Router};
+use ethers::types::{BlockId, BlockNumber, Bytes, Filter, NameOrAddress, TransactionRequest, H256, U256, U64};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::chain::{CallRequest, Chain, ChainError};
+
+pub type SharedChain = Arc<Mutex<Chain>>;
+
+/// JSON-RPC error object.
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Option<Value>,
+}
+
+impl RpcError {
+    fn invalid_params(message: impl Into<String>) -> Self {
+        Self { code: -32602, message: message.into(), data: None }
+    }
+}
+
+impl From<ChainError> for RpcError {
+    fn from(e: ChainError) -> Self {
+        match e {
+            ChainError::Reverted(revert) => Self {
+                code: 3,
+                message: match revert.message() {
+                    Some(reason) if !reason.is_empty() => format!("execution reverted: {reason}"),
+                    _ => "execution reverted".into(),
+                },
+                data: Some(json!(revert.0)),
+            },
+            ChainError::Rejected(message) => Self { code: -32000, message, data: None },
+            ChainError::InvalidParams(message) => Self::invalid_params(message),
+        }
+    }
+}
+
+type RpcResult = Result<Value, RpcError>;
+
+pub fn router(chain: SharedChain) -> Router {
+    Router::new().route("/", post(handle)).with_state(chain)
+}
+
+async fn handle(State(chain): State<SharedChain>, Json(body): Json<Value>) -> Json<Value> {
+    match body {
+        Value::Array(requests) => Json(Value::Array(requests.into_iter().map(|r| respond(&chain, r)).collect())),
+        request => Json(respond(&chain, request)),
+    }
+}
+
+fn respond(chain: &SharedChain, request: Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let method = request.get("method").and_then(Value::as_str).unwrap_or_default().to_string();
+    let params = match request.get("params") {
+        Some(Value::Array(p)) => p.clone(),
+        _ => Vec::new(),
+    };
+    log::debug!("{method} {params:?}");
+
+    let result = {
+        let mut chain = chain.lock().expect("chain lock poisoned");
+        dispatch(&mut chain, &method, &params)
+    };
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => {
+            let mut error = json!({ "code": e.code, "message": e.message });
+            if let Some(data) = e.data {
+                error["data"] = data;
+            }
+            json!({ "jsonrpc": "2.0", "id": id, "error": error })
+        }
+    }
+}
+
+fn param<T: DeserializeOwned>(params: &[Value], index: usize) -> Result<T, RpcError> {
+    let value = params.get(index).cloned().unwrap_or(Value::Null);
+    serde_json::from_value(value).map_err(|e| RpcError::invalid_params(format!("param {index}: {e}")))
+}
+
+fn opt_param<T: DeserializeOwned>(params: &[Value], index: usize) -> Result<Option<T>, RpcError> {
+    match params.get(index) {
+        None | Some(Value::Null) => Ok(None),
+        Some(_) => param(params, index).map(Some),
+    }
+}
+
+fn to_value<T: serde::Serialize>(value: T) -> RpcResult {
+    Ok(serde_json::to_value(value).expect("RPC types serialize"))
+}
+
+fn call_request(tx: TransactionRequest) -> Result<CallRequest, RpcError> {
+    let to = match tx.to {
+        None => None,
+        Some(NameOrAddress::Address(a)) => Some(a),
+        Some(NameOrAddress::Name(n)) => return Err(RpcError::invalid_params(format!("ENS name {n} not supported"))),
+    };
+    Ok(CallRequest {
+        from: tx.from.unwrap_or_default(),
+        to,
+        value: tx.value.unwrap_or_default(),
+        data: tx.data.unwrap_or_default(),
+    })
+}
+
+fn dispatch(chain: &mut Chain, method: &str, params: &[Value]) -> RpcResult {
+    match method {
+        "eth_chainId" => to_value(U64::from(chain.chain_id())),
+        "net_version" => to_value(chain.chain_id().to_string()),
+        "web3_clientVersion" => to_value(concat!("vericortex-devchain/", env!("CARGO_PKG_VERSION"))),
+        "eth_blockNumber" => to_value(U64::from(chain.block_number())),
+        "eth_gasPrice" => to_value(chain.base_fee() + crate::DEFAULT_PRIORITY_FEE),
+        "eth_maxPriorityFeePerGas" => to_value(U256::from(crate::DEFAULT_PRIORITY_FEE)),
+        "eth_accounts" => to_value(Vec::<ethers::types::Address>::new()),
+        "eth_getBalance" => to_value(chain.balance(&param(params, 0)?, opt_param(params, 1)?)?),
+        "eth_getCode" => to_value(chain.code(&param(params, 0)?, opt_param(params, 1)?)?),
+        "eth_getTransactionCount" => to_value(U256::from(chain.nonce(&param(params, 0)?, opt_param(params, 1)?)?)),
+        "eth_call" => {
+            let req = call_request(param(params, 0)?)?;
+            to_value(chain.call(&req, opt_param(params, 1)?)?)
+        }
+        "eth_estimateGas" => {
+            let req = call_request(param(params, 0)?)?;
+            to_value(U256::from(chain.estimate_gas(&req)?))
+        }
+        "eth_sendRawTransaction" => {
+            let raw: Bytes = param(params, 0)?;
+            to_value(chain.send_raw(&raw)?)
+        }
+        "eth_getTransactionByHash" => to_value(chain.transaction(&param::<H256>(params, 0)?)),
+        "eth_getTransactionReceipt" => to_value(chain.receipt(&param::<H256>(params, 0)?)),
+        "eth_getBlockByNumber" | "eth_getBlockByHash" => {
+            let block: BlockId = if method == "eth_getBlockByHash" {
+                BlockId::Hash(param(params, 0)?)
+            } else {
+                BlockId::Number(param::<BlockNumber>(params, 0)?)
+            };
+            let full: bool = opt_param(params, 1)?.unwrap_or(false);
+            if full {
+                to_value(chain.block_with_txs(block))
+            } else {
+                to_value(chain.block(block))
+            }
+        }
+        "eth_feeHistory" => {
+            let count: U256 = param(params, 0)?;
+            let newest: BlockNumber = param(params, 1)?;
+            let percentiles: Vec<f64> = opt_param(params, 2)?.unwrap_or_default();
+            to_value(chain.fee_history(count.as_u64(), newest, &percentiles))
+        }
+        "eth_getLogs" => to_value(chain.logs(&param::<Filter>(params, 0)?)?),
+        "eth_newFilter" => to_value(chain.new_filter(param(params, 0)?)),
+        "eth_newBlockFilter" => to_value(chain.new_block_filter()),
+        "eth_getFilterChanges" => Ok(chain.filter_changes(&param(params, 0)?)?),
+        "eth_getFilterLogs" => to_value(chain.filter_logs(&param(params, 0)?)?),
+        "eth_uninstallFilter" => to_value(chain.uninstall_filter(&param(params, 0)?)),
+        "evm_mine" => to_value(U64::from(chain.mine())),
+        "evm_setAutomine" => {
+            chain.set_automine(param(params, 0)?);
+            to_value(true)
+        }
+        _ => Err(RpcError { code: -32601, message: format!("method {method} not supported"), data: None }),
+    }
+}
+
@@ -0,0 +1,168 @@
+//! `ProofCortexVerifier` (`verifier/BlockDAG_Verifier.sol`) re-implemented
+//! natively: same storage layout, `require` messages, return values and events.
+//! `tests/evm.rs` checks it against the solc-compiled contract.
+
+use std::collections::HashMap;
+
+use ethers::abi::{self, AbiDecode, AbiEncode, Token};
+use ethers::contract::EthEvent;
+use ethers::types::{Address, Bytes, H256, U256};
+use ethers::utils::keccak256;
+use vericortex_sdk::contract::{
+    GetProofReturn, IsProofValidReturn, ProofAnchoredFilter, ProofCortexVerifierCalls, ProofRecord,
+    ProofSubmittedFilter, ProofUpdatedFilter, ProofsReturn, SubmitProofReturn, TrustedSubmitterReturn,
+};
+
+use crate::chain::{EmittedLog, Revert};
+
+fn require(cond: bool, reason: &str) -> Result<(), Revert> {
+    if cond {
+        Ok(())
+    } else {
+        Err(Revert::reason(reason))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Verifier {
+    pub trusted_submitter: Address,
+    pub proofs: HashMap<[u8; 32], ProofRecord>,
+}
+
+/// Result of executing one call.
+pub struct Outcome {
+    pub output: Bytes,
+    pub logs: Vec<EmittedLog>,
+    pub gas_used: u64,
+}
+
+impl Verifier {
+    pub fn new(trusted_submitter: Address) -> Self {
+        Self { trusted_submitter, proofs: HashMap::new() }
+    }
+
+    fn only_trusted(&self, sender: Address) -> Result<(), Revert> {
+        require(sender == self.trusted_submitter, "Not authorized")
+    }
+
+    fn empty_record() -> ProofRecord {
+        ProofRecord {
+            model_id: String::new(),
+            version: String::new(),
+            input_hash: [0; 32],
+            output_hash: [0; 32],
+            trace_hash: [0; 32],
+            proof_hash: [0; 32],
+            submitter: Address::zero(),
+            valid: false,
+            timestamp: U256::zero(),
+            locked: false,
+        }
+    }
+
+    fn record(&self, id: &[u8; 32]) -> ProofRecord {
+        self.proofs.get(id).cloned().unwrap_or_else(Self::empty_record)
+    }
+
+    /// Execute `data` as sent by `sender` in a block with `timestamp`.
+    pub fn execute(&mut self, sender: Address, data: &[u8], timestamp: u64) -> Result<Outcome, Revert> {
+        let call = ProofCortexVerifierCalls::decode(data).map_err(|_| Revert(Bytes::new()))?;
+        let now = U256::from(timestamp);
+        let outcome = |output: Vec<u8>, logs: Vec<EmittedLog>, gas_used: u64| Outcome {
+            output: output.into(),
+            logs,
+            gas_used,
+        };
+
+        match call {
+            ProofCortexVerifierCalls::SubmitProof(c) => {
+                self.only_trusted(sender)?;
+                // keccak256(abi.encodePacked(modelId, version, proofHash, msg.sender)),
+                // spelled out here rather than taken from the SDK it is used to test.
+                let packed = abi::encode_packed(&[
+                    Token::String(c.model_id.clone()),
+                    Token::String(c.version.clone()),
+                    Token::FixedBytes(c.proof_hash.to_vec()),
+                    Token::Address(sender),
+                ])
+                .expect("no dynamic arrays to pack");
+                let proof_id = keccak256(packed);
+                require(self.record(&proof_id).submitter == Address::zero(), "Proof exists")?;
+
+                self.proofs.insert(
+                    proof_id,
+                    ProofRecord {
+                        model_id: c.model_id.clone(),
+                        version: c.version,
+                        input_hash: c.input_hash,
+                        output_hash: c.output_hash,
+                        trace_hash: c.trace_hash,
+                        proof_hash: c.proof_hash,
+                        submitter: sender,
+                        valid: c.valid,
+                        timestamp: now,
+                        locked: false,
+                    },
+                );
+
+                let submitted = EmittedLog {
+                    topics: vec![ProofSubmittedFilter::signature(), H256(proof_id), H256::from(sender)],
+                    data: abi::encode(&[Token::String(c.model_id), Token::Bool(c.valid)]).into(),
+                };
+                let anchored = EmittedLog {
+                    topics: vec![ProofAnchoredFilter::signature(), H256(proof_id)],
+                    data: abi::encode(&[Token::FixedBytes(c.proof_hash.to_vec()), Token::Uint(now)]).into(),
+                };
+                Ok(outcome(SubmitProofReturn { proof_id }.encode(), vec![submitted, anchored], 180_000))
+            }
+            ProofCortexVerifierCalls::UpdateProofResult(c) => {
+                self.only_trusted(sender)?;
+                // A lockProof on an unknown id leaves a record with no submitter,
+                // which is still "Not found".
+                require(self.record(&c.proof_id).submitter != Address::zero(), "Not found")?;
+                let p = self.proofs.get_mut(&c.proof_id).expect("checked above");
+                require(!p.locked, "Immutable")?;
+                p.valid = c.new_validity;
+                p.timestamp = now;
+
+                let updated = EmittedLog {
+                    topics: vec![ProofUpdatedFilter::signature(), H256(c.proof_id)],
+                    data: abi::encode(&[Token::Bool(c.new_validity), Token::Uint(now)]).into(),
+                };
+                Ok(outcome(Vec::new(), vec![updated], 40_000))
+            }
+            ProofCortexVerifierCalls::LockProof(c) => {
+                self.only_trusted(sender)?;
+                // Like the Solidity mapping write, this creates the slot if absent.
+                self.proofs.entry(c.proof_id).or_insert_with(Self::empty_record).locked = true;
+                Ok(outcome(Vec::new(), Vec::new(), 30_000))
+            }
+            ProofCortexVerifierCalls::GetProof(c) => {
+                Ok(outcome(GetProofReturn(self.record(&c.proof_id)).encode(), Vec::new(), 30_000))
+            }
+            ProofCortexVerifierCalls::IsProofValid(c) => {
+                let valid = self.record(&c.proof_id).valid;
+                Ok(outcome(IsProofValidReturn(valid).encode(), Vec::new(), 25_000))
+            }
+            ProofCortexVerifierCalls::Proofs(c) => {
+                let r = self.record(&c.0);
+                let ret = ProofsReturn {
+                    model_id: r.model_id,
+                    version: r.version,
+                    input_hash: r.input_hash,
+                    output_hash: r.output_hash,
+                    trace_hash: r.trace_hash,
+                    proof_hash: r.proof_hash,
+                    submitter: r.submitter,
+                    valid: r.valid,
+                    timestamp: r.timestamp,
+                    locked: r.locked,
+                };
+                Ok(outcome(ret.encode(), Vec::new(), 30_000))
+            }
+            ProofCortexVerifierCalls::TrustedSubmitter(_) => {
+                Ok(outcome(TrustedSubmitterReturn(self.trusted_submitter).encode(), Vec::new(), 23_000))
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use ethers::abi::AbiEncode;
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use vericortex_devchain::{dev_address, dev_wallet, DevChain};
+use vericortex_sdk::contract::ProofCortexVerifier;
+use vericortex_sdk::{compute_proof_id, Hash32, ProofIdPreimage};
+
+type Client = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+fn client(chain: &DevChain, account: usize) -> Arc<Client> {
+    let provider = Provider::<Http>::try_from(chain.url()).unwrap().interval(std::time::Duration::from_millis(10));
+    Arc::new(SignerMiddleware::new(provider, dev_wallet(account).with_chain_id(chain.chain_id())))
+}
+
+async fn submit(verifier: &ProofCortexVerifier<Client>, proof_hash: u8) -> TransactionReceipt {
+    verifier
+        .submit_proof("m".into(), "v1".into(), [1; 32], [2; 32], [0; 32], [proof_hash; 32], true)
+        .send()
+        .await
+        .unwrap()
+        .await
+        .unwrap()
+        .expect("mined")
+}
+
+#[tokio::test]
+async fn calls_revert_with_the_contract_reason() {
+    let chain = DevChain::spawn().unwrap();
+    let address = chain.deploy_verifier(dev_address(0));
+    let outsider = ProofCortexVerifier::new(address, client(&chain, 1));
+
+    let err = outsider
+        .submit_proof("m".into(), "v1".into(), [1; 32], [2; 32], [0; 32], [3; 32], true)
+        .call()
+        .await
+        .unwrap_err();
+    assert_eq!(err.decode_revert::<String>().as_deref(), Some("Not authorized"));
+
+    let trusted = ProofCortexVerifier::new(address, client(&chain, 0));
+    let receipt = submit(&trusted, 3).await;
+    assert_eq!(receipt.status, Some(1.into()));
+    let proof_id = vericortex_sdk::contract::submitted_proof_id(&receipt).unwrap();
+    let record = trusted.get_proof(proof_id.0).call().await.unwrap();
+    assert_eq!(record.proof_hash, [3; 32]);
+    assert!(trusted.is_proof_valid(proof_id.0).call().await.unwrap());
+}
+
+/// `keccak256(abi.encodePacked("m", "v1", bytes32(0x0303…03), dev_address(0)))`,
+/// computed outside this repository with a standalone Keccak-256.
+const PROOF_ID_M_V1_03: &str = "0x7d8a8c23ec579216ed4c771df19672b09f7e980967d20b8b2859f234cecc0ecc";
+
+#[tokio::test]
+async fn proof_ids_and_reverts_follow_the_solidity() {
+    let chain = DevChain::spawn().unwrap();
+    let address = chain.deploy_verifier(dev_address(0));
+    let trusted = ProofCortexVerifier::new(address, client(&chain, 0));
+
+    let receipt = submit(&trusted, 3).await;
+    let proof_id = vericortex_sdk::contract::submitted_proof_id(&receipt).unwrap();
+    assert_eq!(proof_id.to_string(), PROOF_ID_M_V1_03);
+    let preimage = ProofIdPreimage::ProofCortex { model_id: &"m".into(), version: "v1", proof_hash: &Hash32([3; 32]) };
+    assert_eq!(compute_proof_id(&preimage, &vericortex_sdk::Address(dev_address(0).0)), proof_id);
+
+    // lockProof on an unknown id writes the slot but leaves submitter zero,
+    // so an update is "Not found", not "Immutable".
+    let unknown = [9; 32];
+    trusted.lock_proof(unknown).send().await.unwrap().await.unwrap();
+    let err = trusted.update_proof_result(unknown, true).call().await.unwrap_err();
+    assert_eq!(err.decode_revert::<String>().as_deref(), Some("Not found"));
+
+    trusted.lock_proof(proof_id.0).send().await.unwrap().await.unwrap();
+    let err = trusted.update_proof_result(proof_id.0, false).call().await.unwrap_err();
+    assert_eq!(err.decode_revert::<String>().as_deref(), Some("Immutable"));
+}
+
+#[tokio::test]
+async fn reorg_removes_logs_and_state() {
+    let chain = DevChain::spawn().unwrap();
+    let address = chain.deploy_verifier(dev_address(0));
+    let verifier = ProofCortexVerifier::new(address, client(&chain, 0));
+
+    let receipt = submit(&verifier, 7).await;
+    let proof_id = vericortex_sdk::contract::submitted_proof_id(&receipt).unwrap();
+    let mined_at = receipt.block_number.unwrap().as_u64();
+    assert_eq!(chain.logs().len(), 2);
+
+    let head = chain.reorg(1);
+    assert_eq!(head, mined_at + 1);
+    assert!(chain.logs().is_empty());
+    assert!(chain.proof(address, proof_id.0).is_none());
+    let provider = verifier.client().provider().clone();
+    assert!(provider.get_transaction_receipt(receipt.transaction_hash).await.unwrap().is_none());
+    let block = provider.get_block(mined_at).await.unwrap().unwrap();
+    assert_ne!(block.hash, receipt.block_hash);
+}
+
+#[tokio::test]
+async fn queued_transactions_need_a_fee_bump_to_be_replaced() {
+    let chain = DevChain::spawn().unwrap();
+    chain.set_automine(false);
+    let client = client(&chain, 0);
+    let to = dev_address(1);
+
+    let tx = |tip: u64| {
+        Eip1559TransactionRequest::new()
+            .to(to)
+            .value(1)
+            .nonce(0)
+            .max_priority_fee_per_gas(tip)
+            .max_fee_per_gas(10 * tip)
+    };
+    client.send_transaction(tx(1_000_000_000), None).await.unwrap();
+    let err = client.send_transaction(tx(1_050_000_000), None).await.unwrap_err();
+    assert!(err.to_string().contains("replacement transaction underpriced"), "{err}");
+    let replacement = client.send_transaction(tx(1_100_000_000), None).await.unwrap().tx_hash();
+    assert_eq!(chain.pending().len(), 1);
+
+    chain.mine();
+    let receipt = client.get_transaction_receipt(replacement).await.unwrap().unwrap();
+    assert_eq!(receipt.status, Some(1.into()));
+    assert!(chain.pending().is_empty());
+}
+
+/// Stores the first calldata word in slot 0, logs it and returns it.
+const ECHO_RUNTIME: &str = "6000358060005560005260206000a060206000f3";
+/// Copies `ECHO_RUNTIME` (20 bytes at offset 12) out as the deployed code.
+const ECHO_INIT: &str = "6014600c60003960146000f3";
+
+#[tokio::test]
+async fn compiled_contracts_run_on_the_evm() {
+    let chain = DevChain::spawn().unwrap();
+    let init: Bytes = format!("0x{ECHO_INIT}{ECHO_RUNTIME}").parse().unwrap();
+    let address = chain.deploy_code(&init).unwrap();
+    let client = client(&chain, 0);
+    let runtime: Bytes = format!("0x{ECHO_RUNTIME}").parse().unwrap();
+    assert_eq!(client.get_code(address, None).await.unwrap(), runtime);
+
+    let word = Bytes::from(U256::from(7).encode());
+    let tx: TypedTransaction = TransactionRequest::new().to(address).data(word.clone()).into();
+    assert_eq!(client.call(&tx, None).await.unwrap(), word);
+    let receipt = client.send_transaction(tx, None).await.unwrap().await.unwrap().expect("mined");
+    assert_eq!(receipt.status, Some(1.into()));
+    assert_eq!(receipt.logs[0].address, address);
+    assert_eq!(receipt.logs[0].data, word);
+    // Metered by the EVM: the intrinsic 21000 plus a fresh SSTORE.
+    assert!(receipt.gas_used.unwrap() > U256::from(21_000 + 20_000));
+}
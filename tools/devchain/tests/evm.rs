@@ -0,0 +1,140 @@
+//! The native `ProofCortexVerifier` against the solc-compiled contract on revm:
+//! every call must return, revert and log the same.
+
+use std::sync::Arc;
+
+use ethers::abi::{self, AbiEncode, Token};
+use ethers::prelude::*;
+use vericortex_devchain::{dev_address, dev_wallet, DevChain};
+use vericortex_sdk::contract::*;
+
+type Client = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+/// Written by `tools/build-contracts.sh` when `SOLC` is set.
+const ARTIFACT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../verifier/out/ProofCortexVerifier.json");
+
+#[derive(serde::Deserialize)]
+struct Artifact {
+    bytecode: Bytes,
+}
+
+fn client(chain: &DevChain, account: usize) -> Arc<Client> {
+    let provider = Provider::<Http>::try_from(chain.url()).unwrap().interval(std::time::Duration::from_millis(10));
+    Arc::new(SignerMiddleware::new(provider, dev_wallet(account).with_chain_id(chain.chain_id())))
+}
+
+/// Return data, or the revert data of a failed call.
+async fn eth_call(client: &Client, to: Address, data: &Bytes) -> Result<Bytes, Bytes> {
+    let tx = TransactionRequest::new().from(client.address()).to(to).data(data.clone()).into();
+    client.call(&tx, None).await.map_err(|e| {
+        let response = e.as_error_response().expect("a JSON-RPC error");
+        response.as_revert_data().unwrap_or_default()
+    })
+}
+
+struct Verifiers {
+    chain: DevChain,
+    native: Address,
+    compiled: Address,
+}
+
+impl Verifiers {
+    /// Call both verifiers from `account`, then send the call to both in one
+    /// block; returns the (common) call result.
+    async fn step(&self, account: usize, call: ProofCortexVerifierCalls) -> Result<Bytes, Bytes> {
+        let client = client(&self.chain, account);
+        let data: Bytes = call.clone().encode().into();
+        let native = eth_call(&client, self.native, &data).await;
+        let compiled = eth_call(&client, self.compiled, &data).await;
+        assert_eq!(native, compiled, "eth_call {call:?}");
+
+        let nonce = client.get_transaction_count(client.address(), None).await.unwrap();
+        let mut hashes = Vec::new();
+        for (i, to) in [self.native, self.compiled].into_iter().enumerate() {
+            let tx = TransactionRequest::new().to(to).data(data.clone()).gas(1_000_000).nonce(nonce + i);
+            hashes.push(client.send_transaction(tx, None).await.unwrap().tx_hash());
+        }
+        self.chain.mine();
+        let mut receipts = Vec::new();
+        for hash in hashes {
+            receipts.push(client.get_transaction_receipt(hash).await.unwrap().expect("mined"));
+        }
+        assert_eq!(receipts[0].status, receipts[1].status, "status {call:?}");
+        let logs = |r: &TransactionReceipt| r.logs.iter().map(|l| (l.topics.clone(), l.data.clone())).collect::<Vec<_>>();
+        assert_eq!(logs(&receipts[0]), logs(&receipts[1]), "logs {call:?}");
+        native
+    }
+}
+
+fn submit(model_id: &str, version: &str, proof_hash: u8, valid: bool) -> ProofCortexVerifierCalls {
+    ProofCortexVerifierCalls::SubmitProof(SubmitProofCall {
+        model_id: model_id.into(),
+        version: version.into(),
+        input_hash: [1; 32],
+        output_hash: [2; 32],
+        trace_hash: [0; 32],
+        proof_hash: [proof_hash; 32],
+        valid,
+    })
+}
+
+fn reads(proof_id: [u8; 32]) -> [ProofCortexVerifierCalls; 3] {
+    [
+        ProofCortexVerifierCalls::GetProof(GetProofCall { proof_id }),
+        ProofCortexVerifierCalls::Proofs(ProofsCall(proof_id)),
+        ProofCortexVerifierCalls::IsProofValid(IsProofValidCall { proof_id }),
+    ]
+}
+
+fn update(proof_id: [u8; 32], new_validity: bool) -> ProofCortexVerifierCalls {
+    ProofCortexVerifierCalls::UpdateProofResult(UpdateProofResultCall { proof_id, new_validity })
+}
+
+fn lock(proof_id: [u8; 32]) -> ProofCortexVerifierCalls {
+    ProofCortexVerifierCalls::LockProof(LockProofCall { proof_id })
+}
+
+#[tokio::test]
+#[ignore = "needs solc; run tools/build-contracts.sh with SOLC set"]
+async fn the_native_verifier_matches_the_compiled_contract() {
+    let artifact = std::fs::read_to_string(ARTIFACT).expect("compiled verifier artifact");
+    let artifact: Artifact = serde_json::from_str(&artifact).unwrap();
+    let mut init = artifact.bytecode.to_vec();
+    init.extend(abi::encode(&[Token::Address(dev_address(0))]));
+
+    let chain = DevChain::spawn().unwrap();
+    chain.set_automine(false);
+    let compiled = chain.deploy_code(&init).unwrap();
+    let native = chain.deploy_verifier(dev_address(0));
+    let v = Verifiers { chain, native, compiled };
+
+    v.step(1, ProofCortexVerifierCalls::TrustedSubmitter(TrustedSubmitterCall)).await.unwrap();
+    assert!(v.step(1, submit("m", "v1", 3, true)).await.is_err());
+
+    let short = v.step(0, submit("m", "v1", 3, true)).await.unwrap();
+    assert!(v.step(0, submit("m", "v1", 3, false)).await.is_err());
+    // Strings over 31 bytes take the long storage encoding.
+    let long_id = "org/a-model-name-well-past-thirty-one-bytes";
+    let long = v.step(0, submit(long_id, "2025.08-commit-0123456789abcdef0123", 4, false)).await.unwrap();
+    let unknown = [9; 32];
+    for proof_id in [short.as_ref(), long.as_ref(), &unknown[..]] {
+        for call in reads(proof_id.try_into().unwrap()) {
+            v.step(2, call).await.unwrap();
+        }
+    }
+
+    let short: [u8; 32] = short.as_ref().try_into().unwrap();
+    assert!(v.step(0, update(unknown, true)).await.is_err());
+    v.step(0, lock(unknown)).await.unwrap();
+    assert!(v.step(0, update(unknown, true)).await.is_err());
+    assert!(v.step(1, update(short, false)).await.is_err());
+    v.step(0, update(short, false)).await.unwrap();
+    assert!(v.step(1, lock(short)).await.is_err());
+    v.step(0, lock(short)).await.unwrap();
+    assert!(v.step(0, update(short, true)).await.is_err());
+    for proof_id in [short, unknown] {
+        for call in reads(proof_id) {
+            v.step(2, call).await.unwrap();
+        }
+    }
+}
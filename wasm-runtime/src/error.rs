@@ -0,0 +1,24 @@
+use thiserror::Error;
+use vericortex_sdk::Multihash;
+
+/// Why a model could not be loaded or re-executed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Error {
+    #[error("invalid module: {0}")]
+    Invalid(String),
+
+    #[error("module imports `{module}.{name}`; a deterministic model may not import anything")]
+    Import { module: String, name: String },
+
+    #[error("model ABI: {0}")]
+    Abi(String),
+
+    #[error("out of fuel (limit {0})")]
+    OutOfFuel(u64),
+
+    #[error("trap: {0}")]
+    Trap(String),
+
+    #[error("input hashes to {got}, the proof records {expected}")]
+    InputMismatch { expected: Multihash, got: Multihash },
+}
@@ -0,0 +1,199 @@
+//! Deterministic re-execution of models compiled to WebAssembly.
+//!
+//! A proof's `output_hash` only means something if anyone can re-run the
+//! model on the same input and get the same bytes back. This crate runs a
+//! model module under [`wasmi`] so that the result depends on nothing but
+//! the module and the input:
+//!
+//! - no imports: the module gets no clocks, randomness, WASI or other host
+//!   state, and a module that asks for any is refused;
+//! - NaN results of float arithmetic are rewritten to the canonical NaN;
+//! - WebAssembly 2.0 without SIMD and threads;
+//! - execution is metered with fuel and stops when [`Limits::fuel`] runs
+//!   out, and linear memory cannot grow past [`Limits::memory`].
+//!
+//! # Model ABI
+//!
+//! The module exports:
+//!
+//! - `memory`: its linear memory;
+//! - `alloc(len: i32) -> i32`: a buffer of `len` bytes for the input;
+//! - `infer(ptr: i32, len: i32) -> i64`: run on the input at `ptr` and
+//!   return the output's location as `ptr << 32 | len`.
+//!
+//! The output bytes are hashed as they are, so a model whose output is JSON
+//! or a tensor should emit its canonical encoding (see
+//! [`vericortex_sdk::canonical`]) to match `hash-output`.
+//!
+//! [`WasmAdapter`] plugs re-execution into the SDK's adapter registry as the
+//! `wasm` proof type.
+
+pub mod error;
+mod nan;
+
+use wasmi::core::TrapCode;
+use wasmi::{CompilationMode, Config, Engine, Linker, Store, StoreLimits, StoreLimitsBuilder};
+
+use vericortex_sdk::{AdapterError, HashAlg, Multihash, ProofAdapter, ProofJson, Verdict};
+
+pub use error::Error;
+
+/// Resource limits for one execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Fuel for instantiation plus `alloc` plus `infer`; roughly one unit
+    /// per executed instruction.
+    pub fuel: u64,
+    /// Largest linear memory, in bytes.
+    pub memory: usize,
+    /// Largest output, in bytes.
+    pub output: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self { fuel: 10_000_000_000, memory: 512 << 20, output: 16 << 20 }
+    }
+}
+
+/// A validated, NaN-canonicalized and compiled model module.
+pub struct Model {
+    engine: Engine,
+    module: wasmi::Module,
+}
+
+impl Model {
+    pub fn new(wasm: &[u8]) -> Result<Self, Error> {
+        let wasm = nan::canonicalize(wasm)?;
+        let mut config = Config::default();
+        // Eager compilation, so fuel is spent on execution alone.
+        config.consume_fuel(true).compilation_mode(CompilationMode::Eager);
+        let engine = Engine::new(&config);
+        let module = wasmi::Module::new(&engine, &wasm[..]).map_err(|e| Error::Invalid(e.to_string()))?;
+        Ok(Self { engine, module })
+    }
+
+    /// Run the model on `input`.
+    pub fn run(&self, input: &[u8], limits: &Limits) -> Result<Execution, Error> {
+        let state = StoreLimitsBuilder::new()
+            .memory_size(limits.memory)
+            .instances(1)
+            .trap_on_grow_failure(true)
+            .build();
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| state);
+        store.set_fuel(limits.fuel).expect("fuel metering is enabled");
+
+        let trap = |e: wasmi::Error| match e.as_trap_code() {
+            Some(TrapCode::OutOfFuel) => Error::OutOfFuel(limits.fuel),
+            _ => Error::Trap(e.to_string()),
+        };
+        let export = |name: &str, e: wasmi::Error| Error::Abi(format!("export `{name}`: {e}"));
+
+        let instance = Linker::<StoreLimits>::new(&self.engine)
+            .instantiate(&mut store, &self.module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(trap)?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| Error::Abi("no exported `memory`".into()))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&store, "alloc").map_err(|e| export("alloc", e))?;
+        let infer = instance.get_typed_func::<(i32, i32), i64>(&store, "infer").map_err(|e| export("infer", e))?;
+
+        let len = i32::try_from(input.len()).map_err(|_| Error::Abi(format!("input of {} bytes", input.len())))?;
+        let ptr = alloc.call(&mut store, len).map_err(trap)?;
+        memory
+            .write(&mut store, ptr as u32 as usize, input)
+            .map_err(|_| Error::Abi(format!("alloc returned {ptr:#x}, which cannot hold {len} bytes")))?;
+
+        let packed = infer.call(&mut store, (ptr, len)).map_err(trap)? as u64;
+        let (ptr, len) = ((packed >> 32) as usize, packed as u32 as usize);
+        if len > limits.output {
+            return Err(Error::Abi(format!("output of {len} bytes exceeds the {} byte limit", limits.output)));
+        }
+        let output = memory
+            .data(&store)
+            .get(ptr..ptr + len)
+            .ok_or_else(|| Error::Abi(format!("output [{ptr:#x}, +{len}) is outside linear memory")))?
+            .to_vec();
+        let fuel_consumed = limits.fuel - store.get_fuel().expect("fuel metering is enabled");
+        Ok(Execution { output, fuel_consumed })
+    }
+}
+
+/// The result of one run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Execution {
+    pub output: Vec<u8>,
+    /// Identical on every run of the same model on the same input.
+    pub fuel_consumed: u64,
+}
+
+impl Execution {
+    pub fn output_hash(&self, alg: HashAlg) -> Multihash {
+        alg.digest(&self.output)
+    }
+}
+
+/// Re-run `model` on `input` for `proof`: `input` must hash to the proof's
+/// `input_hash`, and the output is hashed with the algorithm of its
+/// `output_hash`.
+pub fn recompute_output_hash(
+    model: &Model,
+    proof: &ProofJson,
+    input: &[u8],
+    limits: &Limits,
+) -> Result<(Multihash, Execution), Error> {
+    let got = proof.input_hash.alg.digest(input);
+    if got != proof.input_hash {
+        return Err(Error::InputMismatch { expected: proof.input_hash, got });
+    }
+    let execution = model.run(input, limits)?;
+    Ok((execution.output_hash(proof.output_hash.alg), execution))
+}
+
+/// The `wasm` [`ProofAdapter`]: re-runs one model on one input, so it is
+/// built for the proof at hand.
+pub struct WasmAdapter {
+    model: Model,
+    input: Vec<u8>,
+    limits: Limits,
+}
+
+impl WasmAdapter {
+    pub const PROOF_TYPE: &'static str = "wasm";
+
+    pub fn new(model: Model, input: Vec<u8>, limits: Limits) -> Self {
+        Self { model, input, limits }
+    }
+}
+
+impl ProofAdapter for WasmAdapter {
+    fn proof_type(&self) -> &str {
+        Self::PROOF_TYPE
+    }
+
+    fn verify(&self, proof: &ProofJson) -> Result<Verdict, AdapterError> {
+        match recompute_output_hash(&self.model, proof, &self.input, &self.limits) {
+            Ok((recomputed, execution)) => {
+                let valid = recomputed == proof.output_hash;
+                let reason = if valid {
+                    "re-running the model reproduces output_hash"
+                } else {
+                    "re-running the model gives a different output_hash"
+                };
+                Ok(Verdict::new(Self::PROOF_TYPE, valid, reason)
+                    .with("algorithm", recomputed.alg)
+                    .with("output_hash", proof.output_hash)
+                    .with("recomputed_output_hash", recomputed)
+                    .with("fuel_consumed", execution.fuel_consumed))
+            }
+            Err(Error::InputMismatch { expected, got }) => {
+                Ok(Verdict::new(Self::PROOF_TYPE, false, "the input does not match input_hash")
+                    .with("input_hash", expected)
+                    .with("recomputed_input_hash", got))
+            }
+            Err(e) => Err(AdapterError::Backend { proof_type: Self::PROOF_TYPE.into(), message: e.to_string() }),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! NaN canonicalization.
+//!
+//! WebAssembly leaves the sign and payload of a NaN produced by float
+//! arithmetic up to the host, so the same module can write different bits
+//! on different machines. Before compiling, every instruction that may
+//! produce such a NaN is followed by a check that replaces any NaN with the
+//! canonical one (`0x7fc00000` / `0x7ff8000000000000`):
+//!
+//! ```text
+//! local.tee $t  f32.const nan  local.get $t  local.get $t  f32.eq  select
+//! ```
+//!
+//! Bit-level operations (`abs`, `neg`, `copysign`, loads, stores,
+//! reinterprets) are deterministic and left alone.
+
+use wasm_encoder::reencode::{self, Reencode};
+use wasm_encoder::{CodeSection, Function, Ieee32, Ieee64, Instruction, ValType};
+use wasmparser::{FunctionBody, Operator, Parser, Payload, Validator, WasmFeatures};
+
+use crate::error::Error;
+
+const CANONICAL_F32: u32 = 0x7fc0_0000;
+const CANONICAL_F64: u64 = 0x7ff8_0000_0000_0000;
+
+/// WebAssembly 2.0 without SIMD, whose float lanes would need the same
+/// treatment. Threads, memory64 and the GC proposals stay off.
+fn features() -> WasmFeatures {
+    WasmFeatures::WASM2.difference(WasmFeatures::SIMD)
+}
+
+#[derive(Clone, Copy)]
+enum Float {
+    F32,
+    F64,
+}
+
+/// The float width of `op` when its result may be a NaN with host-chosen
+/// bits.
+fn produces_nan(op: &Operator) -> Option<Float> {
+    use Operator::*;
+    match op {
+        F32Add | F32Sub | F32Mul | F32Div | F32Sqrt | F32Min | F32Max | F32Ceil | F32Floor | F32Trunc
+        | F32Nearest | F32DemoteF64 => Some(Float::F32),
+        F64Add | F64Sub | F64Mul | F64Div | F64Sqrt | F64Min | F64Max | F64Ceil | F64Floor | F64Trunc
+        | F64Nearest | F64PromoteF32 => Some(Float::F64),
+        _ => None,
+    }
+}
+
+/// Validate `wasm` and return it with every NaN canonicalized.
+pub(crate) fn canonicalize(wasm: &[u8]) -> Result<Vec<u8>, Error> {
+    let invalid = |e: wasmparser::BinaryReaderError| Error::Invalid(e.to_string());
+    Validator::new_with_features(features()).validate_all(wasm).map_err(invalid)?;
+
+    // Parameter counts of the defined functions, in order, so the scratch
+    // locals can be appended after the declared ones.
+    let mut signatures = Vec::new();
+    let mut params = Vec::new();
+    for payload in Parser::new(0).parse_all(wasm) {
+        match payload.map_err(invalid)? {
+            Payload::TypeSection(section) => {
+                for ty in section.into_iter_err_on_gc_types() {
+                    signatures.push(ty.map_err(invalid)?.params().len() as u32);
+                }
+            }
+            Payload::ImportSection(section) => {
+                if let Some(import) = section.into_iter().next() {
+                    let import = import.map_err(invalid)?;
+                    return Err(Error::Import { module: import.module.into(), name: import.name.into() });
+                }
+            }
+            Payload::FunctionSection(section) => {
+                for ty in section {
+                    params.push(signatures[ty.map_err(invalid)? as usize]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut module = wasm_encoder::Module::new();
+    let mut canonicalizer = Canonicalizer { params: params.into_iter() };
+    canonicalizer
+        .parse_core_module(&mut module, Parser::new(0), wasm)
+        .map_err(|e| Error::Invalid(e.to_string()))?;
+    Ok(module.finish())
+}
+
+struct Canonicalizer {
+    params: std::vec::IntoIter<u32>,
+}
+
+impl Reencode for Canonicalizer {
+    type Error = std::convert::Infallible;
+
+    fn parse_function_body(
+        &mut self,
+        code: &mut CodeSection,
+        func: FunctionBody<'_>,
+    ) -> Result<(), reencode::Error<Self::Error>> {
+        let mut locals = Vec::new();
+        let mut next = self.params.next().expect("one function body per declared function");
+        for pair in func.get_locals_reader()? {
+            let (count, ty) = pair?;
+            locals.push((count, self.val_type(ty)?));
+            next += count;
+        }
+        let (t32, t64) = (next, next + 1);
+        locals.push((1, ValType::F32));
+        locals.push((1, ValType::F64));
+
+        let mut f = Function::new(locals);
+        let mut reader = func.get_operators_reader()?;
+        while !reader.eof() {
+            let op = reader.read()?;
+            let float = produces_nan(&op);
+            f.instruction(&self.instruction(op)?);
+            match float {
+                Some(Float::F32) => {
+                    canonical_nan(&mut f, t32, Instruction::F32Const(Ieee32::new(CANONICAL_F32)), Instruction::F32Eq)
+                }
+                Some(Float::F64) => {
+                    canonical_nan(&mut f, t64, Instruction::F64Const(Ieee64::new(CANONICAL_F64)), Instruction::F64Eq)
+                }
+                None => {}
+            }
+        }
+        code.function(&f);
+        Ok(())
+    }
+}
+
+/// Replace the float on top of the stack with `nan` if it is a NaN.
+fn canonical_nan(f: &mut Function, scratch: u32, nan: Instruction<'_>, eq: Instruction<'_>) {
+    f.instruction(&Instruction::LocalTee(scratch))
+        .instruction(&nan)
+        .instruction(&Instruction::LocalGet(scratch))
+        .instruction(&Instruction::LocalGet(scratch))
+        .instruction(&eq)
+        .instruction(&Instruction::Select);
+}
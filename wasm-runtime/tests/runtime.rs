@@ -0,0 +1,156 @@
+use vericortex_sdk::{AdapterError, AdapterRegistry, HashAlg, ProofJson};
+use vericortex_wasm_runtime::{recompute_output_hash, Error, Limits, Model, WasmAdapter};
+
+/// Bump allocator plus an `infer` body that writes its output after the input.
+fn model(infer: &str) -> Model {
+    let wat = format!(
+        r#"(module
+            (memory (export "memory") 1)
+            (global $heap (mut i32) (i32.const 1024))
+            (func (export "alloc") (param $len i32) (result i32)
+                (global.get $heap)
+                (global.set $heap (i32.add (global.get $heap) (local.get $len))))
+            (func (export "infer") (param $ptr i32) (param $len i32) (result i64)
+                (local $i i32) (local $out i32)
+                (local.set $out (i32.add (local.get $ptr) (local.get $len)))
+                {infer}))"#
+    );
+    Model::new(&wat::parse_str(wat).unwrap()).unwrap()
+}
+
+/// Output `$len` bytes at `$out`.
+const RETURN: &str = "(i64.or (i64.shl (i64.extend_i32_u (local.get $out)) (i64.const 32)) (i64.extend_i32_u (local.get $len)))";
+
+fn reverse() -> Model {
+    model(&format!(
+        r#"(block $done (loop $next
+                (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+                (i32.store8
+                    (i32.add (local.get $out) (local.get $i))
+                    (i32.load8_u (i32.sub (i32.add (local.get $ptr) (local.get $len)) (i32.add (local.get $i) (i32.const 1)))))
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br $next)))
+            {RETURN}"#
+    ))
+}
+
+#[test]
+fn reexecution_recomputes_the_proof_output_hash() {
+    let model = reverse();
+    let input = b"the quick brown fox";
+    let mut output = input.to_vec();
+    output.reverse();
+
+    for alg in HashAlg::ALL {
+        let proof = ProofJson::new("vericortex-demo", alg.digest(input), alg.digest(&output));
+        let (hash, execution) = recompute_output_hash(&model, &proof, input, &Limits::default()).unwrap();
+        assert_eq!(execution.output, output);
+        assert_eq!(hash, proof.output_hash);
+    }
+
+    let forged = ProofJson::new("vericortex-demo", HashAlg::Sha2_256.digest(input), HashAlg::Sha2_256.digest(input));
+    let (hash, _) = recompute_output_hash(&model, &forged, input, &Limits::default()).unwrap();
+    assert_ne!(hash, forged.output_hash);
+
+    let other = HashAlg::Sha2_256.digest(b"another input");
+    assert_eq!(
+        recompute_output_hash(&model, &forged, b"another input", &Limits::default()).unwrap_err(),
+        Error::InputMismatch { expected: forged.input_hash, got: other }
+    );
+}
+
+#[test]
+fn adapter_verdict_comes_from_reexecution() {
+    let input = b"abc".to_vec();
+    let mut proof = ProofJson::new("vericortex-demo", HashAlg::Blake3.digest(&input), HashAlg::Blake3.digest(b"cba"));
+    let mut registry = AdapterRegistry::default();
+    registry.register(WasmAdapter::new(reverse(), input.clone(), Limits::default()));
+    registry.set_default(WasmAdapter::PROOF_TYPE);
+
+    let verdict = registry.verify(&proof).unwrap();
+    assert!(verdict.valid, "{}", verdict.reason);
+    assert!(verdict.details["fuel_consumed"].as_u64().unwrap() > 0);
+
+    proof.output_hash = HashAlg::Blake3.digest(b"abc");
+    assert!(!registry.verify(&proof).unwrap().valid);
+    proof.input_hash = HashAlg::Blake3.digest(b"xyz");
+    let verdict = registry.verify(&proof).unwrap();
+    assert_eq!((verdict.valid, &verdict.details["input_hash"]), (false, &serde_json::json!(proof.input_hash)));
+
+    registry.register(WasmAdapter::new(reverse(), input, Limits { fuel: 5, ..Limits::default() }));
+    proof.input_hash = HashAlg::Blake3.digest(b"abc");
+    assert!(matches!(registry.verify(&proof), Err(AdapterError::Backend { .. })));
+}
+
+#[test]
+fn fuel_is_deterministic_and_bounds_execution() {
+    let reverse = reverse();
+    let input = [7u8; 300];
+    let first = reverse.run(&input, &Limits::default()).unwrap();
+    assert_eq!(reverse.run(&input, &Limits::default()).unwrap(), first);
+
+    let exact = Limits { fuel: first.fuel_consumed, ..Limits::default() };
+    assert_eq!(reverse.run(&input, &exact).unwrap(), first);
+    let short = Limits { fuel: first.fuel_consumed - 1, ..Limits::default() };
+    assert_eq!(reverse.run(&input, &short).unwrap_err(), Error::OutOfFuel(short.fuel));
+
+    let spin = model("(loop $forever (br $forever)) (unreachable)");
+    let limits = Limits { fuel: 1_000_000, ..Limits::default() };
+    assert_eq!(spin.run(b"", &limits).unwrap_err(), Error::OutOfFuel(1_000_000));
+}
+
+#[test]
+fn nans_are_canonical() {
+    // 0/0 is a negative NaN on x86; arithmetic on a NaN with a payload
+    // usually keeps the payload. Both come out canonical. `neg` is a bit
+    // operation and keeps its exact result.
+    let nans = model(&format!(
+        r#"(f32.store (local.get $out) (f32.div (f32.const 0) (f32.const 0)))
+            (f64.store offset=4 (local.get $out) (f64.sqrt (f64.const -1)))
+            (f32.store offset=12 (local.get $out) (f32.add (f32.const -nan:0x200001) (f32.const 1)))
+            (f64.store offset=16 (local.get $out) (f64.promote_f32 (f32.const nan:0x1)))
+            (f32.store offset=24 (local.get $out) (f32.neg (f32.const nan)))
+            (local.set $len (i32.const 28))
+            {RETURN}"#
+    ));
+    let output = nans.run(b"", &Limits::default()).unwrap().output;
+    let f32_at = |at: usize| u32::from_le_bytes(output[at..at + 4].try_into().unwrap());
+    let f64_at = |at: usize| u64::from_le_bytes(output[at..at + 8].try_into().unwrap());
+    assert_eq!(f32_at(0), 0x7fc0_0000);
+    assert_eq!(f64_at(4), 0x7ff8_0000_0000_0000);
+    assert_eq!(f32_at(12), 0x7fc0_0000);
+    assert_eq!(f64_at(16), 0x7ff8_0000_0000_0000);
+    assert_eq!(f32_at(24), 0xffc0_0000);
+}
+
+#[test]
+fn host_access_and_nondeterministic_features_are_refused() {
+    let clock = r#"(module
+        (import "wasi_snapshot_preview1" "clock_time_get" (func (param i32 i64 i32) (result i32)))
+        (memory (export "memory") 1))"#;
+    assert_eq!(
+        Model::new(&wat::parse_str(clock).unwrap()).err(),
+        Some(Error::Import { module: "wasi_snapshot_preview1".into(), name: "clock_time_get".into() })
+    );
+
+    let simd = r#"(module (func (result v128) (v128.const i32x4 0 0 0 0)))"#;
+    assert!(matches!(Model::new(&wat::parse_str(simd).unwrap()), Err(Error::Invalid(_))));
+    assert!(matches!(Model::new(b"\0asm"), Err(Error::Invalid(_))));
+
+    let no_infer = r#"(module (memory (export "memory") 1) (func (export "alloc") (param i32) (result i32) (i32.const 0)))"#;
+    let model = Model::new(&wat::parse_str(no_infer).unwrap()).unwrap();
+    assert!(matches!(model.run(b"", &Limits::default()), Err(Error::Abi(_))));
+}
+
+#[test]
+fn memory_and_output_are_bounded() {
+    let grow = model(&format!("(drop (memory.grow (i32.const 16))) {RETURN}"));
+    assert_eq!(grow.run(b"x", &Limits::default()).unwrap().output, b"\0");
+    let small = Limits { memory: 4 << 16, ..Limits::default() };
+    assert!(matches!(grow.run(b"x", &small), Err(Error::Trap(_))));
+
+    let output = Limits { output: 2, ..Limits::default() };
+    assert!(matches!(reverse().run(b"abc", &output), Err(Error::Abi(_))));
+    let outside = model("(i64.const 0xffff_0000_0000_0010)");
+    assert!(matches!(outside.run(b"", &Limits::default()), Err(Error::Abi(_))));
+}